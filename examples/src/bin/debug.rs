@@ -11,6 +11,7 @@ use vulkano::device::{Device, DeviceExtensions};
 use vulkano::format::Format;
 use vulkano::image::ImmutableImage;
 use vulkano::image::Dimensions;
+use vulkano::image::MipmapsCount;
 use vulkano::instance;
 use vulkano::instance::{Instance, InstanceExtensions, PhysicalDevice};
 use vulkano::instance::debug::{DebugCallback, MessageSeverity, MessageType};
@@ -108,8 +109,8 @@ fn main() {
     let pixel_format = Format::R8G8B8A8Uint;
     let dimensions = Dimensions::Dim2d { width: 4096, height: 4096 };
     const DATA: [[u8; 4]; 4096*4096] = [[0; 4]; 4096 * 4096];
-    let _ = ImmutableImage::from_iter(DATA.iter().cloned(), dimensions, pixel_format,
-                                               queue.clone()).unwrap();
+    let _ = ImmutableImage::from_iter(DATA.iter().cloned(), dimensions, MipmapsCount::One,
+                                      pixel_format, queue.clone()).unwrap();
 
     // (At this point you should see a bunch of messages printed to the terminal window - have fun debugging!)
 }
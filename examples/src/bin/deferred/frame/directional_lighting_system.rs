@@ -137,11 +137,11 @@ impl DirectionalLightingSystem {
                                                      self.gfx_queue.family(),
                                                      self.pipeline.clone().subpass())
             .unwrap()
-            .draw(self.pipeline.clone(),
-                  &dynamic_state,
-                  vec![self.vertex_buffer.clone()],
-                  descriptor_set,
-                  push_constants)
+            .bind_and_draw(self.pipeline.clone(),
+                           &dynamic_state,
+                           vec![self.vertex_buffer.clone()],
+                           descriptor_set,
+                           push_constants)
             .unwrap()
             .build()
             .unwrap()
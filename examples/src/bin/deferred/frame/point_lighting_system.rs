@@ -151,11 +151,11 @@ impl PointLightingSystem {
                                                      self.gfx_queue.family(),
                                                      self.pipeline.clone().subpass())
             .unwrap()
-            .draw(self.pipeline.clone(),
-                  &dynamic_state,
-                  vec![self.vertex_buffer.clone()],
-                  descriptor_set,
-                  push_constants)
+            .bind_and_draw(self.pipeline.clone(),
+                           &dynamic_state,
+                           vec![self.vertex_buffer.clone()],
+                           descriptor_set,
+                           push_constants)
             .unwrap()
             .build()
             .unwrap()
@@ -71,17 +71,17 @@ impl TriangleDrawSystem {
                                                      self.gfx_queue.family(),
                                                      self.pipeline.clone().subpass())
             .unwrap()
-            .draw(self.pipeline.clone(),
-                  &DynamicState {
-                      viewports: Some(vec![Viewport {
-                          origin: [0.0, 0.0],
-                          dimensions: [viewport_dimensions[0] as f32,
-                                       viewport_dimensions[1] as f32],
-                          depth_range: 0.0 .. 1.0,
-                      }]),
-                      .. DynamicState::none()
-                  },
-                  vec![self.vertex_buffer.clone()], (), ())
+            .bind_and_draw(self.pipeline.clone(),
+                           &DynamicState {
+                               viewports: Some(vec![Viewport {
+                                   origin: [0.0, 0.0],
+                                   dimensions: [viewport_dimensions[0] as f32,
+                                                viewport_dimensions[1] as f32],
+                                   depth_range: 0.0 .. 1.0,
+                               }]),
+                               .. DynamicState::none()
+                           },
+                           vec![self.vertex_buffer.clone()], (), ())
             .unwrap()
             .build()
             .unwrap()
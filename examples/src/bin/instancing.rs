@@ -236,7 +236,7 @@ void main() {
         let command_buffer = AutoCommandBufferBuilder::primary_one_time_submit(device.clone(), queue.family()).unwrap()
             .begin_render_pass(framebuffers[image_num].clone(), false, clear_values)
             .unwrap()
-            .draw(
+            .bind_and_draw(
                 pipeline.clone(),
                 &dynamic_state,
                 // We pass both our lists of vertices here.
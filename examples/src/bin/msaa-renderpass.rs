@@ -217,7 +217,7 @@ void main() {
         .begin_render_pass(framebuffer.clone(), false, vec![[0.0, 0.0, 1.0, 1.0].into(), ClearValue::None])
         .unwrap()
 
-        .draw(pipeline.clone(), &dynamic_state, vertex_buffer.clone(), (), ())
+        .bind_and_draw(pipeline.clone(), &dynamic_state, vertex_buffer.clone(), (), ())
         .unwrap()
 
         .end_render_pass()
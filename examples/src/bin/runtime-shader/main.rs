@@ -10,7 +10,8 @@
 // This example demonstrates one way of preparing data structures and loading
 // SPIRV shaders from external source (file system).
 //
-// Note that you will need to do all correctness checking by yourself.
+// The inputs, outputs and layout of the shaders are determined at runtime by reflecting the
+// SPIR-V code.
 //
 // vert.glsl and frag.glsl must be built by yourself.
 // One way of building them is to build Khronos' glslang and use
@@ -24,17 +25,12 @@ use vulkano::buffer::BufferUsage;
 use vulkano::buffer::cpu_access::CpuAccessibleBuffer;
 use vulkano::command_buffer::AutoCommandBufferBuilder;
 use vulkano::command_buffer::DynamicState;
-use vulkano::descriptor::descriptor::DescriptorDesc;
-use vulkano::descriptor::descriptor::ShaderStages;
-use vulkano::descriptor::pipeline_layout::PipelineLayoutDesc;
-use vulkano::descriptor::pipeline_layout::PipelineLayoutDescPcRange;
 use vulkano::device::Device;
 use vulkano::device::DeviceExtensions;
-use vulkano::format::Format;
 use vulkano::framebuffer::{Framebuffer, FramebufferAbstract, Subpass, RenderPassAbstract};
 use vulkano::image::SwapchainImage;
 use vulkano::pipeline::GraphicsPipeline;
-use vulkano::pipeline::shader::{GraphicsShaderType, ShaderInterfaceDef, ShaderInterfaceDefEntry, ShaderModule};
+use vulkano::pipeline::shader::ShaderModule;
 use vulkano::pipeline::vertex::SingleBufferDefinition;
 use vulkano::pipeline::viewport::Viewport;
 use vulkano::swapchain::{AcquireError, PresentMode, SurfaceTransform, Swapchain, SwapchainCreationError, ColorSpace};
@@ -46,8 +42,6 @@ use vulkano_win::VkSurfaceBuild;
 
 use winit::Window;
 
-use std::borrow::Cow;
-use std::ffi::CStr;
 use std::fs::File;
 use std::io::Read;
 use std::sync::Arc;
@@ -116,9 +110,10 @@ fn main() {
             .expect("Can't find file src/bin/runtime-shader/vert.spv This example needs to be run from the root of the example crate.");
         let mut v = vec![];
         f.read_to_end(&mut v).unwrap();
-        // Create a ShaderModule on a device the same Shader::load does it.
-        // NOTE: You will have to verify correctness of the data by yourself!
-        unsafe { ShaderModule::new(device.clone(), &v) }.unwrap()
+        // Create a ShaderModule on a device the same Shader::load does it. `with_reflection`
+        // keeps a copy of the code so that its entry points can be introspected below.
+        // NOTE: The SPIR-V code itself is not validated by vulkano!
+        unsafe { ShaderModule::with_reflection(device.clone(), &v) }.unwrap()
     };
 
     let fs = {
@@ -126,230 +121,15 @@ fn main() {
             .expect("Can't find file src/bin/runtime-shader/frag.spv");
         let mut v = vec![];
         f.read_to_end(&mut v).unwrap();
-        unsafe { ShaderModule::new(device.clone(), &v) }.unwrap()
+        unsafe { ShaderModule::with_reflection(device.clone(), &v) }.unwrap()
     };
 
-    // This structure will tell Vulkan how input entries of our vertex shader look like
-    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-    struct VertInput;
-
-    unsafe impl ShaderInterfaceDef for VertInput {
-        type Iter = VertInputIter;
-
-        fn elements(&self) -> VertInputIter {
-            VertInputIter(0)
-        }
-    }
-
-    #[derive(Debug, Copy, Clone)]
-    struct VertInputIter(u16);
-
-    impl Iterator for VertInputIter {
-        type Item = ShaderInterfaceDefEntry;
-
-        #[inline]
-        fn next(&mut self) -> Option<Self::Item> {
-            // There are things to consider when giving out entries:
-            // * There must be only one entry per one location, you can't have
-            //   `color' and `position' entries both at 0..1 locations.  They also
-            //   should not overlap.
-            // * Format of each element must be no larger than 128 bits.
-            if self.0 == 0 {
-                self.0 += 1;
-                return Some(ShaderInterfaceDefEntry {
-                    location: 1..2,
-                    format: Format::R32G32B32Sfloat,
-                    name: Some(Cow::Borrowed("color"))
-                })
-            }
-            if self.0 == 1 {
-                self.0 += 1;
-                return Some(ShaderInterfaceDefEntry {
-                    location: 0..1,
-                    format: Format::R32G32Sfloat,
-                    name: Some(Cow::Borrowed("position"))
-                })
-            }
-            None
-        }
-
-        #[inline]
-        fn size_hint(&self) -> (usize, Option<usize>) {
-            // We must return exact number of entries left in iterator.
-            let len = (2 - self.0) as usize;
-            (len, Some(len))
-        }
-    }
-
-    impl ExactSizeIterator for VertInputIter { }
-
-    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-    struct VertOutput;
-
-    unsafe impl ShaderInterfaceDef for VertOutput {
-        type Iter = VertOutputIter;
-
-        fn elements(&self) -> VertOutputIter {
-            VertOutputIter(0)
-        }
-    }
-
-    // This structure will tell Vulkan how output entries (those passed to next
-    // stage) of our vertex shader look like.
-    #[derive(Debug, Copy, Clone)]
-    struct VertOutputIter(u16);
-
-    impl Iterator for VertOutputIter {
-        type Item = ShaderInterfaceDefEntry;
-
-        #[inline]
-        fn next(&mut self) -> Option<Self::Item> {
-            if self.0 == 0 {
-                self.0 += 1;
-                return Some(ShaderInterfaceDefEntry {
-                    location: 0..1,
-                    format: Format::R32G32B32Sfloat,
-                    name: Some(Cow::Borrowed("v_color"))
-                })
-            }
-            None
-        }
-
-        #[inline]
-        fn size_hint(&self) -> (usize, Option<usize>) {
-            let len = (1 - self.0) as usize;
-            (len, Some(len))
-        }
-    }
-
-    impl ExactSizeIterator for VertOutputIter { }
-
-    // This structure describes layout of this stage.
-    #[derive(Debug, Copy, Clone)]
-    struct VertLayout(ShaderStages);
-    unsafe impl PipelineLayoutDesc for VertLayout {
-        // Number of descriptor sets it takes.
-        fn num_sets(&self) -> usize { 0 }
-        // Number of entries (bindings) in each set.
-        fn num_bindings_in_set(&self, _set: usize) -> Option<usize> { None }
-        // Descriptor descriptions.
-        fn descriptor(&self, _set: usize, _binding: usize) -> Option<DescriptorDesc> { None }
-        // Number of push constants ranges (think: number of push constants).
-        fn num_push_constants_ranges(&self) -> usize { 0 }
-        // Each push constant range in memory.
-        fn push_constants_range(&self, _num: usize) -> Option<PipelineLayoutDescPcRange> { None }
-    }
-
-    // Same as with our vertex shader, but for fragment one instead.
-    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-    struct FragInput;
-    unsafe impl ShaderInterfaceDef for FragInput {
-        type Iter = FragInputIter;
-
-        fn elements(&self) -> FragInputIter {
-            FragInputIter(0)
-        }
-    }
-    #[derive(Debug, Copy, Clone)]
-    struct FragInputIter(u16);
-
-    impl Iterator for FragInputIter {
-        type Item = ShaderInterfaceDefEntry;
-
-        #[inline]
-        fn next(&mut self) -> Option<Self::Item> {
-            if self.0 == 0 {
-                self.0 += 1;
-                return Some(ShaderInterfaceDefEntry {
-                    location: 0..1,
-                    format: Format::R32G32B32Sfloat,
-                    name: Some(Cow::Borrowed("v_color"))
-                })
-            }
-            None
-        }
-
-        #[inline]
-        fn size_hint(&self) -> (usize, Option<usize>) {
-            let len = (1 - self.0) as usize;
-            (len, Some(len))
-        }
-    }
-
-    impl ExactSizeIterator for FragInputIter { }
-
-    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-    struct FragOutput;
-    unsafe impl ShaderInterfaceDef for FragOutput {
-        type Iter = FragOutputIter;
-
-        fn elements(&self) -> FragOutputIter {
-            FragOutputIter(0)
-        }
-    }
-
-    #[derive(Debug, Copy, Clone)]
-    struct FragOutputIter(u16);
-
-    impl Iterator for FragOutputIter {
-        type Item = ShaderInterfaceDefEntry;
-
-        #[inline]
-        fn next(&mut self) -> Option<Self::Item> {
-            // Note that color fragment color entry will be determined
-            // automatically by Vulkano.
-            if self.0 == 0 {
-                self.0 += 1;
-                return Some(ShaderInterfaceDefEntry {
-                    location: 0..1,
-                    format: Format::R32G32B32A32Sfloat,
-                    name: Some(Cow::Borrowed("f_color"))
-                })
-            }
-            None
-        }
-        #[inline]
-        fn size_hint(&self) -> (usize, Option<usize>) {
-            let len = (1 - self.0) as usize;
-            (len, Some(len))
-        }
-    }
-
-    impl ExactSizeIterator for FragOutputIter { }
-
-    // Layout same as with vertex shader.
-    #[derive(Debug, Copy, Clone)]
-    struct FragLayout(ShaderStages);
-    unsafe impl PipelineLayoutDesc for FragLayout {
-        fn num_sets(&self) -> usize { 0 }
-        fn num_bindings_in_set(&self, _set: usize) -> Option<usize> { None }
-        fn descriptor(&self, _set: usize, _binding: usize) -> Option<DescriptorDesc> { None }
-        fn num_push_constants_ranges(&self) -> usize { 0 }
-        fn push_constants_range(&self, _num: usize) -> Option<PipelineLayoutDescPcRange> { None }
-    }
-
-    // NOTE: ShaderModule::*_shader_entry_point calls do not do any error
-    // checking and you have to verify correctness of what you are doing by
-    // yourself.
-    //
-    // You must be extra careful to specify correct entry point, or program will
-    // crash at runtime outside of rust and you will get NO meaningful error
-    // information!
-    let vert_main = unsafe { vs.graphics_entry_point(
-        CStr::from_bytes_with_nul_unchecked(b"main\0"),
-        VertInput,
-        VertOutput,
-        VertLayout(ShaderStages { vertex: true, ..ShaderStages::none() }),
-        GraphicsShaderType::Vertex
-    ) };
-
-    let frag_main = unsafe { fs.graphics_entry_point(
-        CStr::from_bytes_with_nul_unchecked(b"main\0"),
-        FragInput,
-        FragOutput,
-        FragLayout(ShaderStages { fragment: true, ..ShaderStages::none() }),
-        GraphicsShaderType::Fragment
-    ) };
+    // `entry_point_by_name` parses the SPIR-V code of the module in order to find the inputs,
+    // outputs, descriptors and push constants of the entry point. This replaces the
+    // `ShaderInterfaceDef` and `PipelineLayoutDesc` implementations that would otherwise have to
+    // be written by hand, and returns an error if the entry point doesn't exist.
+    let vert_main = vs.entry_point_by_name("main").unwrap();
+    let frag_main = fs.entry_point_by_name("main").unwrap();
 
     let graphics_pipeline = Arc::new(
         GraphicsPipeline::start()
@@ -423,7 +203,7 @@ fn main() {
         let clear_values = vec!([0.0, 0.0, 0.0, 1.0].into());
         let command_buffer = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap()
             .begin_render_pass(framebuffers[image_num].clone(), false, clear_values).unwrap()
-            .draw(graphics_pipeline.clone(), &dynamic_state, vertex_buffer.clone(), (), ()).unwrap()
+            .bind_and_draw(graphics_pipeline.clone(), &dynamic_state, vertex_buffer.clone(), (), ()).unwrap()
             .end_render_pass().unwrap()
             .build().unwrap();
 
@@ -194,7 +194,7 @@ fn main() {
                     1f32.into()
                 ]
             ).unwrap()
-            .draw_indexed(
+            .bind_and_draw_indexed(
                 pipeline.clone(),
                 &DynamicState::none(),
                 vec!(vertex_buffer.clone(), normals_buffer.clone()),
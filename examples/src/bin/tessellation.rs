@@ -263,7 +263,7 @@ fn main() {
         let command_buffer = AutoCommandBufferBuilder::primary_one_time_submit(device.clone(), queue.family()).unwrap()
             .begin_render_pass(framebuffers[image_num].clone(), false, vec![[0.0, 0.0, 0.0, 1.0].into()])
             .unwrap()
-            .draw(pipeline.clone(), &dynamic_state, vertex_buffer.clone(), (), ())
+            .bind_and_draw(pipeline.clone(), &dynamic_state, vertex_buffer.clone(), (), ())
             .unwrap()
             .end_render_pass()
             .unwrap()
@@ -389,7 +389,7 @@ void main() {
             //
             // The last two parameters contain the list of resources to pass to the shaders.
             // Since we used an `EmptyPipeline` object, the objects have to be `()`.
-            .draw(pipeline.clone(), &dynamic_state, vertex_buffer.clone(), (), ())
+            .bind_and_draw(pipeline.clone(), &dynamic_state, vertex_buffer.clone(), (), ())
             .unwrap()
 
             // We leave the render pass by calling `draw_end`. Note that if we had multiple
@@ -193,6 +193,9 @@ pub const STRUCTURE_TYPE_IMAGE_MEMORY_REQUIREMENTS_INFO_2_KHR: u32 = 1000146001;
 pub const STRUCTURE_TYPE_IMAGE_SPARSE_MEMORY_REQUIREMENTS_INFO_2_KHR: u32 = 1000146002;
 pub const STRUCTURE_TYPE_MEMORY_REQUIREMENTS_2_KHR: u32 = 1000146003;
 pub const STRUCTURE_TYPE_SPARSE_IMAGE_MEMORY_REQUIREMENTS_2_KHR: u32 = 1000146004;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_VERTEX_ATTRIBUTE_DIVISOR_PROPERTIES_EXT: u32 = 1000190000;
+pub const STRUCTURE_TYPE_PIPELINE_VERTEX_INPUT_DIVISOR_STATE_CREATE_INFO_EXT: u32 = 1000190001;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_VERTEX_ATTRIBUTE_DIVISOR_FEATURES_EXT: u32 = 1000190002;
 
 pub type SystemAllocationScope = u32;
 pub const SYSTEM_ALLOCATION_SCOPE_COMMAND: u32 = 0;
@@ -2688,6 +2691,35 @@ pub struct DebugUtilsObjectNameInfoEXT {
     pub pObjectName: *const c_char,
 }
 
+#[repr(C)]
+pub struct VertexInputBindingDivisorDescriptionEXT {
+    pub binding: u32,
+    pub divisor: u32,
+}
+
+#[repr(C)]
+pub struct PipelineVertexInputDivisorStateCreateInfoEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub vertexBindingDivisorCount: u32,
+    pub pVertexBindingDivisors: *const VertexInputBindingDivisorDescriptionEXT,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceVertexAttributeDivisorPropertiesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub maxVertexAttribDivisor: u32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceVertexAttributeDivisorFeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub vertexAttributeInstanceRateDivisor: Bool32,
+    pub vertexAttributeInstanceRateZeroDivisor: Bool32,
+}
+
 macro_rules! ptrs {
     ($struct_name:ident, { $($name:ident => ($($param_n:ident: $param_ty:ty),*) -> $ret:ty,)+ }) => (
         pub struct $struct_name {
@@ -2933,6 +2965,8 @@ ptrs!(DevicePointers, {
     CmdPushDescriptorSetWithTemplateKHR => (commandBuffer: CommandBuffer, descriptorUpdateTemplate: DescriptorUpdateTemplateKHR, layout: PipelineLayout, set: u32, pData: *const c_void) -> (),
     GetImageMemoryRequirements2KHR => (device: Device, pInfo: *const ImageMemoryRequirementsInfo2KHR, pMemoryRequirements: *mut MemoryRequirements2KHR) -> (),
     GetBufferMemoryRequirements2KHR => (device: Device, pInfo: *const BufferMemoryRequirementsInfo2KHR, pMemoryRequirements: *mut MemoryRequirements2KHR) -> (),
+    CmdDrawIndirectCountKHR => (commandBuffer: CommandBuffer, buffer: Buffer, offset: DeviceSize, countBuffer: Buffer, countBufferOffset: DeviceSize, maxDrawCount: u32, stride: u32) -> (),
+    CmdDrawIndexedIndirectCountKHR => (commandBuffer: CommandBuffer, buffer: Buffer, offset: DeviceSize, countBuffer: Buffer, countBufferOffset: DeviceSize, maxDrawCount: u32, stride: u32) -> (),
     SetDebugUtilsObjectNameEXT => (device: Device, pNameInfo: *const DebugUtilsObjectNameInfoEXT) -> Result,
     CmdBeginDebugUtilsLabelEXT => (commandBuffer: CommandBuffer, pLabelInfo: *const DebugUtilsLabelEXT) -> Result,
     CmdEndDebugUtilsLabelEXT => (commandBuffer: CommandBuffer) -> Result,
@@ -14,18 +14,20 @@ use syn::Ident;
 use proc_macro2::{Span, TokenStream};
 use shaderc::{Compiler, CompileOptions, TargetEnv};
 
-pub use shaderc::{CompilationArtifact, ShaderKind, IncludeType, ResolvedInclude};
-pub use crate::parse::ParseError;
+pub use shaderc::{CompilationArtifact, ShaderKind, IncludeType, ResolvedInclude, SourceLanguage};
+pub use vulkano::spirv::parse::ParseError;
 
-use crate::parse::Instruction;
-use crate::enums::Capability;
-use crate::enums::StorageClass;
+use vulkano::spirv::parse;
+use vulkano::spirv::parse::{Instruction, Spirv};
+use vulkano::spirv::enums::Capability;
+use vulkano::spirv::enums::ExecutionModel;
+use vulkano::spirv::enums::StorageClass;
 
-use crate::parse;
 use crate::entry_point;
 use crate::structs;
 use crate::descriptor_sets;
 use crate::spec_consts;
+use crate::vertex_input::{self, VertexStruct};
 use crate::read_file_to_string;
 
 fn include_callback(requested_source_path_raw: &str, directive_type: IncludeType,
@@ -110,12 +112,17 @@ fn include_callback(requested_source_path_raw: &str, directive_type: IncludeType
     })
 }
 
-pub fn compile(path: Option<String>, base_path: &impl AsRef<Path>, code: &str, ty: ShaderKind, include_directories: &[impl AsRef<Path>], macro_defines: &[(impl AsRef<str>, impl AsRef<str>)]) -> Result<CompilationArtifact, String> {
-    let mut compiler = Compiler::new().ok_or("failed to create GLSL compiler")?;
+pub fn compile(path: Option<String>, base_path: &impl AsRef<Path>, code: &str, ty: ShaderKind, include_directories: &[impl AsRef<Path>], macro_defines: &[(impl AsRef<str>, impl AsRef<str>)], lang: SourceLanguage, entry_point: &str) -> Result<CompilationArtifact, String> {
+    let mut compiler = Compiler::new().ok_or("failed to create shader compiler")?;
     let mut compile_options = CompileOptions::new()
         .ok_or("failed to initialize compile option")?;
     const ENV_VULKAN_VERSION: u32 = ((1 << 22) | (1 << 12));
     compile_options.set_target_env(TargetEnv::Vulkan, ENV_VULKAN_VERSION);
+    if let SourceLanguage::HLSL = lang {
+        // Use the `register(...)` annotations of HLSL resources as descriptor bindings.
+        compile_options.set_hlsl_io_mapping(true);
+    }
+    compile_options.set_source_language(lang);
     let root_source_path = if let &Some(ref path) = &path {
         path
     } else {
@@ -135,15 +142,171 @@ pub fn compile(path: Option<String>, base_path: &impl AsRef<Path>, code: &str, t
     }
 
     let content = compiler
-        .compile_into_spirv(&code, ty, root_source_path, "main", Some(&compile_options))
+        .compile_into_spirv(&code, ty, root_source_path, entry_point, Some(&compile_options))
         .map_err(|e| e.to_string())?;
 
     Ok(content)
 }
 
-pub fn reflect(name: &str, spirv: &[u32], dump: bool) -> Result<TokenStream, Error> {
-    let struct_name = Ident::new(&name, Span::call_site());
+/// Converts the content of a precompiled `.spv` file into SPIR-V words.
+///
+/// The endianness of the words is determined from the magic number at the start of the file.
+pub fn words_from_bytes(bytes: &[u8]) -> Result<Vec<u32>, String> {
+    if bytes.len() % 4 != 0 {
+        return Err(format!("SPIR-V binary has a length of {} bytes, which is not a multiple of 4",
+                           bytes.len()));
+    }
+
+    let words: Vec<u32> = bytes.chunks(4)
+        .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect();
+
+    match words.first() {
+        Some(&0x07230203) => Ok(words),
+        Some(&0x03022307) => Ok(words.into_iter().map(u32::swap_bytes).collect()),
+        _ => Err("SPIR-V binary doesn't start with the SPIR-V magic number".to_owned()),
+    }
+}
+
+pub fn reflect(name: &str, spirv: &[u32], vertex_structs: &[VertexStruct], dump: bool) -> Result<TokenStream, Error> {
     let doc = parse::parse_spirv(spirv)?;
+    let structs = structs::write_structs(&doc);
+    let ast = reflect_shader(name, spirv, &doc, vertex_structs, quote!{
+        pub mod ty {
+            #structs
+        }
+    });
+
+    if dump {
+        println!("{}", ast.to_string());
+        panic!("`shader!` rust codegen dumped") // TODO: use span from dump
+    }
+
+    Ok(ast)
+}
+
+/// Same as `reflect`, but for several shaders at once. Each shader is written in its own module
+/// named after it, and the structs of all the shaders are written once in a shared `ty` module.
+pub fn reflect_multiple(shaders: &[(Ident, Vec<u32>, Vec<VertexStruct>)], dump: bool) -> Result<TokenStream, Error> {
+    let mut docs = Vec::with_capacity(shaders.len());
+    for &(ref name, ref spirv, ref vertex_structs) in shaders {
+        docs.push((name, spirv, vertex_structs, parse::parse_spirv(spirv)?));
+    }
+
+    check_stage_interfaces(&docs.iter().map(|&(name, _, _, ref doc)| (name, doc)).collect::<Vec<_>>());
+
+    // Structs with the same name must have the same definition in all the shaders, so that
+    // values can be shared between them.
+    let mut structs: Vec<(String, &Ident, TokenStream)> = Vec::new();
+    for &(name, _, _, ref doc) in docs.iter() {
+        for (struct_name, ast) in structs::struct_definitions(doc) {
+            match structs.iter().find(|&&(ref n, _, _)| *n == struct_name) {
+                Some(&(_, other_name, ref other_ast)) => {
+                    if other_ast.to_string() != ast.to_string() {
+                        panic!("Struct `{}` has different definitions in shaders `{}` and `{}`",
+                               struct_name, other_name, name);
+                    }
+                },
+                None => structs.push((struct_name, name, ast)),
+            }
+        }
+    }
+    let structs = structs.into_iter().map(|(_, _, ast)| ast);
+
+    let modules = docs.iter().map(|&(name, spirv, vertex_structs, ref doc)| {
+        let shader = reflect_shader("Shader", spirv, doc, vertex_structs, quote!{
+            pub use super::ty;
+        });
+        quote!{
+            pub mod #name {
+                #shader
+            }
+        }
+    }).collect::<Vec<_>>();
+
+    let ast = quote!{
+        #( #modules )*
+
+        pub mod ty {
+            #( #structs )*
+        }
+    };
+
+    if dump {
+        println!("{}", ast.to_string());
+        panic!("`shader!` rust codegen dumped") // TODO: use span from dump
+    }
+
+    Ok(ast)
+}
+
+/// Checks that the outputs of each graphics stage match the inputs of the next one, the same way
+/// as `GraphicsPipeline` does at runtime.
+///
+/// The check is skipped if the same stage is provided by more than one shader, as it is then
+/// unknown which stages will be used together.
+fn check_stage_interfaces(docs: &[(&Ident, &Spirv)]) {
+    let mut stages = Vec::new();
+    for &(name, doc) in docs {
+        for instruction in doc.instructions.iter() {
+            let order = match instruction {
+                &Instruction::EntryPoint { ref execution, .. } => match *execution {
+                    ExecutionModel::ExecutionModelVertex => 0,
+                    ExecutionModel::ExecutionModelTessellationControl => 1,
+                    ExecutionModel::ExecutionModelTessellationEvaluation => 2,
+                    ExecutionModel::ExecutionModelGeometry => 3,
+                    ExecutionModel::ExecutionModelFragment => 4,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            if stages.iter().any(|&(o, _, _)| o == order) {
+                return;
+            }
+
+            stages.push((order, name, entry_point::stage_interface(doc, instruction)));
+        }
+    }
+
+    stages.sort_by_key(|&(order, _, _)| order);
+
+    for pair in stages.windows(2) {
+        let (_, prev_name, (_, ref outputs)) = pair[0];
+        let (_, next_name, (ref inputs, _)) = pair[1];
+
+        if outputs.len() != inputs.len() {
+            panic!("Shader `{}` has {} outputs but shader `{}` has {} inputs",
+                   prev_name, outputs.len(), next_name, inputs.len());
+        }
+
+        for output in outputs {
+            for location in output.location .. output.location + output.location_len as u32 {
+                let input = inputs.iter().find(|i| {
+                    location >= i.location && location < i.location + i.location_len as u32
+                });
+
+                match input {
+                    None => {
+                        panic!("Output `{}` of shader `{}` at location {} has no matching input \
+                                in shader `{}`", output.name, prev_name, location, next_name);
+                    },
+                    Some(input) if input.format != output.format => {
+                        panic!("Output `{}` of shader `{}` has format {} but input `{}` of \
+                                shader `{}` has format {} at location {}",
+                               output.name, prev_name, output.format, input.name, next_name,
+                               input.format, location);
+                    },
+                    Some(_) => (),
+                }
+            }
+        }
+    }
+}
+
+fn reflect_shader(name: &str, spirv: &[u32], doc: &Spirv, vertex_structs: &[VertexStruct],
+                  ty_module: TokenStream) -> TokenStream {
+    let struct_name = Ident::new(&name, Span::call_site());
 
     // checking whether each required capability is enabled in the Vulkan device
     let mut cap_checks: Vec<TokenStream> = vec!();
@@ -195,10 +358,10 @@ pub fn reflect(name: &str, spirv: &[u32], dump: bool) -> Result<TokenStream, Err
         }
     }
 
-    let structs = structs::write_structs(&doc);
     let descriptor_sets = descriptor_sets::write_descriptor_sets(&doc);
     let specialization_constants = spec_consts::write_specialization_constants(&doc);
-    let ast = quote!{
+    let vertex_structs = vertex_input::write_vertex_structs(&doc, vertex_structs);
+    quote!{
         #[allow(unused_imports)]
         use std::sync::Arc;
         #[allow(unused_imports)]
@@ -270,20 +433,12 @@ pub fn reflect(name: &str, spirv: &[u32], dump: bool) -> Result<TokenStream, Err
 
         #( #entry_points_outside_impl )*
 
-        pub mod ty {
-            #structs
-        }
+        #ty_module
 
         #descriptor_sets
         #specialization_constants
-    };
-
-    if dump {
-        println!("{}", ast.to_string());
-        panic!("`shader!` rust codegen dumped") // TODO: use span from dump
+        #vertex_structs
     }
-
-    Ok(ast)
 }
 
 #[derive(Debug)]
@@ -434,6 +589,20 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_words_from_bytes() {
+        let bytes = include_bytes!("../tests/frag.spv");
+        let words = words_from_bytes(bytes).unwrap();
+        assert_eq!(words[0], 0x07230203);
+        reflect("Shader", &words, &[], false).unwrap();
+
+        let swapped: Vec<u8> = bytes.chunks(4).flat_map(|c| c.iter().rev().cloned()).collect();
+        assert_eq!(words_from_bytes(&swapped).unwrap(), words);
+
+        assert!(words_from_bytes(&bytes[.. 6]).is_err());
+        assert!(words_from_bytes(&[0; 8]).is_err());
+    }
+
     #[test]
     fn test_bad_alignment() {
         // vec3/mat3/mat3x* are problematic in arrays since their rust
@@ -453,7 +622,7 @@ mod tests {
             MyStruct s;
         };
         void main() {}
-        ", ShaderKind::Vertex, &includes, &defines).unwrap();
+        ", ShaderKind::Vertex, &includes, &defines, SourceLanguage::GLSL, "main").unwrap();
         let doc = parse::parse_spirv(comp.as_binary()).unwrap();
         let res = std::panic::catch_unwind(|| structs::write_structs(&doc));
         assert!(res.is_err());
@@ -471,7 +640,7 @@ mod tests {
             MyStruct s;
         };
         void main() {}
-        ", ShaderKind::Vertex, &includes, &defines).unwrap();
+        ", ShaderKind::Vertex, &includes, &defines, SourceLanguage::GLSL, "main").unwrap();
         let doc = parse::parse_spirv(comp.as_binary()).unwrap();
         structs::write_structs(&doc);
     }
@@ -493,7 +662,7 @@ mod tests {
             MyStruct s;
         };
         void main() {}
-        ", ShaderKind::Vertex, &includes, &defines).unwrap();
+        ", ShaderKind::Vertex, &includes, &defines, SourceLanguage::GLSL, "main").unwrap();
         let doc = parse::parse_spirv(comp.as_binary()).unwrap();
         structs::write_structs(&doc);
     }
@@ -508,7 +677,7 @@ mod tests {
         #include \"include_dir_a/target_a.glsl\"
         #include \"include_dir_b/target_b.glsl\"
         void main() {}
-        ", ShaderKind::Vertex, &empty_includes, &defines).expect("Cannot resolve include files");
+        ", ShaderKind::Vertex, &empty_includes, &defines, SourceLanguage::GLSL, "main").expect("Cannot resolve include files");
 
         let _compile_include_paths = compile(Some(String::from("tests/include_test.glsl")), &root_path, "
         #version 450
@@ -516,7 +685,7 @@ mod tests {
         #include <target_b.glsl>
         void main() {}
         ", ShaderKind::Vertex,&[root_path.join("tests/include_dir_a"), root_path.join("tests/include_dir_b")],
-        &defines).expect("Cannot resolve include files");
+        &defines, SourceLanguage::GLSL, "main").expect("Cannot resolve include files");
 
         let _compile_include_paths_with_relative = compile(Some(String::from("tests/include_test.glsl")), &root_path, "
         #version 450
@@ -524,7 +693,7 @@ mod tests {
         #include <../include_dir_b/target_b.glsl>
         void main() {}
         ", ShaderKind::Vertex,&[root_path.join("tests/include_dir_a")],
-        &defines).expect("Cannot resolve include files");
+        &defines, SourceLanguage::GLSL, "main").expect("Cannot resolve include files");
 
         let absolute_path = root_path.join("tests/include_dir_a/target_a.glsl");
         let absolute_path_str = absolute_path.to_str().expect("Cannot run tests in a folder with non unicode characters");
@@ -532,14 +701,159 @@ mod tests {
         #version 450
         #include \"{}\"
         void main() {{}}
-        ", absolute_path_str), ShaderKind::Vertex, &empty_includes, &defines).expect("Cannot resolve include files");
+        ", absolute_path_str), ShaderKind::Vertex, &empty_includes, &defines, SourceLanguage::GLSL, "main").expect("Cannot resolve include files");
 
         let _compile_recursive = compile(Some(String::from("tests/include_test.glsl")), &root_path, "
         #version 450
         #include <target_c.glsl>
         void main() {}
         ", ShaderKind::Vertex,&[root_path.join("tests/include_dir_b"), root_path.join("tests/include_dir_c")],
-        &defines).expect("Cannot resolve include files");
+        &defines, SourceLanguage::GLSL, "main").expect("Cannot resolve include files");
+    }
+
+    #[test]
+    fn test_hlsl() {
+        let includes: [PathBuf;0] = [];
+        let defines: [(String, String);0] = [];
+        let comp = compile(None, &Path::new(""), "
+        cbuffer Constants : register(b0) {
+            float4 offset;
+        };
+
+        float4 main_vs(float2 position : POSITION) : SV_Position {
+            return float4(position, 0.0, 1.0) + offset;
+        }
+        ", ShaderKind::Vertex, &includes, &defines, SourceLanguage::HLSL, "main_vs").unwrap();
+        let doc = parse::parse_spirv(comp.as_binary()).unwrap();
+        let entry_point_names: Vec<_> = doc.instructions.iter().filter_map(|i| match i {
+            Instruction::EntryPoint { name, .. } => Some(name.clone()),
+            _ => None,
+        }).collect();
+        assert_eq!(entry_point_names, vec!["main_vs".to_owned()]);
+        reflect("Shader", comp.as_binary(), &[], false).unwrap();
+    }
+
+    fn compile_stages(vs: &str, fs: &str) -> Vec<(Ident, Vec<u32>, Vec<VertexStruct>)> {
+        let includes: [PathBuf;0] = [];
+        let defines: [(String, String);0] = [];
+        vec![
+            (Ident::new("vs", Span::call_site()),
+             compile(None, &Path::new(""), vs, ShaderKind::Vertex, &includes, &defines, SourceLanguage::GLSL, "main").unwrap().as_binary().to_vec(), Vec::new()),
+            (Ident::new("fs", Span::call_site()),
+             compile(None, &Path::new(""), fs, ShaderKind::Fragment, &includes, &defines, SourceLanguage::GLSL, "main").unwrap().as_binary().to_vec(), Vec::new()),
+        ]
+    }
+
+    #[test]
+    fn test_multiple_shared_structs() {
+        let shaders = compile_stages("
+        #version 450
+        layout(set = 0, binding = 0) uniform Data { vec4 color; } data;
+        layout(location = 0) in vec2 position;
+        layout(location = 0) out vec4 v_color;
+        void main() { v_color = data.color; gl_Position = vec4(position, 0.0, 1.0); }
+        ", "
+        #version 450
+        layout(set = 0, binding = 0) uniform Data { vec4 color; } data;
+        layout(location = 0) in vec4 v_color;
+        layout(location = 0) out vec4 f_color;
+        void main() { f_color = v_color * data.color; }
+        ");
+        let ast = reflect_multiple(&shaders, false).unwrap().to_string();
+        assert_eq!(ast.matches("pub struct Data").count(), 1);
+    }
+
+    #[test]
+    fn test_multiple_different_structs() {
+        let shaders = compile_stages("
+        #version 450
+        layout(set = 0, binding = 0) uniform Data { vec4 color; } data;
+        void main() { gl_Position = data.color; }
+        ", "
+        #version 450
+        layout(set = 0, binding = 0) uniform Data { float color; } data;
+        layout(location = 0) out vec4 f_color;
+        void main() { f_color = vec4(data.color); }
+        ");
+        let res = std::panic::catch_unwind(|| reflect_multiple(&shaders, false));
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_multiple_interface_mismatch() {
+        let shaders = compile_stages("
+        #version 450
+        layout(location = 0) out vec3 v_color;
+        void main() { v_color = vec3(1.0); gl_Position = vec4(0.0); }
+        ", "
+        #version 450
+        layout(location = 0) in vec4 v_color;
+        layout(location = 0) out vec4 f_color;
+        void main() { f_color = v_color; }
+        ");
+        let res = std::panic::catch_unwind(|| reflect_multiple(&shaders, false));
+        assert!(res.is_err());
+    }
+
+    fn vertex_struct(name: &str, per_instance: bool, members: Option<&[&str]>) -> VertexStruct {
+        VertexStruct {
+            name: Ident::new(name, Span::call_site()),
+            per_instance,
+            members: members.map(|m| m.iter().map(|s| s.to_string()).collect()),
+        }
+    }
+
+    #[test]
+    fn test_vertex_structs() {
+        let shaders = compile_stages("
+        #version 450
+        layout(location = 0) in vec2 position;
+        layout(location = 1) in mat4 model;
+        layout(location = 5) in uint index;
+        void main() { gl_Position = model * vec4(position, float(index), 1.0); }
+        ", "
+        #version 450
+        void main() {}
+        ");
+        let spirv = &shaders[0].1;
+
+        let ast = reflect("Shader", spirv, &[vertex_struct("Vertex", false, None)], false)
+            .unwrap().to_string();
+        assert!(ast.contains("pub struct Vertex"));
+        assert!(ast.contains("BuffersDefinition :: new () . vertex :: < Vertex > ()"));
+
+        let ast = reflect("Shader", spirv, &[
+            vertex_struct("Vertex", false, Some(&["index", "position"])),
+            vertex_struct("Instance", true, None),
+        ], false).unwrap().to_string();
+        assert!(ast.contains("pub struct Vertex { pub position : [f32 ; 2usize] , pub index : u32 }"));
+        assert!(ast.contains("pub struct Instance { pub model : [[f32 ; 4usize] ; 4usize] }"));
+        assert!(ast.contains(". vertex :: < Vertex > () . instance :: < Instance > ()"));
+    }
+
+    #[test]
+    fn test_vertex_structs_missing_input() {
+        let shaders = compile_stages("
+        #version 450
+        layout(location = 0) in vec2 position;
+        layout(location = 1) in vec3 color;
+        void main() { gl_Position = vec4(position, color.x, 1.0); }
+        ", "
+        #version 450
+        void main() {}
+        ");
+        let spirv = &shaders[0].1;
+
+        let res = std::panic::catch_unwind(|| {
+            reflect("Shader", spirv, &[vertex_struct("Vertex", false, Some(&["position"]))], false)
+        });
+        assert!(res.is_err());
+
+        let res = std::panic::catch_unwind(|| {
+            reflect("Shader", spirv, &[vertex_struct("Vertex", false, Some(&["normal"])),
+                                       vertex_struct("Instance", true, None)], false)
+        });
+        assert!(res.is_err());
     }
 
     #[test]
@@ -553,10 +867,10 @@ mod tests {
         void main() {}
         #endif
         ";
-        let compile_no_defines = compile(None, &Path::new(""), need_defines, ShaderKind::Vertex, &empty_includes, &no_defines);
+        let compile_no_defines = compile(None, &Path::new(""), need_defines, ShaderKind::Vertex, &empty_includes, &no_defines, SourceLanguage::GLSL, "main");
         assert!(compile_no_defines.is_err());
 
-        let compile_defines = compile(None, &Path::new(""), need_defines, ShaderKind::Vertex, &empty_includes, &defines);
+        let compile_defines = compile(None, &Path::new(""), need_defines, ShaderKind::Vertex, &empty_includes, &defines, SourceLanguage::GLSL, "main");
         compile_defines.expect("Setting shader macros did not work");
     }
 }
@@ -0,0 +1,285 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use proc_macro2::TokenStream;
+use syn::{parse_quote, Attribute, Data, DeriveInput, Fields, Ident, LitStr, Meta, NestedMeta,
+          Result};
+use syn::spanned::Spanned;
+
+/// Writes the implementation of the `Vertex` trait for the struct of `#[derive(Vertex)]`.
+pub fn derive_vertex(input: DeriveInput) -> Result<TokenStream> {
+    if !has_repr_c(&input.attrs) {
+        return Err(syn::Error::new(input.ident.span(),
+                                   "`#[derive(Vertex)]` requires the struct to be `#[repr(C)]`"));
+    }
+
+    if has_repr_packed(&input.attrs) {
+        return Err(syn::Error::new(input.ident.span(),
+                                   "`#[derive(Vertex)]` can't be used on a `#[repr(packed)]` struct"));
+    }
+
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => return Err(syn::Error::new(input.ident.span(),
+                                            "`#[derive(Vertex)]` requires a struct with named fields")),
+        },
+        _ => return Err(syn::Error::new(input.ident.span(),
+                                        "`#[derive(Vertex)]` can only be used on structs")),
+    };
+
+    // The offsets are computed with the layout rules of `#[repr(C)]`, which is why all the fields
+    // must be visited, including the skipped ones.
+    let mut members = Vec::with_capacity(fields.len());
+    let mut member_tys = Vec::with_capacity(fields.len());
+    for field in fields.iter() {
+        let ty = &field.ty;
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+
+        let next_offset = quote!{
+            let align = ::std::mem::align_of::<#ty>();
+            offset = (offset + align - 1) / align * align;
+        };
+
+        if attrs.skip {
+            members.push(quote!{
+                #next_offset
+                offset += ::std::mem::size_of::<#ty>();
+            });
+            continue;
+        }
+
+        let name = match attrs.name {
+            Some(name) => name.value(),
+            None => field.ident.as_ref().unwrap().to_string(),
+        };
+
+        let info = match attrs.format {
+            Some(format) => quote!{
+                VertexMemberInfo {
+                    offset,
+                    ty: VertexMemberTy::U8,
+                    array_size: ::std::mem::size_of::<#ty>(),
+                    format: Some(::vulkano::format::Format::#format),
+                }
+            },
+            None => {
+                member_tys.push(ty);
+                quote!{{
+                    let (ty, array_size) = <#ty as VertexMember>::format();
+                    VertexMemberInfo {
+                        offset,
+                        ty,
+                        array_size,
+                        format: None,
+                    }
+                }}
+            },
+        };
+
+        members.push(quote!{
+            #next_offset
+            if name == #name {
+                return Some(#info);
+            }
+            offset += ::std::mem::size_of::<#ty>();
+        });
+    }
+
+    // `Vertex` requires `'static + Send + Sync`, and the members read without a `#[format]` must
+    // implement `VertexMember`, which may depend on the type parameters.
+    let mut generics = input.generics.clone();
+    {
+        let where_clause = generics.make_where_clause();
+        for param in input.generics.type_params() {
+            let ident = &param.ident;
+            where_clause.predicates.push(parse_quote!{
+                #ident: 'static + ::std::marker::Send + ::std::marker::Sync
+            });
+        }
+        for ty in member_tys {
+            where_clause.predicates.push(parse_quote!{
+                #ty: ::vulkano::pipeline::vertex::VertexMember
+            });
+        }
+    }
+
+    let struct_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote!{
+        #[allow(unsafe_code)]
+        unsafe impl #impl_generics ::vulkano::pipeline::vertex::Vertex for #struct_name #ty_generics
+            #where_clause
+        {
+            #[inline]
+            #[allow(unused_assignments, unused_variables)]
+            fn member(name: &str) -> Option<::vulkano::pipeline::vertex::VertexMemberInfo> {
+                #[allow(unused_imports)]
+                use ::vulkano::pipeline::vertex::VertexMember;
+                #[allow(unused_imports)]
+                use ::vulkano::pipeline::vertex::VertexMemberInfo;
+                #[allow(unused_imports)]
+                use ::vulkano::pipeline::vertex::VertexMemberTy;
+
+                let mut offset = 0usize;
+                #( #members )*
+                None
+            }
+        }
+    })
+}
+
+/// The `#[name(...)]`, `#[format(...)]` and `#[skip]` attributes of a field.
+#[derive(Default)]
+struct FieldAttrs {
+    name: Option<LitStr>,
+    format: Option<Ident>,
+    skip: bool,
+}
+
+impl FieldAttrs {
+    fn parse(attrs: &[Attribute]) -> Result<FieldAttrs> {
+        let mut result = FieldAttrs::default();
+
+        for attr in attrs {
+            if attr.path.is_ident("name") {
+                if result.name.is_some() {
+                    return Err(syn::Error::new(attr.span(), "Only one `name` can be defined"));
+                }
+                result.name = Some(attr.parse_args()?);
+            } else if attr.path.is_ident("format") {
+                if result.format.is_some() {
+                    return Err(syn::Error::new(attr.span(), "Only one `format` can be defined"));
+                }
+                result.format = Some(attr.parse_args()?);
+            } else if attr.path.is_ident("skip") {
+                if !attr.tokens.is_empty() {
+                    return Err(syn::Error::new(attr.span(), "`skip` doesn't take any argument"));
+                }
+                result.skip = true;
+            }
+        }
+
+        if result.skip && (result.name.is_some() || result.format.is_some()) {
+            return Err(syn::Error::new(attrs[0].span(),
+                                       "`name` and `format` can't be used on a skipped field"));
+        }
+
+        Ok(result)
+    }
+}
+
+/// Returns true if the attributes contain `#[repr(C)]`, possibly along with other hints.
+fn has_repr_c(attrs: &[Attribute]) -> bool {
+    repr_hints(attrs).iter().any(|hint| match hint {
+        NestedMeta::Meta(Meta::Path(path)) => path.is_ident("C"),
+        _ => false,
+    })
+}
+
+/// Returns true if the attributes contain `#[repr(packed)]` or `#[repr(packed(N))]`.
+///
+/// The offsets of the members are computed with the alignment of their types, which doesn't
+/// apply to the members of a packed struct.
+fn has_repr_packed(attrs: &[Attribute]) -> bool {
+    repr_hints(attrs).iter().any(|hint| match hint {
+        NestedMeta::Meta(Meta::Path(path)) => path.is_ident("packed"),
+        NestedMeta::Meta(Meta::List(list)) => list.path.is_ident("packed"),
+        _ => false,
+    })
+}
+
+/// Returns the hints of all the `#[repr(...)]` attributes.
+fn repr_hints(attrs: &[Attribute]) -> Vec<NestedMeta> {
+    attrs.iter()
+        .filter(|attr| attr.path.is_ident("repr"))
+        .filter_map(|attr| attr.parse_meta().ok())
+        .flat_map(|meta| match meta {
+            Meta::List(list) => list.nested.into_iter().collect(),
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requires_repr_c() {
+        let input: DeriveInput = syn::parse_quote!{
+            struct Vertex {
+                position: [f32; 2],
+            }
+        };
+        assert!(derive_vertex(input).is_err());
+
+        let input: DeriveInput = syn::parse_quote!{
+            #[repr(C, align(16))]
+            struct Vertex {
+                position: [f32; 2],
+            }
+        };
+        assert!(derive_vertex(input).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_packed() {
+        let input: DeriveInput = syn::parse_quote!{
+            #[repr(C, packed)]
+            struct Vertex {
+                position: [f32; 2],
+            }
+        };
+        assert!(derive_vertex(input).is_err());
+
+        let input: DeriveInput = syn::parse_quote!{
+            #[repr(C)]
+            #[repr(packed(2))]
+            struct Vertex {
+                position: [f32; 2],
+            }
+        };
+        assert!(derive_vertex(input).is_err());
+    }
+
+    #[test]
+    fn test_field_attributes() {
+        let input: DeriveInput = syn::parse_quote!{
+            #[repr(C)]
+            struct Vertex<T> {
+                #[name("position")]
+                pos: [f32; 2],
+                #[skip]
+                padding: T,
+                #[format(R8G8B8A8Unorm)]
+                color: [u8; 4],
+            }
+        };
+        let ast = derive_vertex(input).unwrap().to_string();
+        assert!(ast.contains("\"position\""));
+        assert!(!ast.contains("\"pos\""));
+        assert!(!ast.contains("\"padding\""));
+        assert!(ast.contains("Format :: R8G8B8A8Unorm"));
+        assert!(ast.contains("T : 'static"));
+        assert!(ast.contains("[f32 ; 2] : :: vulkano :: pipeline :: vertex :: VertexMember"));
+        assert!(!ast.contains("T : :: vulkano :: pipeline :: vertex :: VertexMember"));
+
+        let input: DeriveInput = syn::parse_quote!{
+            #[repr(C)]
+            struct Vertex {
+                #[skip]
+                #[name("position")]
+                pos: [f32; 2],
+            }
+        };
+        assert!(derive_vertex(input).is_err());
+    }
+}
@@ -11,8 +11,8 @@ use std::cmp;
 
 use proc_macro2::TokenStream;
 
-use crate::enums::{Dim, Decoration, StorageClass, ImageFormat};
-use crate::parse::{Instruction, Spirv};
+use vulkano::spirv::enums::{Dim, Decoration, StorageClass, ImageFormat};
+use vulkano::spirv::parse::{Instruction, Spirv};
 use crate::spirv_search;
 
 pub fn write_descriptor_sets(doc: &Spirv) -> TokenStream {
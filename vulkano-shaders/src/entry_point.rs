@@ -10,8 +10,8 @@
 use syn::Ident;
 use proc_macro2::{Span, TokenStream};
 
-use crate::enums::{StorageClass, ExecutionModel, ExecutionMode, Decoration};
-use crate::parse::{Instruction, Spirv};
+use vulkano::spirv::enums::{StorageClass, ExecutionModel, ExecutionMode, Decoration};
+use vulkano::spirv::parse::{Instruction, Spirv};
 use crate::spirv_search;
 
 pub fn write_entry_point(doc: &Spirv, instruction: &Instruction) -> (TokenStream, TokenStream) {
@@ -28,16 +28,7 @@ pub fn write_entry_point(doc: &Spirv, instruction: &Instruction) -> (TokenStream
         .chain(ep_name.chars().skip(1))
         .collect();
 
-    let ignore_first_array_in = match *execution {
-        ExecutionModel::ExecutionModelTessellationControl => true,
-        ExecutionModel::ExecutionModelTessellationEvaluation => true,
-        ExecutionModel::ExecutionModelGeometry => true,
-        _ => false,
-    };
-    let ignore_first_array_out = match *execution {
-        ExecutionModel::ExecutionModelTessellationControl => true,
-        _ => false,
-    };
+    let (ignore_first_array_in, ignore_first_array_out) = ignore_first_arrays(execution);
 
     let interface_structs = write_interface_structs(
         doc,
@@ -181,16 +172,53 @@ pub fn write_entry_point(doc: &Spirv, instruction: &Instruction) -> (TokenStream
     (interface_structs, entry_point)
 }
 
-struct Element {
-    location: u32,
-    name: String,
-    format: String,
-    location_len: usize,
+pub struct Element {
+    pub location: u32,
+    pub name: String,
+    pub format: String,
+    pub location_len: usize,
 }
 
 fn write_interface_structs(doc: &Spirv, capitalized_ep_name: &str, interface: &[u32],
                            ignore_first_array_in: bool, ignore_first_array_out: bool)
                            -> TokenStream {
+    let (input_elements, output_elements) =
+        interface_elements(doc, interface, ignore_first_array_in, ignore_first_array_out);
+
+    let input: TokenStream = write_interface_struct(&format!("{}Input", capitalized_ep_name), &input_elements);
+    let output: TokenStream = write_interface_struct(&format!("{}Output", capitalized_ep_name), &output_elements);
+    quote!{ #input #output }
+}
+
+/// Returns the input and output elements of the entry point of a shader stage.
+pub fn stage_interface(doc: &Spirv, instruction: &Instruction) -> (Vec<Element>, Vec<Element>) {
+    let (execution, interface) = match instruction {
+        &Instruction::EntryPoint { ref execution, ref interface, .. } => (execution, interface),
+        _ => unreachable!(),
+    };
+
+    let (ignore_first_array_in, ignore_first_array_out) = ignore_first_arrays(execution);
+    interface_elements(doc, interface, ignore_first_array_in, ignore_first_array_out)
+}
+
+/// Returns whether the outermost array of the inputs and outputs of a stage are per-vertex arrays
+/// that must be ignored.
+fn ignore_first_arrays(execution: &ExecutionModel) -> (bool, bool) {
+    let ignore_first_array_in = match *execution {
+        ExecutionModel::ExecutionModelTessellationControl => true,
+        ExecutionModel::ExecutionModelTessellationEvaluation => true,
+        ExecutionModel::ExecutionModelGeometry => true,
+        _ => false,
+    };
+    let ignore_first_array_out = match *execution {
+        ExecutionModel::ExecutionModelTessellationControl => true,
+        _ => false,
+    };
+    (ignore_first_array_in, ignore_first_array_out)
+}
+
+fn interface_elements(doc: &Spirv, interface: &[u32], ignore_first_array_in: bool,
+                      ignore_first_array_out: bool) -> (Vec<Element>, Vec<Element>) {
     let mut input_elements = vec!();
     let mut output_elements = vec!();
 
@@ -234,9 +262,7 @@ fn write_interface_structs(doc: &Spirv, capitalized_ep_name: &str, interface: &[
         }
     }
 
-    let input: TokenStream = write_interface_struct(&format!("{}Input", capitalized_ep_name), &input_elements);
-    let output: TokenStream = write_interface_struct(&format!("{}Output", capitalized_ep_name), &output_elements);
-    quote!{ #input #output }
+    (input_elements, output_elements)
 }
 
 fn write_interface_struct(struct_name_str: &str, attributes: &[Element]) -> TokenStream {
@@ -294,6 +320,7 @@ fn write_interface_struct(struct_name_str: &str, attributes: &[Element]) -> Toke
 
     quote!{
         #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        #[allow(non_camel_case_types)]
         pub struct #struct_name;
 
         #[allow(unsafe_code)]
@@ -305,6 +332,7 @@ fn write_interface_struct(struct_name_str: &str, attributes: &[Element]) -> Toke
         }
 
         #[derive(Debug, Copy, Clone)]
+        #[allow(non_camel_case_types)]
         pub struct #iter_name { num: u16 }
 
         impl Iterator for #iter_name {
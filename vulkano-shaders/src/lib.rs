@@ -1,5 +1,5 @@
 //! The procedural macro for vulkano's shader system.
-//! Manages the compile-time compilation of GLSL into SPIR-V and generation of assosciated rust code.
+//! Manages the compile-time compilation of GLSL or HLSL into SPIR-V and generation of assosciated rust code.
 //!
 //! # Basic usage
 //!
@@ -104,7 +104,8 @@
 //!
 //! ## `ty: "..."`
 //!
-//! This defines what shader type the given GLSL source will be compiled into.
+//! This defines what shader type the given GLSL source will be compiled into. It is ignored when
+//! using `bytes`.
 //! The type can be any of the following:
 //!
 //! * `vertex`
@@ -119,12 +120,32 @@
 //! ## `src: "..."`
 //!
 //! Provides the raw GLSL source to be compiled in the form of a string. Cannot
-//! be used in conjunction with the `path` field.
+//! be used in conjunction with the `path` or `bytes` fields.
 //!
 //! ## `path: "..."`
 //!
 //! Provides the path to the GLSL source to be compiled, relative to `Cargo.toml`.
-//! Cannot be used in conjunction with the `src` field.
+//! Cannot be used in conjunction with the `src` or `bytes` fields.
+//!
+//! ## `bytes: "..."`
+//!
+//! Provides the path to a precompiled SPIR-V binary, relative to `Cargo.toml`. The binary is not
+//! compiled again, but the same Rust code is generated from it as for GLSL sources. This allows
+//! using shaders produced by other tools, for example HLSL compiled with DXC. Cannot be used in
+//! conjunction with the `src`, `path`, `include` or `define` fields. The `ty` field is optional,
+//! as the type of each entry point is read from the binary.
+//!
+//! ## `lang: "..."`
+//!
+//! Defines the language of the source. Can be either `glsl` (the default) or `hlsl`. The same
+//! Rust code is generated for both languages. For HLSL, the `register(...)` of each resource is
+//! used as its binding.
+//!
+//! ## `entry_point: "..."`
+//!
+//! Name of the function to use as the entry point, `main` by default. This is mostly useful with
+//! HLSL, where a single file often contains the entry points of several stages. The method
+//! generated on `Shader` is named after it, for example `main_vs_entry_point()`.
 //!
 //! ## `include: ["...", "...", ..., "..."]`
 //!
@@ -140,6 +161,92 @@
 //! Adds the given macro definitions to the pre-processor. This is equivalent to passing `-DNAME=VALUE`
 //! on the command line.
 //!
+//! ## `vertex_structs: { Name: { members: ["...", ...], per_instance: false }, ... }`
+//!
+//! Generates vertex structs from the inputs of a vertex shader, so that they can't get out of
+//! sync with the shader. Each struct is `#[repr(C)]`, has one public field per input, named
+//! after the input and sorted by location, and implements the [`Vertex`][Vertex] trait.
+//!
+//! `members` lists the names of the inputs that are part of the struct, and `per_instance`
+//! specifies whether the struct is read once per instance instead of once per vertex. One of the
+//! structs can omit `members`, in which case it contains all the inputs that aren't part of
+//! another struct. Every input must belong to exactly one struct.
+//!
+//! A `vertex_definition()` function is also generated, which returns a `BuffersDefinition` that
+//! reads each struct from its own buffer, in the order in which the structs are declared.
+//!
+//! ```
+//! vulkano_shaders::shader!{
+//!     ty: "vertex",
+//!     src: "
+//! #version 450
+//!
+//! layout(location = 0) in vec2 position;
+//! layout(location = 1) in vec2 offset;
+//!
+//! void main() {
+//!     gl_Position = vec4(position + offset, 0.0, 1.0);
+//! }",
+//!     vertex_structs: {
+//!         Vertex: { members: ["position"] },
+//!         Instance: { per_instance: true },
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let vertex = Vertex { position: [0.0, 1.0] };
+//! let instance = Instance { offset: [0.5, 0.5] };
+//! let definition = vertex_definition();
+//! # let _ = (vertex, instance, definition);
+//! # }
+//! ```
+//!
+//! ## `shaders: { name: { ... }, ... }`
+//!
+//! Generates several shaders in one invocation. Each shader is written in a module with the given
+//! name, and accepts the same fields as a single shader (`ty`, `src`, `path`, `bytes`, `lang`,
+//! `entry_point`, `include`, `define` and `vertex_structs`). The `include` and `define` fields,
+//! when provided next to `shaders`, apply to all the shaders except the ones that use `bytes`.
+//!
+//! The structs of all the shaders are written once in a `ty` module next to the shader modules,
+//! so that for example a uniform buffer struct used by both a vertex and a fragment shader is the
+//! same Rust type. Compilation fails if two shaders declare structs with the same name but
+//! different layouts, or if the outputs of a graphics stage don't match the inputs of the next
+//! stage.
+//!
+//! ```
+//! vulkano_shaders::shader!{
+//!     shaders: {
+//!         vs: {
+//!             ty: "vertex",
+//!             src: "
+//! #version 450
+//!
+//! layout(location = 0) in vec2 position;
+//! layout(location = 0) out vec2 v_uv;
+//!
+//! void main() {
+//!     v_uv = position;
+//!     gl_Position = vec4(position, 0.0, 1.0);
+//! }"
+//!         },
+//!         fs: {
+//!             ty: "fragment",
+//!             src: "
+//! #version 450
+//!
+//! layout(location = 0) in vec2 v_uv;
+//! layout(location = 0) out vec4 f_color;
+//!
+//! void main() {
+//!     f_color = vec4(v_uv, 0.0, 1.0);
+//! }"
+//!         }
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
 //! ## `dump: true`
 //!
 //! The crate fails to compile but prints the generated rust code to stdout.
@@ -154,6 +261,7 @@
 //! [PipelineLayoutDesc]: https://docs.rs/vulkano/*/vulkano/descriptor/pipeline_layout/trait.PipelineLayoutDesc.html
 //! [SpecializationConstants]: https://docs.rs/vulkano/*/vulkano/pipeline/shader/trait.SpecializationConstants.html
 //! [pipeline]: https://docs.rs/vulkano/*/vulkano/pipeline/index.html
+//! [Vertex]: https://docs.rs/vulkano/*/vulkano/pipeline/vertex/trait.Vertex.html
 
 #![doc(html_logo_url = "https://raw.githubusercontent.com/vulkano-rs/vulkano/master/logo.png")]
 
@@ -171,36 +279,64 @@ use syn::parse::{Parse, ParseStream, Result};
 use syn::{Ident, LitStr, LitBool};
 
 mod codegen;
+mod derive_vertex;
 mod descriptor_sets;
 mod entry_point;
-mod enums;
-mod parse;
 mod spec_consts;
 mod structs;
 mod spirv_search;
+mod vertex_input;
 
 use crate::codegen::ShaderKind;
+use crate::codegen::SourceLanguage;
+use crate::vertex_input::VertexStruct;
 
 enum SourceKind {
     Src(String),
     Path(String),
+    Bytes(String),
 }
 
-struct MacroInput {
-    shader_kind: ShaderKind,
+/// Options of a single shader.
+struct ShaderInput {
+    shader_kind: Option<ShaderKind>,
     source_kind: SourceKind,
+    source_language: Option<SourceLanguage>,
+    entry_point: Option<String>,
     include_directories: Vec<String>,
     macro_defines: Vec<(String, String)>,
+    vertex_structs: Vec<VertexStruct>,
+}
+
+enum Shaders {
+    /// `shader!{ ty: ..., src: ... }`
+    Single(ShaderInput),
+    /// `shader!{ shaders: { vs: { ... }, fs: { ... } } }`
+    Multiple(Vec<(Ident, ShaderInput)>),
+}
+
+struct MacroInput {
+    shaders: Shaders,
     dump: bool,
 }
 
-impl Parse for MacroInput {
+/// The fields found between a pair of braces, before they are validated.
+#[derive(Default)]
+struct Fields {
+    shader_kind: Option<ShaderKind>,
+    source_kind: Option<SourceKind>,
+    source_language: Option<SourceLanguage>,
+    entry_point: Option<String>,
+    include_directories: Vec<String>,
+    macro_defines: Vec<(String, String)>,
+    vertex_structs: Option<Vec<VertexStruct>>,
+    shaders: Option<Vec<(Ident, Fields)>>,
+    dump: Option<bool>,
+}
+
+impl Parse for Fields {
     fn parse(input: ParseStream) -> Result<Self> {
-        let mut dump = None;
-        let mut shader_kind = None;
-        let mut source_kind = None;
-        let mut include_directories = Vec::new();
-        let mut macro_defines = Vec::new();
+        let mut fields = Fields::default();
 
         while !input.is_empty() {
             let name: Ident = input.parse()?;
@@ -208,7 +344,7 @@ impl Parse for MacroInput {
 
             match name.to_string().as_ref() {
                 "ty" => {
-                    if shader_kind.is_some() {
+                    if fields.shader_kind.is_some() {
                         panic!("Only one `ty` can be defined")
                     }
 
@@ -222,23 +358,52 @@ impl Parse for MacroInput {
                         "compute" => ShaderKind::Compute,
                         _ => panic!("Unexpected shader type, valid values: vertex, fragment, geometry, tess_ctrl, tess_eval, compute")
                     };
-                    shader_kind = Some(ty);
+                    fields.shader_kind = Some(ty);
                 }
                 "src" => {
-                    if source_kind.is_some() {
-                        panic!("Only one `src` or `path` can be defined")
+                    if fields.source_kind.is_some() {
+                        panic!("Only one `src`, `path` or `bytes` can be defined")
                     }
 
                     let src: LitStr = input.parse()?;
-                    source_kind = Some(SourceKind::Src(src.value()));
+                    fields.source_kind = Some(SourceKind::Src(src.value()));
                 }
                 "path" => {
-                    if source_kind.is_some() {
-                        panic!("Only one `src` or `path` can be defined")
+                    if fields.source_kind.is_some() {
+                        panic!("Only one `src`, `path` or `bytes` can be defined")
+                    }
+
+                    let path: LitStr = input.parse()?;
+                    fields.source_kind = Some(SourceKind::Path(path.value()));
+                }
+                "bytes" => {
+                    if fields.source_kind.is_some() {
+                        panic!("Only one `src`, `path` or `bytes` can be defined")
                     }
 
                     let path: LitStr = input.parse()?;
-                    source_kind = Some(SourceKind::Path(path.value()));
+                    fields.source_kind = Some(SourceKind::Bytes(path.value()));
+                }
+                "lang" => {
+                    if fields.source_language.is_some() {
+                        panic!("Only one `lang` can be defined")
+                    }
+
+                    let lang: LitStr = input.parse()?;
+                    let lang = match lang.value().as_ref() {
+                        "glsl" => SourceLanguage::GLSL,
+                        "hlsl" => SourceLanguage::HLSL,
+                        _ => panic!("Unexpected shader language, valid values: glsl, hlsl")
+                    };
+                    fields.source_language = Some(lang);
+                }
+                "entry_point" => {
+                    if fields.entry_point.is_some() {
+                        panic!("Only one `entry_point` can be defined")
+                    }
+
+                    let name: LitStr = input.parse()?;
+                    fields.entry_point = Some(name.value());
                 }
                 "define" => {
                     let array_input;
@@ -251,7 +416,7 @@ impl Parse for MacroInput {
                         let name: LitStr = tuple_input.parse()?;
                         tuple_input.parse::<Token![,]>()?;
                         let value: LitStr = tuple_input.parse()?;
-                        macro_defines.push((name.value(), value.value()));
+                        fields.macro_defines.push((name.value(), value.value()));
 
                         if !array_input.is_empty() {
                             array_input.parse::<Token![,]>()?;
@@ -265,19 +430,131 @@ impl Parse for MacroInput {
                     while !in_brackets.is_empty() {
                         let path: LitStr = in_brackets.parse()?;
 
-                        include_directories.push(path.value());
+                        fields.include_directories.push(path.value());
 
                         if !in_brackets.is_empty() {
                             in_brackets.parse::<Token![,]>()?;
                         }
                     }
                 }
+                "vertex_structs" => {
+                    if fields.vertex_structs.is_some() {
+                        panic!("Only one `vertex_structs` can be defined")
+                    }
+
+                    let in_braces;
+                    braced!(in_braces in input);
+
+                    let mut vertex_structs: Vec<VertexStruct> = Vec::new();
+                    while !in_braces.is_empty() {
+                        let name: Ident = in_braces.parse()?;
+                        in_braces.parse::<Token![:]>()?;
+
+                        if vertex_structs.iter().any(|s| s.name == name) {
+                            panic!("Vertex struct `{}` is defined multiple times", name)
+                        }
+
+                        let struct_input;
+                        braced!(struct_input in in_braces);
+
+                        let mut members = None;
+                        let mut per_instance = None;
+                        while !struct_input.is_empty() {
+                            let field: Ident = struct_input.parse()?;
+                            struct_input.parse::<Token![:]>()?;
+
+                            match field.to_string().as_ref() {
+                                "members" => {
+                                    if members.is_some() {
+                                        panic!("Only one `members` can be defined")
+                                    }
+
+                                    let in_brackets;
+                                    bracketed!(in_brackets in struct_input);
+
+                                    let mut names = Vec::new();
+                                    while !in_brackets.is_empty() {
+                                        let member: LitStr = in_brackets.parse()?;
+                                        names.push(member.value());
+
+                                        if !in_brackets.is_empty() {
+                                            in_brackets.parse::<Token![,]>()?;
+                                        }
+                                    }
+                                    members = Some(names);
+                                }
+                                "per_instance" => {
+                                    if per_instance.is_some() {
+                                        panic!("Only one `per_instance` can be defined")
+                                    }
+                                    let per_instance_lit: LitBool = struct_input.parse()?;
+                                    per_instance = Some(per_instance_lit.value);
+                                }
+                                field => panic!("Unknown vertex struct field name: {}", field)
+                            }
+
+                            if !struct_input.is_empty() {
+                                struct_input.parse::<Token![,]>()?;
+                            }
+                        }
+
+                        vertex_structs.push(VertexStruct {
+                            name,
+                            per_instance: per_instance.unwrap_or(false),
+                            members,
+                        });
+
+                        if !in_braces.is_empty() {
+                            in_braces.parse::<Token![,]>()?;
+                        }
+                    }
+
+                    if vertex_structs.is_empty() {
+                        panic!("`vertex_structs` must contain at least one struct")
+                    }
+                    fields.vertex_structs = Some(vertex_structs);
+                }
+                "shaders" => {
+                    if fields.shaders.is_some() {
+                        panic!("Only one `shaders` can be defined")
+                    }
+
+                    let in_braces;
+                    braced!(in_braces in input);
+
+                    let mut shaders = Vec::new();
+                    while !in_braces.is_empty() {
+                        let name: Ident = in_braces.parse()?;
+                        in_braces.parse::<Token![:]>()?;
+
+                        let shader_input;
+                        braced!(shader_input in in_braces);
+                        let shader: Fields = shader_input.parse()?;
+
+                        if shader.shaders.is_some() || shader.dump.is_some() {
+                            panic!("`shaders` and `dump` can only be defined at the top level")
+                        }
+                        if shaders.iter().any(|&(ref n, _): &(Ident, Fields)| *n == name) {
+                            panic!("Shader `{}` is defined multiple times", name)
+                        }
+                        shaders.push((name, shader));
+
+                        if !in_braces.is_empty() {
+                            in_braces.parse::<Token![,]>()?;
+                        }
+                    }
+
+                    if shaders.is_empty() {
+                        panic!("`shaders` must contain at least one shader")
+                    }
+                    fields.shaders = Some(shaders);
+                }
                 "dump" => {
-                    if dump.is_some() {
+                    if fields.dump.is_some() {
                         panic!("Only one `dump` can be defined")
                     }
                     let dump_lit: LitBool = input.parse()?;
-                    dump = Some(dump_lit.value);
+                    fields.dump = Some(dump_lit.value);
                 }
                 name => panic!(format!("Unknown field name: {}", name))
             }
@@ -287,19 +564,82 @@ impl Parse for MacroInput {
             }
         }
 
-        let shader_kind = match shader_kind {
-            Some(shader_kind) => shader_kind,
-            None => panic!("Please provide a shader type e.g. `ty: \"vertex\"`")
-        };
+        Ok(fields)
+    }
+}
 
-        let source_kind = match source_kind {
+impl Fields {
+    /// Checks that the fields describe a valid shader.
+    fn into_shader_input(self) -> ShaderInput {
+        let source_kind = match self.source_kind {
             Some(source_kind) => source_kind,
             None => panic!("Please provide a source e.g. `path: \"foo.glsl\"` or `src: \"glsl source code here ...\"`")
         };
 
-        let dump = dump.unwrap_or(false);
+        match source_kind {
+            SourceKind::Bytes(_) => {
+                if !self.include_directories.is_empty() || !self.macro_defines.is_empty() ||
+                    self.source_language.is_some() || self.entry_point.is_some()
+                {
+                    panic!("`include`, `define`, `lang` and `entry_point` can't be used with `bytes`, as the SPIR-V is already compiled")
+                }
+            }
+            _ => {
+                if self.shader_kind.is_none() {
+                    panic!("Please provide a shader type e.g. `ty: \"vertex\"`")
+                }
+            }
+        }
+
+        ShaderInput {
+            shader_kind: self.shader_kind,
+            source_kind,
+            source_language: self.source_language,
+            entry_point: self.entry_point,
+            include_directories: self.include_directories,
+            macro_defines: self.macro_defines,
+            vertex_structs: self.vertex_structs.unwrap_or_default(),
+        }
+    }
+}
+
+impl Parse for MacroInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut fields: Fields = input.parse()?;
+        let dump = fields.dump.take().unwrap_or(false);
+
+        let shaders = match fields.shaders.take() {
+            None => Shaders::Single(fields.into_shader_input()),
+            Some(shaders) => {
+                if fields.shader_kind.is_some() || fields.source_kind.is_some() ||
+                    fields.source_language.is_some() || fields.entry_point.is_some() ||
+                    fields.vertex_structs.is_some()
+                {
+                    panic!("`ty`, `src`, `path`, `bytes`, `lang`, `entry_point` and `vertex_structs` \
+                            must be defined for each shader when using `shaders`")
+                }
+
+                // `include` and `define` at the top level apply to all the shaders that are
+                // compiled, but not to the ones that are already SPIR-V.
+                Shaders::Multiple(shaders.into_iter().map(|(name, mut shader)| {
+                    if let Some(SourceKind::Bytes(_)) = shader.source_kind {
+                        return (name, shader.into_shader_input());
+                    }
+
+                    let mut include_directories = fields.include_directories.clone();
+                    include_directories.append(&mut shader.include_directories);
+                    shader.include_directories = include_directories;
+
+                    let mut macro_defines = fields.macro_defines.clone();
+                    macro_defines.append(&mut shader.macro_defines);
+                    shader.macro_defines = macro_defines;
+
+                    (name, shader.into_shader_input())
+                }).collect())
+            }
+        };
 
-        Ok(MacroInput { shader_kind, source_kind, include_directories, dump, macro_defines })
+        Ok(MacroInput { shaders, dump })
     }
 }
 
@@ -310,16 +650,33 @@ pub(self) fn read_file_to_string(full_path: &Path) -> IoResult<String> {
     Ok(buf)
 }
 
-#[proc_macro]
-pub fn shader(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let input = parse_macro_input!(input as MacroInput);
-    let root = env::var("CARGO_MANIFEST_DIR").unwrap_or(".".into());
-    let root_path = Path::new(&root);
+pub(self) fn read_file_to_bytes(full_path: &Path) -> IoResult<Vec<u8>> {
+    let mut buf = Vec::new();
+    File::open(full_path)
+        .and_then(|mut file| file.read_to_end(&mut buf))?;
+    Ok(buf)
+}
+
+/// Compiles the shader if necessary and returns its SPIR-V words.
+fn shader_words(input: &ShaderInput, root_path: &Path) -> Vec<u32> {
+    if let SourceKind::Bytes(ref path) = input.source_kind {
+        let full_path = root_path.join(path);
+
+        let bytes = if full_path.is_file() {
+            read_file_to_bytes(&full_path)
+                .expect(&format!("Error reading SPIR-V from {:?}", path))
+        } else {
+            panic!("File {:?} was not found ; note that the path must be relative to your Cargo.toml", path);
+        };
+
+        return codegen::words_from_bytes(&bytes)
+            .unwrap_or_else(|err| panic!("Invalid SPIR-V in {:?}: {}", path, err));
+    }
 
     let (path, source_code) = match input.source_kind {
-        SourceKind::Src(source) => (None, source),
-        SourceKind::Path(path) => (Some(path.clone()), {
-            let full_path = root_path.join(&path);
+        SourceKind::Src(ref source) => (None, source.clone()),
+        SourceKind::Path(ref path) => (Some(path.clone()), {
+            let full_path = root_path.join(path);
 
             if full_path.is_file() {
                 read_file_to_string(&full_path)
@@ -327,7 +684,8 @@ pub fn shader(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             } else {
                 panic!("File {:?} was not found ; note that the path must be relative to your Cargo.toml", path);
             }
-        })
+        }),
+        SourceKind::Bytes(_) => unreachable!(),
     };
 
     let include_paths = input.include_directories.iter().map(|include_directory| {
@@ -337,6 +695,86 @@ pub fn shader(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         full_include_path
     }).collect::<Vec<_>>();
 
-    let content = codegen::compile(path, &root_path, &source_code, input.shader_kind, &include_paths, &input.macro_defines).unwrap();
-    codegen::reflect("Shader", content.as_binary(), input.dump).unwrap().into()
+    let source_language = input.source_language.unwrap_or(SourceLanguage::GLSL);
+    let entry_point = input.entry_point.as_ref().map(|s| s.as_str()).unwrap_or("main");
+    let content = codegen::compile(path, &root_path, &source_code, input.shader_kind.unwrap(), &include_paths, &input.macro_defines, source_language, entry_point).unwrap();
+    content.as_binary().to_vec()
+}
+
+#[proc_macro]
+pub fn shader(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as MacroInput);
+    let root = env::var("CARGO_MANIFEST_DIR").unwrap_or(".".into());
+    let root_path = Path::new(&root);
+
+    match input.shaders {
+        Shaders::Single(shader) => {
+            let words = shader_words(&shader, root_path);
+            codegen::reflect("Shader", &words, &shader.vertex_structs, input.dump).unwrap().into()
+        }
+        Shaders::Multiple(shaders) => {
+            let shaders = shaders.into_iter().map(|(name, shader)| {
+                let words = shader_words(&shader, root_path);
+                (name, words, shader.vertex_structs)
+            }).collect::<Vec<_>>();
+            codegen::reflect_multiple(&shaders, input.dump).unwrap().into()
+        }
+    }
+}
+
+/// Implements the `Vertex` trait of vulkano on a struct, as an alternative to `impl_vertex!`.
+///
+/// The struct must be `#[repr(C)]`, must not be `#[repr(packed)]`, and must have named fields.
+/// Unlike `impl_vertex!`, it doesn't require `Default` and can be used on generic structs. Each
+/// field is matched with the shader input that has the same name, and the following attributes
+/// can be used on the fields:
+///
+/// * `#[name("...")]` matches the field with the shader input of the given name instead.
+/// * `#[format(...)]` reads the field with the given [`Format`][Format], for example
+///   `#[format(R8G8B8A8Unorm)]` to read a `vec4` input from a `[u8; 4]`. The field can then be
+///   of any type with the same size as the format.
+/// * `#[skip]` ignores the field, for example if it is padding or only used on the CPU.
+///
+/// ```
+/// use vulkano_shaders::Vertex;
+///
+/// #[derive(Vertex, Copy, Clone)]
+/// #[repr(C)]
+/// struct MyVertex {
+///     #[name("position")]
+///     pos: [f32; 3],
+///     #[format(R8G8B8A8Unorm)]
+///     color: [u8; 4],
+///     #[skip]
+///     id: u32,
+/// }
+/// ```
+///
+/// On a generic struct, the implementation requires the type parameters to be `'static + Send +
+/// Sync`, and the types of the fields read without a `#[format]` to implement `VertexMember`:
+///
+/// ```
+/// use vulkano::pipeline::vertex::Vertex;
+/// use vulkano_shaders::Vertex;
+///
+/// #[derive(Vertex, Copy, Clone)]
+/// #[repr(C)]
+/// struct GenericVertex<T> {
+///     position: T,
+/// }
+///
+/// fn member_offset<V: Vertex>(name: &str) -> Option<usize> {
+///     V::member(name).map(|info| info.offset)
+/// }
+///
+/// assert_eq!(member_offset::<GenericVertex<[f32; 2]>>("position"), Some(0));
+/// ```
+///
+/// [Format]: https://docs.rs/vulkano/*/vulkano/format/enum.Format.html
+#[proc_macro_derive(Vertex, attributes(name, format, skip))]
+pub fn derive_vertex(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    derive_vertex::derive_vertex(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
 }
@@ -12,8 +12,8 @@ use std::mem;
 use syn::Ident;
 use proc_macro2::{Span, TokenStream};
 
-use crate::enums::Decoration;
-use crate::parse::{Instruction, Spirv};
+use vulkano::spirv::enums::Decoration;
+use vulkano::spirv::parse::{Instruction, Spirv};
 use crate::spirv_search;
 use crate::structs;
 
@@ -89,6 +89,10 @@ pub fn write_specialization_constants(doc: &Spirv) -> TokenStream {
         let mut map_entries = Vec::new();
         let mut curr_offset = 0;
         for spec_const in &spec_consts {
+            // Each constant is aligned like the corresponding member of the `#[repr(C)]` struct.
+            let alignment = spec_const.rust_alignment;
+            curr_offset = alignment * ((curr_offset + alignment - 1) / alignment);
+
             let constant_id = spec_const.constant_id;
             let rust_size = spec_const.rust_size;
             map_entries.push(quote!{
@@ -101,7 +105,6 @@ pub fn write_specialization_constants(doc: &Spirv) -> TokenStream {
 
             assert_ne!(spec_const.rust_size, 0);
             curr_offset += spec_const.rust_size as u32;
-            curr_offset = spec_const.rust_alignment * (1 + (curr_offset - 1) / spec_const.rust_alignment);
         }
         map_entries
     };
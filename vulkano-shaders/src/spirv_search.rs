@@ -7,8 +7,8 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
-use crate::parse::{Instruction, Spirv};
-use crate::enums::Decoration;
+use vulkano::spirv::parse::{Instruction, Spirv};
+use vulkano::spirv::enums::Decoration;
 
 /// Returns the vulkano `Format` and number of occupied locations from an id.
 ///
@@ -12,24 +12,35 @@ use std::mem;
 use syn::Ident;
 use proc_macro2::{Span, TokenStream};
 
-use crate::parse::{Instruction, Spirv};
-use crate::enums::Decoration;
+use vulkano::spirv::parse::{Instruction, Spirv};
+use vulkano::spirv::enums::Decoration;
 use crate::spirv_search;
 
 /// Translates all the structs that are contained in the SPIR-V document as Rust structs.
 pub fn write_structs(doc: &Spirv) -> TokenStream {
+    let structs = struct_definitions(doc).into_iter().map(|(_, ast)| ast);
+
+    quote!{
+        #( #structs )*
+    }
+}
+
+/// Returns the name and the Rust definition of each struct that is contained in the SPIR-V
+/// document. Structs that can't be translated, such as built-ins, are skipped.
+pub fn struct_definitions(doc: &Spirv) -> Vec<(String, TokenStream)> {
     let mut structs = vec!();
     for instruction in &doc.instructions {
         match *instruction {
-            Instruction::TypeStruct { result_id, ref member_types } =>
-                structs.push(write_struct(doc, result_id, member_types).0),
+            Instruction::TypeStruct { result_id, ref member_types } => {
+                let ast = write_struct(doc, result_id, member_types).0;
+                if !ast.is_empty() {
+                    structs.push((spirv_search::name_from_id(doc, result_id), ast));
+                }
+            },
             _ => ()
         }
     }
-
-    quote!{
-        #( #structs )*
-    }
+    structs
 }
 
 /// Analyzes a single struct, returns a string containing its Rust definition, plus its size.
@@ -0,0 +1,187 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use syn::Ident;
+use proc_macro2::{Span, TokenStream};
+
+use crate::entry_point::{self, Element};
+use vulkano::spirv::enums::ExecutionModel;
+use vulkano::spirv::parse::{Instruction, Spirv};
+
+/// A vertex struct requested with the `vertex_structs` field of the macro.
+pub struct VertexStruct {
+    /// Name of the generated struct.
+    pub name: Ident,
+    /// True if the struct is read once per instance instead of once per vertex.
+    pub per_instance: bool,
+    /// Names of the inputs of the vertex shader that are members of the struct. `None` means all
+    /// the inputs that aren't members of another struct.
+    pub members: Option<Vec<String>>,
+}
+
+/// Writes one `#[repr(C)]` struct implementing `Vertex` for each requested vertex struct, and a
+/// `vertex_definition` function binding them in order.
+pub fn write_vertex_structs(doc: &Spirv, vertex_structs: &[VertexStruct]) -> TokenStream {
+    if vertex_structs.is_empty() {
+        return quote!{};
+    }
+
+    let mut inputs = None;
+    for instruction in doc.instructions.iter() {
+        if let &Instruction::EntryPoint { execution: ExecutionModel::ExecutionModelVertex, .. } = instruction {
+            if inputs.is_some() {
+                panic!("`vertex_structs` can't be used with a shader that has several vertex entry points");
+            }
+            inputs = Some(entry_point::stage_interface(doc, instruction).0);
+        }
+    }
+    let inputs = match inputs {
+        Some(inputs) => inputs,
+        None => panic!("`vertex_structs` can only be used with vertex shaders"),
+    };
+
+    let members = assign_inputs(&inputs, vertex_structs);
+
+    let structs = vertex_structs.iter().zip(members.iter()).map(|(vertex_struct, members)| {
+        let name = &vertex_struct.name;
+        let fields = members.iter().map(|element| member_name(element)).collect::<Vec<_>>();
+        let types = members.iter().map(|element| member_type(element));
+
+        quote!{
+            #[repr(C)]
+            #[derive(Debug, Default, Copy, Clone, PartialEq)]
+            #[allow(non_snake_case)]
+            pub struct #name {
+                #( pub #fields: #types ),*
+            }
+
+            ::vulkano::impl_vertex!(#name #( , #fields )*);
+        }
+    }).collect::<Vec<_>>();
+
+    let bindings = vertex_structs.iter().map(|vertex_struct| {
+        let name = &vertex_struct.name;
+        if vertex_struct.per_instance {
+            quote!{ .instance::<#name>() }
+        } else {
+            quote!{ .vertex::<#name>() }
+        }
+    });
+
+    quote!{
+        #( #structs )*
+
+        /// Returns a vertex definition that reads each vertex struct from its own buffer, in the
+        /// order in which the structs were declared.
+        #[allow(dead_code)]
+        pub fn vertex_definition() -> ::vulkano::pipeline::vertex::BuffersDefinition {
+            ::vulkano::pipeline::vertex::BuffersDefinition::new() #( #bindings )*
+        }
+    }
+}
+
+/// Returns the inputs of the vertex shader that belong to each vertex struct, sorted by location.
+fn assign_inputs<'a>(inputs: &'a [Element], vertex_structs: &[VertexStruct]) -> Vec<Vec<&'a Element>> {
+    if vertex_structs.iter().filter(|s| s.members.is_none()).count() > 1 {
+        panic!("Only one vertex struct can omit its `members`");
+    }
+
+    let find_input = |name: &str| -> &'a Element {
+        match inputs.iter().find(|e| e.name == name) {
+            Some(element) => element,
+            None => panic!("The vertex shader has no input named `{}`", name),
+        }
+    };
+
+    let mut assigned: Vec<&str> = Vec::new();
+    let mut result = vertex_structs.iter().map(|vertex_struct| {
+        let members = match vertex_struct.members {
+            Some(ref members) => members,
+            None => return Vec::new(),
+        };
+
+        members.iter().map(|name| {
+            if assigned.contains(&name.as_str()) {
+                panic!("Input `{}` is a member of several vertex structs", name);
+            }
+            assigned.push(name);
+            find_input(name)
+        }).collect::<Vec<_>>()
+    }).collect::<Vec<_>>();
+
+    let remaining = inputs.iter()
+        .filter(|e| !assigned.contains(&e.name.as_str()))
+        .collect::<Vec<_>>();
+
+    match vertex_structs.iter().position(|s| s.members.is_none()) {
+        Some(pos) => result[pos] = remaining,
+        None => if let Some(element) = remaining.first() {
+            panic!("Input `{}` of the vertex shader isn't a member of any vertex struct", element.name);
+        },
+    }
+
+    for members in result.iter_mut() {
+        members.sort_by_key(|e| e.location);
+    }
+
+    result
+}
+
+/// Returns the name of a vertex struct member that matches an input of the vertex shader.
+///
+/// The attributes of a vertex definition are matched with the inputs of the shader by name, so
+/// the member must have the exact name of the input.
+fn member_name(element: &Element) -> Ident {
+    match syn::parse_str::<Ident>(&element.name) {
+        Ok(ident) => ident,
+        Err(_) => panic!("Input `{}` at location {} of the vertex shader doesn't have a valid Rust \
+                          name, which is required to use it in a vertex struct",
+                         element.name, element.location),
+    }
+}
+
+/// Returns the Rust type of a vertex struct member that matches an input of the vertex shader.
+fn member_type(element: &Element) -> TokenStream {
+    let format = &element.format;
+    let (components, kind) = if format.ends_with("Sfloat") {
+        (&format[.. format.len() - 6], "f")
+    } else if format.ends_with("Sint") {
+        (&format[.. format.len() - 4], "i")
+    } else if format.ends_with("Uint") {
+        (&format[.. format.len() - 4], "u")
+    } else {
+        panic!("Input `{}` has format {}, which can't be used in a vertex struct", element.name, format);
+    };
+
+    let bits = components[1 ..].chars().take_while(|c| c.is_digit(10)).collect::<String>();
+    let num_components = components.chars().filter(|c| c.is_alphabetic()).count();
+
+    if kind == "f" && bits == "16" {
+        panic!("Input `{}` is a 16-bits float, which has no Rust equivalent and can't be used in a \
+                vertex struct", element.name);
+    }
+
+    if kind != "f" && bits == "64" {
+        panic!("Input `{}` is a 64-bits integer, which can't be used in a vertex struct", element.name);
+    }
+
+    let scalar = Ident::new(&format!("{}{}", kind, bits), Span::call_site());
+    let ty = if num_components == 1 {
+        quote!{ #scalar }
+    } else {
+        quote!{ [#scalar; #num_components] }
+    };
+
+    if element.location_len == 1 {
+        ty
+    } else {
+        let location_len = element.location_len;
+        quote!{ [#ty; #location_len] }
+    }
+}
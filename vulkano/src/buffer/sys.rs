@@ -52,6 +52,8 @@ pub struct UnsafeBuffer {
     device: Arc<Device>,
     size: usize,
     usage: vk::BufferUsageFlags,
+    // True if the buffer was created with `Sharing::Exclusive`.
+    exclusive_sharing: bool,
 }
 
 impl UnsafeBuffer {
@@ -100,6 +102,11 @@ impl UnsafeBuffer {
             return Err(BufferCreationError::SparseResidencyAliasedFeatureNotEnabled);
         }
 
+        let exclusive_sharing = match sharing {
+            Sharing::Exclusive => true,
+            Sharing::Concurrent(_) => false,
+        };
+
         let buffer = {
             let (sh_mode, sh_indices) = match sharing {
                 Sharing::Exclusive => (vk::SHARING_MODE_EXCLUSIVE, SmallVec::<[u32; 8]>::new()),
@@ -203,6 +210,7 @@ impl UnsafeBuffer {
             device: device.clone(),
             size: size as usize,
             usage: usage_bits,
+            exclusive_sharing,
         };
 
         Ok((obj, mem_reqs))
@@ -251,6 +259,13 @@ impl UnsafeBuffer {
         self.size
     }
 
+    /// Returns true if the buffer was created with `Sharing::Exclusive`, in which case it is
+    /// owned by a single queue family at a time.
+    #[inline]
+    pub fn exclusive_sharing(&self) -> bool {
+        self.exclusive_sharing
+    }
+
     #[inline]
     pub fn usage_transfer_source(&self) -> bool {
         (self.usage & vk::BUFFER_USAGE_TRANSFER_SRC_BIT) != 0
@@ -7,28 +7,40 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
+use fnv::FnvHashMap;
+use fnv::FnvHashSet;
+use smallvec::SmallVec;
 use std::error;
 use std::fmt;
 use std::iter;
 use std::mem;
+use std::ops::Range;
 use std::slice;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 
 use OomError;
+use VulkanObject;
 use buffer::BufferAccess;
 use buffer::TypedBufferAccess;
+use command_buffer::ClearAttachment;
+use command_buffer::ClearRect;
 use command_buffer::CommandBuffer;
 use command_buffer::CommandBufferExecError;
+use command_buffer::DispatchIndirectCommand;
 use command_buffer::DrawIndirectCommand;
 use command_buffer::DrawIndexedIndirectCommand;
 use command_buffer::DynamicState;
+use command_buffer::PipelineBarrier;
 use command_buffer::StateCacher;
 use command_buffer::StateCacherOutcome;
+use command_buffer::barrier::Barrier;
+use command_buffer::barrier::FamilyId;
 use command_buffer::pool::CommandPoolBuilderAlloc;
 use command_buffer::pool::standard::StandardCommandPoolAlloc;
 use command_buffer::pool::standard::StandardCommandPoolBuilder;
+use command_buffer::synced::OwnershipTransfer;
 use command_buffer::synced::SyncCommandBuffer;
 use command_buffer::synced::SyncCommandBufferBuilder;
 use command_buffer::synced::SyncCommandBufferBuilderError;
@@ -39,10 +51,13 @@ use command_buffer::sys::KindSecondaryRenderPass;
 use command_buffer::sys::UnsafeCommandBuffer;
 use command_buffer::sys::UnsafeCommandBufferBuilderBufferImageCopy;
 use command_buffer::sys::UnsafeCommandBufferBuilderColorImageClear;
+use command_buffer::sys::UnsafeCommandBufferBuilderDepthStencilImageClear;
 use command_buffer::sys::UnsafeCommandBufferBuilderImageAspect;
 use command_buffer::sys::UnsafeCommandBufferBuilderImageBlit;
 use command_buffer::sys::UnsafeCommandBufferBuilderImageCopy;
+use command_buffer::sys::UnsafeCommandBufferBuilderImageResolve;
 use command_buffer::validity::*;
+use descriptor::descriptor_set::DescriptorSet;
 use descriptor::descriptor_set::DescriptorSetsCollection;
 use descriptor::pipeline_layout::PipelineLayoutAbstract;
 use device::Device;
@@ -67,14 +82,25 @@ use image::ImageLayout;
 use instance::QueueFamily;
 use pipeline::ComputePipelineAbstract;
 use pipeline::GraphicsPipelineAbstract;
+use pipeline::depth_stencil::DynamicStencilValue;
 use pipeline::input_assembly::Index;
+use pipeline::vertex::InputRate;
+use pipeline::vertex::VertexBuffersCollection;
 use pipeline::vertex::VertexSource;
+use pipeline::viewport::Scissor;
+use pipeline::viewport::Viewport;
 use query::QueryPipelineStatisticFlags;
+use query::QueryPoolAbstract;
+use query::QueryResultElement;
+use query::QueryResultFlags;
+use query::QueryType;
 use sampler::Filter;
 use sync::AccessCheckError;
 use sync::AccessFlagBits;
+use sync::Event;
 use sync::GpuFuture;
 use sync::PipelineStages;
+use vk;
 
 /// Note that command buffers allocated from the default command pool (`Arc<StandardCommandPool>`)
 /// don't implement the `Send` and `Sync` traits. If you use this pool, then the
@@ -84,15 +110,61 @@ pub struct AutoCommandBufferBuilder<P = StandardCommandPoolBuilder> {
     inner: SyncCommandBufferBuilder<P>,
     state_cacher: StateCacher,
 
+    // Id of the queue family the command buffer is created for.
+    queue_family: u32,
+
     // True if the queue family supports graphics operations.
     graphics_allowed: bool,
 
     // True if the queue family supports compute operations.
     compute_allowed: bool,
 
+    // True if the queue family supports timestamp queries.
+    timestamps_allowed: bool,
+
+    // Queries that are currently active, indexed by their type. Only one query of each type can
+    // be active at any given time.
+    active_queries: FnvHashMap<vk::QueryType, ActiveQuery>,
+
+    // Queries that have been begun or written to since they were last reset by this command
+    // buffer. Using them again without resetting them first is an error.
+    used_queries: FnvHashSet<(vk::QueryPool, u32)>,
+
+    // Number of debug markers that were begun in this command buffer and not ended yet.
+    debug_marker_depth: u32,
+
+    // Events that have been set by this command buffer and not reset since, with the stages
+    // that were passed when setting them.
+    set_events: FnvHashMap<vk::Event, PipelineStages>,
+
+    // Graphics pipeline that is currently bound, or `None` if no graphics pipeline was bound or
+    // if the state was invalidated by executing secondary command buffers.
+    graphics_pipeline: Option<Arc<dyn GraphicsPipelineAbstract + Send + Sync>>,
+
+    // Descriptor sets that were bound to the graphics bind point with `bind_descriptor_sets`.
+    // Cleared by the draw commands that take their own descriptor sets.
+    descriptor_sets: Vec<Arc<dyn DescriptorSet + Send + Sync>>,
+
+    // Vertex buffers that were bound with `bind_vertex_buffers` along with the size of their
+    // elements if known, or `None` if the bound vertex buffers are unknown because a draw command
+    // that takes its own vertex buffers was used.
+    vertex_buffers: Option<Vec<(Arc<dyn BufferAccess + Send + Sync>, Option<usize>)>>,
+
+    // Number of indices in the index buffer that was bound with `bind_index_buffer`, or `None`
+    // if no index buffer was bound with it.
+    index_buffer_len: Option<u32>,
+
+    // Parts of the bound state that have already been validated by a previous draw command
+    // that uses the bound state.
+    bound_state_checks: BoundStateChecks,
+
     // If we're inside a render pass, contains the render pass and the subpass index.
     render_pass: Option<(Box<dyn RenderPassAbstract>, u32)>,
 
+    // If we're inside a render pass, contains the width, height and number of layers of the
+    // framebuffer, if known.
+    framebuffer_dimensions: Option<[u32; 3]>,
+
     // True if we are a secondary command buffer.
     secondary_cb: bool,
 
@@ -104,6 +176,39 @@ pub struct AutoCommandBufferBuilder<P = StandardCommandPoolBuilder> {
     flags: Flags,
 }
 
+// A query that was begun but not ended yet.
+struct ActiveQuery {
+    query_pool: vk::QueryPool,
+    query: u32,
+    // Index of the subpass the query was begun in, or `None` if it was begun outside of a render
+    // pass.
+    subpass: Option<u32>,
+}
+
+// Parts of the state bound with the `bind_*` methods that were validated against the bound
+// graphics pipeline. Each part is reset whenever the state it depends on changes, so that
+// consecutive draws don't validate it again.
+struct BoundStateChecks {
+    // True if the dynamic state was checked.
+    dynamic_state: bool,
+    // True if the descriptor sets were checked.
+    descriptor_sets: bool,
+    // Number of vertices and instances available in the bound vertex buffers, if known. Each of
+    // them is `None` if the vertex definition has no binding of that rate.
+    vertex_counts: Option<(Option<u32>, Option<u32>)>,
+}
+
+impl BoundStateChecks {
+    #[inline]
+    fn none() -> BoundStateChecks {
+        BoundStateChecks {
+            dynamic_state: false,
+            descriptor_sets: false,
+            vertex_counts: None,
+        }
+    }
+}
+
 impl AutoCommandBufferBuilder<StandardCommandPoolBuilder> {
     #[inline]
     pub fn new(device: Arc<Device>, queue_family: QueueFamily)
@@ -367,14 +472,15 @@ impl AutoCommandBufferBuilder<StandardCommandPoolBuilder> {
               F: FramebufferAbstract
     {
         unsafe {
-            let (secondary_cb, render_pass) = match kind {
-                Kind::Primary => (false, None),
+            let (secondary_cb, render_pass, framebuffer_dimensions) = match kind {
+                Kind::Primary => (false, None, None),
                 Kind::Secondary { render_pass: Some(ref sec), .. } => {
                     let render_pass = sec.subpass.render_pass().clone();
                     let index = sec.subpass.index();
-                    (true, Some((Box::new(render_pass) as Box<_>, index)))
+                    let dimensions = sec.framebuffer.as_ref().map(|f| f.dimensions());
+                    (true, Some((Box::new(render_pass) as Box<_>, index)), dimensions)
                 },
-                Kind::Secondary { render_pass: None, .. } => (true, None),
+                Kind::Secondary { render_pass: None, .. } => (true, None, None),
             };
 
             let pool = Device::standard_command_pool(&device, queue_family);
@@ -383,13 +489,26 @@ impl AutoCommandBufferBuilder<StandardCommandPoolBuilder> {
 
             let graphics_allowed = queue_family.supports_graphics();
             let compute_allowed = queue_family.supports_compute();
+            let timestamps_allowed = queue_family.timestamp_valid_bits().is_some();
 
             Ok(AutoCommandBufferBuilder {
                    inner: inner?,
                    state_cacher,
+                   queue_family: queue_family.id(),
                    graphics_allowed,
                    compute_allowed,
+                   timestamps_allowed,
+                   active_queries: FnvHashMap::default(),
+                   used_queries: FnvHashSet::default(),
+                   debug_marker_depth: 0,
+                   set_events: FnvHashMap::default(),
+                   graphics_pipeline: None,
+                   descriptor_sets: Vec::new(),
+                   vertex_buffers: Some(Vec::new()),
+                   index_buffer_len: None,
+                   bound_state_checks: BoundStateChecks::none(),
                    render_pass,
+                   framebuffer_dimensions,
                    secondary_cb,
                    subpass_secondary: false,
                    flags,
@@ -448,6 +567,83 @@ impl<P> AutoCommandBufferBuilder<P> {
         Ok(())
     }
 
+    // Forgets the state that was bound with the `bind_*` methods. Called by the commands that
+    // bind their own descriptor sets and buffers, or that invalidate the bound state.
+    #[inline]
+    fn forget_bound_state(&mut self) {
+        self.descriptor_sets.clear();
+        self.vertex_buffers = None;
+        self.index_buffer_len = None;
+        self.bound_state_checks = BoundStateChecks::none();
+    }
+
+    // Checks that the state bound with the `bind_*` methods allows drawing with the bound
+    // graphics pipeline. Returns the number of vertices and instances available in the bound
+    // vertex buffers, or `None` for a rate if the vertex definition has no binding of that rate.
+    //
+    // Only the parts of the state that changed since the previous call are checked again.
+    fn check_bound_state(&mut self) -> Result<(Option<u32>, Option<u32>), DrawBoundError> {
+        let pipeline = match self.graphics_pipeline {
+            Some(ref pipeline) => pipeline.clone(),
+            None => return Err(AutoCommandBufferBuilderContextError::GraphicsPipelineNotBound.into()),
+        };
+
+        self.ensure_inside_render_pass_inline(&*pipeline)?;
+
+        if !self.bound_state_checks.dynamic_state {
+            let dynamic = self.state_cacher
+                .complete_dynamic_state(&*pipeline, &DynamicState::none());
+            check_dynamic_state_validity(&*pipeline, &dynamic)?;
+            check_dynamic_state_set(&*pipeline, &self.state_cacher)?;
+            self.bound_state_checks.dynamic_state = true;
+        }
+
+        if !self.bound_state_checks.descriptor_sets {
+            check_descriptor_sets_validity(&*pipeline, &self.descriptor_sets)?;
+            self.bound_state_checks.descriptor_sets = true;
+        }
+
+        if let Some(counts) = self.bound_state_checks.vertex_counts {
+            return Ok(counts);
+        }
+
+        let vertex_buffers = match self.vertex_buffers {
+            Some(ref vertex_buffers) => vertex_buffers,
+            None => return Err(AutoCommandBufferBuilderContextError::VertexBuffersNotBound.into()),
+        };
+
+        let element_sizes = vertex_buffers.iter().map(|&(_, size)| size).collect::<Vec<_>>();
+        check_bound_vertex_buffers(&*pipeline, &element_sizes)?;
+
+        let vertex_buffers = vertex_buffers.iter().map(|&(ref buffer, _)| buffer.clone()).collect();
+        let (_, vertex_count, instance_count) = pipeline.decode(vertex_buffers);
+        let rates = pipeline.vertex_buffer_input_rates();
+        let has_vertex_rate = rates.iter().any(|&rate| rate == InputRate::Vertex);
+        let has_instance_rate = rates.iter().any(|&rate| rate != InputRate::Vertex);
+        let to_u32 = |count: usize| count.min(u32::max_value() as usize) as u32;
+        let counts = (if has_vertex_rate { Some(to_u32(vertex_count)) } else { None },
+                      if has_instance_rate { Some(to_u32(instance_count)) } else { None });
+        self.bound_state_checks.vertex_counts = Some(counts);
+        Ok(counts)
+    }
+
+    // Returns the queue family the command buffer is created for.
+    #[inline]
+    pub(crate) fn queue_family(&self) -> QueueFamily {
+        self.device().physical_device().queue_family_by_id(self.queue_family).unwrap()
+    }
+
+    // Returns an error if a query that was begun inside the current subpass is still active.
+    #[inline]
+    fn ensure_no_active_query_in_subpass(&self)
+                                         -> Result<(), AutoCommandBufferBuilderContextError> {
+        if self.active_queries.values().any(|q| q.subpass.is_some()) {
+            Err(AutoCommandBufferBuilderContextError::QueryIsActive)
+        } else {
+            Ok(())
+        }
+    }
+
     /// Builds the command buffer.
     #[inline]
     pub fn build(self) -> Result<AutoCommandBuffer<P::Alloc>, BuildError>
@@ -457,6 +653,14 @@ impl<P> AutoCommandBufferBuilder<P> {
             return Err(AutoCommandBufferBuilderContextError::ForbiddenInsideRenderPass.into());
         }
 
+        if !self.active_queries.is_empty() {
+            return Err(AutoCommandBufferBuilderContextError::QueryIsActive.into());
+        }
+
+        if self.debug_marker_depth != 0 {
+            return Err(AutoCommandBufferBuilderContextError::DebugMarkerIsActive.into());
+        }
+
         let submit_state = match self.flags {
             Flags::None => {
                 SubmitState::ExclusiveUse { in_use: AtomicBool::new(false) }
@@ -475,6 +679,75 @@ impl<P> AutoCommandBufferBuilder<P> {
            })
     }
 
+    /// Adds a command that acquires the ownership of a buffer from another queue family.
+    ///
+    /// Shortcut for `pipeline_barrier` with a single `PipelineBarrier::acquire_buffer_ownership`.
+    #[inline]
+    pub fn acquire_buffer_ownership<B>(self, buffer: B, source: QueueFamily)
+                                       -> Result<Self, PipelineBarrierError>
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        self.pipeline_barrier(PipelineBarrier::new().acquire_buffer_ownership(buffer, source))
+    }
+
+    /// Adds a command that acquires the ownership of an image from another queue family.
+    ///
+    /// Shortcut for `pipeline_barrier` with a single `PipelineBarrier::acquire_image_ownership`.
+    #[inline]
+    pub fn acquire_image_ownership<I>(self, image: I, source: QueueFamily)
+                                      -> Result<Self, PipelineBarrierError>
+        where I: ImageAccess + Send + Sync + 'static
+    {
+        self.pipeline_barrier(PipelineBarrier::new().acquire_image_ownership(image, source))
+    }
+
+    /// Adds a command that begins a query.
+    ///
+    /// The query will be active until `end_query` is called for the same query. Only one query
+    /// of each type can be active at the same time. If the query was begun inside of a subpass,
+    /// it must be ended inside of that same subpass.
+    ///
+    /// The query must have been reset with `reset_query_pool` before it can be used again in the
+    /// same command buffer. If `precise` is true, the query must be an occlusion query and the
+    /// `occlusion_query_precise` feature must be enabled.
+    #[inline]
+    pub fn begin_query<Q>(mut self, query_pool: Q, query: u32, precise: bool)
+                          -> Result<Self, BeginQueryError>
+        where Q: QueryPoolAbstract + Send + Sync + 'static
+    {
+        unsafe {
+            check_begin_query(self.device(), &query_pool, query, precise)?;
+
+            let ty = query_pool.inner().ty();
+            let supported = match ty {
+                QueryType::Occlusion => self.graphics_allowed,
+                QueryType::PipelineStatistics(_) => self.graphics_allowed || self.compute_allowed,
+                QueryType::Timestamp => return Err(CheckBeginQueryError::NotPermitted.into()),
+            };
+            if !supported {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            if self.active_queries.contains_key(&ty.into_vulkan()) {
+                return Err(AutoCommandBufferBuilderContextError::QueryIsActive.into());
+            }
+
+            let raw_pool = query_pool.inner().internal_object();
+            if !self.used_queries.insert((raw_pool, query)) {
+                return Err(AutoCommandBufferBuilderContextError::QueryNotReset.into());
+            }
+
+            self.active_queries.insert(ty.into_vulkan(),
+                                       ActiveQuery {
+                                           query_pool: raw_pool,
+                                           query,
+                                           subpass: self.render_pass.as_ref().map(|rp| rp.1),
+                                       });
+            self.inner.begin_query(query_pool, query, precise);
+            Ok(self)
+        }
+    }
+
     /// Adds a command that enters a render pass.
     ///
     /// If `secondary` is true, then you will only be able to add secondary command buffers while
@@ -559,96 +832,400 @@ impl<P> AutoCommandBufferBuilder<P> {
             };
             self.inner
                 .begin_render_pass(framebuffer.clone(), contents, clear_values)?;
+            self.framebuffer_dimensions = Some(framebuffer.dimensions());
             self.render_pass = Some((Box::new(framebuffer) as Box<_>, 0));
             self.subpass_secondary = secondary;
             Ok(self)
         }
     }
 
-    /// Adds a command that copies an image to another.
+    /// Binds the pipeline, the descriptor sets, the push constants and the `vertex_buffer`, then
+    /// draws once.
     ///
-    /// Copy operations have several restrictions:
+    /// All the state is validated again on every call. When doing many draws with the same
+    /// state, prefer binding it with the `bind_*` methods and calling `draw`.
     ///
-    /// - Copy operations are only allowed on queue families that support transfer, graphics, or
-    ///   compute operations.
-    /// - The number of samples in the source and destination images must be equal.
-    /// - The size of the uncompressed element format of the source image must be equal to the
-    ///   compressed element format of the destination.
-    /// - If you copy between depth, stencil or depth-stencil images, the format of both images
-    ///   must match exactly.
-    /// - For two-dimensional images, the Z coordinate must be 0 for the image offsets and 1 for
-    ///   the extent. Same for the Y coordinate for one-dimensional images.
-    /// - For non-array images, the base array layer must be 0 and the number of layers must be 1.
+    /// To use only some data in the buffer, wrap it in a `vulkano::buffer::BufferSlice`.
+    #[inline]
+    pub fn bind_and_draw<V, Gp, S, Pc>(mut self, pipeline: Gp, dynamic: &DynamicState,
+                                       vertex_buffer: V, sets: S, constants: Pc)
+                                       -> Result<Self, DrawError>
+        where Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
+              S: DescriptorSetsCollection
+    {
+        unsafe {
+            // TODO: must check that pipeline is compatible with render pass
+
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            let dynamic = self.state_cacher.complete_dynamic_state(&pipeline, dynamic);
+            check_dynamic_state_validity(&pipeline, &dynamic)?;
+            check_dynamic_state_set(&pipeline, &self.state_cacher)?;
+            check_push_constants_validity(&pipeline, &constants)?;
+            check_descriptor_sets_validity(&pipeline, &sets)?;
+            check_no_dynamic_offsets(&sets)?;
+            let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+                self.graphics_pipeline = Some(Arc::new(pipeline.clone()));
+            }
+
+            let dynamic = self.state_cacher.dynamic_state(&dynamic);
+
+            push_constants(&mut self.inner, pipeline.clone(), constants);
+            set_state(&mut self.inner, &dynamic);
+            descriptor_sets(&mut self.inner,
+                            &mut self.state_cacher,
+                            true,
+                            pipeline.clone(),
+                            sets)?;
+            vertex_buffers(&mut self.inner,
+                           &mut self.state_cacher,
+                           vb_infos.vertex_buffers)?;
+
+            self.forget_bound_state();
+            debug_assert!(self.graphics_allowed);
+
+            self.inner.draw(vb_infos.vertex_count as u32,
+                            vb_infos.instance_count as u32,
+                            0,
+                            0);
+            Ok(self)
+        }
+    }
+
+    /// Same as `bind_and_draw`, but also binds the `index_buffer` and draws with it.
     ///
-    /// If `layer_count` is greater than 1, the copy will happen between each individual layer as
-    /// if they were separate images.
+    /// To use only some data in a buffer, wrap it in a `vulkano::buffer::BufferSlice`.
+    #[inline]
+    pub fn bind_and_draw_indexed<V, Gp, S, Pc, Ib, I>(mut self, pipeline: Gp,
+                                                      dynamic: &DynamicState, vertex_buffer: V,
+                                                      index_buffer: Ib, sets: S, constants: Pc)
+                                                      -> Result<Self, DrawIndexedError>
+        where Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
+              S: DescriptorSetsCollection,
+              Ib: BufferAccess + TypedBufferAccess<Content = [I]> + Send + Sync + 'static,
+              I: Index + 'static
+    {
+        unsafe {
+            // TODO: must check that pipeline is compatible with render pass
+
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            let ib_infos = check_index_buffer(self.device(), &index_buffer)?;
+            let dynamic = self.state_cacher.complete_dynamic_state(&pipeline, dynamic);
+            check_dynamic_state_validity(&pipeline, &dynamic)?;
+            check_dynamic_state_set(&pipeline, &self.state_cacher)?;
+            check_push_constants_validity(&pipeline, &constants)?;
+            check_descriptor_sets_validity(&pipeline, &sets)?;
+            check_no_dynamic_offsets(&sets)?;
+            let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+                self.graphics_pipeline = Some(Arc::new(pipeline.clone()));
+            }
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_index_buffer(&index_buffer, I::ty())
+            {
+                self.inner.bind_index_buffer(index_buffer, I::ty())?;
+            }
+
+            let dynamic = self.state_cacher.dynamic_state(&dynamic);
+
+            push_constants(&mut self.inner, pipeline.clone(), constants);
+            set_state(&mut self.inner, &dynamic);
+            descriptor_sets(&mut self.inner,
+                            &mut self.state_cacher,
+                            true,
+                            pipeline.clone(),
+                            sets)?;
+            vertex_buffers(&mut self.inner,
+                           &mut self.state_cacher,
+                           vb_infos.vertex_buffers)?;
+            // TODO: how to handle an index out of range of the vertex buffers?
+
+            self.forget_bound_state();
+            debug_assert!(self.graphics_allowed);
+
+            self.inner
+                .draw_indexed(ib_infos.num_indices as u32,
+                              vb_infos.instance_count as u32,
+                              0,
+                              0,
+                              0);
+            Ok(self)
+        }
+    }
+
+    /// Binds descriptor sets to the graphics bind point, for use by `draw` and `draw_indexed`.
+    ///
+    /// The sets are bound starting from set 0, and are checked against the layout of the
+    /// currently bound graphics pipeline. `dynamic_offsets` must contain one offset for each
+    /// dynamic buffer descriptor of the sets, in order.
     ///
     /// # Panic
     ///
-    /// - Panics if the source or the destination was not created with `device`.
+    /// - Panics if one of the buffers of the sets was not created with the same device as this
+    ///   command buffer.
     ///
-    pub fn copy_image<S, D>(mut self, source: S, source_offset: [i32; 3],
-                            source_base_array_layer: u32, source_mip_level: u32,
-                            destination: D, destination_offset: [i32; 3],
-                            destination_base_array_layer: u32, destination_mip_level: u32,
-                            extent: [u32; 3], layer_count: u32)
-                            -> Result<Self, CopyImageError>
-        where S: ImageAccess + Send + Sync + 'static,
-              D: ImageAccess + Send + Sync + 'static
+    #[inline]
+    pub fn bind_descriptor_sets<S, I>(mut self, sets: S, dynamic_offsets: I)
+                                      -> Result<Self, BindDescriptorSetsError>
+        where S: DescriptorSetsCollection,
+              I: IntoIterator<Item = u32>
     {
         unsafe {
-            self.ensure_outside_render_pass()?;
-
-            check_copy_image(self.device(),
-                             &source,
-                             source_offset,
-                             source_base_array_layer,
-                             source_mip_level,
-                             &destination,
-                             destination_offset,
-                             destination_base_array_layer,
-                             destination_mip_level,
-                             extent,
-                             layer_count)?;
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
 
-            let copy = UnsafeCommandBufferBuilderImageCopy {
-                // TODO: Allowing choosing a subset of the image aspects, but note that if color
-                // is included, neither depth nor stencil may.
-                aspect: UnsafeCommandBufferBuilderImageAspect {
-                    color: source.has_color(),
-                    depth: !source.has_color() &&
-                           source.has_depth() && destination.has_depth(),
-                    stencil: !source.has_color() &&
-                             source.has_stencil() && destination.has_stencil(),
+            let pipeline = match self.graphics_pipeline {
+                Some(ref pipeline) => pipeline.clone(),
+                None => {
+                    return Err(AutoCommandBufferBuilderContextError::GraphicsPipelineNotBound
+                                   .into());
                 },
-                source_mip_level,
-                destination_mip_level,
-                source_base_array_layer,
-                destination_base_array_layer,
-                layer_count,
-                source_offset,
-                destination_offset,
-                extent,
             };
 
-            // TODO: Allow choosing layouts, but note that only Transfer*Optimal and General are
-            // valid.
-            self.inner
-                .copy_image(source, ImageLayout::TransferSrcOptimal,
-                            destination, ImageLayout::TransferDstOptimal,
-                            iter::once(copy))?;
+            let sets = sets.into_vec()
+                .into_iter()
+                .map(Arc::from)
+                .collect::<Vec<Arc<dyn DescriptorSet + Send + Sync>>>();
+            let dynamic_offsets = dynamic_offsets.into_iter().collect::<Vec<_>>();
+            check_descriptor_sets_validity(&*pipeline, &sets)?;
+            check_dynamic_offsets(self.device(), &sets, &dynamic_offsets)?;
+
+            let first_binding = {
+                let mut compare = self.state_cacher.bind_descriptor_sets(true);
+                for set in sets.iter() {
+                    compare.add(set);
+                }
+                compare.compare()
+            };
+
+            // The dynamic offsets can change even if the sets don't, so they are always bound
+            // again in that situation.
+            let first_binding = if dynamic_offsets.is_empty() {
+                first_binding
+            } else {
+                Some(0)
+            };
+
+            if let Some(first_binding) = first_binding {
+                let mut sets_binder = self.inner.bind_descriptor_sets();
+                for set in sets.iter().skip(first_binding as usize) {
+                    sets_binder.add(set.clone());
+                }
+                sets_binder
+                    .submit(true, pipeline, first_binding, dynamic_offsets.into_iter())?;
+            }
+
+            self.descriptor_sets = sets;
+            self.bound_state_checks.descriptor_sets = true;
             Ok(self)
         }
     }
 
-    /// Adds a command that blits an image to another.
+    /// Binds an index buffer, for use by `draw_indexed`.
     ///
-    /// A *blit* is similar to an image copy operation, except that the portion of the image that
-    /// is transferred can be resized. You choose an area of the source and an area of the
-    /// destination, and the implementation will resize the area of the source so that it matches
-    /// the size of the area of the destination before writing it.
+    /// To use only some data in the buffer, wrap it in a `vulkano::buffer::BufferSlice`.
     ///
-    /// Blit operations have several restrictions:
+    /// # Panic
+    ///
+    /// - Panics if the buffer was not created with the same device as this command buffer.
+    ///
+    #[inline]
+    pub fn bind_index_buffer<Ib, I>(mut self, index_buffer: Ib)
+                                    -> Result<Self, BindIndexBufferError>
+        where Ib: BufferAccess + TypedBufferAccess<Content = [I]> + Send + Sync + 'static,
+              I: Index + 'static
+    {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            let ib_infos = check_index_buffer(self.device(), &index_buffer)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_index_buffer(&index_buffer, I::ty())
+            {
+                self.inner.bind_index_buffer(index_buffer, I::ty())?;
+            }
+
+            self.index_buffer_len = Some(ib_infos.num_indices as u32);
+            Ok(self)
+        }
+    }
+
+    /// Binds a graphics pipeline, for use by `draw` and `draw_indexed`.
+    ///
+    /// The descriptor sets, vertex buffers and dynamic state that are already bound stay bound,
+    /// and are checked against the new pipeline by the next draw.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the pipeline was not created with the same device as this command buffer.
+    ///
+    #[inline]
+    pub fn bind_pipeline_graphics<Gp>(mut self, pipeline: Gp)
+                                      -> Result<Self, AutoCommandBufferBuilderContextError>
+        where Gp: GraphicsPipelineAbstract + Send + Sync + 'static
+    {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily);
+            }
+
+            assert_eq!(pipeline.device().internal_object(),
+                       self.device().internal_object());
+
+            let pipeline = Arc::new(pipeline) as Arc<dyn GraphicsPipelineAbstract + Send + Sync>;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+                self.graphics_pipeline = Some(pipeline);
+                self.bound_state_checks = BoundStateChecks::none();
+            }
+
+            Ok(self)
+        }
+    }
+
+    /// Binds vertex buffers starting from binding 0, for use by `draw` and `draw_indexed`.
+    ///
+    /// The buffers can be a typed buffer, a tuple of typed buffers, or a `Vec` of type-erased
+    /// buffers. When drawing, their number must match the number of vertex buffer bindings of
+    /// the bound graphics pipeline, and the size of the elements of the typed buffers must match
+    /// the stride of their binding.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if one of the buffers was not created with the same device as this command
+    ///   buffer.
+    ///
+    #[inline]
+    pub fn bind_vertex_buffers<V>(mut self, vertex_buffers: V)
+                                  -> Result<Self, BindVertexBuffersError>
+        where V: VertexBuffersCollection
+    {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            let vertex_buffers = vertex_buffers.into_vec();
+            {
+                let buffers = vertex_buffers
+                    .iter()
+                    .map(|&(ref buffer, _)| buffer)
+                    .collect::<Vec<_>>();
+                check_bind_vertex_buffers(self.device(), &buffers)?;
+            }
+
+            let boxed = vertex_buffers
+                .iter()
+                .map(|&(ref vb, _)| Box::new(vb.clone()) as Box<_>)
+                .collect();
+            self::vertex_buffers(&mut self.inner, &mut self.state_cacher, boxed)?;
+
+            self.vertex_buffers = Some(vertex_buffers);
+            self.bound_state_checks.vertex_counts = None;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that copies an image to another.
+    ///
+    /// Copy operations have several restrictions:
+    ///
+    /// - Copy operations are only allowed on queue families that support transfer, graphics, or
+    ///   compute operations.
+    /// - The number of samples in the source and destination images must be equal.
+    /// - The size of the uncompressed element format of the source image must be equal to the
+    ///   compressed element format of the destination.
+    /// - If you copy between depth, stencil or depth-stencil images, the format of both images
+    ///   must match exactly.
+    /// - For two-dimensional images, the Z coordinate must be 0 for the image offsets and 1 for
+    ///   the extent. Same for the Y coordinate for one-dimensional images.
+    /// - For non-array images, the base array layer must be 0 and the number of layers must be 1.
+    ///
+    /// If `layer_count` is greater than 1, the copy will happen between each individual layer as
+    /// if they were separate images.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the source or the destination was not created with `device`.
+    ///
+    pub fn copy_image<S, D>(mut self, source: S, source_offset: [i32; 3],
+                            source_base_array_layer: u32, source_mip_level: u32,
+                            destination: D, destination_offset: [i32; 3],
+                            destination_base_array_layer: u32, destination_mip_level: u32,
+                            extent: [u32; 3], layer_count: u32)
+                            -> Result<Self, CopyImageError>
+        where S: ImageAccess + Send + Sync + 'static,
+              D: ImageAccess + Send + Sync + 'static
+    {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+
+            check_copy_image(self.device(),
+                             &source,
+                             source_offset,
+                             source_base_array_layer,
+                             source_mip_level,
+                             &destination,
+                             destination_offset,
+                             destination_base_array_layer,
+                             destination_mip_level,
+                             extent,
+                             layer_count)?;
+
+            let copy = UnsafeCommandBufferBuilderImageCopy {
+                // TODO: Allowing choosing a subset of the image aspects, but note that if color
+                // is included, neither depth nor stencil may.
+                aspect: UnsafeCommandBufferBuilderImageAspect {
+                    color: source.has_color(),
+                    depth: !source.has_color() &&
+                           source.has_depth() && destination.has_depth(),
+                    stencil: !source.has_color() &&
+                             source.has_stencil() && destination.has_stencil(),
+                },
+                source_mip_level,
+                destination_mip_level,
+                source_base_array_layer,
+                destination_base_array_layer,
+                layer_count,
+                source_offset,
+                destination_offset,
+                extent,
+            };
+
+            // TODO: Allow choosing layouts, but note that only Transfer*Optimal and General are
+            // valid.
+            self.inner
+                .copy_image(source, ImageLayout::TransferSrcOptimal,
+                            destination, ImageLayout::TransferDstOptimal,
+                            iter::once(copy))?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that blits an image to another.
+    ///
+    /// A *blit* is similar to an image copy operation, except that the portion of the image that
+    /// is transferred can be resized. You choose an area of the source and an area of the
+    /// destination, and the implementation will resize the area of the source so that it matches
+    /// the size of the area of the destination before writing it.
+    ///
+    /// Blit operations have several restrictions:
     ///
     /// - Blit operations are only allowed on queue families that support graphics operations.
     /// - The format of the source and destination images must support blit operations, which
@@ -735,6 +1312,61 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
     }
 
+    /// Adds a command that clears regions of attachments of the current subpass.
+    ///
+    /// Contrary to the other clear commands, this one must be called inside of a subpass that
+    /// allows inline commands. Each attachment in `attachments` is cleared in all the rects of
+    /// `rects`, which must be within the dimensions and layers of the framebuffer. In a secondary
+    /// command buffer whose framebuffer is unknown, the rects are checked against the maximum
+    /// framebuffer dimensions of the device instead.
+    #[inline]
+    pub fn clear_attachments<A, R>(mut self, attachments: A, rects: R)
+                                   -> Result<Self, ClearAttachmentsError>
+        where A: IntoIterator<Item = ClearAttachment>,
+              R: IntoIterator<Item = ClearRect>
+    {
+        unsafe {
+            let attachments: SmallVec<[_; 8]> = attachments.into_iter().collect();
+            let rects: SmallVec<[_; 4]> = rects.into_iter().collect();
+
+            match self.render_pass {
+                Some((ref rp, index)) => {
+                    if self.subpass_secondary {
+                        return Err(AutoCommandBufferBuilderContextError::WrongSubpassType.into());
+                    }
+
+                    // A secondary command buffer doesn't always know its framebuffer, in which
+                    // case the rects can only be checked against the limits of the device.
+                    let framebuffer_dimensions = match self.framebuffer_dimensions {
+                        Some(dimensions) => dimensions,
+                        None => {
+                            let limits = self.device().physical_device().limits();
+                            [
+                                limits.max_framebuffer_width(),
+                                limits.max_framebuffer_height(),
+                                limits.max_framebuffer_layers(),
+                            ]
+                        },
+                    };
+
+                    check_clear_attachments(&**rp,
+                                            index,
+                                            framebuffer_dimensions,
+                                            &attachments,
+                                            &rects)?;
+                },
+                None => {
+                    return Err(AutoCommandBufferBuilderContextError::ForbiddenOutsideRenderPass
+                                   .into());
+                },
+            }
+
+            self.inner
+                .clear_attachments(attachments.into_iter(), rects.into_iter());
+            Ok(self)
+        }
+    }
+
     /// Adds a command that clears all the layers and mipmap levels of a color image with a
     /// specific value.
     ///
@@ -800,6 +1432,89 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
     }
 
+    /// Adds a command that clears all the layers and mipmap levels of a depth, stencil or
+    /// depth-stencil image with a specific value.
+    ///
+    /// The aspects of the image that are cleared depend on the variant of `value`: `Depth` only
+    /// clears the depth, `Stencil` only clears the stencil, and `DepthStencil` clears both.
+    #[inline]
+    pub fn clear_depth_stencil_image<I>(self, image: I, value: ClearValue)
+                                        -> Result<Self, ClearDepthStencilImageError>
+        where I: ImageAccess + Send + Sync + 'static
+    {
+        let layers = image.dimensions().array_layers();
+        let levels = image.mipmap_levels();
+
+        self.clear_depth_stencil_image_dimensions(image, 0, layers, 0, levels, value)
+    }
+
+    /// Adds a command that clears a depth, stencil or depth-stencil image with a specific value.
+    ///
+    /// See `clear_depth_stencil_image` for the meaning of `value`.
+    pub fn clear_depth_stencil_image_dimensions<I>(mut self, image: I, first_layer: u32,
+                                                   num_layers: u32, first_mipmap: u32,
+                                                   num_mipmaps: u32, value: ClearValue)
+                                                   -> Result<Self, ClearDepthStencilImageError>
+        where I: ImageAccess + Send + Sync + 'static
+    {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.ensure_outside_render_pass()?;
+            check_clear_depth_stencil_image(self.device(),
+                                            &image,
+                                            first_layer,
+                                            num_layers,
+                                            first_mipmap,
+                                            num_mipmaps,
+                                            value)?;
+
+            let (depth, stencil, aspect) = match value {
+                ClearValue::Depth(depth) => {
+                    (depth, 0, UnsafeCommandBufferBuilderImageAspect {
+                        color: false,
+                        depth: true,
+                        stencil: false,
+                    })
+                },
+                ClearValue::Stencil(stencil) => {
+                    (0.0, stencil, UnsafeCommandBufferBuilderImageAspect {
+                        color: false,
+                        depth: false,
+                        stencil: true,
+                    })
+                },
+                ClearValue::DepthStencil((depth, stencil)) => {
+                    (depth, stencil, UnsafeCommandBufferBuilderImageAspect {
+                        color: false,
+                        depth: true,
+                        stencil: true,
+                    })
+                },
+                _ => unreachable!(),
+            };
+
+            let region = UnsafeCommandBufferBuilderDepthStencilImageClear {
+                aspect,
+                base_mip_level: first_mipmap,
+                level_count: num_mipmaps,
+                base_array_layer: first_layer,
+                layer_count: num_layers,
+            };
+
+            // TODO: let choose layout
+            self.inner
+                .clear_depth_stencil_image(image,
+                                           ImageLayout::TransferDstOptimal,
+                                           depth,
+                                           stencil,
+                                           iter::once(region))?;
+            Ok(self)
+        }
+    }
+
     /// Adds a command that copies from a buffer to another.
     ///
     /// This command will copy from the source to the destination. If their size is not equal, then
@@ -944,6 +1659,90 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
     }
 
+    /// Adds a command that copies the results of a range of queries to a buffer.
+    ///
+    /// The results of each query are written one after the other, using either 32 or 64 bits
+    /// per value depending on the content of the buffer. If `flags.with_availability` is true,
+    /// an additional value is written after the results of each query.
+    #[inline]
+    pub fn copy_query_pool_results<Q, D, T>(mut self, query_pool: Q, queries: Range<u32>,
+                                            destination: D, flags: QueryResultFlags)
+                                            -> Result<Self, CopyQueryPoolResultsError>
+        where Q: QueryPoolAbstract + Send + Sync + 'static,
+              D: TypedBufferAccess<Content = [T]> + Send + Sync + 'static,
+              T: QueryResultElement
+    {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            let stride = check_copy_query_pool_results(self.device(),
+                                                       &query_pool,
+                                                       queries.clone(),
+                                                       &destination,
+                                                       flags)?;
+
+            if !self.graphics_allowed && !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.inner
+                .copy_query_pool_results(query_pool, queries, destination, stride, flags)?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that opens a debug marker region, for debugging tools such as RenderDoc.
+    ///
+    /// The region must be closed with `debug_marker_end`. Requires the `ext_debug_utils`
+    /// instance extension to be enabled.
+    #[inline]
+    pub fn debug_marker_begin(mut self, name: &str, color: [f32; 4])
+                              -> Result<Self, DebugMarkerError> {
+        unsafe {
+            let name = check_debug_marker(self.device(), name)?;
+
+            if !self.graphics_allowed && !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.inner.debug_marker_begin(name, color);
+            self.debug_marker_depth += 1;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that closes the debug marker region that was last opened with
+    /// `debug_marker_begin` in this command buffer.
+    #[inline]
+    pub fn debug_marker_end(mut self) -> Result<Self, DebugMarkerError> {
+        unsafe {
+            if self.debug_marker_depth == 0 {
+                return Err(AutoCommandBufferBuilderContextError::DebugMarkerNotActive.into());
+            }
+
+            self.inner.debug_marker_end();
+            self.debug_marker_depth -= 1;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that inserts a single debug marker, for debugging tools such as RenderDoc.
+    ///
+    /// Requires the `ext_debug_utils` instance extension to be enabled.
+    #[inline]
+    pub fn debug_marker_insert(mut self, name: &str, color: [f32; 4])
+                               -> Result<Self, DebugMarkerError> {
+        unsafe {
+            let name = check_debug_marker(self.device(), name)?;
+
+            if !self.graphics_allowed && !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.inner.debug_marker_insert(name, color);
+            Ok(self)
+        }
+    }
+
     #[inline]
     pub fn dispatch<Cp, S, Pc>(mut self, dimensions: [u32; 3], pipeline: Cp, sets: S, constants: Pc)
                                -> Result<Self, DispatchError>
@@ -958,6 +1757,7 @@ impl<P> AutoCommandBufferBuilder<P> {
             self.ensure_outside_render_pass()?;
             check_push_constants_validity(&pipeline, &constants)?;
             check_descriptor_sets_validity(&pipeline, &sets)?;
+            check_no_dynamic_offsets(&sets)?;
             check_dispatch(pipeline.device(), dimensions)?;
 
             if let StateCacherOutcome::NeedChange =
@@ -978,89 +1778,164 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
     }
 
-    /// Draw once, using the `vertex_buffer`.
+    /// Adds a command that executes a compute shader, reading the number of work groups from
+    /// the first `vulkano::command_buffer::DispatchIndirectCommand` struct in `indirect_buffer`.
     ///
-    /// To use only some data in the buffer, wrap it in a `vulkano::buffer::BufferSlice`.
+    /// The dispatch dimensions are only known when the command buffer executes, so they are not
+    /// checked against the limits of the device.
+    ///
+    /// To use only some data in a buffer, wrap it in a `vulkano::buffer::BufferSlice`.
     #[inline]
-    pub fn draw<V, Gp, S, Pc>(mut self, pipeline: Gp, dynamic: &DynamicState, vertex_buffer: V, sets: S,
-                              constants: Pc)
-                              -> Result<Self, DrawError>
-        where Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
-              S: DescriptorSetsCollection
+    pub fn dispatch_indirect<Cp, S, Pc, Ib>(mut self, indirect_buffer: Ib, pipeline: Cp, sets: S,
+                                            constants: Pc)
+                                            -> Result<Self, DispatchIndirectError>
+        where Cp: ComputePipelineAbstract + Send + Sync + 'static + Clone, // TODO: meh for Clone
+              S: DescriptorSetsCollection,
+              Ib: BufferAccess
+                      + TypedBufferAccess<Content = [DispatchIndirectCommand]>
+                      + Send
+                      + Sync
+                      + 'static
     {
         unsafe {
-            // TODO: must check that pipeline is compatible with render pass
+            if !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
 
-            self.ensure_inside_render_pass_inline(&pipeline)?;
-            check_dynamic_state_validity(&pipeline, dynamic)?;
+            self.ensure_outside_render_pass()?;
             check_push_constants_validity(&pipeline, &constants)?;
             check_descriptor_sets_validity(&pipeline, &sets)?;
-            let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
+            check_no_dynamic_offsets(&sets)?;
+            check_indirect_buffer(self.device(), &indirect_buffer)?;
 
             if let StateCacherOutcome::NeedChange =
-                self.state_cacher.bind_graphics_pipeline(&pipeline)
+                self.state_cacher.bind_compute_pipeline(&pipeline)
             {
-                self.inner.bind_pipeline_graphics(pipeline.clone());
+                self.inner.bind_pipeline_compute(pipeline.clone());
             }
 
-            let dynamic = self.state_cacher.dynamic_state(dynamic);
-
             push_constants(&mut self.inner, pipeline.clone(), constants);
-            set_state(&mut self.inner, &dynamic);
             descriptor_sets(&mut self.inner,
                             &mut self.state_cacher,
-                            true,
+                            false,
                             pipeline.clone(),
                             sets)?;
-            vertex_buffers(&mut self.inner,
-                           &mut self.state_cacher,
-                           vb_infos.vertex_buffers)?;
+
+            self.inner.dispatch_indirect(indirect_buffer)?;
+            Ok(self)
+        }
+    }
+
+    /// Draws using the graphics pipeline, descriptor sets, vertex buffers and push constants
+    /// that were bound with `bind_pipeline_graphics`, `bind_descriptor_sets`,
+    /// `bind_vertex_buffers` and `push_constants`, and the dynamic state that was set with the
+    /// `set_*` methods.
+    ///
+    /// Only the parts of the bound state that changed since the previous draw are checked
+    /// again, which makes this cheaper than `bind_and_draw` when doing many draws with the same
+    /// state.
+    ///
+    /// The vertices and instances are taken from the vertex and instance buffers of the vertex
+    /// definition. If the vertex definition has no binding of one of these rates, the
+    /// corresponding range isn't limited, for example to draw instances that are only told
+    /// apart by `gl_InstanceIndex`.
+    #[inline]
+    pub fn draw(mut self, vertex_count: u32, instance_count: u32, first_vertex: u32,
+                first_instance: u32)
+                -> Result<Self, DrawBoundError> {
+        unsafe {
+            let (num_vertices, num_instances) = self.check_bound_state()?;
+            check_draw(num_vertices,
+                       num_instances,
+                       vertex_count,
+                       instance_count,
+                       first_vertex,
+                       first_instance)?;
 
             debug_assert!(self.graphics_allowed);
 
-            self.inner.draw(vb_infos.vertex_count as u32,
-                            vb_infos.instance_count as u32,
-                            0,
-                            0);
+            self.inner
+                .draw(vertex_count, instance_count, first_vertex, first_instance);
+            Ok(self)
+        }
+    }
+
+    /// Same as `draw`, but uses the index buffer that was bound with `bind_index_buffer`.
+    ///
+    /// > **Note**: The indices are only read when the command buffer executes, so they can't be
+    /// > checked against the number of vertices in the bound vertex buffers. Reading a vertex
+    /// > out of range returns undefined values, unless the `robust_buffer_access` feature is
+    /// > enabled in which case it returns zeroes.
+    #[inline]
+    pub fn draw_indexed(mut self, index_count: u32, instance_count: u32, first_index: u32,
+                        vertex_offset: i32, first_instance: u32)
+                        -> Result<Self, DrawBoundError> {
+        unsafe {
+            let num_indices = match self.index_buffer_len {
+                Some(len) => len,
+                None => {
+                    return Err(AutoCommandBufferBuilderContextError::IndexBufferNotBound.into());
+                },
+            };
+
+            let (_, num_instances) = self.check_bound_state()?;
+            check_draw_indexed(num_indices,
+                               num_instances,
+                               index_count,
+                               instance_count,
+                               first_index,
+                               first_instance)?;
+
+            debug_assert!(self.graphics_allowed);
+
+            self.inner.draw_indexed(index_count,
+                                    instance_count,
+                                    first_index,
+                                    vertex_offset,
+                                    first_instance);
             Ok(self)
         }
     }
 
-    /// Draw once, using the `vertex_buffer` and the `index_buffer`.
+    /// Performs multiple draws, one draw for each `vulkano::command_buffer::DrawIndirectCommand` struct in `indirect_buffer`.
+    /// The `vertex_buffer` is used by all draws.
     ///
     /// To use only some data in a buffer, wrap it in a `vulkano::buffer::BufferSlice`.
     #[inline]
-    pub fn draw_indexed<V, Gp, S, Pc, Ib, I>(mut self, pipeline: Gp, dynamic: &DynamicState,
-                                             vertex_buffer: V, index_buffer: Ib, sets: S, constants: Pc)
-                                             -> Result<Self, DrawIndexedError>
+    pub fn draw_indirect<V, Gp, S, Pc, Ib>(mut self, pipeline: Gp, dynamic: &DynamicState,
+                                           vertex_buffer: V, indirect_buffer: Ib, sets: S, constants: Pc)
+                                           -> Result<Self, DrawIndirectError>
         where Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
               S: DescriptorSetsCollection,
-              Ib: BufferAccess + TypedBufferAccess<Content = [I]> + Send + Sync + 'static,
-              I: Index + 'static
+              Ib: BufferAccess
+                      + TypedBufferAccess<Content = [DrawIndirectCommand]>
+                      + Send
+                      + Sync
+                      + 'static
     {
         unsafe {
             // TODO: must check that pipeline is compatible with render pass
 
             self.ensure_inside_render_pass_inline(&pipeline)?;
-            let ib_infos = check_index_buffer(self.device(), &index_buffer)?;
-            check_dynamic_state_validity(&pipeline, dynamic)?;
+            let dynamic = self.state_cacher.complete_dynamic_state(&pipeline, dynamic);
+            check_dynamic_state_validity(&pipeline, &dynamic)?;
+            check_dynamic_state_set(&pipeline, &self.state_cacher)?;
             check_push_constants_validity(&pipeline, &constants)?;
             check_descriptor_sets_validity(&pipeline, &sets)?;
+            check_no_dynamic_offsets(&sets)?;
             let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
+            check_indirect_buffer(self.device(), &indirect_buffer)?;
+
+            let draw_count = indirect_buffer.len() as u32;
 
             if let StateCacherOutcome::NeedChange =
                 self.state_cacher.bind_graphics_pipeline(&pipeline)
             {
                 self.inner.bind_pipeline_graphics(pipeline.clone());
+                self.graphics_pipeline = Some(Arc::new(pipeline.clone()));
             }
 
-            if let StateCacherOutcome::NeedChange =
-                self.state_cacher.bind_index_buffer(&index_buffer, I::ty())
-            {
-                self.inner.bind_index_buffer(index_buffer, I::ty())?;
-            }
-
-            let dynamic = self.state_cacher.dynamic_state(dynamic);
+            let dynamic = self.state_cacher.dynamic_state(&dynamic);
 
             push_constants(&mut self.inner, pipeline.clone(), constants);
             set_state(&mut self.inner, &dynamic);
@@ -1072,54 +1947,65 @@ impl<P> AutoCommandBufferBuilder<P> {
             vertex_buffers(&mut self.inner,
                            &mut self.state_cacher,
                            vb_infos.vertex_buffers)?;
-            // TODO: how to handle an index out of range of the vertex buffers?
 
+            self.forget_bound_state();
             debug_assert!(self.graphics_allowed);
 
             self.inner
-                .draw_indexed(ib_infos.num_indices as u32,
-                              vb_infos.instance_count as u32,
-                              0,
-                              0,
-                              0);
+                .draw_indirect(indirect_buffer,
+                               draw_count,
+                               mem::size_of::<DrawIndirectCommand>() as u32)?;
             Ok(self)
         }
     }
 
-    /// Performs multiple draws, one draw for each `vulkano::command_buffer::DrawIndirectCommand` struct in `indirect_buffer`.
-    /// The `vertex_buffer` is used by all draws.
+    /// Performs multiple draws, reading the number of draws from `count_buffer` when the command
+    /// buffer executes. Each draw uses one `vulkano::command_buffer::DrawIndirectCommand` struct
+    /// of `indirect_buffer`, and the `vertex_buffer` is used by all draws.
+    ///
+    /// The number of draws is the minimum between the value of `count_buffer` and the number of
+    /// commands in `indirect_buffer`. The `khr_draw_indirect_count` extension must be enabled,
+    /// and the `multi_draw_indirect` feature must be enabled if `indirect_buffer` contains more
+    /// than one command.
     ///
     /// To use only some data in a buffer, wrap it in a `vulkano::buffer::BufferSlice`.
     #[inline]
-    pub fn draw_indirect<V, Gp, S, Pc, Ib>(mut self, pipeline: Gp, dynamic: &DynamicState,
-                                           vertex_buffer: V, indirect_buffer: Ib, sets: S, constants: Pc)
-                                           -> Result<Self, DrawIndirectError>
+    pub fn draw_indirect_count<V, Gp, S, Pc, Ib, Cb>(mut self, pipeline: Gp, dynamic: &DynamicState,
+                                                     vertex_buffer: V, indirect_buffer: Ib,
+                                                     count_buffer: Cb, sets: S, constants: Pc)
+                                                     -> Result<Self, DrawIndirectCountError>
         where Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
               S: DescriptorSetsCollection,
               Ib: BufferAccess
                       + TypedBufferAccess<Content = [DrawIndirectCommand]>
                       + Send
                       + Sync
-                      + 'static
+                      + 'static,
+              Cb: BufferAccess + TypedBufferAccess<Content = u32> + Send + Sync + 'static
     {
         unsafe {
-            // TODO: must check that pipeline is compatible with render pass
-
             self.ensure_inside_render_pass_inline(&pipeline)?;
-            check_dynamic_state_validity(&pipeline, dynamic)?;
+            let dynamic = self.state_cacher.complete_dynamic_state(&pipeline, dynamic);
+            check_dynamic_state_validity(&pipeline, &dynamic)?;
+            check_dynamic_state_set(&pipeline, &self.state_cacher)?;
             check_push_constants_validity(&pipeline, &constants)?;
             check_descriptor_sets_validity(&pipeline, &sets)?;
+            check_no_dynamic_offsets(&sets)?;
             let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
+            check_indirect_buffer(self.device(), &indirect_buffer)?;
+            check_indirect_buffer(self.device(), &count_buffer)?;
 
-            let draw_count = indirect_buffer.len() as u32;
+            let max_draw_count = indirect_buffer.len() as u32;
+            check_draw_indirect_count(self.device(), max_draw_count)?;
 
             if let StateCacherOutcome::NeedChange =
                 self.state_cacher.bind_graphics_pipeline(&pipeline)
             {
                 self.inner.bind_pipeline_graphics(pipeline.clone());
+                self.graphics_pipeline = Some(Arc::new(pipeline.clone()));
             }
 
-            let dynamic = self.state_cacher.dynamic_state(dynamic);
+            let dynamic = self.state_cacher.dynamic_state(&dynamic);
 
             push_constants(&mut self.inner, pipeline.clone(), constants);
             set_state(&mut self.inner, &dynamic);
@@ -1132,12 +2018,14 @@ impl<P> AutoCommandBufferBuilder<P> {
                            &mut self.state_cacher,
                            vb_infos.vertex_buffers)?;
 
+            self.forget_bound_state();
             debug_assert!(self.graphics_allowed);
 
             self.inner
-                .draw_indirect(indirect_buffer,
-                               draw_count,
-                               mem::size_of::<DrawIndirectCommand>() as u32)?;
+                .draw_indirect_count(indirect_buffer,
+                                    count_buffer,
+                                    max_draw_count,
+                                    mem::size_of::<DrawIndirectCommand>() as u32)?;
             Ok(self)
         }
     }
@@ -1165,10 +2053,14 @@ impl<P> AutoCommandBufferBuilder<P> {
 
             self.ensure_inside_render_pass_inline(&pipeline)?;
             let ib_infos = check_index_buffer(self.device(), &index_buffer)?;
-            check_dynamic_state_validity(&pipeline, dynamic)?;
+            let dynamic = self.state_cacher.complete_dynamic_state(&pipeline, dynamic);
+            check_dynamic_state_validity(&pipeline, &dynamic)?;
+            check_dynamic_state_set(&pipeline, &self.state_cacher)?;
             check_push_constants_validity(&pipeline, &constants)?;
             check_descriptor_sets_validity(&pipeline, &sets)?;
+            check_no_dynamic_offsets(&sets)?;
             let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
+            check_indirect_buffer(self.device(), &indirect_buffer)?;
 
             let draw_count = indirect_buffer.len() as u32;
 
@@ -1176,6 +2068,7 @@ impl<P> AutoCommandBufferBuilder<P> {
                 self.state_cacher.bind_graphics_pipeline(&pipeline)
             {
                 self.inner.bind_pipeline_graphics(pipeline.clone());
+                self.graphics_pipeline = Some(Arc::new(pipeline.clone()));
             }
 
             if let StateCacherOutcome::NeedChange =
@@ -1184,7 +2077,7 @@ impl<P> AutoCommandBufferBuilder<P> {
                 self.inner.bind_index_buffer(index_buffer, I::ty())?;
             }
 
-            let dynamic = self.state_cacher.dynamic_state(dynamic);
+            let dynamic = self.state_cacher.dynamic_state(&dynamic);
 
             push_constants(&mut self.inner, pipeline.clone(), constants);
             set_state(&mut self.inner, &dynamic);
@@ -1197,6 +2090,7 @@ impl<P> AutoCommandBufferBuilder<P> {
                            &mut self.state_cacher,
                            vb_infos.vertex_buffers)?;
 
+            self.forget_bound_state();
             debug_assert!(self.graphics_allowed);
 
             self.inner
@@ -1207,6 +2101,110 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
     }
 
+    /// Performs multiple indexed draws, reading the number of draws from `count_buffer` when the
+    /// command buffer executes. Each draw uses one
+    /// `vulkano::command_buffer::DrawIndexedIndirectCommand` struct of `indirect_buffer`, and the
+    /// `index_buffer` and `vertex_buffer` are used by all draws.
+    ///
+    /// Same as `draw_indirect_count`, but for indexed draws.
+    #[inline]
+    pub fn draw_indexed_indirect_count<V, Gp, S, Pc, Ib, Inb, Cb, I>(
+        mut self, pipeline: Gp, dynamic: &DynamicState, vertex_buffer: V, index_buffer: Ib,
+        indirect_buffer: Inb, count_buffer: Cb, sets: S, constants: Pc)
+        -> Result<Self, DrawIndexedIndirectCountError>
+        where Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
+              S: DescriptorSetsCollection,
+              Ib: BufferAccess + TypedBufferAccess<Content = [I]> + Send + Sync + 'static,
+              Inb: BufferAccess
+                      + TypedBufferAccess<Content = [DrawIndexedIndirectCommand]>
+                      + Send
+                      + Sync
+                      + 'static,
+              Cb: BufferAccess + TypedBufferAccess<Content = u32> + Send + Sync + 'static,
+              I: Index + 'static
+    {
+        unsafe {
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            let ib_infos = check_index_buffer(self.device(), &index_buffer)?;
+            let dynamic = self.state_cacher.complete_dynamic_state(&pipeline, dynamic);
+            check_dynamic_state_validity(&pipeline, &dynamic)?;
+            check_dynamic_state_set(&pipeline, &self.state_cacher)?;
+            check_push_constants_validity(&pipeline, &constants)?;
+            check_descriptor_sets_validity(&pipeline, &sets)?;
+            check_no_dynamic_offsets(&sets)?;
+            let vb_infos = check_vertex_buffers(&pipeline, vertex_buffer)?;
+            check_indirect_buffer(self.device(), &indirect_buffer)?;
+            check_indirect_buffer(self.device(), &count_buffer)?;
+
+            let max_draw_count = indirect_buffer.len() as u32;
+            check_draw_indirect_count(self.device(), max_draw_count)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+                self.graphics_pipeline = Some(Arc::new(pipeline.clone()));
+            }
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_index_buffer(&index_buffer, I::ty())
+            {
+                self.inner.bind_index_buffer(index_buffer, I::ty())?;
+            }
+
+            let dynamic = self.state_cacher.dynamic_state(&dynamic);
+
+            push_constants(&mut self.inner, pipeline.clone(), constants);
+            set_state(&mut self.inner, &dynamic);
+            descriptor_sets(&mut self.inner,
+                            &mut self.state_cacher,
+                            true,
+                            pipeline.clone(),
+                            sets)?;
+            vertex_buffers(&mut self.inner,
+                           &mut self.state_cacher,
+                           vb_infos.vertex_buffers)?;
+
+            self.forget_bound_state();
+            debug_assert!(self.graphics_allowed);
+
+            self.inner
+                .draw_indexed_indirect_count(indirect_buffer,
+                                            count_buffer,
+                                            max_draw_count,
+                                            mem::size_of::<DrawIndexedIndirectCommand>() as u32)?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that ends an active query.
+    #[inline]
+    pub fn end_query<Q>(mut self, query_pool: Q, query: u32) -> Result<Self, EndQueryError>
+        where Q: QueryPoolAbstract + Send + Sync + 'static
+    {
+        unsafe {
+            check_end_query(self.device(), &query_pool, query)?;
+
+            let ty = query_pool.inner().ty().into_vulkan();
+            let raw_pool = query_pool.inner().internal_object();
+            match self.active_queries.get(&ty) {
+                Some(active) if active.query_pool == raw_pool && active.query == query => {
+                    if active.subpass != self.render_pass.as_ref().map(|rp| rp.1) {
+                        return Err(AutoCommandBufferBuilderContextError::QueryScopeMismatch
+                                       .into());
+                    }
+                },
+                _ => {
+                    return Err(AutoCommandBufferBuilderContextError::QueryNotActive.into());
+                },
+            }
+
+            self.active_queries.remove(&ty);
+            self.inner.end_query(query_pool, query);
+            Ok(self)
+        }
+    }
+
     /// Adds a command that ends the current render pass.
     ///
     /// This must be called after you went through all the subpasses and before you can build
@@ -1231,10 +2229,13 @@ impl<P> AutoCommandBufferBuilder<P> {
                 },
             }
 
+            self.ensure_no_active_query_in_subpass()?;
+
             debug_assert!(self.graphics_allowed);
 
             self.inner.end_render_pass();
             self.render_pass = None;
+            self.framebuffer_dimensions = None;
             Ok(self)
         }
     }
@@ -1255,6 +2256,8 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
 
         self.state_cacher.invalidate();
+        self.graphics_pipeline = None;
+        self.forget_bound_state();
 
         Ok(self)
     }
@@ -1273,75 +2276,608 @@ impl<P> AutoCommandBufferBuilder<P> {
             for cmd_buffer in command_buffers {
                 builder.add(cmd_buffer);
             }
-            builder.submit()?;
-        }
+            builder.submit()?;
+        }
+
+        self.state_cacher.invalidate();
+        self.graphics_pipeline = None;
+        self.forget_bound_state();
+
+        Ok(self)
+    }
+
+    /// Adds a command that writes the content of a buffer.
+    ///
+    /// This function is similar to the `memset` function in C. The `data` parameter is a number
+    /// that will be repeatedly written through the entire buffer.
+    ///
+    /// > **Note**: This function is technically safe because buffers can only contain integers or
+    /// > floating point numbers, which are always valid whatever their memory representation is.
+    /// > But unless your buffer actually contains only 32-bits integers, you are encouraged to use
+    /// > this function only for zeroing the content of a buffer by passing `0` for the data.
+    // TODO: not safe because of signalling NaNs
+    #[inline]
+    pub fn fill_buffer<B>(mut self, buffer: B, data: u32) -> Result<Self, FillBufferError>
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            check_fill_buffer(self.device(), &buffer)?;
+            self.inner.fill_buffer(buffer, data);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that generates the mipmaps of an image from the content of its first
+    /// mipmap level.
+    ///
+    /// Each mipmap level is blitted into the next one, with a linear filter if the format of the
+    /// image supports it and a nearest filter otherwise. The content of all the mipmap levels
+    /// other than the first one is overwritten.
+    ///
+    /// The image must have been created with both the transfer source and transfer destination
+    /// usages, and its format must support blitting. There is no compute fallback for the formats
+    /// that don't, so their mipmaps must be generated by other means.
+    #[inline]
+    pub fn generate_mipmaps<I>(mut self, image: I) -> Result<Self, GenerateMipmapsError>
+        where I: ImageAccess + Send + Sync + 'static
+    {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.ensure_outside_render_pass()?;
+            check_generate_mipmaps(self.device(), &image)?;
+
+            let filter = if image.has_color() && image.inner().image.supports_linear_filtering() {
+                Filter::Linear
+            } else {
+                Filter::Nearest
+            };
+
+            self.inner.generate_mipmaps(image, filter)?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that jumps to the next subpass of the current render pass.
+    #[inline]
+    pub fn next_subpass(mut self, secondary: bool)
+                        -> Result<Self, AutoCommandBufferBuilderContextError> {
+        unsafe {
+            if self.secondary_cb {
+                return Err(AutoCommandBufferBuilderContextError::ForbiddenInSecondary);
+            }
+
+            self.ensure_no_active_query_in_subpass()?;
+
+            match self.render_pass {
+                None => {
+                    return Err(AutoCommandBufferBuilderContextError::ForbiddenOutsideRenderPass);
+                },
+                Some((ref rp, ref mut index)) => {
+                    if *index + 1 >= rp.num_subpasses() as u32 {
+                        return Err(AutoCommandBufferBuilderContextError::NumSubpassesMismatch {
+                                       actual: rp.num_subpasses() as u32,
+                                       current: *index,
+                                   });
+                    } else {
+                        *index += 1;
+                    }
+                },
+            };
+
+            self.subpass_secondary = secondary;
+
+            debug_assert!(self.graphics_allowed);
+
+            let contents = if secondary {
+                SubpassContents::SecondaryCommandBuffers
+            } else {
+                SubpassContents::Inline
+            };
+            self.inner.next_subpass(contents);
+            Ok(self)
+        }
+    }
+
+    /// Adds explicit barriers to the command buffer: image layout transitions and queue family
+    /// ownership transfers.
+    ///
+    /// All the barriers are checked before any of them is recorded. See the documentation of
+    /// `PipelineBarrier` for more information.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if one of the resources was not created with the same device as the command
+    ///   buffer.
+    /// - Panics if one of the queue families doesn't belong to the physical device of the
+    ///   command buffer.
+    ///
+    pub fn pipeline_barrier(mut self, barrier: PipelineBarrier)
+                            -> Result<Self, PipelineBarrierError> {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+
+            let device = self.device().clone();
+            let physical_device = device.physical_device();
+            let family = |id: FamilyId| {
+                assert_eq!(id.physical_device, physical_device.index());
+                physical_device.queue_family_by_id(id.id).unwrap()
+            };
+
+            for barrier in &barrier.barriers {
+                match *barrier {
+                    Barrier::ImageLayoutTransition { ref image, layout, stages, access } => {
+                        check_image_layout_transition(&device, image, layout, stages, access)?;
+                    },
+                    Barrier::BufferOwnershipTransfer { ref buffer, family: id, .. } => {
+                        check_buffer_ownership_transfer(&device, buffer, self.queue_family,
+                                                        family(id))?;
+                    },
+                    Barrier::ImageOwnershipTransfer { ref image, family: id, .. } => {
+                        check_image_ownership_transfer(&device, image, self.queue_family,
+                                                       family(id))?;
+                    },
+                }
+            }
+
+            // The source and destination families of a transfer, depending on its direction.
+            let queue_family = self.queue_family;
+            let families = |release: bool, other: FamilyId| if release {
+                (OwnershipTransfer::Release, queue_family, other.id)
+            } else {
+                (OwnershipTransfer::Acquire, other.id, queue_family)
+            };
+
+            for barrier in barrier.barriers {
+                match barrier {
+                    Barrier::ImageLayoutTransition { image, layout, stages, access } => {
+                        self.inner.image_layout_transition(image, layout, stages, access)?;
+                    },
+                    Barrier::BufferOwnershipTransfer { buffer, release, family } => {
+                        let (transfer, source, destination) = families(release, family);
+                        self.inner
+                            .buffer_ownership_transfer(buffer, transfer, source, destination)?;
+                    },
+                    Barrier::ImageOwnershipTransfer { image, release, family } => {
+                        let (transfer, source, destination) = families(release, family);
+                        self.inner
+                            .image_ownership_transfer(image, transfer, source, destination)?;
+                    },
+                }
+            }
+
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that sets the push constants of the currently bound graphics pipeline,
+    /// for use by `draw` and `draw_indexed`.
+    #[inline]
+    pub fn push_constants<Pc>(mut self, constants: Pc) -> Result<Self, PushConstantsError> {
+        unsafe {
+            let pipeline = match self.graphics_pipeline {
+                Some(ref pipeline) => pipeline.clone(),
+                None => {
+                    return Err(AutoCommandBufferBuilderContextError::GraphicsPipelineNotBound
+                                   .into());
+                },
+            };
+
+            check_push_constants_validity(&*pipeline, &constants)?;
+            push_constants(&mut self.inner, pipeline, constants);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that releases the ownership of a buffer to another queue family.
+    ///
+    /// Shortcut for `pipeline_barrier` with a single `PipelineBarrier::release_buffer_ownership`.
+    #[inline]
+    pub fn release_buffer_ownership<B>(self, buffer: B, destination: QueueFamily)
+                                       -> Result<Self, PipelineBarrierError>
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        self.pipeline_barrier(PipelineBarrier::new().release_buffer_ownership(buffer, destination))
+    }
+
+    /// Adds a command that releases the ownership of an image to another queue family.
+    ///
+    /// Shortcut for `pipeline_barrier` with a single `PipelineBarrier::release_image_ownership`.
+    #[inline]
+    pub fn release_image_ownership<I>(self, image: I, destination: QueueFamily)
+                                      -> Result<Self, PipelineBarrierError>
+        where I: ImageAccess + Send + Sync + 'static
+    {
+        self.pipeline_barrier(PipelineBarrier::new().release_image_ownership(image, destination))
+    }
+
+    /// Adds a command that resolves a multisampled image into a single-sampled image.
+    ///
+    /// Each pixel of the destination receives the average of the samples of the corresponding
+    /// pixel of the source. The source must be multisampled, the destination must not be, and
+    /// both images must have the same color format.
+    ///
+    /// If `layer_count` is greater than 1, the resolve will happen between each individual layer
+    /// as if they were separate images.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the source or the destination was not created with `device`.
+    ///
+    pub fn resolve_image<S, D>(mut self, source: S, source_offset: [i32; 3],
+                               source_base_array_layer: u32, source_mip_level: u32,
+                               destination: D, destination_offset: [i32; 3],
+                               destination_base_array_layer: u32, destination_mip_level: u32,
+                               extent: [u32; 3], layer_count: u32)
+                               -> Result<Self, ResolveImageError>
+        where S: ImageAccess + Send + Sync + 'static,
+              D: ImageAccess + Send + Sync + 'static
+    {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.ensure_outside_render_pass()?;
+
+            check_resolve_image(self.device(),
+                                &source,
+                                source_offset,
+                                source_base_array_layer,
+                                source_mip_level,
+                                &destination,
+                                destination_offset,
+                                destination_base_array_layer,
+                                destination_mip_level,
+                                extent,
+                                layer_count)?;
+
+            let resolve = UnsafeCommandBufferBuilderImageResolve {
+                aspect: UnsafeCommandBufferBuilderImageAspect {
+                    color: true,
+                    depth: false,
+                    stencil: false,
+                },
+                source_mip_level,
+                destination_mip_level,
+                source_base_array_layer,
+                destination_base_array_layer,
+                layer_count,
+                source_offset,
+                destination_offset,
+                extent,
+            };
+
+            // TODO: Allow choosing layouts, but note that only Transfer*Optimal and General are
+            // valid.
+            self.inner
+                .resolve_image(source, ImageLayout::TransferSrcOptimal,
+                               destination, ImageLayout::TransferDstOptimal,
+                               iter::once(resolve))?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that resets an event to the unsignaled state once all the previous
+    /// commands have reached the given stages.
+    #[inline]
+    pub fn reset_event(mut self, event: Arc<Event>, stages: PipelineStages)
+                       -> Result<Self, EventError> {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            check_event(self.device(), &event, stages)?;
+
+            if !self.graphics_allowed && !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.set_events.remove(&event.internal_object());
+            self.inner.reset_event(event, stages);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that resets a range of queries, so that they can be used again.
+    ///
+    /// None of the queries in the range must be active.
+    #[inline]
+    pub fn reset_query_pool<Q>(mut self, query_pool: Q, queries: Range<u32>)
+                               -> Result<Self, ResetQueryPoolError>
+        where Q: QueryPoolAbstract + Send + Sync + 'static
+    {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            check_reset_query_pool(self.device(), &query_pool, queries.clone())?;
+
+            if !self.graphics_allowed && !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            let raw_pool = query_pool.inner().internal_object();
+            if self.active_queries.values().any(|q| {
+                q.query_pool == raw_pool && q.query >= queries.start && q.query < queries.end
+            }) {
+                return Err(AutoCommandBufferBuilderContextError::QueryIsActive.into());
+            }
+
+            for query in queries.clone() {
+                self.used_queries.remove(&(raw_pool, query));
+            }
+
+            self.inner.reset_query_pool(query_pool, queries);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that sets the blend constants.
+    ///
+    /// The blend constants can be set before or after binding a graphics pipeline. They are
+    /// used by the draws whose pipeline has the blend constants set as dynamic.
+    #[inline]
+    pub fn set_blend_constants(mut self, constants: [f32; 4])
+                               -> Result<Self, SetDynamicStateError> {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.set_blend_constants(constants)
+            {
+                self.inner.set_blend_constants(constants);
+                self.bound_state_checks.dynamic_state = false;
+            }
+
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that sets the depth bias.
+    ///
+    /// The depth bias can be set before or after binding a graphics pipeline. It is used by the
+    /// draws whose pipeline has the depth bias set as dynamic. If `clamp` is not 0.0, the
+    /// `depth_bias_clamp` feature must be enabled.
+    #[inline]
+    pub fn set_depth_bias(mut self, constant_factor: f32, clamp: f32, slope_factor: f32)
+                          -> Result<Self, SetDynamicStateError> {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            check_set_depth_bias(self.device(), clamp)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.set_depth_bias(constant_factor, clamp, slope_factor)
+            {
+                self.inner.set_depth_bias(constant_factor, clamp, slope_factor);
+                self.bound_state_checks.dynamic_state = false;
+            }
+
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that sets the depth bounds.
+    ///
+    /// The depth bounds can be set before or after binding a graphics pipeline. They are used
+    /// by the draws whose pipeline has the depth bounds set as dynamic. Both values must be
+    /// between 0.0 and 1.0.
+    #[inline]
+    pub fn set_depth_bounds(mut self, min: f32, max: f32) -> Result<Self, SetDynamicStateError> {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            check_set_depth_bounds(min, max)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.set_depth_bounds(min, max)
+            {
+                self.inner.set_depth_bounds(min, max);
+                self.bound_state_checks.dynamic_state = false;
+            }
+
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that signals an event once all the previous commands have reached the
+    /// given stages.
+    ///
+    /// This is the first half of a split barrier. Call `wait_events` before the commands that
+    /// must wait for the event, and record other commands in between that can execute
+    /// meanwhile.
+    #[inline]
+    pub fn set_event(mut self, event: Arc<Event>, stages: PipelineStages)
+                     -> Result<Self, EventError> {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            check_event(self.device(), &event, stages)?;
+
+            if !self.graphics_allowed && !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.set_events.insert(event.internal_object(), stages);
+            self.inner.set_event(event, stages);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that sets the line width.
+    ///
+    /// The line width can be set before or after binding a graphics pipeline. It is used by the
+    /// draws whose pipeline has the line width set as dynamic. If the width is not 1.0, the
+    /// `wide_lines` feature must be enabled.
+    #[inline]
+    pub fn set_line_width(mut self, line_width: f32) -> Result<Self, SetDynamicStateError> {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            check_set_line_width(self.device(), line_width)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.set_line_width(line_width)
+            {
+                self.inner.set_line_width(line_width);
+                self.bound_state_checks.dynamic_state = false;
+            }
+
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that sets scissors, starting at `first_scissor`.
+    ///
+    /// The scissors can be set before or after binding a graphics pipeline. They are used by the
+    /// draws whose pipeline has the scissors set as dynamic, and which check that a scissor is
+    /// set for each of their viewports. Setting any scissor other than the first one requires
+    /// the `multi_viewport` feature.
+    #[inline]
+    pub fn set_scissor<I>(mut self, first_scissor: u32, scissors: I)
+                          -> Result<Self, SetDynamicStateError>
+        where I: IntoIterator<Item = Scissor>
+    {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            let scissors = scissors.into_iter().collect::<Vec<_>>();
+            check_set_scissor(self.device(), first_scissor, scissors.len() as u32)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.set_scissor(first_scissor, &scissors)
+            {
+                self.inner.set_scissor(first_scissor, scissors.into_iter());
+                self.bound_state_checks.dynamic_state = false;
+            }
+
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that sets the stencil compare mask of the given faces.
+    ///
+    /// The compare mask can be set before or after binding a graphics pipeline. It is used by
+    /// the draws whose pipeline has the stencil compare mask set as dynamic.
+    #[inline]
+    pub fn set_stencil_compare_mask(mut self, compare_mask: DynamicStencilValue)
+                                    -> Result<Self, SetDynamicStateError> {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.set_stencil_compare_mask(compare_mask)
+            {
+                self.inner.set_stencil_compare_mask(compare_mask);
+                self.bound_state_checks.dynamic_state = false;
+            }
+
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that sets the stencil reference of the given faces.
+    ///
+    /// The reference can be set before or after binding a graphics pipeline. It is used by the
+    /// draws whose pipeline has the stencil reference set as dynamic.
+    #[inline]
+    pub fn set_stencil_reference(mut self, reference: DynamicStencilValue)
+                                 -> Result<Self, SetDynamicStateError> {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
 
-        self.state_cacher.invalidate();
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.set_stencil_reference(reference)
+            {
+                self.inner.set_stencil_reference(reference);
+                self.bound_state_checks.dynamic_state = false;
+            }
 
-        Ok(self)
+            Ok(self)
+        }
     }
 
-    /// Adds a command that writes the content of a buffer.
-    ///
-    /// This function is similar to the `memset` function in C. The `data` parameter is a number
-    /// that will be repeatedly written through the entire buffer.
+    /// Adds a command that sets the stencil write mask of the given faces.
     ///
-    /// > **Note**: This function is technically safe because buffers can only contain integers or
-    /// > floating point numbers, which are always valid whatever their memory representation is.
-    /// > But unless your buffer actually contains only 32-bits integers, you are encouraged to use
-    /// > this function only for zeroing the content of a buffer by passing `0` for the data.
-    // TODO: not safe because of signalling NaNs
+    /// The write mask can be set before or after binding a graphics pipeline. It is used by the
+    /// draws whose pipeline has the stencil write mask set as dynamic.
     #[inline]
-    pub fn fill_buffer<B>(mut self, buffer: B, data: u32) -> Result<Self, FillBufferError>
-        where B: BufferAccess + Send + Sync + 'static
-    {
+    pub fn set_stencil_write_mask(mut self, write_mask: DynamicStencilValue)
+                                  -> Result<Self, SetDynamicStateError> {
         unsafe {
-            self.ensure_outside_render_pass()?;
-            check_fill_buffer(self.device(), &buffer)?;
-            self.inner.fill_buffer(buffer, data);
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.set_stencil_write_mask(write_mask)
+            {
+                self.inner.set_stencil_write_mask(write_mask);
+                self.bound_state_checks.dynamic_state = false;
+            }
+
             Ok(self)
         }
     }
 
-    /// Adds a command that jumps to the next subpass of the current render pass.
+    /// Adds a command that sets viewports, starting at `first_viewport`.
+    ///
+    /// The viewports can be set before or after binding a graphics pipeline. They are used by
+    /// the draws whose pipeline has the viewports set as dynamic, and which check that each of
+    /// their viewports is set. Setting any viewport other than the first one requires the
+    /// `multi_viewport` feature.
     #[inline]
-    pub fn next_subpass(mut self, secondary: bool)
-                        -> Result<Self, AutoCommandBufferBuilderContextError> {
+    pub fn set_viewport<I>(mut self, first_viewport: u32, viewports: I)
+                           -> Result<Self, SetDynamicStateError>
+        where I: IntoIterator<Item = Viewport>
+    {
         unsafe {
-            if self.secondary_cb {
-                return Err(AutoCommandBufferBuilderContextError::ForbiddenInSecondary);
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
             }
 
-            match self.render_pass {
-                None => {
-                    return Err(AutoCommandBufferBuilderContextError::ForbiddenOutsideRenderPass);
-                },
-                Some((ref rp, ref mut index)) => {
-                    if *index + 1 >= rp.num_subpasses() as u32 {
-                        return Err(AutoCommandBufferBuilderContextError::NumSubpassesMismatch {
-                                       actual: rp.num_subpasses() as u32,
-                                       current: *index,
-                                   });
-                    } else {
-                        *index += 1;
-                    }
-                },
-            };
-
-            self.subpass_secondary = secondary;
+            let viewports = viewports.into_iter().collect::<Vec<_>>();
+            check_set_viewport(self.device(), first_viewport, viewports.len() as u32)?;
 
-            debug_assert!(self.graphics_allowed);
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.set_viewport(first_viewport, &viewports)
+            {
+                self.inner.set_viewport(first_viewport, viewports.into_iter());
+                self.bound_state_checks.dynamic_state = false;
+            }
 
-            let contents = if secondary {
-                SubpassContents::SecondaryCommandBuffers
-            } else {
-                SubpassContents::Inline
-            };
-            self.inner.next_subpass(contents);
             Ok(self)
         }
     }
 
+    /// Adds a pipeline barrier that transitions an image to the given layout.
+    ///
+    /// Shortcut for `pipeline_barrier` with a single `PipelineBarrier::image_layout_transition`.
+    /// This is mostly useful in combination with the unsafe layers, or to choose where a
+    /// transition happens instead of letting the builder insert it before the next usage.
+    #[inline]
+    pub fn transition_image_layout<I>(self, image: I, layout: ImageLayout,
+                                      stages: PipelineStages, access: AccessFlagBits)
+                                      -> Result<Self, PipelineBarrierError>
+        where I: ImageAccess + Send + Sync + 'static
+    {
+        self.pipeline_barrier(PipelineBarrier::new()
+                                  .image_layout_transition(image, layout, stages, access))
+    }
+
     /// Adds a command that writes data to a buffer.
     ///
     /// If `data` is larger than the buffer, only the part of `data` that fits is written. If the
@@ -1367,6 +2903,81 @@ impl<P> AutoCommandBufferBuilder<P> {
             Ok(self)
         }
     }
+
+    /// Adds a command that waits for events to be signaled before executing the stages of the
+    /// following commands given by `destination`.
+    ///
+    /// This is the second half of a split barrier. All the events must have been set earlier in
+    /// this command buffer with `set_event`, and the source stages of the wait are the stages
+    /// that were passed when setting them.
+    ///
+    /// This only adds an execution dependency. The memory accesses to the buffers and images
+    /// used by the command buffer are still synchronized automatically.
+    ///
+    /// > **Note**: The events stay signaled after the wait. You should reset them with
+    /// > `reset_event` if the command buffer is going to be submitted multiple times.
+    #[inline]
+    pub fn wait_events<I>(mut self, events: I, destination: PipelineStages)
+                          -> Result<Self, EventError>
+        where I: IntoIterator<Item = Arc<Event>>
+    {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+
+            if !self.graphics_allowed && !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            let events = events.into_iter().collect::<Vec<_>>();
+            let mut source = PipelineStages::none();
+            for event in events.iter() {
+                check_event(self.device(), event, destination)?;
+
+                match self.set_events.get(&event.internal_object()) {
+                    Some(&stages) => source = source | stages,
+                    None => return Err(AutoCommandBufferBuilderContextError::EventNotSet.into()),
+                }
+            }
+
+            if events.is_empty() {
+                return Ok(self);
+            }
+
+            self.inner.wait_events(events,
+                                   source,
+                                   AccessFlagBits::none(),
+                                   destination,
+                                   AccessFlagBits::none());
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that writes a timestamp to a timestamp query.
+    ///
+    /// The timestamp is written once all the previous commands have reached the given pipeline
+    /// stage. Exactly one stage must be set in `stage`. The query must have been reset with
+    /// `reset_query_pool` before it can be written again in the same command buffer.
+    #[inline]
+    pub fn write_timestamp<Q>(mut self, query_pool: Q, query: u32, stage: PipelineStages)
+                              -> Result<Self, WriteTimestampError>
+        where Q: QueryPoolAbstract + Send + Sync + 'static
+    {
+        unsafe {
+            check_write_timestamp(self.device(), &query_pool, query, stage)?;
+
+            if !self.timestamps_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            let raw_pool = query_pool.inner().internal_object();
+            if !self.used_queries.insert((raw_pool, query)) {
+                return Err(AutoCommandBufferBuilderContextError::QueryNotReset.into());
+            }
+
+            self.inner.write_timestamp(query_pool, query, stage);
+            Ok(self)
+        }
+    }
 }
 
 unsafe impl<P> DeviceOwned for AutoCommandBufferBuilder<P> {
@@ -1653,11 +3264,35 @@ macro_rules! err_gen {
     );
 }
 
+err_gen!(BindDescriptorSetsError {
+             AutoCommandBufferBuilderContextError,
+             CheckDescriptorSetsValidityError,
+             CheckDynamicOffsetsError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(BindIndexBufferError {
+             AutoCommandBufferBuilderContextError,
+             CheckIndexBufferError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(BindVertexBuffersError {
+             AutoCommandBufferBuilderContextError,
+             CheckVertexBufferError,
+             SyncCommandBufferBuilderError,
+         });
+
 err_gen!(BuildError {
              AutoCommandBufferBuilderContextError,
              OomError,
          });
 
+err_gen!(BeginQueryError {
+             AutoCommandBufferBuilderContextError,
+             CheckBeginQueryError,
+         });
+
 err_gen!(BeginRenderPassError {
              AutoCommandBufferBuilderContextError,
              SyncCommandBufferBuilderError,
@@ -1675,12 +3310,23 @@ err_gen!(BlitImageError {
              SyncCommandBufferBuilderError,
          });
 
+err_gen!(ClearAttachmentsError {
+             AutoCommandBufferBuilderContextError,
+             CheckClearAttachmentsError,
+         });
+
 err_gen!(ClearColorImageError {
              AutoCommandBufferBuilderContextError,
              CheckClearColorImageError,
              SyncCommandBufferBuilderError,
          });
 
+err_gen!(ClearDepthStencilImageError {
+             AutoCommandBufferBuilderContextError,
+             CheckClearDepthStencilImageError,
+             SyncCommandBufferBuilderError,
+         });
+
 err_gen!(CopyBufferError {
              AutoCommandBufferBuilderContextError,
              CheckCopyBufferError,
@@ -1693,33 +3339,64 @@ err_gen!(CopyBufferImageError {
              SyncCommandBufferBuilderError,
          });
 
+err_gen!(CopyQueryPoolResultsError {
+             AutoCommandBufferBuilderContextError,
+             CheckCopyQueryPoolResultsError,
+             SyncCommandBufferBuilderError,
+         });
+
 err_gen!(FillBufferError {
              AutoCommandBufferBuilderContextError,
              CheckFillBufferError,
          });
 
+err_gen!(DebugMarkerError {
+             AutoCommandBufferBuilderContextError,
+             CheckDebugMarkerError,
+         });
+
 err_gen!(DispatchError {
              AutoCommandBufferBuilderContextError,
              CheckPushConstantsValidityError,
              CheckDescriptorSetsValidityError,
+             CheckDynamicOffsetsError,
              CheckDispatchError,
              SyncCommandBufferBuilderError,
          });
 
+err_gen!(DispatchIndirectError {
+             AutoCommandBufferBuilderContextError,
+             CheckPushConstantsValidityError,
+             CheckDescriptorSetsValidityError,
+             CheckDynamicOffsetsError,
+             CheckIndirectBufferError,
+             SyncCommandBufferBuilderError,
+         });
+
 err_gen!(DrawError {
              AutoCommandBufferBuilderContextError,
              CheckDynamicStateValidityError,
              CheckPushConstantsValidityError,
              CheckDescriptorSetsValidityError,
+             CheckDynamicOffsetsError,
              CheckVertexBufferError,
              SyncCommandBufferBuilderError,
          });
 
+err_gen!(DrawBoundError {
+             AutoCommandBufferBuilderContextError,
+             CheckDynamicStateValidityError,
+             CheckDescriptorSetsValidityError,
+             CheckVertexBufferError,
+             CheckDrawError,
+         });
+
 err_gen!(DrawIndexedError {
              AutoCommandBufferBuilderContextError,
              CheckDynamicStateValidityError,
              CheckPushConstantsValidityError,
              CheckDescriptorSetsValidityError,
+             CheckDynamicOffsetsError,
              CheckVertexBufferError,
              CheckIndexBufferError,
              SyncCommandBufferBuilderError,
@@ -1730,7 +3407,21 @@ err_gen!(DrawIndirectError {
              CheckDynamicStateValidityError,
              CheckPushConstantsValidityError,
              CheckDescriptorSetsValidityError,
+             CheckDynamicOffsetsError,
+             CheckVertexBufferError,
+             CheckIndirectBufferError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(DrawIndirectCountError {
+             AutoCommandBufferBuilderContextError,
+             CheckDynamicStateValidityError,
+             CheckPushConstantsValidityError,
+             CheckDescriptorSetsValidityError,
+             CheckDynamicOffsetsError,
              CheckVertexBufferError,
+             CheckIndirectBufferError,
+             CheckDrawIndirectCountError,
              SyncCommandBufferBuilderError,
          });
 
@@ -1739,21 +3430,84 @@ err_gen!(DrawIndexedIndirectError {
              CheckDynamicStateValidityError,
              CheckPushConstantsValidityError,
              CheckDescriptorSetsValidityError,
+             CheckDynamicOffsetsError,
+             CheckVertexBufferError,
+             CheckIndexBufferError,
+             CheckIndirectBufferError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(DrawIndexedIndirectCountError {
+             AutoCommandBufferBuilderContextError,
+             CheckDynamicStateValidityError,
+             CheckPushConstantsValidityError,
+             CheckDescriptorSetsValidityError,
+             CheckDynamicOffsetsError,
              CheckVertexBufferError,
              CheckIndexBufferError,
+             CheckIndirectBufferError,
+             CheckDrawIndirectCountError,
              SyncCommandBufferBuilderError,
          });
 
+err_gen!(EndQueryError {
+             AutoCommandBufferBuilderContextError,
+             CheckEndQueryError,
+         });
+
+err_gen!(EventError {
+             AutoCommandBufferBuilderContextError,
+             CheckEventError,
+         });
+
 err_gen!(ExecuteCommandsError {
              AutoCommandBufferBuilderContextError,
              SyncCommandBufferBuilderError,
          });
 
+err_gen!(GenerateMipmapsError {
+             AutoCommandBufferBuilderContextError,
+             CheckGenerateMipmapsError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(PipelineBarrierError {
+             AutoCommandBufferBuilderContextError,
+             CheckPipelineBarrierError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(PushConstantsError {
+             AutoCommandBufferBuilderContextError,
+             CheckPushConstantsValidityError,
+         });
+
+err_gen!(ResetQueryPoolError {
+             AutoCommandBufferBuilderContextError,
+             CheckResetQueryPoolError,
+         });
+
+err_gen!(ResolveImageError {
+             AutoCommandBufferBuilderContextError,
+             CheckResolveImageError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(SetDynamicStateError {
+             AutoCommandBufferBuilderContextError,
+             CheckSetDynamicStateError,
+         });
+
 err_gen!(UpdateBufferError {
              AutoCommandBufferBuilderContextError,
              CheckUpdateBufferError,
          });
 
+err_gen!(WriteTimestampError {
+             AutoCommandBufferBuilderContextError,
+             CheckWriteTimestampError,
+         });
+
 #[derive(Debug, Copy, Clone)]
 pub enum AutoCommandBufferBuilderContextError {
     /// Operation forbidden in a secondary command buffer.
@@ -1781,6 +3535,32 @@ pub enum AutoCommandBufferBuilderContextError {
     /// Tried to use a graphics pipeline whose render pass is incompatible with the current render
     /// pass.
     IncompatibleRenderPass,
+    /// Tried to end a debug marker region while none was begun in this command buffer.
+    DebugMarkerNotActive,
+    /// Tried to build a command buffer while a debug marker region begun in it is still open.
+    DebugMarkerIsActive,
+    /// Tried to wait for an event that wasn't set earlier in this command buffer.
+    EventNotSet,
+    /// Tried to set a dynamic state, bind descriptor sets, set push constants or draw with the
+    /// bound state while no graphics pipeline is bound.
+    GraphicsPipelineNotBound,
+    /// Tried to draw with the bound state while no index buffer was bound with
+    /// `bind_index_buffer`.
+    IndexBufferNotBound,
+    /// Tried to begin a query while another query of the same type is active, to reset a query
+    /// that is active, or to leave the scope of a query that is still active.
+    QueryIsActive,
+    /// Tried to end a query that isn't active.
+    QueryNotActive,
+    /// Tried to use a query that was already used in this command buffer without resetting it
+    /// in between.
+    QueryNotReset,
+    /// Tried to end a query in a different subpass than the one it was begun in, or inside a
+    /// render pass while it was begun outside of it.
+    QueryScopeMismatch,
+    /// Tried to draw with the bound state while the bound vertex buffers are unknown, because a
+    /// command that binds its own vertex buffers was used since the last `bind_vertex_buffers`.
+    VertexBuffersNotBound,
 }
 
 impl error::Error for AutoCommandBufferBuilderContextError {
@@ -1816,6 +3596,45 @@ impl error::Error for AutoCommandBufferBuilderContextError {
                 "tried to use a graphics pipeline whose render pass is incompatible with the \
                  current render pass"
             },
+            AutoCommandBufferBuilderContextError::DebugMarkerNotActive => {
+                "tried to end a debug marker region while none was begun in this command buffer"
+            },
+            AutoCommandBufferBuilderContextError::DebugMarkerIsActive => {
+                "tried to build a command buffer while a debug marker region begun in it is \
+                 still open"
+            },
+            AutoCommandBufferBuilderContextError::EventNotSet => {
+                "tried to wait for an event that wasn't set earlier in this command buffer"
+            },
+            AutoCommandBufferBuilderContextError::GraphicsPipelineNotBound => {
+                "tried to set a dynamic state, bind descriptor sets, set push constants or draw \
+                 with the bound state while no graphics pipeline is bound"
+            },
+            AutoCommandBufferBuilderContextError::IndexBufferNotBound => {
+                "tried to draw with the bound state while no index buffer was bound with \
+                 `bind_index_buffer`"
+            },
+            AutoCommandBufferBuilderContextError::QueryIsActive => {
+                "tried to begin a query while another query of the same type is active, to \
+                 reset a query that is active, or to leave the scope of a query that is still \
+                 active"
+            },
+            AutoCommandBufferBuilderContextError::QueryNotActive => {
+                "tried to end a query that isn't active"
+            },
+            AutoCommandBufferBuilderContextError::QueryNotReset => {
+                "tried to use a query that was already used in this command buffer without \
+                 resetting it in between"
+            },
+            AutoCommandBufferBuilderContextError::QueryScopeMismatch => {
+                "tried to end a query in a different subpass than the one it was begun in, or \
+                 inside a render pass while it was begun outside of it"
+            },
+            AutoCommandBufferBuilderContextError::VertexBuffersNotBound => {
+                "tried to draw with the bound state while the bound vertex buffers are unknown, \
+                 because a command that binds its own vertex buffers was used since the last \
+                 `bind_vertex_buffers`"
+            },
         }
     }
 }
@@ -1826,3 +3645,226 @@ impl fmt::Display for AutoCommandBufferBuilderContextError {
         write!(fmt, "{}", error::Error::description(self))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use command_buffer::AutoCommandBufferBuilder;
+    use command_buffer::AutoCommandBufferBuilderContextError;
+    use command_buffer::BeginQueryError;
+    use command_buffer::BuildError;
+    use command_buffer::EndQueryError;
+    use command_buffer::PipelineBarrier;
+    use command_buffer::PipelineBarrierError;
+    use command_buffer::validity::CheckBeginQueryError;
+    use command_buffer::validity::CheckPipelineBarrierError;
+    use format::Format;
+    use framebuffer::Framebuffer;
+    use image::ImageLayout;
+    use image::attachment::AttachmentImage;
+    use query::QueryPool;
+    use query::QueryType;
+    use std::sync::Arc;
+    use sync::AccessFlagBits;
+    use sync::PipelineStages;
+
+    #[test]
+    fn begin_query_not_reset() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let pool = QueryPool::new(device.clone(), QueryType::Occlusion, 4).unwrap();
+
+        let builder = AutoCommandBufferBuilder::new(device, queue.family())
+            .unwrap()
+            .begin_query(pool.clone(), 0, false)
+            .unwrap()
+            .end_query(pool.clone(), 0)
+            .unwrap();
+
+        match builder.begin_query(pool, 0, false) {
+            Err(BeginQueryError::AutoCommandBufferBuilderContextError(
+                AutoCommandBufferBuilderContextError::QueryNotReset)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn begin_query_reset() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let pool = QueryPool::new(device.clone(), QueryType::Occlusion, 4).unwrap();
+
+        let _ = AutoCommandBufferBuilder::new(device, queue.family())
+            .unwrap()
+            .begin_query(pool.clone(), 0, false)
+            .unwrap()
+            .end_query(pool.clone(), 0)
+            .unwrap()
+            .reset_query_pool(pool.clone(), 0 .. 1)
+            .unwrap()
+            .begin_query(pool.clone(), 0, false)
+            .unwrap()
+            .end_query(pool, 0)
+            .unwrap()
+            .build()
+            .unwrap();
+    }
+
+    #[test]
+    fn begin_query_already_active() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let pool = QueryPool::new(device.clone(), QueryType::Occlusion, 4).unwrap();
+
+        let builder = AutoCommandBufferBuilder::new(device, queue.family())
+            .unwrap()
+            .begin_query(pool.clone(), 0, false)
+            .unwrap();
+
+        match builder.begin_query(pool, 1, false) {
+            Err(BeginQueryError::AutoCommandBufferBuilderContextError(
+                AutoCommandBufferBuilderContextError::QueryIsActive)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn begin_query_timestamp() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let pool = QueryPool::new(device.clone(), QueryType::Timestamp, 4).unwrap();
+
+        let builder = AutoCommandBufferBuilder::new(device, queue.family()).unwrap();
+        match builder.begin_query(pool, 0, false) {
+            Err(BeginQueryError::CheckBeginQueryError(CheckBeginQueryError::NotPermitted)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn end_query_not_active() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let pool = QueryPool::new(device.clone(), QueryType::Occlusion, 4).unwrap();
+
+        let builder = AutoCommandBufferBuilder::new(device, queue.family())
+            .unwrap()
+            .begin_query(pool.clone(), 0, false)
+            .unwrap();
+
+        match builder.end_query(pool, 1) {
+            Err(EndQueryError::AutoCommandBufferBuilderContextError(
+                AutoCommandBufferBuilderContextError::QueryNotActive)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn build_with_active_query() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let pool = QueryPool::new(device.clone(), QueryType::Occlusion, 4).unwrap();
+
+        let builder = AutoCommandBufferBuilder::new(device, queue.family())
+            .unwrap()
+            .begin_query(pool, 0, false)
+            .unwrap();
+
+        match builder.build() {
+            Err(BuildError::AutoCommandBufferBuilderContextError(
+                AutoCommandBufferBuilderContextError::QueryIsActive)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn build_with_open_debug_marker() {
+        let (device, queue) = gfx_dev_and_queue!();
+        if !device.instance().loaded_extensions().ext_debug_utils {
+            return;
+        }
+
+        let builder = AutoCommandBufferBuilder::new(device, queue.family())
+            .unwrap()
+            .debug_marker_begin("marker", [1.0, 1.0, 1.0, 1.0])
+            .unwrap();
+
+        match builder.build() {
+            Err(BuildError::AutoCommandBufferBuilderContextError(
+                AutoCommandBufferBuilderContextError::DebugMarkerIsActive)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn query_scope_mismatch() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let pool = QueryPool::new(device.clone(), QueryType::Occlusion, 4).unwrap();
+
+        let render_pass = Arc::new(::single_pass_renderpass!(device.clone(),
+            attachments: {
+                color: {
+                    load: Clear,
+                    store: DontCare,
+                    format: Format::R8G8B8A8Unorm,
+                    samples: 1,
+                }
+            },
+            pass: {
+                color: [color],
+                depth_stencil: {}
+            }
+        ).unwrap());
+
+        let image = AttachmentImage::new(device.clone(), [32, 32], Format::R8G8B8A8Unorm).unwrap();
+        let framebuffer = Arc::new(Framebuffer::start(render_pass)
+                                       .add(image)
+                                       .unwrap()
+                                       .build()
+                                       .unwrap());
+
+        // A query begun outside of a render pass can't be ended inside of it.
+        let builder = AutoCommandBufferBuilder::new(device.clone(), queue.family())
+            .unwrap()
+            .begin_query(pool.clone(), 0, false)
+            .unwrap()
+            .begin_render_pass(framebuffer.clone(), false, vec![[0.0, 0.0, 0.0, 1.0].into()])
+            .unwrap();
+
+        match builder.end_query(pool.clone(), 0) {
+            Err(EndQueryError::AutoCommandBufferBuilderContextError(
+                AutoCommandBufferBuilderContextError::QueryScopeMismatch)) => (),
+            _ => panic!(),
+        }
+
+        // A query begun inside of a subpass must be ended before the end of the render pass.
+        let builder = AutoCommandBufferBuilder::new(device, queue.family())
+            .unwrap()
+            .begin_render_pass(framebuffer, false, vec![[0.0, 0.0, 0.0, 1.0].into()])
+            .unwrap()
+            .begin_query(pool, 0, false)
+            .unwrap();
+
+        match builder.end_render_pass() {
+            Err(AutoCommandBufferBuilderContextError::QueryIsActive) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn pipeline_barrier_checks_all_barriers() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let image = AttachmentImage::new(device.clone(), [16, 16], Format::R8G8B8A8Unorm).unwrap();
+
+        let stages = PipelineStages {
+            color_attachment_output: true,
+            ..PipelineStages::none()
+        };
+        let barrier = PipelineBarrier::new()
+            .image_layout_transition(image.clone(),
+                                     ImageLayout::ColorAttachmentOptimal,
+                                     stages,
+                                     AccessFlagBits::none())
+            .release_image_ownership(image, queue.family());
+
+        let builder = AutoCommandBufferBuilder::new(device, queue.family()).unwrap();
+        match builder.pipeline_barrier(barrier) {
+            Err(PipelineBarrierError::CheckPipelineBarrierError(
+                CheckPipelineBarrierError::SameQueueFamily)) => (),
+            _ => panic!(),
+        }
+    }
+}
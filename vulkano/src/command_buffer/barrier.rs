@@ -0,0 +1,212 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::sync::Arc;
+
+use buffer::BufferAccess;
+use image::ImageAccess;
+use image::ImageLayout;
+use instance::QueueFamily;
+use sync::AccessFlagBits;
+use sync::PipelineStages;
+
+/// A list of explicit barriers, to add to a command buffer with
+/// `AutoCommandBufferBuilder::pipeline_barrier`.
+///
+/// The builder already inserts the barriers that are needed between the commands of a command
+/// buffer. The barriers of this list are for the things it can't guess: choosing where an image
+/// is transitioned to another layout, and transferring the ownership of a resource between two
+/// queue families.
+///
+/// The barriers are checked together before any of them is recorded, and are merged with the
+/// barriers that the builder inserts automatically when possible.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use vulkano::buffer::BufferAccess;
+/// # use vulkano::command_buffer::AutoCommandBufferBuilder;
+/// # use vulkano::command_buffer::PipelineBarrier;
+/// # use vulkano::device::Device;
+/// # use vulkano::device::Queue;
+/// # use vulkano::image::ImageAccess;
+/// # let device: Arc<Device> = return;
+/// # let graphics_queue: Arc<Queue> = return;
+/// # let transfer_queue: Arc<Queue> = return;
+/// # let buffer: Arc<dyn BufferAccess + Send + Sync> = return;
+/// # let image: Arc<dyn ImageAccess + Send + Sync> = return;
+/// // Hand a buffer and an image over from the transfer queue to the graphics queue.
+/// let barrier = PipelineBarrier::new()
+///     .release_buffer_ownership(buffer, graphics_queue.family())
+///     .release_image_ownership(image, graphics_queue.family());
+///
+/// let builder = AutoCommandBufferBuilder::primary(device.clone(), transfer_queue.family())
+///     .unwrap()
+///     .pipeline_barrier(barrier)
+///     .unwrap();
+/// ```
+pub struct PipelineBarrier {
+    pub(super) barriers: Vec<Barrier>,
+}
+
+// A single barrier of a `PipelineBarrier`.
+pub(super) enum Barrier {
+    // Transitions an image to a layout.
+    ImageLayoutTransition {
+        image: Arc<dyn ImageAccess + Send + Sync>,
+        layout: ImageLayout,
+        stages: PipelineStages,
+        access: AccessFlagBits,
+    },
+
+    // Transfers the ownership of a buffer between the queue family of the command buffer and
+    // another one.
+    BufferOwnershipTransfer {
+        buffer: Arc<dyn BufferAccess + Send + Sync>,
+        release: bool,
+        family: FamilyId,
+    },
+
+    // Same as `BufferOwnershipTransfer`, but for an image.
+    ImageOwnershipTransfer {
+        image: Arc<dyn ImageAccess + Send + Sync>,
+        release: bool,
+        family: FamilyId,
+    },
+}
+
+// Identifies a queue family without borrowing its physical device.
+#[derive(Debug, Copy, Clone)]
+pub(super) struct FamilyId {
+    // Index of the physical device of the queue family.
+    pub(super) physical_device: usize,
+    // Id of the queue family within its physical device.
+    pub(super) id: u32,
+}
+
+impl<'a> From<QueueFamily<'a>> for FamilyId {
+    #[inline]
+    fn from(family: QueueFamily<'a>) -> FamilyId {
+        FamilyId {
+            physical_device: family.physical_device().index(),
+            id: family.id(),
+        }
+    }
+}
+
+impl PipelineBarrier {
+    /// Builds an empty list of barriers.
+    #[inline]
+    pub fn new() -> PipelineBarrier {
+        PipelineBarrier { barriers: Vec::new() }
+    }
+
+    /// Returns true if the list doesn't contain any barrier.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.barriers.is_empty()
+    }
+
+    /// Adds a barrier that transitions an image to the given layout.
+    ///
+    /// The barrier waits for all the previous commands that use the image, and makes the image
+    /// available to the given stages with the given accesses. The subsequent commands that use
+    /// the image in another layout transition it again automatically, and the image is
+    /// transitioned back to its final layout requirement at the end of the command buffer.
+    #[inline]
+    pub fn image_layout_transition<I>(mut self, image: I, layout: ImageLayout,
+                                      stages: PipelineStages, access: AccessFlagBits)
+                                      -> PipelineBarrier
+        where I: ImageAccess + Send + Sync + 'static
+    {
+        self.barriers.push(Barrier::ImageLayoutTransition {
+                               image: Arc::new(image),
+                               layout,
+                               stages,
+                               access,
+                           });
+        self
+    }
+
+    /// Adds a barrier that releases the ownership of a buffer to another queue family.
+    ///
+    /// This is the first half of a queue family ownership transfer: the buffer must then be
+    /// acquired with `acquire_buffer_ownership` in a command buffer of `destination`. The buffer
+    /// can't be used anymore in this command buffer after it has been released.
+    ///
+    /// Ownership transfers are only possible for resources that were created with exclusive
+    /// sharing.
+    #[inline]
+    pub fn release_buffer_ownership<B>(mut self, buffer: B, destination: QueueFamily)
+                                       -> PipelineBarrier
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        self.barriers.push(Barrier::BufferOwnershipTransfer {
+                               buffer: Arc::new(buffer),
+                               release: true,
+                               family: destination.into(),
+                           });
+        self
+    }
+
+    /// Adds a barrier that acquires the ownership of a buffer from another queue family.
+    ///
+    /// This is the second half of a queue family ownership transfer: `release_buffer_ownership`
+    /// must have been used with the same buffer in a command buffer of `source`, and that
+    /// command buffer must be executed before this one, for example by waiting on a semaphore.
+    /// The buffer must not have been used before in this command buffer.
+    #[inline]
+    pub fn acquire_buffer_ownership<B>(mut self, buffer: B, source: QueueFamily)
+                                       -> PipelineBarrier
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        self.barriers.push(Barrier::BufferOwnershipTransfer {
+                               buffer: Arc::new(buffer),
+                               release: false,
+                               family: source.into(),
+                           });
+        self
+    }
+
+    /// Adds a barrier that releases the ownership of an image to another queue family.
+    ///
+    /// Same as `release_buffer_ownership`, but for images. The image is transitioned from its
+    /// final layout requirement to its initial layout requirement as part of the transfer, and
+    /// isn't transitioned anymore when the command buffer is built.
+    #[inline]
+    pub fn release_image_ownership<I>(mut self, image: I, destination: QueueFamily)
+                                      -> PipelineBarrier
+        where I: ImageAccess + Send + Sync + 'static
+    {
+        self.barriers.push(Barrier::ImageOwnershipTransfer {
+                               image: Arc::new(image),
+                               release: true,
+                               family: destination.into(),
+                           });
+        self
+    }
+
+    /// Adds a barrier that acquires the ownership of an image from another queue family.
+    ///
+    /// Same as `acquire_buffer_ownership`, but for images. The image is in its initial layout
+    /// requirement after it has been acquired.
+    #[inline]
+    pub fn acquire_image_ownership<I>(mut self, image: I, source: QueueFamily)
+                                      -> PipelineBarrier
+        where I: ImageAccess + Send + Sync + 'static
+    {
+        self.barriers.push(Barrier::ImageOwnershipTransfer {
+                               image: Arc::new(image),
+                               release: false,
+                               family: source.into(),
+                           });
+        self
+    }
+}
@@ -76,27 +76,50 @@
 pub use self::auto::AutoCommandBuffer;
 pub use self::auto::AutoCommandBufferBuilder;
 pub use self::auto::AutoCommandBufferBuilderContextError;
+pub use self::auto::BeginQueryError;
 pub use self::auto::BeginRenderPassError;
+pub use self::auto::BindDescriptorSetsError;
+pub use self::auto::BindIndexBufferError;
+pub use self::auto::BindVertexBuffersError;
 pub use self::auto::BlitImageError;
 pub use self::auto::BuildError;
+pub use self::auto::ClearAttachmentsError;
 pub use self::auto::ClearColorImageError;
+pub use self::auto::ClearDepthStencilImageError;
 pub use self::auto::CopyBufferError;
 pub use self::auto::CopyBufferImageError;
 pub use self::auto::CopyImageError;
+pub use self::auto::CopyQueryPoolResultsError;
+pub use self::auto::DebugMarkerError;
 pub use self::auto::DispatchError;
+pub use self::auto::DispatchIndirectError;
+pub use self::auto::DrawBoundError;
 pub use self::auto::DrawError;
 pub use self::auto::DrawIndexedError;
+pub use self::auto::DrawIndexedIndirectCountError;
 pub use self::auto::DrawIndexedIndirectError;
+pub use self::auto::DrawIndirectCountError;
 pub use self::auto::DrawIndirectError;
+pub use self::auto::EndQueryError;
+pub use self::auto::EventError;
 pub use self::auto::ExecuteCommandsError;
 pub use self::auto::FillBufferError;
+pub use self::auto::GenerateMipmapsError;
+pub use self::auto::PipelineBarrierError;
+pub use self::auto::PushConstantsError;
+pub use self::auto::ResetQueryPoolError;
+pub use self::auto::ResolveImageError;
+pub use self::auto::SetDynamicStateError;
 pub use self::auto::UpdateBufferError;
+pub use self::auto::WriteTimestampError;
+pub use self::barrier::PipelineBarrier;
 pub use self::state_cacher::StateCacher;
 pub use self::state_cacher::StateCacherOutcome;
 pub use self::traits::CommandBuffer;
 pub use self::traits::CommandBufferExecError;
 pub use self::traits::CommandBufferExecFuture;
 
+use format::ClearValue;
 use pipeline::viewport::Scissor;
 use pipeline::viewport::Viewport;
 use pipeline::depth_stencil::DynamicStencilValue;
@@ -108,6 +131,7 @@ pub mod sys;
 pub mod validity;
 
 mod auto;
+mod barrier;
 mod state_cacher;
 mod traits;
 
@@ -138,7 +162,40 @@ pub struct DispatchIndirectCommand {
     pub z: u32,
 }
 
+/// An attachment of the current subpass to clear with `clear_attachments`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ClearAttachment {
+    /// Clears a color attachment. The index is the index of the attachment in the list of color
+    /// attachments of the subpass, and the value must be a color value matching its format.
+    Color(u32, ClearValue),
+    /// Clears the depth aspect of the depth-stencil attachment of the subpass. The depth value
+    /// must be between 0.0 and 1.0.
+    Depth(f32),
+    /// Clears the stencil aspect of the depth-stencil attachment of the subpass.
+    Stencil(u32),
+    /// Clears both aspects of the depth-stencil attachment of the subpass. The depth value must
+    /// be between 0.0 and 1.0.
+    DepthStencil((f32, u32)),
+}
+
+/// A region of the framebuffer to clear with `clear_attachments`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ClearRect {
+    /// Coordinates of the top-left hand corner of the rectangle, in pixels.
+    pub rect_offset: [u32; 2],
+    /// Width and height of the rectangle, in pixels.
+    pub rect_extent: [u32; 2],
+    /// First layer of the framebuffer to clear.
+    pub base_array_layer: u32,
+    /// Number of layers of the framebuffer to clear.
+    pub layer_count: u32,
+}
+
 /// The dynamic state to use for a draw command.
+///
+/// When drawing with an `AutoCommandBufferBuilder`, the states that are `None` but dynamic in
+/// the pipeline are taken from the values that were set earlier in the command buffer, for
+/// example with `set_viewport`.
 // TODO: probably not the right location
 #[derive(Debug, Clone)]
 pub struct DynamicState {
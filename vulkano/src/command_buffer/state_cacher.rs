@@ -13,7 +13,10 @@ use command_buffer::DynamicState;
 use descriptor::DescriptorSet;
 use pipeline::ComputePipelineAbstract;
 use pipeline::GraphicsPipelineAbstract;
+use pipeline::depth_stencil::DynamicStencilValue;
 use pipeline::input_assembly::IndexType;
+use pipeline::viewport::Scissor;
+use pipeline::viewport::Viewport;
 use smallvec::SmallVec;
 use std::ops::Range;
 use vk;
@@ -26,6 +29,12 @@ use vk;
 pub struct StateCacher {
     // The dynamic state to synchronize with `CmdSetState`.
     dynamic_state: DynamicState,
+    // The depth bias currently set, as `(constant_factor, clamp, slope_factor)`.
+    depth_bias: Option<(f32, f32, f32)>,
+    // The depth bounds currently set, as `(min, max)`.
+    depth_bounds: Option<(f32, f32)>,
+    // The blend constants currently set.
+    blend_constants: Option<[f32; 4]>,
     // The compute pipeline currently bound. 0 if nothing bound.
     compute_pipeline: vk::Pipeline,
     // The graphics pipeline currently bound. 0 if nothing bound.
@@ -62,6 +71,9 @@ impl StateCacher {
     pub fn new() -> StateCacher {
         StateCacher {
             dynamic_state: DynamicState::none(),
+            depth_bias: None,
+            depth_bounds: None,
+            blend_constants: None,
             compute_pipeline: 0,
             graphics_pipeline: 0,
             compute_descriptor_sets: SmallVec::new(),
@@ -78,6 +90,9 @@ impl StateCacher {
     #[inline]
     pub fn invalidate(&mut self) {
         self.dynamic_state = DynamicState::none();
+        self.depth_bias = None;
+        self.depth_bounds = None;
+        self.blend_constants = None;
         self.compute_pipeline = 0;
         self.graphics_pipeline = 0;
         self.compute_descriptor_sets = SmallVec::new();
@@ -115,6 +130,140 @@ impl StateCacher {
         changed
     }
 
+    /// Returns a copy of `incoming` where the states that are dynamic in `pipeline` and that
+    /// `incoming` doesn't specify are replaced with the states currently set.
+    ///
+    /// This lets draw commands use the states that were set with the explicit setters of the
+    /// command buffer builder.
+    pub fn complete_dynamic_state<P>(&self, pipeline: &P, incoming: &DynamicState)
+                                     -> DynamicState
+        where P: ?Sized + GraphicsPipelineAbstract
+    {
+        let mut result = incoming.clone();
+
+        macro_rules! complete {
+            ($field:ident, $is_dynamic:ident) => (
+                if result.$field.is_none() && pipeline.$is_dynamic() {
+                    result.$field = self.dynamic_state.$field.clone();
+                }
+            );
+        }
+
+        complete!(line_width, has_dynamic_line_width);
+        complete!(viewports, has_dynamic_viewports);
+        complete!(scissors, has_dynamic_scissors);
+        complete!(compare_mask, has_dynamic_stencil_compare_mask);
+        complete!(reference, has_dynamic_stencil_reference);
+        complete!(write_mask, has_dynamic_stencil_write_mask);
+
+        result
+    }
+
+    /// Returns the depth bias currently set, as `(constant_factor, clamp, slope_factor)`.
+    #[inline]
+    pub fn depth_bias(&self) -> Option<(f32, f32, f32)> {
+        self.depth_bias
+    }
+
+    /// Returns the depth bounds currently set, as `(min, max)`.
+    #[inline]
+    pub fn depth_bounds(&self) -> Option<(f32, f32)> {
+        self.depth_bounds
+    }
+
+    /// Returns the blend constants currently set.
+    #[inline]
+    pub fn blend_constants(&self) -> Option<[f32; 4]> {
+        self.blend_constants
+    }
+
+    /// Checks whether we need to set the line width.
+    ///
+    /// This function also updates the state cacher. The state cacher assumes that the state
+    /// changes are going to be performed after this function returns.
+    #[inline]
+    pub fn set_line_width(&mut self, line_width: f32) -> StateCacherOutcome {
+        compare_and_set(&mut self.dynamic_state.line_width, line_width)
+    }
+
+    /// Checks whether we need to set viewports, starting at `first_viewport`.
+    ///
+    /// This function also updates the state cacher. The state cacher assumes that the state
+    /// changes are going to be performed after this function returns.
+    #[inline]
+    pub fn set_viewport(&mut self, first_viewport: u32, viewports: &[Viewport])
+                        -> StateCacherOutcome {
+        compare_and_set_range(&mut self.dynamic_state.viewports, first_viewport, viewports)
+    }
+
+    /// Checks whether we need to set scissors, starting at `first_scissor`.
+    ///
+    /// This function also updates the state cacher. The state cacher assumes that the state
+    /// changes are going to be performed after this function returns.
+    #[inline]
+    pub fn set_scissor(&mut self, first_scissor: u32, scissors: &[Scissor])
+                       -> StateCacherOutcome {
+        compare_and_set_range(&mut self.dynamic_state.scissors, first_scissor, scissors)
+    }
+
+    /// Checks whether we need to set the depth bias.
+    ///
+    /// This function also updates the state cacher. The state cacher assumes that the state
+    /// changes are going to be performed after this function returns.
+    #[inline]
+    pub fn set_depth_bias(&mut self, constant_factor: f32, clamp: f32, slope_factor: f32)
+                          -> StateCacherOutcome {
+        compare_and_set(&mut self.depth_bias, (constant_factor, clamp, slope_factor))
+    }
+
+    /// Checks whether we need to set the depth bounds.
+    ///
+    /// This function also updates the state cacher. The state cacher assumes that the state
+    /// changes are going to be performed after this function returns.
+    #[inline]
+    pub fn set_depth_bounds(&mut self, min: f32, max: f32) -> StateCacherOutcome {
+        compare_and_set(&mut self.depth_bounds, (min, max))
+    }
+
+    /// Checks whether we need to set the blend constants.
+    ///
+    /// This function also updates the state cacher. The state cacher assumes that the state
+    /// changes are going to be performed after this function returns.
+    #[inline]
+    pub fn set_blend_constants(&mut self, constants: [f32; 4]) -> StateCacherOutcome {
+        compare_and_set(&mut self.blend_constants, constants)
+    }
+
+    /// Checks whether we need to set the stencil compare mask.
+    ///
+    /// This function also updates the state cacher. The state cacher assumes that the state
+    /// changes are going to be performed after this function returns.
+    #[inline]
+    pub fn set_stencil_compare_mask(&mut self, compare_mask: DynamicStencilValue)
+                                    -> StateCacherOutcome {
+        compare_and_set(&mut self.dynamic_state.compare_mask, compare_mask)
+    }
+
+    /// Checks whether we need to set the stencil write mask.
+    ///
+    /// This function also updates the state cacher. The state cacher assumes that the state
+    /// changes are going to be performed after this function returns.
+    #[inline]
+    pub fn set_stencil_write_mask(&mut self, write_mask: DynamicStencilValue)
+                                  -> StateCacherOutcome {
+        compare_and_set(&mut self.dynamic_state.write_mask, write_mask)
+    }
+
+    /// Checks whether we need to set the stencil reference.
+    ///
+    /// This function also updates the state cacher. The state cacher assumes that the state
+    /// changes are going to be performed after this function returns.
+    #[inline]
+    pub fn set_stencil_reference(&mut self, reference: DynamicStencilValue)
+                                 -> StateCacherOutcome {
+        compare_and_set(&mut self.dynamic_state.reference, reference)
+    }
+
     /// Starts the process of comparing a list of descriptor sets to the descriptor sets currently
     /// in cache.
     ///
@@ -159,6 +308,26 @@ impl StateCacher {
             StateCacherOutcome::AlreadyOk
         } else {
             self.graphics_pipeline = inner;
+
+            // Binding a pipeline overwrites the states that it doesn't declare as dynamic.
+            macro_rules! forget_static {
+                ($field:expr, $is_dynamic:ident) => (
+                    if !pipeline.$is_dynamic() {
+                        $field = None;
+                    }
+                );
+            }
+
+            forget_static!(self.dynamic_state.line_width, has_dynamic_line_width);
+            forget_static!(self.dynamic_state.viewports, has_dynamic_viewports);
+            forget_static!(self.dynamic_state.scissors, has_dynamic_scissors);
+            forget_static!(self.dynamic_state.compare_mask, has_dynamic_stencil_compare_mask);
+            forget_static!(self.dynamic_state.write_mask, has_dynamic_stencil_write_mask);
+            forget_static!(self.dynamic_state.reference, has_dynamic_stencil_reference);
+            forget_static!(self.depth_bias, has_dynamic_depth_bias);
+            forget_static!(self.depth_bounds, has_dynamic_depth_bounds);
+            forget_static!(self.blend_constants, has_dynamic_blend_constants);
+
             StateCacherOutcome::NeedChange
         }
     }
@@ -230,6 +399,57 @@ impl StateCacher {
     }
 }
 
+// Compares `current` with `incoming`, and updates `current`.
+#[inline]
+fn compare_and_set<T>(current: &mut Option<T>, incoming: T) -> StateCacherOutcome
+    where T: PartialEq
+{
+    if current.as_ref() == Some(&incoming) {
+        StateCacherOutcome::AlreadyOk
+    } else {
+        *current = Some(incoming);
+        StateCacherOutcome::NeedChange
+    }
+}
+
+// Same as `compare_and_set`, but for a range of elements of a list starting at `first`. If the
+// elements before `first` are unknown, the list is forgotten.
+fn compare_and_set_range<T>(current: &mut Option<Vec<T>>, first: u32, incoming: &[T])
+                            -> StateCacherOutcome
+    where T: Clone + PartialEq
+{
+    let first = first as usize;
+
+    match *current {
+        Some(ref mut list) if first <= list.len() => {
+            if list.len() >= first + incoming.len() &&
+                &list[first .. first + incoming.len()] == incoming
+            {
+                return StateCacherOutcome::AlreadyOk;
+            }
+
+            for (offset, value) in incoming.iter().enumerate() {
+                if first + offset < list.len() {
+                    list[first + offset] = value.clone();
+                } else {
+                    list.push(value.clone());
+                }
+            }
+
+            return StateCacherOutcome::NeedChange;
+        },
+        _ => (),
+    }
+
+    *current = if first == 0 {
+        Some(incoming.to_vec())
+    } else {
+        None
+    };
+
+    StateCacherOutcome::NeedChange
+}
+
 /// Helper struct for comparing descriptor sets.
 ///
 /// > **Note**: For reliability reasons, if you drop/leak this struct before calling `compare` then
@@ -359,6 +579,8 @@ mod tests {
     use buffer::BufferUsage;
     use buffer::CpuAccessibleBuffer;
     use command_buffer::state_cacher::StateCacher;
+    use command_buffer::state_cacher::StateCacherOutcome;
+    use pipeline::viewport::Scissor;
 
     #[test]
     fn vb_caching_single() {
@@ -470,4 +692,40 @@ mod tests {
             assert_eq!(bind_vb.compare(), Some(0 .. 1));
         }
     }
+
+    #[test]
+    fn dynamic_state_caching() {
+        let mut cacher = StateCacher::new();
+
+        assert_eq!(cacher.set_line_width(2.0), StateCacherOutcome::NeedChange);
+        assert_eq!(cacher.set_line_width(2.0), StateCacherOutcome::AlreadyOk);
+        assert_eq!(cacher.set_line_width(1.0), StateCacherOutcome::NeedChange);
+
+        assert_eq!(cacher.set_blend_constants([0.0; 4]), StateCacherOutcome::NeedChange);
+        assert_eq!(cacher.set_blend_constants([0.0; 4]), StateCacherOutcome::AlreadyOk);
+
+        cacher.invalidate();
+        assert_eq!(cacher.set_line_width(1.0), StateCacherOutcome::NeedChange);
+        assert_eq!(cacher.blend_constants(), None);
+    }
+
+    #[test]
+    fn scissor_range_caching() {
+        let mut cacher = StateCacher::new();
+        let a = Scissor::irrelevant();
+        let b = Scissor {
+            origin: [1, 2],
+            dimensions: [3, 4],
+        };
+
+        // The first scissor is unknown, so the cache can't be used.
+        assert_eq!(cacher.set_scissor(1, &[b]), StateCacherOutcome::NeedChange);
+        assert_eq!(cacher.set_scissor(1, &[b]), StateCacherOutcome::NeedChange);
+
+        assert_eq!(cacher.set_scissor(0, &[a, b]), StateCacherOutcome::NeedChange);
+        assert_eq!(cacher.set_scissor(1, &[b]), StateCacherOutcome::AlreadyOk);
+        assert_eq!(cacher.set_scissor(0, &[a]), StateCacherOutcome::AlreadyOk);
+        assert_eq!(cacher.set_scissor(1, &[a]), StateCacherOutcome::NeedChange);
+        assert_eq!(cacher.set_scissor(0, &[a, a]), StateCacherOutcome::AlreadyOk);
+    }
 }
@@ -37,6 +37,7 @@ use image::ImageLayout;
 use sync::AccessCheckError;
 use sync::AccessError;
 use sync::AccessFlagBits;
+use sync::Event;
 use sync::GpuFuture;
 use sync::PipelineStages;
 
@@ -75,6 +76,15 @@ pub struct SyncCommandBufferBuilder<P> {
 
     // True if we're a secondary command buffer.
     is_secondary: bool,
+
+    // True if the builder is allowed to use events instead of pipeline barriers when there are
+    // other commands between the two commands that collide. Events can't be used if the command
+    // buffer can be executed multiple times simultaneously.
+    use_events: bool,
+
+    // Events that have been created for the split barriers. They must be kept alive as long as
+    // the command buffer.
+    events: Vec<Arc<Event>>,
 }
 
 // # How pipeline stages work in Vulkan
@@ -152,6 +162,9 @@ pub enum SyncCommandBufferBuilderError {
         command2_param: Cow<'static, str>,
         command2_offset: usize,
     },
+
+    /// Not enough memory to allocate the event of a split barrier.
+    OomError(OomError),
 }
 
 impl error::Error for SyncCommandBufferBuilderError {
@@ -161,10 +174,28 @@ impl error::Error for SyncCommandBufferBuilderError {
             SyncCommandBufferBuilderError::Conflict { .. } => {
                 "unsolvable conflict"
             },
+            SyncCommandBufferBuilderError::OomError(_) => {
+                "not enough memory to allocate the event of a split barrier"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            SyncCommandBufferBuilderError::OomError(ref err) => Some(err),
+            _ => None,
         }
     }
 }
 
+impl From<OomError> for SyncCommandBufferBuilderError {
+    #[inline]
+    fn from(err: OomError) -> SyncCommandBufferBuilderError {
+        SyncCommandBufferBuilderError::OomError(err)
+    }
+}
+
 impl fmt::Display for SyncCommandBufferBuilderError {
     #[inline]
     fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
@@ -172,6 +203,30 @@ impl fmt::Display for SyncCommandBufferBuilderError {
     }
 }
 
+// Builds a `Conflict` error between two resources, each designated by the index of its command,
+// its type and its index within the command.
+fn conflict_error<P>(commands_lock: &Commands<P>, first: (usize, KeyTy, usize),
+                     second: (usize, KeyTy, usize))
+                     -> SyncCommandBufferBuilderError {
+    let param_name = |cmd: &dyn Command<P>, ty, index| match ty {
+        KeyTy::Buffer => cmd.buffer_name(index),
+        KeyTy::Image => cmd.image_name(index),
+    };
+
+    let cmd1 = &*commands_lock.commands[first.0];
+    let cmd2 = &*commands_lock.commands[second.0];
+
+    SyncCommandBufferBuilderError::Conflict {
+        command1_name: cmd1.name(),
+        command1_param: param_name(cmd1, first.1, first.2),
+        command1_offset: first.0,
+
+        command2_name: cmd2.name(),
+        command2_param: param_name(cmd2, second.1, second.2),
+        command2_offset: second.0,
+    }
+}
+
 // List of commands stored inside a `SyncCommandBufferBuilder`.
 struct Commands<P> {
     // Only the commands before `first_unflushed` have already been sent to the inner
@@ -228,6 +283,17 @@ pub enum KeyTy {
     Image,
 }
 
+/// Direction of a queue family ownership transfer of a resource.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OwnershipTransfer {
+    /// The ownership of the resource is released to another queue family. The resource can't be
+    /// used anymore afterwards in the same command buffer.
+    Release,
+    /// The ownership of the resource is acquired from another queue family. This must be the
+    /// first command that uses the resource in the command buffer.
+    Acquire,
+}
+
 // Key that identifies a resource. Implements `PartialEq`, `Eq` and `Hash` so that two resources
 // that conflict with each other compare equal.
 //
@@ -349,6 +415,12 @@ struct ResourceState {
 
     // Current layout at this stage of the building.
     current_layout: ImageLayout,
+
+    // Index of the last command that used this resource.
+    last_command_id: usize,
+
+    // True if the ownership of the resource was released to another queue family.
+    released: bool,
 }
 
 impl ResourceState {
@@ -386,8 +458,20 @@ impl<P> SyncCommandBufferBuilder<P> {
             Kind::Secondary { ref render_pass, .. } => (true, render_pass.is_some()),
         };
 
+        // Events can only be used on queue families that support graphics or compute operations.
+        let family = pool.queue_family();
+        let use_events = match flags {
+            Flags::SimultaneousUse => false,
+            Flags::None | Flags::OneTimeSubmit => {
+                family.supports_graphics() || family.supports_compute()
+            },
+        };
+
         let cmd = UnsafeCommandBufferBuilder::new(pool, kind, flags)?;
-        Ok(SyncCommandBufferBuilder::from_unsafe_cmd(cmd, is_secondary, inside_render_pass))
+        let mut builder = SyncCommandBufferBuilder::from_unsafe_cmd(cmd, is_secondary,
+                                                                    inside_render_pass);
+        builder.use_events = use_events;
+        Ok(builder)
     }
 
     /// Builds a `SyncCommandBufferBuilder` from an existing `UnsafeCommandBufferBuilder`.
@@ -399,6 +483,9 @@ impl<P> SyncCommandBufferBuilder<P> {
     /// In addition to this, the `UnsafeCommandBufferBuilder` should be empty. If it isn't, then
     /// you must take into account the fact that the `SyncCommandBufferBuilder` won't be aware of
     /// any existing resource usage.
+    ///
+    /// Since the builder doesn't know whether the command buffer can be executed multiple times
+    /// simultaneously, it will only use pipeline barriers and never events.
     #[inline]
     pub unsafe fn from_unsafe_cmd(cmd: UnsafeCommandBufferBuilder<P>, is_secondary: bool,
                                   inside_render_pass: bool)
@@ -415,6 +502,8 @@ impl<P> SyncCommandBufferBuilder<P> {
                                               commands: Vec::new(),
                                           })),
             is_secondary,
+            use_events: false,
+            events: Vec::new(),
         }
     }
 
@@ -466,6 +555,27 @@ impl<P> SyncCommandBufferBuilder<P> {
                                     access: AccessFlagBits, start_layout: ImageLayout,
                                     end_layout: ImageLayout)
                                     -> Result<(), SyncCommandBufferBuilderError> {
+        self.prev_cmd_resource_transfer(resource_ty,
+                                        resource_index,
+                                        exclusive,
+                                        stages,
+                                        access,
+                                        start_layout,
+                                        end_layout,
+                                        None)
+    }
+
+    // Same as `prev_cmd_resource`, but for a command that may also transfer the ownership of
+    // the resource between queue families.
+    //
+    // Returns an error if the resource is used after its ownership was released, or if its
+    // ownership is acquired after it was already used.
+    pub(super) fn prev_cmd_resource_transfer(&mut self, resource_ty: KeyTy,
+                                             resource_index: usize, exclusive: bool,
+                                             stages: PipelineStages, access: AccessFlagBits,
+                                             start_layout: ImageLayout, end_layout: ImageLayout,
+                                             transfer: Option<OwnershipTransfer>)
+                                             -> Result<(), SyncCommandBufferBuilderError> {
         // Anti-dumbness checks.
         debug_assert!(exclusive || start_layout == end_layout);
         debug_assert!(access.is_compatible_with(&stages));
@@ -502,15 +612,77 @@ impl<P> SyncCommandBufferBuilder<P> {
                 let entry_key_resource_ty = entry.key().resource_ty;
                 let entry = entry.into_mut();
 
+                // A barrier can't solve these situations.
+                if entry.released || transfer == Some(OwnershipTransfer::Acquire) {
+                    let commands_lock = self.commands.lock().unwrap();
+                    return Err(conflict_error(&commands_lock,
+                                              (collision_cmd_id,
+                                               entry_key_resource_ty,
+                                               entry_key_resource_index),
+                                              (latest_command_id, resource_ty, resource_index)));
+                }
+
                 // Find out if we have a collision with the pending commands.
                 if exclusive || entry.exclusive || entry.current_layout != start_layout {
                     // Collision found between `latest_command_id` and `collision_cmd_id`.
 
+                    // If there are other commands between the last command that used the
+                    // resource and the current one, we split the barrier with an event: the
+                    // event is set right after the last usage, and we wait for it right before
+                    // the current command. This lets the commands in between run without
+                    // waiting.
+                    let split_event = {
+                        let outside_render_pass = self.commands
+                            .lock()
+                            .unwrap()
+                            .latest_render_pass_enter
+                            .is_none();
+
+                        if self.use_events && outside_render_pass && !entry.stages.host &&
+                            entry.last_command_id >= first_unflushed_cmd_id &&
+                            latest_command_id - entry.last_command_id > 1
+                        {
+                            Some(Arc::new(Event::from_pool(self.inner.device().clone())?))
+                        } else {
+                            None
+                        }
+                    };
+
+                    if let Some(ref event) = split_event {
+                        unsafe {
+                            // Flush the pending barrier.
+                            self.inner.pipeline_barrier(&self.pending_barrier);
+                            self.pending_barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
+
+                            // Flush the commands up to the last usage, set the event, then flush
+                            // the commands in between. The event is reset right before being set
+                            // rather than after the wait, so that it is unsignaled again when the
+                            // command buffer is executed another time.
+                            let mut commands_lock = self.commands.lock().unwrap();
+                            let start = commands_lock.first_unflushed;
+                            let producer = entry.last_command_id;
+                            for command in &mut commands_lock.commands[start ..= producer] {
+                                command.send(&mut self.inner);
+                            }
+                            self.inner.reset_event(event, entry.stages);
+                            self.inner.set_event(event, entry.stages);
+                            for command in
+                                &mut commands_lock.commands[producer + 1 .. latest_command_id]
+                            {
+                                command.send(&mut self.inner);
+                            }
+                            commands_lock.first_unflushed = latest_command_id;
+                        }
+                    }
+
                     // We now want to modify the current pipeline barrier in order to handle the
                     // collision. But since the pipeline barrier is going to be submitted before
                     // the flushed commands, it would be a mistake if `collision_cmd_id` hasn't
                     // been flushed yet.
-                    if collision_cmd_id >= first_unflushed_cmd_id || entry.current_layout != start_layout {
+                    if split_event.is_none() &&
+                        (collision_cmd_id >= first_unflushed_cmd_id ||
+                             entry.current_layout != start_layout)
+                    {
                         unsafe {
                             // Flush the pending barrier.
                             self.inner.pipeline_barrier(&self.pending_barrier);
@@ -528,26 +700,13 @@ impl<P> SyncCommandBufferBuilder<P> {
                                     latest_command_id
                                 };
                                 if collision_cmd_id >= end {
-                                    let cmd1 = &commands_lock.commands[collision_cmd_id];
-                                    let cmd2 = &commands_lock.commands[latest_command_id];
-                                    return Err(SyncCommandBufferBuilderError::Conflict {
-                                                   command1_name: cmd1.name(),
-                                                   command1_param: match entry_key_resource_ty {
-                                                       KeyTy::Buffer => cmd1.buffer_name(entry_key_resource_index),
-                                                       KeyTy::Image =>
-                                                           cmd1.image_name(entry_key_resource_index),
-                                                   },
-                                                   command1_offset: collision_cmd_id,
-
-                                                   command2_name: cmd2.name(),
-                                                   command2_param: match resource_ty {
-                                                       KeyTy::Buffer =>
-                                                           cmd2.buffer_name(resource_index),
-                                                       KeyTy::Image =>
-                                                           cmd2.image_name(resource_index),
-                                                   },
-                                                   command2_offset: latest_command_id,
-                                               });
+                                    return Err(conflict_error(&commands_lock,
+                                                              (collision_cmd_id,
+                                                               entry_key_resource_ty,
+                                                               entry_key_resource_index),
+                                                              (latest_command_id,
+                                                               resource_ty,
+                                                               resource_index)));
                                 }
                                 for command in &mut commands_lock.commands[start .. end] {
                                     command.send(&mut self.inner);
@@ -557,7 +716,9 @@ impl<P> SyncCommandBufferBuilder<P> {
                         }
                     }
 
-                    // Modify the pipeline barrier to handle the collision.
+                    // Modify the pipeline barrier to handle the collision. In case of a split
+                    // barrier, the barrier is passed to the wait instead.
+                    let mut event_barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
                     unsafe {
                         let commands_lock = self.commands.lock().unwrap();
                         match resource_ty {
@@ -565,7 +726,11 @@ impl<P> SyncCommandBufferBuilder<P> {
                                 let buf = commands_lock.commands[latest_command_id]
                                     .buffer(resource_index);
 
-                                let b = &mut self.pending_barrier;
+                                let b = if split_event.is_some() {
+                                    &mut event_barrier
+                                } else {
+                                    &mut self.pending_barrier
+                                };
                                 b.add_buffer_memory_barrier(buf,
                                                             entry.stages,
                                                             entry.access,
@@ -581,7 +746,11 @@ impl<P> SyncCommandBufferBuilder<P> {
                                 let img = commands_lock.commands[latest_command_id]
                                     .image(resource_index);
 
-                                let b = &mut self.pending_barrier;
+                                let b = if split_event.is_some() {
+                                    &mut event_barrier
+                                } else {
+                                    &mut self.pending_barrier
+                                };
                                 b.add_image_memory_barrier(img,
                                                            0 .. img.mipmap_levels(),
                                                            0 .. img.dimensions().array_layers(),
@@ -597,6 +766,13 @@ impl<P> SyncCommandBufferBuilder<P> {
                         };
                     }
 
+                    if let Some(event) = split_event {
+                        unsafe {
+                            self.inner.wait_events(Some(&*event), &event_barrier);
+                        }
+                        self.events.push(event);
+                    }
+
                     // Update state.
                     entry.stages = stages;
                     entry.access = access;
@@ -608,6 +784,8 @@ impl<P> SyncCommandBufferBuilder<P> {
                         // layout to `Undefined`, a buffer read must not touch it.
                         entry.current_layout = end_layout;
                     }
+                    entry.last_command_id = latest_command_id;
+                    entry.released = transfer == Some(OwnershipTransfer::Release);
 
                 } else {
                     // There is no collision. Simply merge the stages and accesses.
@@ -615,6 +793,7 @@ impl<P> SyncCommandBufferBuilder<P> {
                     //       this would simplify the job of the driver, but is it worth it?
                     entry.stages = entry.stages | stages;
                     entry.access = entry.access | access;
+                    entry.last_command_id = latest_command_id;
                 }
             },
 
@@ -691,6 +870,8 @@ impl<P> SyncCommandBufferBuilder<P> {
                     exclusive: actually_exclusive,
                     initial_layout: actual_start_layout,
                     current_layout: end_layout,     // TODO: what if we reach the end with Undefined? that's not correct?
+                    last_command_id: latest_command_id,
+                    released: transfer == Some(OwnershipTransfer::Release),
                 });
             },
         }
@@ -724,7 +905,8 @@ impl<P> SyncCommandBufferBuilder<P> {
                 let mut barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
 
                 for (key, state) in &mut self.resources {
-                    if key.resource_ty != KeyTy::Image {
+                    // Images whose ownership was released can't be transitioned anymore.
+                    if key.resource_ty != KeyTy::Image || state.released {
                         continue;
                     }
 
@@ -780,6 +962,7 @@ impl<P> SyncCommandBufferBuilder<P> {
                inner: self.inner.build()?,
                resources: final_resources_states,
                commands: final_commands,
+               events: self.events,
            })
     }
 }
@@ -804,6 +987,9 @@ pub struct SyncCommandBuffer<P> {
     // being used. Each element of `resources` has a copy of this `Arc`, but we need to keep one
     // here in case `resources` is empty.
     commands: Arc<Mutex<Vec<Box<dyn FinalCommand + Send + Sync>>>>,
+
+    // Events used by the split barriers of this command buffer. Kept alive here.
+    events: Vec<Arc<Event>>,
 }
 
 // Usage of a resource in a finished command buffer.
@@ -1048,6 +1234,12 @@ impl<P> AsRef<UnsafeCommandBuffer<P>> for SyncCommandBuffer<P> {
 }
 
 impl<P> SyncCommandBuffer<P> {
+    // Returns the events used by the split barriers of this command buffer.
+    #[cfg(test)]
+    pub(super) fn events(&self) -> &[Arc<Event>] {
+        &self.events
+    }
+
     /// Tries to lock the resources used by the command buffer.
     ///
     /// > **Note**: You should call this in the implementation of the `CommandBuffer` trait.
@@ -10,24 +10,35 @@
 use smallvec::SmallVec;
 use std::any::Any;
 use std::borrow::Cow;
+use std::ffi::CString;
+use std::iter;
 use std::mem;
+use std::ops::Range;
 use std::ptr;
 use std::sync::Arc;
 
 use buffer::BufferAccess;
+use buffer::TypedBufferAccess;
+use command_buffer::ClearAttachment;
+use command_buffer::ClearRect;
 use command_buffer::CommandBuffer;
 use command_buffer::synced::base::Command;
 use command_buffer::synced::base::FinalCommand;
 use command_buffer::synced::base::KeyTy;
+use command_buffer::synced::base::OwnershipTransfer;
 use command_buffer::synced::base::SyncCommandBufferBuilder;
 use command_buffer::synced::base::SyncCommandBufferBuilderError;
 use command_buffer::sys::UnsafeCommandBufferBuilder;
 use command_buffer::sys::UnsafeCommandBufferBuilderBindVertexBuffer;
 use command_buffer::sys::UnsafeCommandBufferBuilderBufferImageCopy;
 use command_buffer::sys::UnsafeCommandBufferBuilderColorImageClear;
+use command_buffer::sys::UnsafeCommandBufferBuilderDepthStencilImageClear;
 use command_buffer::sys::UnsafeCommandBufferBuilderExecuteCommands;
+use command_buffer::sys::UnsafeCommandBufferBuilderImageAspect;
 use command_buffer::sys::UnsafeCommandBufferBuilderImageCopy;
 use command_buffer::sys::UnsafeCommandBufferBuilderImageBlit;
+use command_buffer::sys::UnsafeCommandBufferBuilderImageResolve;
+use command_buffer::sys::UnsafeCommandBufferBuilderPipelineBarrier;
 use descriptor::descriptor::DescriptorDescTy;
 use descriptor::descriptor::ShaderStages;
 use descriptor::descriptor_set::DescriptorSet;
@@ -44,12 +55,57 @@ use pipeline::viewport::Scissor;
 use pipeline::viewport::Viewport;
 use pipeline::depth_stencil::DynamicStencilValue;
 use pipeline::depth_stencil::StencilFaceFlags;
+use query::QueryPoolAbstract;
+use query::QueryResultElement;
+use query::QueryResultFlags;
 use sampler::Filter;
 use sync::AccessFlagBits;
 use sync::Event;
 use sync::PipelineStages;
 
 impl<P> SyncCommandBufferBuilder<P> {
+    /// Calls `vkCmdBeginQuery` on the builder.
+    #[inline]
+    pub unsafe fn begin_query<Q>(&mut self, query_pool: Q, query: u32, precise: bool)
+        where Q: QueryPoolAbstract + Send + Sync + 'static
+    {
+        struct Cmd<Q> {
+            query_pool: Q,
+            query: u32,
+            precise: bool,
+        }
+
+        impl<P, Q> Command<P> for Cmd<Q>
+            where Q: QueryPoolAbstract + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdBeginQuery"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.begin_query(self.query_pool.inner().query(self.query).unwrap(), self.precise);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<Q>(Q);
+                impl<Q> FinalCommand for Fin<Q>
+                    where Q: QueryPoolAbstract + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdBeginQuery"
+                    }
+                }
+                Box::new(Fin(self.query_pool))
+            }
+        }
+
+        self.append_command(Cmd {
+                                query_pool,
+                                query,
+                                precise,
+                            });
+    }
+
     /// Calls `vkBeginRenderPass` on the builder.
     // TODO: it shouldn't be possible to get an error if the framebuffer checked conflicts already
     // TODO: after begin_render_pass has been called, flushing should be forbidden and an error
@@ -302,6 +358,99 @@ impl<P> SyncCommandBufferBuilder<P> {
         }
     }
 
+    /// Adds a pipeline barrier that releases or acquires the ownership of a buffer between
+    /// `source_family` and `destination_family`.
+    ///
+    /// The transfer must be performed twice: once with `OwnershipTransfer::Release` in a command
+    /// buffer of the source queue family, then once with `OwnershipTransfer::Acquire` in a
+    /// command buffer of the destination queue family. The buffer can't be used after it has
+    /// been released, and it must not have been used before it is acquired.
+    pub unsafe fn buffer_ownership_transfer<B>(&mut self, buffer: B, transfer: OwnershipTransfer,
+                                               source_family: u32, destination_family: u32)
+                                               -> Result<(), SyncCommandBufferBuilderError>
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        struct Cmd<B> {
+            buffer: B,
+            transfer: OwnershipTransfer,
+            families: (u32, u32),
+        }
+
+        impl<P, B> Command<P> for Cmd<B>
+            where B: BufferAccess + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdPipelineBarrier"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                let (src_stages, src_access, dst_stages, dst_access) =
+                    ownership_transfer_barrier(self.transfer);
+                let mut barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
+                barrier.add_buffer_memory_barrier(&self.buffer,
+                                                  src_stages,
+                                                  src_access,
+                                                  dst_stages,
+                                                  dst_access,
+                                                  false,
+                                                  Some(self.families),
+                                                  0,
+                                                  self.buffer.size());
+                out.pipeline_barrier(&barrier);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<B>(B);
+                impl<B> FinalCommand for Fin<B>
+                    where B: BufferAccess + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdPipelineBarrier"
+                    }
+                    fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                        "buffer".into()
+                    }
+                }
+                Box::new(Fin(self.buffer))
+            }
+
+            fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                assert_eq!(num, 0);
+                &self.buffer
+            }
+
+            fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                "buffer".into()
+            }
+        }
+
+        self.append_command(Cmd {
+                                buffer,
+                                transfer,
+                                families: (source_family, destination_family),
+                            });
+        self.prev_cmd_resource_transfer(KeyTy::Buffer,
+                                        0,
+                                        true,
+                                        PipelineStages {
+                                            all_commands: true,
+                                            ..PipelineStages::none()
+                                        },
+                                        AccessFlagBits {
+                                            memory_read: true,
+                                            memory_write: true,
+                                            ..AccessFlagBits::none()
+                                        },
+                                        ImageLayout::Undefined,
+                                        ImageLayout::Undefined,
+                                        Some(transfer))?;
+        Ok(())
+    }
+
     /// Calls `vkCmdCopyImage` on the builder.
     ///
     /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
@@ -564,6 +713,44 @@ impl<P> SyncCommandBufferBuilder<P> {
         Ok(())
     }
 
+    /// Calls `vkCmdClearAttachments` on the builder.
+    ///
+    /// Does nothing if the list of attachments or the list of rects is empty, as it would be a
+    /// no-op and isn't a valid usage of the command anyway.
+    #[inline]
+    pub unsafe fn clear_attachments<A, R>(&mut self, attachments: A, rects: R)
+        where A: Iterator<Item = ClearAttachment> + Send + Sync + 'static,
+              R: Iterator<Item = ClearRect> + Send + Sync + 'static
+    {
+        struct Cmd<A, R> {
+            attachments: Option<A>,
+            rects: Option<R>,
+        }
+
+        impl<P, A, R> Command<P> for Cmd<A, R>
+            where A: Iterator<Item = ClearAttachment>,
+                  R: Iterator<Item = ClearRect>
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdClearAttachments"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.clear_attachments(self.attachments.take().unwrap(),
+                                      self.rects.take().unwrap());
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                Box::new("vkCmdClearAttachments")
+            }
+        }
+
+        self.append_command(Cmd {
+                                attachments: Some(attachments),
+                                rects: Some(rects),
+                            });
+    }
+
     /// Calls `vkCmdClearColorImage` on the builder.
     ///
     /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
@@ -654,6 +841,102 @@ impl<P> SyncCommandBufferBuilder<P> {
         Ok(())
     }
 
+    /// Calls `vkCmdClearDepthStencilImage` on the builder.
+    ///
+    /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
+    /// usage of the command anyway.
+    pub unsafe fn clear_depth_stencil_image<I, R>(&mut self, image: I, layout: ImageLayout,
+                                                  depth: f32, stencil: u32, regions: R)
+                                                  -> Result<(), SyncCommandBufferBuilderError>
+        where I: ImageAccess + Send + Sync + 'static,
+              R: Iterator<Item = UnsafeCommandBufferBuilderDepthStencilImageClear>
+                     + Send
+                     + Sync
+                     + 'static
+    {
+        struct Cmd<I, R> {
+            image: Option<I>,
+            layout: ImageLayout,
+            depth: f32,
+            stencil: u32,
+            regions: Option<R>,
+        }
+
+        impl<P, I, R> Command<P> for Cmd<I, R>
+            where I: ImageAccess + Send + Sync + 'static,
+                  R: Iterator<Item = UnsafeCommandBufferBuilderDepthStencilImageClear>
+                         + Send
+                         + Sync
+                         + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdClearDepthStencilImage"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.clear_depth_stencil_image(self.image.as_ref().unwrap(),
+                                              self.layout,
+                                              self.depth,
+                                              self.stencil,
+                                              self.regions.take().unwrap());
+            }
+
+            fn into_final_command(mut self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<I>(I);
+                impl<I> FinalCommand for Fin<I>
+                    where I: ImageAccess + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdClearDepthStencilImage"
+                    }
+                    fn image(&self, num: usize) -> &dyn ImageAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn image_name(&self, num: usize) -> Cow<'static, str> {
+                        assert_eq!(num, 0);
+                        "target".into()
+                    }
+                }
+
+                // Note: borrow checker somehow doesn't accept `self.image` without using an Option.
+                Box::new(Fin(self.image.take().unwrap()))
+            }
+
+            fn image(&self, num: usize) -> &dyn ImageAccess {
+                assert_eq!(num, 0);
+                self.image.as_ref().unwrap()
+            }
+
+            fn image_name(&self, num: usize) -> Cow<'static, str> {
+                assert_eq!(num, 0);
+                "target".into()
+            }
+        }
+
+        self.append_command(Cmd {
+                                image: Some(image),
+                                layout,
+                                depth,
+                                stencil,
+                                regions: Some(regions),
+                            });
+        self.prev_cmd_resource(KeyTy::Image,
+                               0,
+                               true,
+                               PipelineStages {
+                                   transfer: true,
+                                   ..PipelineStages::none()
+                               },
+                               AccessFlagBits {
+                                   transfer_write: true,
+                                   ..AccessFlagBits::none()
+                               },
+                               layout,
+                               layout)?;
+        Ok(())
+    }
+
     /// Calls `vkCmdCopyBuffer` on the builder.
     ///
     /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
@@ -1015,92 +1298,87 @@ impl<P> SyncCommandBufferBuilder<P> {
         Ok(())
     }
 
-    /// Calls `vkCmdDispatch` on the builder.
-    #[inline]
-    pub unsafe fn dispatch(&mut self, dimensions: [u32; 3]) {
-        struct Cmd {
-            dimensions: [u32; 3],
-        }
-
-        impl<P> Command<P> for Cmd {
-            fn name(&self) -> &'static str {
-                "vkCmdDispatch"
-            }
-
-            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.dispatch(self.dimensions);
-            }
-
-            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
-                Box::new("vkCmdDispatch")
-            }
-        }
-
-        self.append_command(Cmd { dimensions });
-    }
-
-    /// Calls `vkCmdDispatchIndirect` on the builder.
+    /// Calls `vkCmdCopyQueryPoolResults` on the builder.
     #[inline]
-    pub unsafe fn dispatch_indirect<B>(&mut self, buffer: B)
-                                       -> Result<(), SyncCommandBufferBuilderError>
-        where B: BufferAccess + Send + Sync + 'static
+    pub unsafe fn copy_query_pool_results<Q, D, T>(&mut self, query_pool: Q, queries: Range<u32>,
+                                                   destination: D, stride: usize,
+                                                   flags: QueryResultFlags)
+                                                   -> Result<(), SyncCommandBufferBuilderError>
+        where Q: QueryPoolAbstract + Send + Sync + 'static,
+              D: TypedBufferAccess<Content = [T]> + Send + Sync + 'static,
+              T: QueryResultElement
     {
-        struct Cmd<B> {
-            buffer: B,
-        }
-
-        impl<P, B> Command<P> for Cmd<B>
-            where B: BufferAccess + Send + Sync + 'static
+        struct Cmd<Q, D> {
+            query_pool: Q,
+            queries: Range<u32>,
+            destination: D,
+            stride: usize,
+            flags: QueryResultFlags,
+        }
+
+        impl<P, Q, D, T> Command<P> for Cmd<Q, D>
+            where Q: QueryPoolAbstract + Send + Sync + 'static,
+                  D: TypedBufferAccess<Content = [T]> + Send + Sync + 'static,
+                  T: QueryResultElement
         {
             fn name(&self) -> &'static str {
-                "vkCmdDispatchIndirect"
+                "vkCmdCopyQueryPoolResults"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.dispatch_indirect(&self.buffer);
+                let queries = self.query_pool
+                    .inner()
+                    .queries_range(self.queries.start, self.queries.end - self.queries.start)
+                    .unwrap();
+                out.copy_query_pool_results(queries, &self.destination, self.stride, self.flags);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
-                struct Fin<B>(B);
-                impl<B> FinalCommand for Fin<B>
-                    where B: BufferAccess + Send + Sync + 'static
+                struct Fin<Q, D>(Q, D);
+                impl<Q, D> FinalCommand for Fin<Q, D>
+                    where Q: QueryPoolAbstract + Send + Sync + 'static,
+                          D: BufferAccess + Send + Sync + 'static
                 {
                     fn name(&self) -> &'static str {
-                        "vkCmdDispatchIndirect"
+                        "vkCmdCopyQueryPoolResults"
                     }
                     fn buffer(&self, num: usize) -> &dyn BufferAccess {
                         assert_eq!(num, 0);
-                        &self.0
+                        &self.1
                     }
-                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
-                        assert_eq!(num, 0);
-                        "indirect buffer".into()
+                    fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                        "destination".into()
                     }
                 }
-                Box::new(Fin(self.buffer))
+                Box::new(Fin(self.query_pool, self.destination))
             }
 
             fn buffer(&self, num: usize) -> &dyn BufferAccess {
                 assert_eq!(num, 0);
-                &self.buffer
+                &self.destination
             }
 
-            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
-                assert_eq!(num, 0);
-                "indirect buffer".into()
+            fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                "destination".into()
             }
         }
 
-        self.append_command(Cmd { buffer });
+        self.append_command(Cmd {
+                                query_pool,
+                                queries,
+                                destination,
+                                stride,
+                                flags,
+                            });
         self.prev_cmd_resource(KeyTy::Buffer,
                                0,
-                               false,
+                               true,
                                PipelineStages {
-                                   draw_indirect: true,
+                                   transfer: true,
                                    ..PipelineStages::none()
-                               }, // TODO: is draw_indirect correct?
+                               },
                                AccessFlagBits {
-                                   indirect_command_read: true,
+                                   transfer_write: true,
                                    ..AccessFlagBits::none()
                                },
                                ImageLayout::Undefined,
@@ -1108,46 +1386,211 @@ impl<P> SyncCommandBufferBuilder<P> {
         Ok(())
     }
 
-    /// Calls `vkCmdDraw` on the builder.
+    /// Calls `vkCmdBeginDebugUtilsLabelEXT` on the builder.
     #[inline]
-    pub unsafe fn draw(&mut self, vertex_count: u32, instance_count: u32, first_vertex: u32,
-                       first_instance: u32) {
+    pub unsafe fn debug_marker_begin(&mut self, name: CString, color: [f32; 4]) {
         struct Cmd {
-            vertex_count: u32,
-            instance_count: u32,
-            first_vertex: u32,
-            first_instance: u32,
+            name: CString,
+            color: [f32; 4],
         }
 
         impl<P> Command<P> for Cmd {
             fn name(&self) -> &'static str {
-                "vkCmdDraw"
+                "vkCmdBeginDebugUtilsLabelEXT"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.draw(self.vertex_count,
-                         self.instance_count,
-                         self.first_vertex,
-                         self.first_instance);
+                out.debug_marker_begin(&self.name, self.color);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
-                Box::new("vkCmdDraw")
+                Box::new("vkCmdBeginDebugUtilsLabelEXT")
             }
         }
 
-        self.append_command(Cmd {
-                                vertex_count,
-                                instance_count,
-                                first_vertex,
-                                first_instance,
-                            });
-
+        self.append_command(Cmd { name, color });
     }
 
-    /// Calls `vkCmdDrawIndexed` on the builder.
+    /// Calls `vkCmdEndDebugUtilsLabelEXT` on the builder.
     #[inline]
-    pub unsafe fn draw_indexed(&mut self, index_count: u32, instance_count: u32,
+    pub unsafe fn debug_marker_end(&mut self) {
+        struct Cmd;
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdEndDebugUtilsLabelEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.debug_marker_end();
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                Box::new("vkCmdEndDebugUtilsLabelEXT")
+            }
+        }
+
+        self.append_command(Cmd);
+    }
+
+    /// Calls `vkCmdInsertDebugUtilsLabelEXT` on the builder.
+    #[inline]
+    pub unsafe fn debug_marker_insert(&mut self, name: CString, color: [f32; 4]) {
+        struct Cmd {
+            name: CString,
+            color: [f32; 4],
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdInsertDebugUtilsLabelEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.debug_marker_insert(&self.name, self.color);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                Box::new("vkCmdInsertDebugUtilsLabelEXT")
+            }
+        }
+
+        self.append_command(Cmd { name, color });
+    }
+
+    /// Calls `vkCmdDispatch` on the builder.
+    #[inline]
+    pub unsafe fn dispatch(&mut self, dimensions: [u32; 3]) {
+        struct Cmd {
+            dimensions: [u32; 3],
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdDispatch"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.dispatch(self.dimensions);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                Box::new("vkCmdDispatch")
+            }
+        }
+
+        self.append_command(Cmd { dimensions });
+    }
+
+    /// Calls `vkCmdDispatchIndirect` on the builder.
+    #[inline]
+    pub unsafe fn dispatch_indirect<B>(&mut self, buffer: B)
+                                       -> Result<(), SyncCommandBufferBuilderError>
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        struct Cmd<B> {
+            buffer: B,
+        }
+
+        impl<P, B> Command<P> for Cmd<B>
+            where B: BufferAccess + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdDispatchIndirect"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.dispatch_indirect(&self.buffer);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<B>(B);
+                impl<B> FinalCommand for Fin<B>
+                    where B: BufferAccess + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdDispatchIndirect"
+                    }
+                    fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        assert_eq!(num, 0);
+                        "indirect buffer".into()
+                    }
+                }
+                Box::new(Fin(self.buffer))
+            }
+
+            fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                assert_eq!(num, 0);
+                &self.buffer
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                assert_eq!(num, 0);
+                "indirect buffer".into()
+            }
+        }
+
+        self.append_command(Cmd { buffer });
+        self.prev_cmd_resource(KeyTy::Buffer,
+                               0,
+                               false,
+                               PipelineStages {
+                                   draw_indirect: true,
+                                   ..PipelineStages::none()
+                               }, // TODO: is draw_indirect correct?
+                               AccessFlagBits {
+                                   indirect_command_read: true,
+                                   ..AccessFlagBits::none()
+                               },
+                               ImageLayout::Undefined,
+                               ImageLayout::Undefined)?;
+        Ok(())
+    }
+
+    /// Calls `vkCmdDraw` on the builder.
+    #[inline]
+    pub unsafe fn draw(&mut self, vertex_count: u32, instance_count: u32, first_vertex: u32,
+                       first_instance: u32) {
+        struct Cmd {
+            vertex_count: u32,
+            instance_count: u32,
+            first_vertex: u32,
+            first_instance: u32,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdDraw"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.draw(self.vertex_count,
+                         self.instance_count,
+                         self.first_vertex,
+                         self.first_instance);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                Box::new("vkCmdDraw")
+            }
+        }
+
+        self.append_command(Cmd {
+                                vertex_count,
+                                instance_count,
+                                first_vertex,
+                                first_instance,
+                            });
+
+    }
+
+    /// Calls `vkCmdDrawIndexed` on the builder.
+    #[inline]
+    pub unsafe fn draw_indexed(&mut self, index_count: u32, instance_count: u32,
                                first_index: u32, vertex_offset: i32, first_instance: u32) {
         struct Cmd {
             index_count: u32,
@@ -1259,6 +1702,107 @@ impl<P> SyncCommandBufferBuilder<P> {
         Ok(())
     }
 
+    /// Calls `vkCmdDrawIndirectCountKHR` on the builder.
+    #[inline]
+    pub unsafe fn draw_indirect_count<B, C>(&mut self, buffer: B, count_buffer: C,
+                                            max_draw_count: u32, stride: u32)
+                                            -> Result<(), SyncCommandBufferBuilderError>
+        where B: BufferAccess + Send + Sync + 'static,
+              C: BufferAccess + Send + Sync + 'static
+    {
+        struct Cmd<B, C> {
+            buffer: B,
+            count_buffer: C,
+            max_draw_count: u32,
+            stride: u32,
+        }
+
+        impl<P, B, C> Command<P> for Cmd<B, C>
+            where B: BufferAccess + Send + Sync + 'static,
+                  C: BufferAccess + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdDrawIndirectCountKHR"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.draw_indirect_count(&self.buffer,
+                                        &self.count_buffer,
+                                        self.max_draw_count,
+                                        self.stride);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<B, C>(B, C);
+                impl<B, C> FinalCommand for Fin<B, C>
+                    where B: BufferAccess + Send + Sync + 'static,
+                          C: BufferAccess + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdDrawIndirectCountKHR"
+                    }
+                    fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                        match num {
+                            0 => &self.0,
+                            1 => &self.1,
+                            _ => panic!(),
+                        }
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        match num {
+                            0 => "indirect buffer".into(),
+                            1 => "count buffer".into(),
+                            _ => panic!(),
+                        }
+                    }
+                }
+                let cmd = *self;
+                Box::new(Fin(cmd.buffer, cmd.count_buffer))
+            }
+
+            fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                match num {
+                    0 => &self.buffer,
+                    1 => &self.count_buffer,
+                    _ => panic!(),
+                }
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                match num {
+                    0 => "indirect buffer".into(),
+                    1 => "count buffer".into(),
+                    _ => panic!(),
+                }
+            }
+        }
+
+        self.append_command(Cmd {
+                                buffer,
+                                count_buffer,
+                                max_draw_count,
+                                stride,
+                            });
+
+        for num in 0 .. 2 {
+            self.prev_cmd_resource(KeyTy::Buffer,
+                                   num,
+                                   false,
+                                   PipelineStages {
+                                       draw_indirect: true,
+                                       ..PipelineStages::none()
+                                   },
+                                   AccessFlagBits {
+                                       indirect_command_read: true,
+                                       ..AccessFlagBits::none()
+                                   },
+                                   ImageLayout::Undefined,
+                                   ImageLayout::Undefined)?;
+        }
+
+        Ok(())
+    }
+
     /// Calls `vkCmdDrawIndexedIndirect` on the builder.
     #[inline]
     pub unsafe fn draw_indexed_indirect<B>(&mut self, buffer: B, draw_count: u32, stride: u32)
@@ -1334,6 +1878,144 @@ impl<P> SyncCommandBufferBuilder<P> {
         Ok(())
     }
 
+    /// Calls `vkCmdDrawIndexedIndirectCountKHR` on the builder.
+    #[inline]
+    pub unsafe fn draw_indexed_indirect_count<B, C>(&mut self, buffer: B, count_buffer: C,
+                                                    max_draw_count: u32, stride: u32)
+                                                    -> Result<(), SyncCommandBufferBuilderError>
+        where B: BufferAccess + Send + Sync + 'static,
+              C: BufferAccess + Send + Sync + 'static
+    {
+        struct Cmd<B, C> {
+            buffer: B,
+            count_buffer: C,
+            max_draw_count: u32,
+            stride: u32,
+        }
+
+        impl<P, B, C> Command<P> for Cmd<B, C>
+            where B: BufferAccess + Send + Sync + 'static,
+                  C: BufferAccess + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdDrawIndexedIndirectCountKHR"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.draw_indexed_indirect_count(&self.buffer,
+                                                &self.count_buffer,
+                                                self.max_draw_count,
+                                                self.stride);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<B, C>(B, C);
+                impl<B, C> FinalCommand for Fin<B, C>
+                    where B: BufferAccess + Send + Sync + 'static,
+                          C: BufferAccess + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdDrawIndexedIndirectCountKHR"
+                    }
+                    fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                        match num {
+                            0 => &self.0,
+                            1 => &self.1,
+                            _ => panic!(),
+                        }
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        match num {
+                            0 => "indirect buffer".into(),
+                            1 => "count buffer".into(),
+                            _ => panic!(),
+                        }
+                    }
+                }
+                let cmd = *self;
+                Box::new(Fin(cmd.buffer, cmd.count_buffer))
+            }
+
+            fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                match num {
+                    0 => &self.buffer,
+                    1 => &self.count_buffer,
+                    _ => panic!(),
+                }
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                match num {
+                    0 => "indirect buffer".into(),
+                    1 => "count buffer".into(),
+                    _ => panic!(),
+                }
+            }
+        }
+
+        self.append_command(Cmd {
+                                buffer,
+                                count_buffer,
+                                max_draw_count,
+                                stride,
+                            });
+
+        for num in 0 .. 2 {
+            self.prev_cmd_resource(KeyTy::Buffer,
+                                   num,
+                                   false,
+                                   PipelineStages {
+                                       draw_indirect: true,
+                                       ..PipelineStages::none()
+                                   },
+                                   AccessFlagBits {
+                                       indirect_command_read: true,
+                                       ..AccessFlagBits::none()
+                                   },
+                                   ImageLayout::Undefined,
+                                   ImageLayout::Undefined)?;
+        }
+
+        Ok(())
+    }
+
+    /// Calls `vkCmdEndQuery` on the builder.
+    #[inline]
+    pub unsafe fn end_query<Q>(&mut self, query_pool: Q, query: u32)
+        where Q: QueryPoolAbstract + Send + Sync + 'static
+    {
+        struct Cmd<Q> {
+            query_pool: Q,
+            query: u32,
+        }
+
+        impl<P, Q> Command<P> for Cmd<Q>
+            where Q: QueryPoolAbstract + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdEndQuery"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.end_query(self.query_pool.inner().query(self.query).unwrap());
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<Q>(Q);
+                impl<Q> FinalCommand for Fin<Q>
+                    where Q: QueryPoolAbstract + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdEndQuery"
+                    }
+                }
+                Box::new(Fin(self.query_pool))
+            }
+        }
+
+        self.append_command(Cmd { query_pool, query });
+    }
+
     /// Calls `vkCmdEndRenderPass` on the builder.
     #[inline]
     pub unsafe fn end_render_pass(&mut self) {
@@ -1348,91 +2030,395 @@ impl<P> SyncCommandBufferBuilder<P> {
                 out.end_render_pass();
             }
 
-            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
-                Box::new("vkCmdEndRenderPass")
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                Box::new("vkCmdEndRenderPass")
+            }
+        }
+
+        self.append_command(Cmd);
+        self.prev_cmd_left_render_pass();
+    }
+
+    /// Starts the process of executing secondary command buffers. Returns an intermediate struct
+    /// which can be used to add the command buffers.
+    #[inline]
+    pub unsafe fn execute_commands(&mut self) -> SyncCommandBufferBuilderExecuteCommands<P> {
+        SyncCommandBufferBuilderExecuteCommands {
+            builder: self,
+            inner: UnsafeCommandBufferBuilderExecuteCommands::new(),
+            command_buffers: Vec::new(),
+        }
+    }
+
+    /// Calls `vkCmdFillBuffer` on the builder.
+    #[inline]
+    pub unsafe fn fill_buffer<B>(&mut self, buffer: B, data: u32)
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        struct Cmd<B> {
+            buffer: B,
+            data: u32,
+        }
+
+        impl<P, B> Command<P> for Cmd<B>
+            where B: BufferAccess + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdFillBuffer"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.fill_buffer(&self.buffer, self.data);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<B>(B);
+                impl<B> FinalCommand for Fin<B>
+                    where B: BufferAccess + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdFillBuffer"
+                    }
+                    fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                        "destination".into()
+                    }
+                }
+                Box::new(Fin(self.buffer))
+            }
+
+            fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                assert_eq!(num, 0);
+                &self.buffer
+            }
+
+            fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                "destination".into()
+            }
+        }
+
+        self.append_command(Cmd { buffer, data });
+        self.prev_cmd_resource(KeyTy::Buffer,
+                               0,
+                               true,
+                               PipelineStages {
+                                   transfer: true,
+                                   ..PipelineStages::none()
+                               },
+                               AccessFlagBits {
+                                   transfer_write: true,
+                                   ..AccessFlagBits::none()
+                               },
+                               ImageLayout::Undefined,
+                               ImageLayout::Undefined)
+            .unwrap();
+    }
+
+    /// Generates the mipmaps of `image` by successively blitting each mipmap level into the next
+    /// one.
+    ///
+    /// All the mipmap levels are expected to be in the `TransferDstOptimal` layout, and the
+    /// content of the first level must have been written. The levels are transitioned one by one
+    /// to the `TransferSrcOptimal` layout as they are read from, which is the layout the whole
+    /// image is in afterwards.
+    pub unsafe fn generate_mipmaps<I>(&mut self, image: I, filter: Filter)
+                                      -> Result<(), SyncCommandBufferBuilderError>
+        where I: ImageAccess + Send + Sync + 'static
+    {
+        struct Cmd<I> {
+            image: I,
+            filter: Filter,
+        }
+
+        impl<P, I> Command<P> for Cmd<I>
+            where I: ImageAccess + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdBlitImage"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                let image = &self.image;
+                let dimensions = image.dimensions();
+                let layers = dimensions.array_layers();
+
+                // Makes the writes to a mipmap level visible to the blits that read from it.
+                let transition_to_source = |out: &mut UnsafeCommandBufferBuilder<P>, level| {
+                    let mut barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
+                    barrier.add_image_memory_barrier(image,
+                                                     level .. level + 1,
+                                                     0 .. layers,
+                                                     PipelineStages {
+                                                         transfer: true,
+                                                         ..PipelineStages::none()
+                                                     },
+                                                     AccessFlagBits {
+                                                         transfer_write: true,
+                                                         ..AccessFlagBits::none()
+                                                     },
+                                                     PipelineStages {
+                                                         transfer: true,
+                                                         ..PipelineStages::none()
+                                                     },
+                                                     AccessFlagBits {
+                                                         transfer_read: true,
+                                                         ..AccessFlagBits::none()
+                                                     },
+                                                     false,
+                                                     None,
+                                                     ImageLayout::TransferDstOptimal,
+                                                     ImageLayout::TransferSrcOptimal);
+                    out.pipeline_barrier(&barrier);
+                };
+
+                let aspect = UnsafeCommandBufferBuilderImageAspect {
+                    color: image.has_color(),
+                    depth: !image.has_color() && image.has_depth(),
+                    stencil: !image.has_color() && image.has_stencil(),
+                };
+
+                let to_offset = |level| {
+                    let dims = dimensions.mipmap_dimensions(level).unwrap();
+                    [dims.width() as i32, dims.height() as i32, dims.depth() as i32]
+                };
+
+                for level in 1 .. image.mipmap_levels() {
+                    transition_to_source(out, level - 1);
+
+                    let blit = UnsafeCommandBufferBuilderImageBlit {
+                        aspect,
+                        source_mip_level: level - 1,
+                        destination_mip_level: level,
+                        source_base_array_layer: 0,
+                        destination_base_array_layer: 0,
+                        layer_count: layers,
+                        source_top_left: [0, 0, 0],
+                        source_bottom_right: to_offset(level - 1),
+                        destination_top_left: [0, 0, 0],
+                        destination_bottom_right: to_offset(level),
+                    };
+
+                    out.blit_image(image,
+                                   ImageLayout::TransferSrcOptimal,
+                                   image,
+                                   ImageLayout::TransferDstOptimal,
+                                   iter::once(blit),
+                                   self.filter);
+                }
+
+                transition_to_source(out, image.mipmap_levels() - 1);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<I>(I);
+                impl<I> FinalCommand for Fin<I>
+                    where I: ImageAccess + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdBlitImage"
+                    }
+                    fn image(&self, num: usize) -> &dyn ImageAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn image_name(&self, _: usize) -> Cow<'static, str> {
+                        "image".into()
+                    }
+                }
+                Box::new(Fin(self.image))
+            }
+
+            fn image(&self, num: usize) -> &dyn ImageAccess {
+                assert_eq!(num, 0);
+                &self.image
+            }
+
+            fn image_name(&self, _: usize) -> Cow<'static, str> {
+                "image".into()
+            }
+        }
+
+        self.append_command(Cmd { image, filter });
+        self.prev_cmd_resource(KeyTy::Image,
+                               0,
+                               true,
+                               PipelineStages {
+                                   transfer: true,
+                                   ..PipelineStages::none()
+                               },
+                               AccessFlagBits {
+                                   transfer_read: true,
+                                   transfer_write: true,
+                                   ..AccessFlagBits::none()
+                               },
+                               ImageLayout::TransferDstOptimal,
+                               ImageLayout::TransferSrcOptimal)?;
+        Ok(())
+    }
+
+    /// Adds a pipeline barrier that transitions `image` to `layout`.
+    ///
+    /// The barrier itself is generated by the automatic synchronization, which waits for the
+    /// previous usages of the image and makes it available to `stages` with `access`. The image
+    /// is still transitioned back to its final layout requirement when the command buffer is
+    /// built.
+    pub unsafe fn image_layout_transition<I>(&mut self, image: I, layout: ImageLayout,
+                                             stages: PipelineStages, access: AccessFlagBits)
+                                             -> Result<(), SyncCommandBufferBuilderError>
+        where I: ImageAccess + Send + Sync + 'static
+    {
+        struct Cmd<I> {
+            image: I,
+        }
+
+        impl<P, I> Command<P> for Cmd<I>
+            where I: ImageAccess + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdPipelineBarrier"
+            }
+
+            unsafe fn send(&mut self, _: &mut UnsafeCommandBufferBuilder<P>) {
+                // The barrier has already been added by `prev_cmd_resource`.
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<I>(I);
+                impl<I> FinalCommand for Fin<I>
+                    where I: ImageAccess + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdPipelineBarrier"
+                    }
+                    fn image(&self, num: usize) -> &dyn ImageAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn image_name(&self, _: usize) -> Cow<'static, str> {
+                        "image".into()
+                    }
+                }
+                Box::new(Fin(self.image))
+            }
+
+            fn image(&self, num: usize) -> &dyn ImageAccess {
+                assert_eq!(num, 0);
+                &self.image
+            }
+
+            fn image_name(&self, _: usize) -> Cow<'static, str> {
+                "image".into()
             }
         }
 
-        self.append_command(Cmd);
-        self.prev_cmd_left_render_pass();
-    }
-
-    /// Starts the process of executing secondary command buffers. Returns an intermediate struct
-    /// which can be used to add the command buffers.
-    #[inline]
-    pub unsafe fn execute_commands(&mut self) -> SyncCommandBufferBuilderExecuteCommands<P> {
-        SyncCommandBufferBuilderExecuteCommands {
-            builder: self,
-            inner: UnsafeCommandBufferBuilderExecuteCommands::new(),
-            command_buffers: Vec::new(),
-        }
+        self.append_command(Cmd { image });
+        self.prev_cmd_resource(KeyTy::Image, 0, true, stages, access, layout, layout)?;
+        Ok(())
     }
 
-    /// Calls `vkCmdFillBuffer` on the builder.
-    #[inline]
-    pub unsafe fn fill_buffer<B>(&mut self, buffer: B, data: u32)
-        where B: BufferAccess + Send + Sync + 'static
+    /// Adds a pipeline barrier that releases or acquires the ownership of an image between
+    /// `source_family` and `destination_family`.
+    ///
+    /// Same as `buffer_ownership_transfer`, but for images. The image is expected to be in its
+    /// final layout requirement when it is released, and is transitioned to its initial layout
+    /// requirement as part of the transfer.
+    pub unsafe fn image_ownership_transfer<I>(&mut self, image: I, transfer: OwnershipTransfer,
+                                              source_family: u32, destination_family: u32)
+                                              -> Result<(), SyncCommandBufferBuilderError>
+        where I: ImageAccess + Send + Sync + 'static
     {
-        struct Cmd<B> {
-            buffer: B,
-            data: u32,
+        struct Cmd<I> {
+            image: I,
+            transfer: OwnershipTransfer,
+            families: (u32, u32),
         }
 
-        impl<P, B> Command<P> for Cmd<B>
-            where B: BufferAccess + Send + Sync + 'static
+        impl<P, I> Command<P> for Cmd<I>
+            where I: ImageAccess + Send + Sync + 'static
         {
             fn name(&self) -> &'static str {
-                "vkCmdFillBuffer"
+                "vkCmdPipelineBarrier"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.fill_buffer(&self.buffer, self.data);
+                let (src_stages, src_access, dst_stages, dst_access) =
+                    ownership_transfer_barrier(self.transfer);
+                let mut barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
+                barrier.add_image_memory_barrier(&self.image,
+                                                 0 .. self.image.mipmap_levels(),
+                                                 0 .. self.image.dimensions().array_layers(),
+                                                 src_stages,
+                                                 src_access,
+                                                 dst_stages,
+                                                 dst_access,
+                                                 false,
+                                                 Some(self.families),
+                                                 self.image.final_layout_requirement(),
+                                                 self.image.initial_layout_requirement());
+                out.pipeline_barrier(&barrier);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
-                struct Fin<B>(B);
-                impl<B> FinalCommand for Fin<B>
-                    where B: BufferAccess + Send + Sync + 'static
+                struct Fin<I>(I);
+                impl<I> FinalCommand for Fin<I>
+                    where I: ImageAccess + Send + Sync + 'static
                 {
                     fn name(&self) -> &'static str {
-                        "vkCmdFillBuffer"
+                        "vkCmdPipelineBarrier"
                     }
-                    fn buffer(&self, num: usize) -> &dyn BufferAccess {
+                    fn image(&self, num: usize) -> &dyn ImageAccess {
                         assert_eq!(num, 0);
                         &self.0
                     }
-                    fn buffer_name(&self, _: usize) -> Cow<'static, str> {
-                        "destination".into()
+                    fn image_name(&self, _: usize) -> Cow<'static, str> {
+                        "image".into()
                     }
                 }
-                Box::new(Fin(self.buffer))
+                Box::new(Fin(self.image))
             }
 
-            fn buffer(&self, num: usize) -> &dyn BufferAccess {
+            fn image(&self, num: usize) -> &dyn ImageAccess {
                 assert_eq!(num, 0);
-                &self.buffer
+                &self.image
             }
 
-            fn buffer_name(&self, _: usize) -> Cow<'static, str> {
-                "destination".into()
+            fn image_name(&self, _: usize) -> Cow<'static, str> {
+                "image".into()
             }
         }
 
-        self.append_command(Cmd { buffer, data });
-        self.prev_cmd_resource(KeyTy::Buffer,
-                               0,
-                               true,
-                               PipelineStages {
-                                   transfer: true,
-                                   ..PipelineStages::none()
-                               },
-                               AccessFlagBits {
-                                   transfer_write: true,
-                                   ..AccessFlagBits::none()
-                               },
-                               ImageLayout::Undefined,
-                               ImageLayout::Undefined)
-            .unwrap();
+        // When releasing, the image must be in its final layout before the barrier. When
+        // acquiring, the barrier leaves the image in its initial layout.
+        let start_layout = match transfer {
+            OwnershipTransfer::Release => image.final_layout_requirement(),
+            OwnershipTransfer::Acquire => image.initial_layout_requirement(),
+        };
+        let end_layout = image.initial_layout_requirement();
+
+        self.append_command(Cmd {
+                                image,
+                                transfer,
+                                families: (source_family, destination_family),
+                            });
+        self.prev_cmd_resource_transfer(KeyTy::Image,
+                                        0,
+                                        true,
+                                        PipelineStages {
+                                            all_commands: true,
+                                            ..PipelineStages::none()
+                                        },
+                                        AccessFlagBits {
+                                            memory_read: true,
+                                            memory_write: true,
+                                            ..AccessFlagBits::none()
+                                        },
+                                        start_layout,
+                                        end_layout,
+                                        Some(transfer))?;
+        Ok(())
     }
 
     /// Calls `vkCmdNextSubpass` on the builder.
@@ -1550,6 +2536,180 @@ impl<P> SyncCommandBufferBuilder<P> {
         self.append_command(Cmd { event, stages });
     }
 
+    /// Calls `vkCmdResetQueryPool` on the builder.
+    #[inline]
+    pub unsafe fn reset_query_pool<Q>(&mut self, query_pool: Q, queries: Range<u32>)
+        where Q: QueryPoolAbstract + Send + Sync + 'static
+    {
+        struct Cmd<Q> {
+            query_pool: Q,
+            queries: Range<u32>,
+        }
+
+        impl<P, Q> Command<P> for Cmd<Q>
+            where Q: QueryPoolAbstract + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdResetQueryPool"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                let queries = self.query_pool
+                    .inner()
+                    .queries_range(self.queries.start, self.queries.end - self.queries.start)
+                    .unwrap();
+                out.reset_query_pool(queries);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<Q>(Q);
+                impl<Q> FinalCommand for Fin<Q>
+                    where Q: QueryPoolAbstract + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdResetQueryPool"
+                    }
+                }
+                Box::new(Fin(self.query_pool))
+            }
+        }
+
+        self.append_command(Cmd {
+                                query_pool,
+                                queries,
+                            });
+    }
+
+    /// Calls `vkCmdResolveImage` on the builder.
+    ///
+    /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
+    /// usage of the command anyway.
+    #[inline]
+    pub unsafe fn resolve_image<S, D, R>(&mut self, source: S, source_layout: ImageLayout,
+                                         destination: D, destination_layout: ImageLayout,
+                                         regions: R)
+                                         -> Result<(), SyncCommandBufferBuilderError>
+        where S: ImageAccess + Send + Sync + 'static,
+              D: ImageAccess + Send + Sync + 'static,
+              R: Iterator<Item = UnsafeCommandBufferBuilderImageResolve> + Send + Sync + 'static
+    {
+        struct Cmd<S, D, R> {
+            source: Option<S>,
+            source_layout: ImageLayout,
+            destination: Option<D>,
+            destination_layout: ImageLayout,
+            regions: Option<R>,
+        }
+
+        impl<P, S, D, R> Command<P> for Cmd<S, D, R>
+            where S: ImageAccess + Send + Sync + 'static,
+                  D: ImageAccess + Send + Sync + 'static,
+                  R: Iterator<Item = UnsafeCommandBufferBuilderImageResolve>
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdResolveImage"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.resolve_image(self.source.as_ref().unwrap(),
+                                  self.source_layout,
+                                  self.destination.as_ref().unwrap(),
+                                  self.destination_layout,
+                                  self.regions.take().unwrap());
+            }
+
+            fn into_final_command(mut self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<S, D>(S, D);
+                impl<S, D> FinalCommand for Fin<S, D>
+                    where S: ImageAccess + Send + Sync + 'static,
+                          D: ImageAccess + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdResolveImage"
+                    }
+                    fn image(&self, num: usize) -> &dyn ImageAccess {
+                        if num == 0 {
+                            &self.0
+                        } else if num == 1 {
+                            &self.1
+                        } else {
+                            panic!()
+                        }
+                    }
+                    fn image_name(&self, num: usize) -> Cow<'static, str> {
+                        if num == 0 {
+                            "source".into()
+                        } else if num == 1 {
+                            "destination".into()
+                        } else {
+                            panic!()
+                        }
+                    }
+                }
+
+                // Note: borrow checker somehow doesn't accept `self.source` and `self.destination`
+                // without using an Option.
+                Box::new(Fin(self.source.take().unwrap(),
+                             self.destination.take().unwrap()))
+            }
+
+            fn image(&self, num: usize) -> &dyn ImageAccess {
+                if num == 0 {
+                    self.source.as_ref().unwrap()
+                } else if num == 1 {
+                    self.destination.as_ref().unwrap()
+                } else {
+                    panic!()
+                }
+            }
+
+            fn image_name(&self, num: usize) -> Cow<'static, str> {
+                if num == 0 {
+                    "source".into()
+                } else if num == 1 {
+                    "destination".into()
+                } else {
+                    panic!()
+                }
+            }
+        }
+
+        self.append_command(Cmd {
+                                source: Some(source),
+                                source_layout,
+                                destination: Some(destination),
+                                destination_layout,
+                                regions: Some(regions),
+                            });
+        self.prev_cmd_resource(KeyTy::Image,
+                               0,
+                               false,
+                               PipelineStages {
+                                   transfer: true,
+                                   ..PipelineStages::none()
+                               },
+                               AccessFlagBits {
+                                   transfer_read: true,
+                                   ..AccessFlagBits::none()
+                               },
+                               source_layout,
+                               source_layout)?;
+        self.prev_cmd_resource(KeyTy::Image,
+                               1,
+                               true,
+                               PipelineStages {
+                                   transfer: true,
+                                   ..PipelineStages::none()
+                               },
+                               AccessFlagBits {
+                                   transfer_write: true,
+                                   ..AccessFlagBits::none()
+                               },
+                               destination_layout,
+                               destination_layout)?;
+        Ok(())
+    }
+
     /// Calls `vkCmdSetBlendConstants` on the builder.
     #[inline]
     pub unsafe fn set_blend_constants(&mut self, constants: [f32; 4]) {
@@ -1907,6 +3067,139 @@ impl<P> SyncCommandBufferBuilder<P> {
                                ImageLayout::Undefined)
             .unwrap();
     }
+
+    /// Calls `vkCmdWaitEvents` on the builder.
+    ///
+    /// Adds a global memory barrier between `source_access` of `source` and `destination_access`
+    /// of `destination`. Resources used by the command buffer are still synchronized
+    /// automatically.
+    #[inline]
+    pub unsafe fn wait_events(&mut self, events: Vec<Arc<Event>>, source: PipelineStages,
+                              source_access: AccessFlagBits, destination: PipelineStages,
+                              destination_access: AccessFlagBits) {
+        struct Cmd {
+            events: Vec<Arc<Event>>,
+            source: PipelineStages,
+            source_access: AccessFlagBits,
+            destination: PipelineStages,
+            destination_access: AccessFlagBits,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdWaitEvents"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                let mut barrier = UnsafeCommandBufferBuilderPipelineBarrier::new();
+                barrier.add_memory_barrier(self.source, self.source_access, self.destination,
+                                           self.destination_access, false);
+                out.wait_events(self.events.iter().map(|e| &**e), &barrier);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin(Vec<Arc<Event>>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdWaitEvents"
+                    }
+                }
+                Box::new(Fin(self.events))
+            }
+        }
+
+        self.append_command(Cmd {
+                                events,
+                                source,
+                                source_access,
+                                destination,
+                                destination_access,
+                            });
+    }
+
+    /// Calls `vkCmdWriteTimestamp` on the builder.
+    #[inline]
+    pub unsafe fn write_timestamp<Q>(&mut self, query_pool: Q, query: u32, stage: PipelineStages)
+        where Q: QueryPoolAbstract + Send + Sync + 'static
+    {
+        struct Cmd<Q> {
+            query_pool: Q,
+            query: u32,
+            stage: PipelineStages,
+        }
+
+        impl<P, Q> Command<P> for Cmd<Q>
+            where Q: QueryPoolAbstract + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdWriteTimestamp"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.write_timestamp(self.query_pool.inner().query(self.query).unwrap(),
+                                    self.stage);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<dyn FinalCommand + Send + Sync> {
+                struct Fin<Q>(Q);
+                impl<Q> FinalCommand for Fin<Q>
+                    where Q: QueryPoolAbstract + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdWriteTimestamp"
+                    }
+                }
+                Box::new(Fin(self.query_pool))
+            }
+        }
+
+        self.append_command(Cmd {
+                                query_pool,
+                                query,
+                                stage,
+                            });
+    }
+}
+
+// Returns the source stages and accesses and the destination stages and accesses of the
+// barrier that performs one half of a queue family ownership transfer.
+//
+// The destination accesses of a release and the source accesses of an acquire are ignored by
+// Vulkan, since the two halves are synchronized with a semaphore.
+fn ownership_transfer_barrier(transfer: OwnershipTransfer)
+                              -> (PipelineStages, AccessFlagBits, PipelineStages, AccessFlagBits) {
+    let all_commands = PipelineStages {
+        all_commands: true,
+        ..PipelineStages::none()
+    };
+
+    match transfer {
+        OwnershipTransfer::Release => {
+            (all_commands,
+             AccessFlagBits {
+                 memory_write: true,
+                 ..AccessFlagBits::none()
+             },
+             PipelineStages {
+                 bottom_of_pipe: true,
+                 ..PipelineStages::none()
+             },
+             AccessFlagBits::none())
+        },
+        OwnershipTransfer::Acquire => {
+            (PipelineStages {
+                 top_of_pipe: true,
+                 ..PipelineStages::none()
+             },
+             AccessFlagBits::none(),
+             all_commands,
+             AccessFlagBits {
+                 memory_read: true,
+                 memory_write: true,
+                 ..AccessFlagBits::none()
+             })
+        },
+    }
 }
 
 pub struct SyncCommandBufferBuilderBindDescriptorSets<'b, P: 'b> {
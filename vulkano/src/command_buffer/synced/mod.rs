@@ -9,6 +9,7 @@
 
 //! Contains `SyncCommandBufferBuilder` and `SyncCommandBuffer`.
 
+pub use self::base::OwnershipTransfer;
 pub use self::base::SyncCommandBuffer;
 pub use self::base::SyncCommandBufferBuilder;
 pub use self::base::SyncCommandBufferBuilderError;
@@ -11,11 +11,15 @@ use std::iter;
 
 use buffer::BufferUsage;
 use buffer::CpuAccessibleBuffer;
+use command_buffer::synced::base::OwnershipTransfer;
 use command_buffer::synced::base::SyncCommandBufferBuilder;
 use command_buffer::synced::base::SyncCommandBufferBuilderError;
 use command_buffer::sys::Flags;
 use command_buffer::sys::Kind;
 use device::Device;
+use device::DeviceExtensions;
+use features::Features;
+use instance::PhysicalDevice;
 
 #[test]
 fn basic_creation() {
@@ -42,3 +46,137 @@ fn basic_conflict() {
         };
     }
 }
+
+#[test]
+fn split_barrier() {
+    unsafe {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pool = Device::standard_command_pool(&device, queue.family());
+        let mut sync = SyncCommandBufferBuilder::new(&pool, Kind::primary(), Flags::None).unwrap();
+
+        let src = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32)
+            .unwrap();
+        let other = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32)
+            .unwrap();
+        let dst = CpuAccessibleBuffer::from_data(device, BufferUsage::all(), 0u32).unwrap();
+
+        // The fill of `other` is independent and sits between the producer and the consumer of
+        // `src`, so the builder synchronizes them with an event.
+        sync.fill_buffer(src.clone(), 1);
+        sync.fill_buffer(other.clone(), 2);
+        sync.copy_buffer(src.clone(), dst.clone(), iter::once((0, 0, 4))).unwrap();
+
+        let cb = sync.build().unwrap();
+        assert_eq!(cb.events().len(), 1);
+        assert!(!cb.events()[0].signaled().unwrap());
+    }
+}
+
+#[test]
+fn no_split_barrier_between_adjacent_commands() {
+    unsafe {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pool = Device::standard_command_pool(&device, queue.family());
+        let mut sync = SyncCommandBufferBuilder::new(&pool, Kind::primary(), Flags::None).unwrap();
+
+        let src = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32)
+            .unwrap();
+        let dst = CpuAccessibleBuffer::from_data(device, BufferUsage::all(), 0u32).unwrap();
+
+        // Nothing can run between the producer and the consumer, so a pipeline barrier is used.
+        sync.fill_buffer(src.clone(), 1);
+        sync.copy_buffer(src.clone(), dst.clone(), iter::once((0, 0, 4))).unwrap();
+
+        let cb = sync.build().unwrap();
+        assert!(cb.events().is_empty());
+    }
+}
+
+#[test]
+fn no_split_barrier_on_transfer_queue() {
+    unsafe {
+        let instance = instance!();
+        let physical = match PhysicalDevice::enumerate(&instance).next() {
+            Some(p) => p,
+            None => return,
+        };
+        let family = match physical
+            .queue_families()
+            .find(|q| !q.supports_graphics() && !q.supports_compute())
+        {
+            Some(q) => q,
+            None => return,
+        };
+        let (device, _) = match Device::new(physical,
+                                            &Features::none(),
+                                            &DeviceExtensions::none(),
+                                            iter::once((family, 0.5)))
+        {
+            Ok(r) => r,
+            Err(_) => return,
+        };
+
+        let pool = Device::standard_command_pool(&device, family);
+        let mut sync = SyncCommandBufferBuilder::new(&pool, Kind::primary(), Flags::None).unwrap();
+
+        let a = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32).unwrap();
+        let b = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32).unwrap();
+        let c = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32).unwrap();
+        let d = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32).unwrap();
+        let e = CpuAccessibleBuffer::from_data(device, BufferUsage::all(), 0u32).unwrap();
+
+        // Same pattern as `split_barrier`, but transfer queues can't use events so the builder
+        // falls back to a pipeline barrier.
+        sync.copy_buffer(a.clone(), b.clone(), iter::once((0, 0, 4))).unwrap();
+        sync.copy_buffer(c.clone(), d.clone(), iter::once((0, 0, 4))).unwrap();
+        sync.copy_buffer(b.clone(), e.clone(), iter::once((0, 0, 4))).unwrap();
+
+        let cb = sync.build().unwrap();
+        assert!(cb.events().is_empty());
+    }
+}
+
+#[test]
+fn use_after_release() {
+    unsafe {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pool = Device::standard_command_pool(&device, queue.family());
+        let mut sync = SyncCommandBufferBuilder::new(&pool, Kind::primary(), Flags::None).unwrap();
+
+        let buf = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32)
+            .unwrap();
+        let dst = CpuAccessibleBuffer::from_data(device, BufferUsage::all(), 0u32).unwrap();
+
+        let family = queue.family().id();
+        sync.buffer_ownership_transfer(buf.clone(), OwnershipTransfer::Release, family, family + 1)
+            .unwrap();
+
+        match sync.copy_buffer(buf, dst, iter::once((0, 0, 4))) {
+            Err(SyncCommandBufferBuilderError::Conflict { .. }) => (),
+            _ => panic!(),
+        };
+    }
+}
+
+#[test]
+fn acquire_after_use() {
+    unsafe {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pool = Device::standard_command_pool(&device, queue.family());
+        let mut sync = SyncCommandBufferBuilder::new(&pool, Kind::primary(), Flags::None).unwrap();
+
+        let buf = CpuAccessibleBuffer::from_data(device, BufferUsage::all(), 0u32).unwrap();
+
+        let family = queue.family().id();
+        sync.fill_buffer(buf.clone(), 0);
+
+        match sync.buffer_ownership_transfer(buf, OwnershipTransfer::Acquire, family + 1, family) {
+            Err(SyncCommandBufferBuilderError::Conflict { .. }) => (),
+            _ => panic!(),
+        };
+    }
+}
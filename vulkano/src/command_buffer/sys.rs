@@ -18,7 +18,10 @@ use OomError;
 use VulkanObject;
 use buffer::BufferAccess;
 use buffer::BufferInner;
+use buffer::TypedBufferAccess;
 use check_errors;
+use command_buffer::ClearAttachment;
+use command_buffer::ClearRect;
 use command_buffer::CommandBuffer;
 use command_buffer::pool::CommandPool;
 use command_buffer::pool::CommandPoolAlloc;
@@ -48,6 +51,8 @@ use pipeline::viewport::Scissor;
 use pipeline::viewport::Viewport;
 use pipeline::depth_stencil::StencilFaceFlags;
 use query::QueryPipelineStatisticFlags;
+use query::QueryResultElement;
+use query::QueryResultFlags;
 use query::UnsafeQueriesRange;
 use query::UnsafeQuery;
 use sampler::Filter;
@@ -764,18 +769,84 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                         filter as u32);
     }
 
-    // TODO: missing structs
-    /*/// Calls `vkCmdClearAttachments` on the builder.
+    /// Calls `vkCmdClearAttachments` on the builder.
     ///
     /// Does nothing if the list of attachments or the list of rects is empty, as it would be a
     /// no-op and isn't a valid usage of the command anyway.
     #[inline]
     pub unsafe fn clear_attachments<A, R>(&mut self, attachments: A, rects: R)
-        where A: Iterator<Item = >,
-              R: Iterator<Item = >
+        where A: Iterator<Item = ClearAttachment>,
+              R: Iterator<Item = ClearRect>
     {
-        let attachments: SmallVec<[_; 16]> = attachments.map().collect();
-        let rects: SmallVec<[_; 4]> = rects.map().collect();
+        let attachments: SmallVec<[_; 16]> = attachments
+            .map(|attachment| match attachment {
+                     ClearAttachment::Color(index, value) => {
+                         let color = match value {
+                             ClearValue::Float(val) => vk::ClearColorValue { float32: val },
+                             ClearValue::Int(val) => vk::ClearColorValue { int32: val },
+                             ClearValue::Uint(val) => vk::ClearColorValue { uint32: val },
+                             _ => vk::ClearColorValue { float32: [0.0; 4] },
+                         };
+
+                         vk::ClearAttachment {
+                             aspectMask: vk::IMAGE_ASPECT_COLOR_BIT,
+                             colorAttachment: index,
+                             clearValue: vk::ClearValue { color },
+                         }
+                     },
+                     ClearAttachment::Depth(depth) => {
+                         vk::ClearAttachment {
+                             aspectMask: vk::IMAGE_ASPECT_DEPTH_BIT,
+                             colorAttachment: 0,
+                             clearValue: vk::ClearValue {
+                                 depthStencil: vk::ClearDepthStencilValue { depth, stencil: 0 },
+                             },
+                         }
+                     },
+                     ClearAttachment::Stencil(stencil) => {
+                         vk::ClearAttachment {
+                             aspectMask: vk::IMAGE_ASPECT_STENCIL_BIT,
+                             colorAttachment: 0,
+                             clearValue: vk::ClearValue {
+                                 depthStencil: vk::ClearDepthStencilValue {
+                                     depth: 0.0,
+                                     stencil,
+                                 },
+                             },
+                         }
+                     },
+                     ClearAttachment::DepthStencil((depth, stencil)) => {
+                         vk::ClearAttachment {
+                             aspectMask: vk::IMAGE_ASPECT_DEPTH_BIT |
+                                 vk::IMAGE_ASPECT_STENCIL_BIT,
+                             colorAttachment: 0,
+                             clearValue: vk::ClearValue {
+                                 depthStencil: vk::ClearDepthStencilValue { depth, stencil },
+                             },
+                         }
+                     },
+                 })
+            .collect();
+
+        let rects: SmallVec<[_; 4]> = rects
+            .map(|rect| {
+                debug_assert!(rect.layer_count != 0);
+                vk::ClearRect {
+                    rect: vk::Rect2D {
+                        offset: vk::Offset2D {
+                            x: rect.rect_offset[0] as i32,
+                            y: rect.rect_offset[1] as i32,
+                        },
+                        extent: vk::Extent2D {
+                            width: rect.rect_extent[0],
+                            height: rect.rect_extent[1],
+                        },
+                    },
+                    baseArrayLayer: rect.base_array_layer,
+                    layerCount: rect.layer_count,
+                }
+            })
+            .collect();
 
         if attachments.is_empty() || rects.is_empty() {
             return;
@@ -783,9 +854,12 @@ impl<P> UnsafeCommandBufferBuilder<P> {
 
         let vk = self.device().pointers();
         let cmd = self.internal_object();
-        vk.CmdClearAttachments(cmd, attachments.len() as u32, attachments.as_ptr(),
-                               rects.len() as u32, rects.as_ptr());
-    }*/
+        vk.CmdClearAttachments(cmd,
+                               attachments.len() as u32,
+                               attachments.as_ptr(),
+                               rects.len() as u32,
+                               rects.as_ptr());
+    }
 
     /// Calls `vkCmdClearColorImage` on the builder.
     ///
@@ -855,6 +929,59 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                               regions.as_ptr());
     }
 
+    /// Calls `vkCmdClearDepthStencilImage` on the builder.
+    ///
+    /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
+    /// usage of the command anyway.
+    pub unsafe fn clear_depth_stencil_image<I, R>(&mut self, image: &I, layout: ImageLayout,
+                                                  depth: f32, stencil: u32, regions: R)
+        where I: ?Sized + ImageAccess,
+              R: Iterator<Item = UnsafeCommandBufferBuilderDepthStencilImageClear>
+    {
+        debug_assert!(image.format().ty().is_depth_and_or_stencil());
+
+        let image = image.inner();
+        debug_assert!(image.image.usage_transfer_destination());
+        debug_assert!(layout == ImageLayout::General || layout == ImageLayout::TransferDstOptimal);
+
+        let clear_value = vk::ClearDepthStencilValue { depth, stencil };
+
+        let regions: SmallVec<[_; 8]> = regions
+            .filter_map(|region| {
+                debug_assert!(!region.aspect.color);
+                debug_assert!(region.layer_count + region.base_array_layer <=
+                                  image.num_layers as u32);
+                debug_assert!(region.level_count + region.base_mip_level <=
+                                  image.num_mipmap_levels as u32);
+
+                if region.layer_count == 0 || region.level_count == 0 {
+                    return None;
+                }
+
+                Some(vk::ImageSubresourceRange {
+                         aspectMask: region.aspect.to_vk_bits(),
+                         baseMipLevel: region.base_mip_level + image.first_mipmap_level as u32,
+                         levelCount: region.level_count,
+                         baseArrayLayer: region.base_array_layer + image.first_layer as u32,
+                         layerCount: region.layer_count,
+                     })
+            })
+            .collect();
+
+        if regions.is_empty() {
+            return;
+        }
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdClearDepthStencilImage(cmd,
+                                     image.image.internal_object(),
+                                     layout as u32,
+                                     &clear_value,
+                                     regions.len() as u32,
+                                     regions.as_ptr());
+    }
+
     /// Calls `vkCmdCopyBuffer` on the builder.
     ///
     /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
@@ -1029,13 +1156,19 @@ impl<P> UnsafeCommandBufferBuilder<P> {
 
     /// Calls `vkCmdCopyQueryPoolResults` on the builder.
     #[inline]
-    pub unsafe fn copy_query_pool_results(&mut self, queries: UnsafeQueriesRange,
-                                          destination: &dyn BufferAccess, stride: usize) {
+    pub unsafe fn copy_query_pool_results<D, T>(&mut self, queries: UnsafeQueriesRange,
+                                                destination: &D, stride: usize,
+                                                flags: QueryResultFlags)
+        where D: ?Sized + TypedBufferAccess<Content = [T]>,
+              T: QueryResultElement
+    {
         let destination = destination.inner();
         debug_assert!(destination.offset < destination.buffer.size());
         debug_assert!(destination.buffer.usage_transfer_destination());
+        debug_assert_eq!(destination.offset % mem::size_of::<T>(), 0);
+        debug_assert_eq!(stride % mem::size_of::<T>(), 0);
 
-        let flags = 0; // FIXME:
+        let flags = Into::<vk::QueryResultFlags>::into(flags) | T::FLAG;
 
         let vk = self.device().pointers();
         let cmd = self.internal_object();
@@ -1153,6 +1286,70 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                                   stride);
     }
 
+    /// Calls `vkCmdDrawIndirectCountKHR` on the builder.
+    #[inline]
+    pub unsafe fn draw_indirect_count<B, C>(&mut self, buffer: &B, count_buffer: &C,
+                                            max_draw_count: u32, stride: u32)
+        where B: ?Sized + BufferAccess,
+              C: ?Sized + BufferAccess
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        debug_assert!(self.device().loaded_extensions().khr_draw_indirect_count);
+        debug_assert!((stride % 4) == 0 &&
+                          stride as usize >= mem::size_of::<vk::DrawIndirectCommand>());
+
+        let inner = buffer.inner();
+        debug_assert!(inner.offset < inner.buffer.size());
+        debug_assert!(inner.buffer.usage_indirect_buffer());
+
+        let count_inner = count_buffer.inner();
+        debug_assert!(count_inner.offset < count_inner.buffer.size());
+        debug_assert!(count_inner.buffer.usage_indirect_buffer());
+        debug_assert_eq!(count_inner.offset % 4, 0);
+
+        vk.CmdDrawIndirectCountKHR(cmd,
+                                   inner.buffer.internal_object(),
+                                   inner.offset as vk::DeviceSize,
+                                   count_inner.buffer.internal_object(),
+                                   count_inner.offset as vk::DeviceSize,
+                                   max_draw_count,
+                                   stride);
+    }
+
+    /// Calls `vkCmdDrawIndexedIndirectCountKHR` on the builder.
+    #[inline]
+    pub unsafe fn draw_indexed_indirect_count<B, C>(&mut self, buffer: &B, count_buffer: &C,
+                                                    max_draw_count: u32, stride: u32)
+        where B: ?Sized + BufferAccess,
+              C: ?Sized + BufferAccess
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        debug_assert!(self.device().loaded_extensions().khr_draw_indirect_count);
+        debug_assert!((stride % 4) == 0 &&
+                          stride as usize >= mem::size_of::<vk::DrawIndexedIndirectCommand>());
+
+        let inner = buffer.inner();
+        debug_assert!(inner.offset < inner.buffer.size());
+        debug_assert!(inner.buffer.usage_indirect_buffer());
+
+        let count_inner = count_buffer.inner();
+        debug_assert!(count_inner.offset < count_inner.buffer.size());
+        debug_assert!(count_inner.buffer.usage_indirect_buffer());
+        debug_assert_eq!(count_inner.offset % 4, 0);
+
+        vk.CmdDrawIndexedIndirectCountKHR(cmd,
+                                          inner.buffer.internal_object(),
+                                          inner.offset as vk::DeviceSize,
+                                          count_inner.buffer.internal_object(),
+                                          count_inner.offset as vk::DeviceSize,
+                                          max_draw_count,
+                                          stride);
+    }
+
     /// Calls `vkCmdEndQuery` on the builder.
     #[inline]
     pub unsafe fn end_query(&mut self, query: UnsafeQuery) {
@@ -1272,6 +1469,97 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                             data as *const D as *const _);
     }
 
+    /// Calls `vkCmdResolveImage` on the builder.
+    ///
+    /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
+    /// usage of the command anyway.
+    #[inline]
+    pub unsafe fn resolve_image<S, D, R>(&mut self, source: &S, source_layout: ImageLayout,
+                                         destination: &D, destination_layout: ImageLayout,
+                                         regions: R)
+        where S: ?Sized + ImageAccess,
+              D: ?Sized + ImageAccess,
+              R: Iterator<Item = UnsafeCommandBufferBuilderImageResolve>
+    {
+        debug_assert_eq!(source.format(), destination.format());
+        debug_assert!(source.has_color());
+
+        debug_assert!(source.samples() > 1);
+        let source = source.inner();
+        debug_assert!(source.image.usage_transfer_source());
+        debug_assert!(source_layout == ImageLayout::General ||
+                          source_layout == ImageLayout::TransferSrcOptimal);
+
+        debug_assert_eq!(destination.samples(), 1);
+        let destination = destination.inner();
+        debug_assert!(destination.image.usage_transfer_destination());
+        debug_assert!(destination_layout == ImageLayout::General ||
+                          destination_layout == ImageLayout::TransferDstOptimal);
+
+        let regions: SmallVec<[_; 8]> = regions
+            .filter_map(|resolve| {
+                debug_assert!(resolve.source_base_array_layer + resolve.layer_count <=
+                                  source.num_layers as u32);
+                debug_assert!(resolve.destination_base_array_layer + resolve.layer_count <=
+                                  destination.num_layers as u32);
+                debug_assert!(resolve.source_mip_level < source.num_mipmap_levels as u32);
+                debug_assert!(resolve.destination_mip_level <
+                                  destination.num_mipmap_levels as u32);
+
+                if resolve.layer_count == 0 {
+                    return None;
+                }
+
+                Some(vk::ImageResolve {
+                    srcSubresource: vk::ImageSubresourceLayers {
+                        aspectMask: resolve.aspect.to_vk_bits(),
+                        mipLevel: resolve.source_mip_level + source.first_mipmap_level as u32,
+                        baseArrayLayer: resolve.source_base_array_layer +
+                            source.first_layer as u32,
+                        layerCount: resolve.layer_count,
+                    },
+                    srcOffset: vk::Offset3D {
+                        x: resolve.source_offset[0],
+                        y: resolve.source_offset[1],
+                        z: resolve.source_offset[2],
+                    },
+                    dstSubresource: vk::ImageSubresourceLayers {
+                        aspectMask: resolve.aspect.to_vk_bits(),
+                        mipLevel: resolve.destination_mip_level +
+                            destination.first_mipmap_level as u32,
+                        baseArrayLayer: resolve.destination_base_array_layer +
+                            destination.first_layer as u32,
+                        layerCount: resolve.layer_count,
+                    },
+                    dstOffset: vk::Offset3D {
+                        x: resolve.destination_offset[0],
+                        y: resolve.destination_offset[1],
+                        z: resolve.destination_offset[2],
+                    },
+                    extent: vk::Extent3D {
+                        width: resolve.extent[0],
+                        height: resolve.extent[1],
+                        depth: resolve.extent[2],
+                    },
+                })
+            })
+            .collect();
+
+        if regions.is_empty() {
+            return;
+        }
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdResolveImage(cmd,
+                           source.image.internal_object(),
+                           source_layout as u32,
+                           destination.image.internal_object(),
+                           destination_layout as u32,
+                           regions.len() as u32,
+                           regions.as_ptr());
+    }
+
     /// Calls `vkCmdResetEvent` on the builder.
     #[inline]
     pub unsafe fn reset_event(&mut self, event: &Event, stages: PipelineStages) {
@@ -1461,6 +1749,39 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                            data as *const D as *const _);
     }
 
+    /// Calls `vkCmdWaitEvents` on the builder.
+    ///
+    /// The stages, memory barriers, buffer barriers and image barriers of `command` are used as
+    /// the parameters of the wait. The dependency flags of `command` are ignored.
+    #[inline]
+    pub unsafe fn wait_events<'a, I>(&mut self, events: I,
+                                     command: &UnsafeCommandBufferBuilderPipelineBarrier)
+        where I: IntoIterator<Item = &'a Event>
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        let events: SmallVec<[_; 4]> = events.into_iter().map(|e| e.internal_object()).collect();
+        if events.is_empty() {
+            return;
+        }
+
+        debug_assert_ne!(command.src_stage_mask, 0);
+        debug_assert_ne!(command.dst_stage_mask, 0);
+
+        vk.CmdWaitEvents(cmd,
+                         events.len() as u32,
+                         events.as_ptr(),
+                         command.src_stage_mask,
+                         command.dst_stage_mask,
+                         command.memory_barriers.len() as u32,
+                         command.memory_barriers.as_ptr(),
+                         command.buffer_barriers.len() as u32,
+                         command.buffer_barriers.as_ptr(),
+                         command.image_barriers.len() as u32,
+                         command.image_barriers.as_ptr());
+    }
+
     /// Calls `vkCmdWriteTimestamp` on the builder.
     #[inline]
     pub unsafe fn write_timestamp(&mut self, query: UnsafeQuery, stages: PipelineStages) {
@@ -1632,6 +1953,16 @@ pub struct UnsafeCommandBufferBuilderColorImageClear {
     pub layer_count: u32,
 }
 
+// TODO: move somewhere else?
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UnsafeCommandBufferBuilderDepthStencilImageClear {
+    pub aspect: UnsafeCommandBufferBuilderImageAspect,
+    pub base_mip_level: u32,
+    pub level_count: u32,
+    pub base_array_layer: u32,
+    pub layer_count: u32,
+}
+
 // TODO: move somewhere else?
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct UnsafeCommandBufferBuilderBufferImageCopy {
@@ -1675,6 +2006,20 @@ pub struct UnsafeCommandBufferBuilderImageBlit {
     pub destination_bottom_right: [i32; 3],
 }
 
+// TODO: move somewhere else?
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UnsafeCommandBufferBuilderImageResolve {
+    pub aspect: UnsafeCommandBufferBuilderImageAspect,
+    pub source_mip_level: u32,
+    pub destination_mip_level: u32,
+    pub source_base_array_layer: u32,
+    pub destination_base_array_layer: u32,
+    pub layer_count: u32,
+    pub source_offset: [i32; 3],
+    pub destination_offset: [i32; 3],
+    pub extent: [u32; 3],
+}
+
 /// Command that adds a pipeline barrier to a command buffer builder.
 ///
 /// A pipeline barrier is a low-level system-ish command that is often necessary for safety. By
@@ -0,0 +1,328 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use command_buffer::ClearAttachment;
+use command_buffer::ClearRect;
+use format::ClearValue;
+use format::FormatTy;
+use framebuffer::RenderPassDesc;
+
+/// Checks whether a clear attachments command is valid in the given subpass.
+///
+/// `framebuffer_dimensions` contains the width, height and number of layers of the framebuffer.
+/// If they are unknown, which is the case for a secondary command buffer that doesn't specify its
+/// framebuffer, the maximum framebuffer dimensions of the device should be passed instead.
+///
+/// # Panic
+///
+/// - Panics if `subpass` is not a valid subpass index of `render_pass`.
+///
+pub fn check_clear_attachments<Rp>(render_pass: &Rp, subpass: u32,
+                                   framebuffer_dimensions: [u32; 3],
+                                   attachments: &[ClearAttachment], rects: &[ClearRect])
+                                   -> Result<(), CheckClearAttachmentsError>
+    where Rp: ?Sized + RenderPassDesc
+{
+    let subpass_desc = render_pass
+        .subpass_desc(subpass as usize)
+        .expect("Invalid subpass index");
+
+    for attachment in attachments {
+        match *attachment {
+            ClearAttachment::Color(index, value) => {
+                let atch_num = match subpass_desc.color_attachments.get(index as usize) {
+                    Some(&(atch_num, _)) => atch_num,
+                    None => return Err(CheckClearAttachmentsError::AttachmentIndexOutOfRange {
+                                           index,
+                                           num_color_attachments: subpass_desc
+                                               .color_attachments
+                                               .len() as u32,
+                                       }),
+                };
+
+                let format_ty = render_pass.attachment_desc(atch_num).unwrap().format.ty();
+                let matches = match value {
+                    ClearValue::Float(_) => format_ty == FormatTy::Float,
+                    ClearValue::Int(_) => format_ty == FormatTy::Sint,
+                    ClearValue::Uint(_) => format_ty == FormatTy::Uint,
+                    _ => false,
+                };
+
+                if !matches {
+                    return Err(CheckClearAttachmentsError::ClearValueMismatch);
+                }
+            },
+            ClearAttachment::Depth(_) |
+            ClearAttachment::Stencil(_) |
+            ClearAttachment::DepthStencil(_) => {
+                let atch_num = match subpass_desc.depth_stencil {
+                    Some((atch_num, _)) => atch_num,
+                    None => return Err(CheckClearAttachmentsError::NoDepthStencilAttachment),
+                };
+
+                let format_ty = render_pass.attachment_desc(atch_num).unwrap().format.ty();
+                let has_depth = format_ty == FormatTy::Depth ||
+                    format_ty == FormatTy::DepthStencil;
+                let has_stencil = format_ty == FormatTy::Stencil ||
+                    format_ty == FormatTy::DepthStencil;
+
+                let matches = match *attachment {
+                    ClearAttachment::Depth(_) => has_depth,
+                    ClearAttachment::Stencil(_) => has_stencil,
+                    _ => has_depth && has_stencil,
+                };
+
+                if !matches {
+                    return Err(CheckClearAttachmentsError::ClearValueMismatch);
+                }
+
+                match *attachment {
+                    ClearAttachment::Depth(depth) |
+                    ClearAttachment::DepthStencil((depth, _)) => {
+                        if !(depth >= 0.0 && depth <= 1.0) {
+                            return Err(CheckClearAttachmentsError::DepthOutOfRange);
+                        }
+                    },
+                    _ => (),
+                }
+            },
+        }
+    }
+
+    for rect in rects {
+        if rect.rect_extent[0] == 0 || rect.rect_extent[1] == 0 || rect.layer_count == 0 {
+            return Err(CheckClearAttachmentsError::EmptyRect);
+        }
+
+        let in_range = |start: u32, len: u32, max: u32| {
+            start.checked_add(len).map_or(false, |end| end <= max)
+        };
+
+        if !in_range(rect.rect_offset[0], rect.rect_extent[0], framebuffer_dimensions[0]) ||
+            !in_range(rect.rect_offset[1], rect.rect_extent[1], framebuffer_dimensions[1]) ||
+            !in_range(rect.base_array_layer, rect.layer_count, framebuffer_dimensions[2])
+        {
+            return Err(CheckClearAttachmentsError::RectOutOfRange);
+        }
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_clear_attachments`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckClearAttachmentsError {
+    /// The index of a color attachment is out of range of the color attachments of the subpass.
+    AttachmentIndexOutOfRange {
+        /// The index that was passed.
+        index: u32,
+        /// Number of color attachments of the subpass.
+        num_color_attachments: u32,
+    },
+    /// A depth or stencil value was passed but the subpass has no depth-stencil attachment.
+    NoDepthStencilAttachment,
+    /// The clear value doesn't match the format of the attachment.
+    ClearValueMismatch,
+    /// The depth value must be between 0.0 and 1.0.
+    DepthOutOfRange,
+    /// A rect has a width, a height or a number of layers of 0.
+    EmptyRect,
+    /// A rect is outside of the dimensions or layers of the framebuffer.
+    RectOutOfRange,
+}
+
+impl error::Error for CheckClearAttachmentsError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckClearAttachmentsError::AttachmentIndexOutOfRange { .. } => {
+                "the index of a color attachment is out of range of the color attachments of the \
+                 subpass"
+            },
+            CheckClearAttachmentsError::NoDepthStencilAttachment => {
+                "a depth or stencil value was passed but the subpass has no depth-stencil \
+                 attachment"
+            },
+            CheckClearAttachmentsError::ClearValueMismatch => {
+                "the clear value doesn't match the format of the attachment"
+            },
+            CheckClearAttachmentsError::DepthOutOfRange => {
+                "the depth value must be between 0.0 and 1.0"
+            },
+            CheckClearAttachmentsError::EmptyRect => {
+                "a rect has a width, a height or a number of layers of 0"
+            },
+            CheckClearAttachmentsError::RectOutOfRange => {
+                "a rect is outside of the dimensions or layers of the framebuffer"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckClearAttachmentsError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use format::Format;
+    use framebuffer::AttachmentDescription;
+    use framebuffer::EmptySinglePassRenderPassDesc;
+    use framebuffer::LoadOp;
+    use framebuffer::PassDependencyDescription;
+    use framebuffer::PassDescription;
+    use framebuffer::RenderPassDescClearValues;
+    use framebuffer::StoreOp;
+    use image::ImageLayout;
+
+    // Render pass whose only subpass uses a depth attachment.
+    struct DepthRenderPassDesc;
+
+    unsafe impl RenderPassDesc for DepthRenderPassDesc {
+        fn num_attachments(&self) -> usize {
+            1
+        }
+
+        fn attachment_desc(&self, num: usize) -> Option<AttachmentDescription> {
+            if num != 0 {
+                return None;
+            }
+
+            Some(AttachmentDescription {
+                     format: Format::D16Unorm,
+                     samples: 1,
+                     load: LoadOp::Load,
+                     store: StoreOp::Store,
+                     stencil_load: LoadOp::Load,
+                     stencil_store: StoreOp::Store,
+                     initial_layout: ImageLayout::DepthStencilAttachmentOptimal,
+                     final_layout: ImageLayout::DepthStencilAttachmentOptimal,
+                 })
+        }
+
+        fn num_subpasses(&self) -> usize {
+            1
+        }
+
+        fn subpass_desc(&self, num: usize) -> Option<PassDescription> {
+            if num != 0 {
+                return None;
+            }
+
+            Some(PassDescription {
+                     color_attachments: vec![],
+                     depth_stencil: Some((0, ImageLayout::DepthStencilAttachmentOptimal)),
+                     input_attachments: vec![],
+                     resolve_attachments: vec![],
+                     preserve_attachments: vec![],
+                 })
+        }
+
+        fn num_dependencies(&self) -> usize {
+            0
+        }
+
+        fn dependency_desc(&self, _: usize) -> Option<PassDependencyDescription> {
+            None
+        }
+    }
+
+    unsafe impl RenderPassDescClearValues<Vec<ClearValue>> for DepthRenderPassDesc {
+        fn convert_clear_values(&self, values: Vec<ClearValue>)
+                                -> Box<dyn Iterator<Item = ClearValue>> {
+            Box::new(values.into_iter())
+        }
+    }
+
+    #[test]
+    fn attachment_index_out_of_range() {
+        let attachments = [ClearAttachment::Color(0, ClearValue::Float([0.0; 4]))];
+
+        match check_clear_attachments(&EmptySinglePassRenderPassDesc,
+                                      0,
+                                      [16, 16, 1],
+                                      &attachments,
+                                      &[]) {
+            Err(CheckClearAttachmentsError::AttachmentIndexOutOfRange {
+                    index: 0,
+                    num_color_attachments: 0,
+                }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn no_depth_stencil_attachment() {
+        let attachments = [ClearAttachment::Depth(1.0)];
+
+        match check_clear_attachments(&EmptySinglePassRenderPassDesc,
+                                      0,
+                                      [16, 16, 1],
+                                      &attachments,
+                                      &[]) {
+            Err(CheckClearAttachmentsError::NoDepthStencilAttachment) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn depth_out_of_range() {
+        let attachments = [ClearAttachment::Depth(1.5)];
+
+        match check_clear_attachments(&DepthRenderPassDesc, 0, [16, 16, 1], &attachments, &[]) {
+            Err(CheckClearAttachmentsError::DepthOutOfRange) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn rect_out_of_range() {
+        let rects = [ClearRect {
+                         rect_offset: [8, 8],
+                         rect_extent: [16, 16],
+                         base_array_layer: 0,
+                         layer_count: 1,
+                     }];
+
+        match check_clear_attachments(&EmptySinglePassRenderPassDesc,
+                                      0,
+                                      [16, 16, 1],
+                                      &[],
+                                      &rects) {
+            Err(CheckClearAttachmentsError::RectOutOfRange) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn rect_overflow() {
+        let rects = [ClearRect {
+                         rect_offset: [8, 0],
+                         rect_extent: [u32::max_value(), 16],
+                         base_array_layer: 0,
+                         layer_count: 1,
+                     }];
+
+        match check_clear_attachments(&EmptySinglePassRenderPassDesc,
+                                      0,
+                                      [16, 16, 1],
+                                      &[],
+                                      &rects) {
+            Err(CheckClearAttachmentsError::RectOutOfRange) => (),
+            _ => panic!(),
+        }
+    }
+}
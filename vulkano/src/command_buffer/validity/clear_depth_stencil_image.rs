@@ -0,0 +1,152 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use VulkanObject;
+use device::Device;
+use format::ClearValue;
+use image::ImageAccess;
+
+/// Checks whether a clear depth-stencil image command is valid.
+///
+/// `clear_value` must be a `Depth`, `Stencil` or `DepthStencil` value, and determines which
+/// aspects of the image are cleared.
+///
+/// # Panic
+///
+/// - Panics if the image was not created with `device`.
+///
+pub fn check_clear_depth_stencil_image<I>(device: &Device, image: &I, first_layer: u32,
+                                          num_layers: u32, first_mipmap: u32, num_mipmaps: u32,
+                                          clear_value: ClearValue)
+                                          -> Result<(), CheckClearDepthStencilImageError>
+    where I: ?Sized + ImageAccess
+{
+    assert_eq!(image.inner().image.device().internal_object(),
+               device.internal_object());
+
+    if !image.has_depth() && !image.has_stencil() {
+        return Err(CheckClearDepthStencilImageError::NotDepthStencilFormat);
+    }
+
+    if !image.inner().image.usage_transfer_destination() {
+        return Err(CheckClearDepthStencilImageError::MissingTransferUsage);
+    }
+
+    let (depth, stencil) = match clear_value {
+        ClearValue::Depth(depth) => (Some(depth), false),
+        ClearValue::Stencil(_) => (None, true),
+        ClearValue::DepthStencil((depth, _)) => (Some(depth), true),
+        _ => return Err(CheckClearDepthStencilImageError::WrongClearValue),
+    };
+
+    if (depth.is_some() && !image.has_depth()) || (stencil && !image.has_stencil()) {
+        return Err(CheckClearDepthStencilImageError::WrongClearValue);
+    }
+
+    if let Some(depth) = depth {
+        if !(depth >= 0.0 && depth <= 1.0) {
+            return Err(CheckClearDepthStencilImageError::DepthOutOfRange);
+        }
+    }
+
+    if first_layer
+        .checked_add(num_layers)
+        .map_or(true, |end| end > image.dimensions().array_layers())
+    {
+        return Err(CheckClearDepthStencilImageError::OutOfRange);
+    }
+
+    if first_mipmap
+        .checked_add(num_mipmaps)
+        .map_or(true, |end| end > image.mipmap_levels())
+    {
+        return Err(CheckClearDepthStencilImageError::OutOfRange);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_clear_depth_stencil_image`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckClearDepthStencilImageError {
+    /// The image doesn't have a depth and/or stencil format.
+    NotDepthStencilFormat,
+    /// The image is missing the transfer destination usage.
+    MissingTransferUsage,
+    /// The clear value is not a depth and/or stencil value, or clears an aspect that the image
+    /// doesn't have.
+    WrongClearValue,
+    /// The depth value must be between 0.0 and 1.0.
+    DepthOutOfRange,
+    /// The array layers and mipmap levels are out of range.
+    OutOfRange,
+}
+
+impl error::Error for CheckClearDepthStencilImageError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckClearDepthStencilImageError::NotDepthStencilFormat => {
+                "the image doesn't have a depth and/or stencil format"
+            },
+            CheckClearDepthStencilImageError::MissingTransferUsage => {
+                "the image is missing the transfer destination usage"
+            },
+            CheckClearDepthStencilImageError::WrongClearValue => {
+                "the clear value is not a depth and/or stencil value, or clears an aspect that \
+                 the image doesn't have"
+            },
+            CheckClearDepthStencilImageError::DepthOutOfRange => {
+                "the depth value must be between 0.0 and 1.0"
+            },
+            CheckClearDepthStencilImageError::OutOfRange => {
+                "the array layers and mipmap levels are out of range"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckClearDepthStencilImageError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use format::Format;
+    use image::AttachmentImage;
+
+    #[test]
+    fn not_depth_stencil() {
+        let (device, _) = gfx_dev_and_queue!();
+        let image = AttachmentImage::new(device.clone(), [16, 16], Format::R8G8B8A8Unorm).unwrap();
+
+        match check_clear_depth_stencil_image(&device, &image, 0, 1, 0, 1, ClearValue::Depth(1.0)) {
+            Err(CheckClearDepthStencilImageError::NotDepthStencilFormat) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn missing_usage() {
+        let (device, _) = gfx_dev_and_queue!();
+        let image = AttachmentImage::new(device.clone(), [16, 16], Format::D16Unorm).unwrap();
+
+        match check_clear_depth_stencil_image(&device, &image, 0, 1, 0, 1, ClearValue::Depth(1.0)) {
+            Err(CheckClearDepthStencilImageError::MissingTransferUsage) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::ffi::CString;
+use std::fmt;
+
+use device::Device;
+
+/// Checks whether a debug marker command is valid, and converts its name to a C string.
+pub fn check_debug_marker(device: &Device, name: &str)
+                          -> Result<CString, CheckDebugMarkerError> {
+    if !device.instance().loaded_extensions().ext_debug_utils {
+        return Err(CheckDebugMarkerError::ExtensionNotEnabled);
+    }
+
+    CString::new(name).map_err(|_| CheckDebugMarkerError::NameContainsNul)
+}
+
+/// Error that can happen from `check_debug_marker`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckDebugMarkerError {
+    /// The `ext_debug_utils` instance extension wasn't enabled.
+    ExtensionNotEnabled,
+    /// The name of the marker contains a nul character.
+    NameContainsNul,
+}
+
+impl error::Error for CheckDebugMarkerError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckDebugMarkerError::ExtensionNotEnabled => {
+                "the ext_debug_utils instance extension wasn't enabled"
+            },
+            CheckDebugMarkerError::NameContainsNul => {
+                "the name of the marker contains a nul character"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckDebugMarkerError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
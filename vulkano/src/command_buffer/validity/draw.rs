@@ -0,0 +1,183 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+/// Checks whether a draw command stays within the bound vertex buffers.
+///
+/// `num_vertices` and `num_instances` are the number of vertices and instances available in the
+/// bound vertex buffers, or `None` if the vertex definition has no per-vertex or per-instance
+/// binding, in which case the corresponding range isn't limited.
+pub fn check_draw(num_vertices: Option<u32>, num_instances: Option<u32>, vertex_count: u32,
+                  instance_count: u32, first_vertex: u32, first_instance: u32)
+                  -> Result<(), CheckDrawError> {
+    if let Some(num_vertices) = num_vertices {
+        if !in_range(first_vertex, vertex_count, num_vertices) {
+            return Err(CheckDrawError::VerticesOutOfRange {
+                           first: first_vertex,
+                           count: vertex_count,
+                           max: num_vertices,
+                       });
+        }
+    }
+
+    check_instances(num_instances, instance_count, first_instance)
+}
+
+/// Checks whether an indexed draw command stays within the bound index buffer and vertex
+/// buffers.
+///
+/// `num_indices` is the number of indices in the bound index buffer, and `num_instances` the
+/// number of instances available in the bound vertex buffers, or `None` if the vertex
+/// definition has no per-instance binding.
+pub fn check_draw_indexed(num_indices: u32, num_instances: Option<u32>, index_count: u32,
+                          instance_count: u32, first_index: u32, first_instance: u32)
+                          -> Result<(), CheckDrawError> {
+    if !in_range(first_index, index_count, num_indices) {
+        return Err(CheckDrawError::IndicesOutOfRange {
+                       first: first_index,
+                       count: index_count,
+                       max: num_indices,
+                   });
+    }
+
+    check_instances(num_instances, instance_count, first_instance)
+}
+
+// Checks the range of instances of a draw command, if the vertex definition limits it.
+fn check_instances(num_instances: Option<u32>, instance_count: u32, first_instance: u32)
+                   -> Result<(), CheckDrawError> {
+    let num_instances = match num_instances {
+        Some(n) => n,
+        None => return Ok(()),
+    };
+
+    if !in_range(first_instance, instance_count, num_instances) {
+        return Err(CheckDrawError::InstancesOutOfRange {
+                       first: first_instance,
+                       count: instance_count,
+                       max: num_instances,
+                   });
+    }
+
+    Ok(())
+}
+
+#[inline]
+fn in_range(first: u32, count: u32, max: u32) -> bool {
+    first.checked_add(count).map_or(false, |end| end <= max)
+}
+
+/// Error that can happen when checking the range of a draw command.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckDrawError {
+    /// The range of vertices goes past the end of the bound vertex buffers.
+    VerticesOutOfRange {
+        /// First vertex that was passed.
+        first: u32,
+        /// Number of vertices that was passed.
+        count: u32,
+        /// Number of vertices available in the vertex buffers.
+        max: u32,
+    },
+    /// The range of instances goes past the end of the bound vertex buffers.
+    InstancesOutOfRange {
+        /// First instance that was passed.
+        first: u32,
+        /// Number of instances that was passed.
+        count: u32,
+        /// Number of instances available in the vertex buffers.
+        max: u32,
+    },
+    /// The range of indices goes past the end of the bound index buffer.
+    IndicesOutOfRange {
+        /// First index that was passed.
+        first: u32,
+        /// Number of indices that was passed.
+        count: u32,
+        /// Number of indices available in the index buffer.
+        max: u32,
+    },
+}
+
+impl error::Error for CheckDrawError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckDrawError::VerticesOutOfRange { .. } => {
+                "the range of vertices goes past the end of the bound vertex buffers"
+            },
+            CheckDrawError::InstancesOutOfRange { .. } => {
+                "the range of instances goes past the end of the bound vertex buffers"
+            },
+            CheckDrawError::IndicesOutOfRange { .. } => {
+                "the range of indices goes past the end of the bound index buffer"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckDrawError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw_in_range() {
+        assert!(check_draw(Some(6), Some(1), 3, 1, 3, 0).is_ok());
+        assert!(check_draw(Some(6), Some(4), 0, 0, 6, 4).is_ok());
+    }
+
+    #[test]
+    fn draw_out_of_range() {
+        match check_draw(Some(6), Some(1), 4, 1, 3, 0) {
+            Err(CheckDrawError::VerticesOutOfRange { first: 3, count: 4, max: 6 }) => (),
+            _ => panic!(),
+        }
+
+        match check_draw(Some(6), Some(1), 1, 1, 0, 1) {
+            Err(CheckDrawError::InstancesOutOfRange { .. }) => (),
+            _ => panic!(),
+        }
+
+        match check_draw(Some(6), Some(1), 1, 1, u32::max_value(), 0) {
+            Err(CheckDrawError::VerticesOutOfRange { .. }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn draw_unlimited() {
+        assert!(check_draw(Some(6), None, 3, 16, 0, 0).is_ok());
+        assert!(check_draw(None, Some(4), 100, 4, 0, 0).is_ok());
+        assert!(check_draw_indexed(12, None, 12, 16, 0, 0).is_ok());
+
+        match check_draw(None, Some(4), 1, 1, 0, 4) {
+            Err(CheckDrawError::InstancesOutOfRange { .. }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn draw_indexed_out_of_range() {
+        assert!(check_draw_indexed(12, Some(1), 12, 1, 0, 0).is_ok());
+
+        match check_draw_indexed(12, Some(1), 6, 1, 7, 0) {
+            Err(CheckDrawError::IndicesOutOfRange { .. }) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use device::Device;
+
+/// Checks whether an indirect draw command that reads its draw count from a buffer is valid.
+///
+/// `max_draw_count` is the maximum number of draws, which is the number of draw commands in the
+/// indirect buffer.
+pub fn check_draw_indirect_count(device: &Device, max_draw_count: u32)
+                                 -> Result<(), CheckDrawIndirectCountError> {
+    if !device.loaded_extensions().khr_draw_indirect_count {
+        return Err(CheckDrawIndirectCountError::ExtensionNotEnabled);
+    }
+
+    if max_draw_count > 1 && !device.enabled_features().multi_draw_indirect {
+        return Err(CheckDrawIndirectCountError::MultiDrawIndirectFeatureNotEnabled);
+    }
+
+    let limit = device.physical_device().limits().max_draw_indirect_count();
+    if max_draw_count > limit {
+        return Err(CheckDrawIndirectCountError::MaxDrawCountExceeded {
+                       requested: max_draw_count,
+                       limit,
+                   });
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_draw_indirect_count`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckDrawIndirectCountError {
+    /// The `khr_draw_indirect_count` extension must be enabled on the device.
+    ExtensionNotEnabled,
+    /// The `multi_draw_indirect` feature must be enabled in order to do more than one draw.
+    MultiDrawIndirectFeatureNotEnabled,
+    /// The maximum number of draws exceeds the limit of the device.
+    MaxDrawCountExceeded {
+        /// The maximum number of draws that was requested.
+        requested: u32,
+        /// The `max_draw_indirect_count` limit of the device.
+        limit: u32,
+    },
+}
+
+impl error::Error for CheckDrawIndirectCountError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckDrawIndirectCountError::ExtensionNotEnabled => {
+                "the `khr_draw_indirect_count` extension must be enabled on the device"
+            },
+            CheckDrawIndirectCountError::MultiDrawIndirectFeatureNotEnabled => {
+                "the `multi_draw_indirect` feature must be enabled in order to do more than one \
+                 draw"
+            },
+            CheckDrawIndirectCountError::MaxDrawCountExceeded { .. } => {
+                "the maximum number of draws exceeds the limit of the device"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckDrawIndirectCountError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extension_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        match check_draw_indirect_count(&device, 1) {
+            Err(CheckDrawIndirectCountError::ExtensionNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,280 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use descriptor::descriptor::DescriptorBufferDesc;
+use descriptor::descriptor::DescriptorDescTy;
+use descriptor::descriptor_set::DescriptorSet;
+use descriptor::descriptor_set::DescriptorSetsCollection;
+use device::Device;
+
+/// Checks whether dynamic offsets are valid for the given list of descriptor sets.
+///
+/// There must be exactly one offset for each array element of each dynamic buffer descriptor,
+/// ordered by set, then by binding, then by array element.
+pub fn check_dynamic_offsets<S>(device: &Device, sets: &[S], dynamic_offsets: &[u32])
+                                -> Result<(), CheckDynamicOffsetsError>
+    where S: DescriptorSet
+{
+    let expected = sets
+        .iter()
+        .map(|set| {
+            (0 .. set.num_bindings())
+                .filter_map(|binding| set.descriptor(binding))
+                .filter(|desc| is_dynamic(&desc.ty).is_some())
+                .map(|desc| desc.array_count as usize)
+                .sum::<usize>()
+        })
+        .sum::<usize>();
+
+    if expected != dynamic_offsets.len() {
+        return Err(CheckDynamicOffsetsError::CountMismatch {
+                       expected,
+                       obtained: dynamic_offsets.len(),
+                   });
+    }
+
+    let limits = device.physical_device().limits();
+    let mut offsets = dynamic_offsets.iter().enumerate();
+
+    for set in sets {
+        for binding in 0 .. set.num_bindings() {
+            let desc = match set.descriptor(binding) {
+                Some(desc) => desc,
+                None => continue,
+            };
+
+            let alignment = match is_dynamic(&desc.ty) {
+                Some(true) => limits.min_storage_buffer_offset_alignment(),
+                Some(false) => limits.min_uniform_buffer_offset_alignment(),
+                None => continue,
+            };
+
+            // Buffers attached to this binding, in the order of the array elements.
+            let mut buffers = (0 .. set.num_buffers())
+                .filter_map(|num| set.buffer(num))
+                .filter(|&(_, b)| b as usize == binding)
+                .map(|(buffer, _)| buffer);
+
+            for _ in 0 .. desc.array_count {
+                let (index, &offset) = offsets.next().unwrap();
+
+                if offset as u64 % alignment != 0 {
+                    return Err(CheckDynamicOffsetsError::UnalignedOffset { index });
+                }
+
+                if let Some(buffer) = buffers.next() {
+                    let inner = buffer.inner();
+                    let end = inner.offset + offset as usize + buffer.size();
+                    if end > inner.buffer.size() {
+                        return Err(CheckDynamicOffsetsError::OutOfRange { index });
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the given descriptor sets don't contain any dynamic buffer descriptor, for the
+/// commands that bind descriptor sets without dynamic offsets.
+pub fn check_no_dynamic_offsets<S>(sets: &S) -> Result<(), CheckDynamicOffsetsError>
+    where S: ?Sized + DescriptorSetsCollection
+{
+    let mut expected = 0;
+    let mut set_num = 0;
+
+    while let Some(num_bindings) = sets.num_bindings_in_set(set_num) {
+        expected += (0 .. num_bindings)
+            .filter_map(|binding| sets.descriptor(set_num, binding))
+            .filter(|desc| is_dynamic(&desc.ty).is_some())
+            .map(|desc| desc.array_count as usize)
+            .sum::<usize>();
+        set_num += 1;
+    }
+
+    if expected != 0 {
+        return Err(CheckDynamicOffsetsError::CountMismatch {
+                       expected,
+                       obtained: 0,
+                   });
+    }
+
+    Ok(())
+}
+
+// If the descriptor is a dynamic buffer, returns whether it is a storage buffer.
+#[inline]
+fn is_dynamic(ty: &DescriptorDescTy) -> Option<bool> {
+    match *ty {
+        DescriptorDescTy::Buffer(DescriptorBufferDesc {
+                                     dynamic: Some(true),
+                                     storage,
+                                 }) => Some(storage),
+        _ => None,
+    }
+}
+
+/// Error that can happen when checking dynamic offsets.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckDynamicOffsetsError {
+    /// The number of dynamic offsets doesn't match the number of dynamic buffer descriptors.
+    CountMismatch {
+        /// Number of dynamic buffer descriptors in the sets.
+        expected: usize,
+        /// Number of dynamic offsets that were passed.
+        obtained: usize,
+    },
+    /// A dynamic offset is not a multiple of the minimum offset alignment of its buffer type.
+    UnalignedOffset {
+        /// Index of the offset in the list.
+        index: usize,
+    },
+    /// A dynamic offset would make the descriptor go past the end of its buffer.
+    OutOfRange {
+        /// Index of the offset in the list.
+        index: usize,
+    },
+}
+
+impl error::Error for CheckDynamicOffsetsError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckDynamicOffsetsError::CountMismatch { .. } => {
+                "the number of dynamic offsets doesn't match the number of dynamic buffer \
+                 descriptors"
+            },
+            CheckDynamicOffsetsError::UnalignedOffset { .. } => {
+                "a dynamic offset is not a multiple of the minimum offset alignment of its buffer \
+                 type"
+            },
+            CheckDynamicOffsetsError::OutOfRange { .. } => {
+                "a dynamic offset would make the descriptor go past the end of its buffer"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckDynamicOffsetsError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer::BufferAccess;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use descriptor::descriptor::DescriptorDesc;
+    use descriptor::descriptor::ShaderStages;
+    use descriptor::descriptor_set::PersistentDescriptorSet;
+    use descriptor::pipeline_layout::PipelineLayoutDesc;
+    use descriptor::pipeline_layout::RuntimePipelineDesc;
+    use std::iter;
+    use std::sync::Arc;
+
+    // Builds a set with one dynamic uniform buffer whose range is `alignment` bytes, in a
+    // buffer of `4 * alignment` bytes. Returns the set and the alignment.
+    fn dynamic_set(device: &Arc<Device>) -> (Arc<dyn DescriptorSet + Send + Sync>, usize) {
+        let alignment = device
+            .physical_device()
+            .limits()
+            .min_uniform_buffer_offset_alignment() as usize;
+
+        let desc = DescriptorDesc {
+            ty: DescriptorDescTy::Buffer(DescriptorBufferDesc {
+                                             dynamic: Some(true),
+                                             storage: false,
+                                         }),
+            array_count: 1,
+            stages: ShaderStages::all_graphics(),
+            readonly: true,
+        };
+
+        let layout = RuntimePipelineDesc::new(iter::once(iter::once(Some(desc))), iter::empty())
+            .unwrap()
+            .build(device.clone())
+            .unwrap();
+
+        let buffer = CpuAccessibleBuffer::from_iter(device.clone(),
+                                                    BufferUsage::uniform_buffer(),
+                                                    (0 .. 4 * alignment).map(|_| 0u8))
+            .unwrap();
+
+        let set = PersistentDescriptorSet::start(layout, 0)
+            .add_buffer(buffer.into_buffer_slice().slice(0 .. alignment).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+
+        (Arc::new(set), alignment)
+    }
+
+    #[test]
+    fn valid_offsets() {
+        let (device, _) = gfx_dev_and_queue!();
+        let (set, alignment) = dynamic_set(&device);
+
+        for &offset in &[0, alignment as u32, 3 * alignment as u32] {
+            check_dynamic_offsets(&device, &[set.clone()], &[offset]).unwrap();
+        }
+    }
+
+    #[test]
+    fn count_mismatch() {
+        let (device, _) = gfx_dev_and_queue!();
+        let (set, _) = dynamic_set(&device);
+
+        match check_dynamic_offsets(&device, &[set.clone()], &[]) {
+            Err(CheckDynamicOffsetsError::CountMismatch {
+                    expected: 1,
+                    obtained: 0,
+                }) => (),
+            _ => panic!(),
+        }
+
+        match check_no_dynamic_offsets(&vec![set]) {
+            Err(CheckDynamicOffsetsError::CountMismatch { .. }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn unaligned_offset() {
+        let (device, _) = gfx_dev_and_queue!();
+        let (set, alignment) = dynamic_set(&device);
+
+        if alignment == 1 {
+            return;
+        }
+
+        match check_dynamic_offsets(&device, &[set], &[1]) {
+            Err(CheckDynamicOffsetsError::UnalignedOffset { index: 0 }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn out_of_range() {
+        let (device, _) = gfx_dev_and_queue!();
+        let (set, alignment) = dynamic_set(&device);
+
+        match check_dynamic_offsets(&device, &[set], &[4 * alignment as u32]) {
+            Err(CheckDynamicOffsetsError::OutOfRange { index: 0 }) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -11,12 +11,13 @@ use std::error;
 use std::fmt;
 
 use command_buffer::DynamicState;
+use command_buffer::StateCacher;
 use pipeline::GraphicsPipelineAbstract;
 
 /// Checks whether states that are about to be set are correct.
 pub fn check_dynamic_state_validity<Pl>(pipeline: &Pl, state: &DynamicState)
                                         -> Result<(), CheckDynamicStateValidityError>
-    where Pl: GraphicsPipelineAbstract
+    where Pl: ?Sized + GraphicsPipelineAbstract
 {
     let device = pipeline.device();
 
@@ -107,6 +108,27 @@ pub fn check_dynamic_state_validity<Pl>(pipeline: &Pl, state: &DynamicState)
     Ok(())
 }
 
+/// Checks whether the dynamic states that are not part of `DynamicState` and that the pipeline
+/// needs have been set earlier in the command buffer.
+pub fn check_dynamic_state_set<Pl>(pipeline: &Pl, state_cacher: &StateCacher)
+                                   -> Result<(), CheckDynamicStateValidityError>
+    where Pl: ?Sized + GraphicsPipelineAbstract
+{
+    if pipeline.has_dynamic_depth_bias() && state_cacher.depth_bias().is_none() {
+        return Err(CheckDynamicStateValidityError::DepthBiasMissing);
+    }
+
+    if pipeline.has_dynamic_depth_bounds() && state_cacher.depth_bounds().is_none() {
+        return Err(CheckDynamicStateValidityError::DepthBoundsMissing);
+    }
+
+    if pipeline.has_dynamic_blend_constants() && state_cacher.blend_constants().is_none() {
+        return Err(CheckDynamicStateValidityError::BlendConstantsMissing);
+    }
+
+    Ok(())
+}
+
 /// Error that can happen when validating dynamic states.
 #[derive(Debug, Copy, Clone)]
 pub enum CheckDynamicStateValidityError {
@@ -151,6 +173,12 @@ pub enum CheckDynamicStateValidityError {
     ReferenceNotDynamic,
     /// The pipeline has dynamic reference, but no reference was passed.
     ReferenceMissing,
+    /// The pipeline has dynamic depth bias, but the depth bias wasn't set.
+    DepthBiasMissing,
+    /// The pipeline has dynamic depth bounds, but the depth bounds weren't set.
+    DepthBoundsMissing,
+    /// The pipeline has dynamic blend constants, but the blend constants weren't set.
+    BlendConstantsMissing,
 }
 
 impl error::Error for CheckDynamicStateValidityError {
@@ -205,6 +233,15 @@ impl error::Error for CheckDynamicStateValidityError {
             CheckDynamicStateValidityError::ReferenceMissing => {
                 "the pipeline has dynamic reference, but no reference was passed"
             },
+            CheckDynamicStateValidityError::DepthBiasMissing => {
+                "the pipeline has dynamic depth bias, but the depth bias wasn't set"
+            },
+            CheckDynamicStateValidityError::DepthBoundsMissing => {
+                "the pipeline has dynamic depth bounds, but the depth bounds weren't set"
+            },
+            CheckDynamicStateValidityError::BlendConstantsMissing => {
+                "the pipeline has dynamic blend constants, but the blend constants weren't set"
+            },
         }
     }
 }
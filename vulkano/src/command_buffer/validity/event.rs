@@ -0,0 +1,125 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use VulkanObject;
+use device::Device;
+use device::DeviceOwned;
+use sync::Event;
+use sync::PipelineStages;
+
+/// Checks whether a command that sets, resets or waits for an event is valid.
+///
+/// # Panic
+///
+/// - Panics if the event was not created with `device`.
+///
+pub fn check_event(device: &Device, event: &Event, stages: PipelineStages)
+                   -> Result<(), CheckEventError> {
+    assert_eq!(event.device().internal_object(),
+               device.internal_object());
+
+    if stages == PipelineStages::none() {
+        return Err(CheckEventError::NoStage);
+    }
+
+    if stages.host {
+        return Err(CheckEventError::HostStage);
+    }
+
+    if stages.geometry_shader && !device.enabled_features().geometry_shader {
+        return Err(CheckEventError::GeometryShaderFeatureNotEnabled);
+    }
+
+    if (stages.tessellation_control_shader || stages.tessellation_evaluation_shader) &&
+        !device.enabled_features().tessellation_shader
+    {
+        return Err(CheckEventError::TessellationShaderFeatureNotEnabled);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_event`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckEventError {
+    /// At least one pipeline stage must be specified.
+    NoStage,
+    /// The host stage can't be used when setting, resetting or waiting for an event from a
+    /// command buffer.
+    HostStage,
+    /// The geometry shader stage was used but the `geometry_shader` feature is not enabled.
+    GeometryShaderFeatureNotEnabled,
+    /// A tessellation stage was used but the `tessellation_shader` feature is not enabled.
+    TessellationShaderFeatureNotEnabled,
+}
+
+impl error::Error for CheckEventError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckEventError::NoStage => {
+                "at least one pipeline stage must be specified"
+            },
+            CheckEventError::HostStage => {
+                "the host stage can't be used when setting, resetting or waiting for an event \
+                 from a command buffer"
+            },
+            CheckEventError::GeometryShaderFeatureNotEnabled => {
+                "the geometry shader stage was used but the `geometry_shader` feature is not \
+                 enabled"
+            },
+            CheckEventError::TessellationShaderFeatureNotEnabled => {
+                "a tessellation stage was used but the `tessellation_shader` feature is not \
+                 enabled"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckEventError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_stage() {
+        let (device, _) = gfx_dev_and_queue!();
+        let event = Event::alloc(device.clone()).unwrap();
+
+        match check_event(&device, &event, PipelineStages::none()) {
+            Err(CheckEventError::NoStage) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn host_stage() {
+        let (device, _) = gfx_dev_and_queue!();
+        let event = Event::alloc(device.clone()).unwrap();
+
+        let stages = PipelineStages {
+            host: true,
+            ..PipelineStages::none()
+        };
+
+        match check_event(&device, &event, stages) {
+            Err(CheckEventError::HostStage) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use VulkanObject;
+use device::Device;
+use image::ImageAccess;
+
+/// Checks whether a command that generates the mipmaps of an image is valid.
+///
+/// The mipmaps are generated with blits, so the format of the image must support being the
+/// source and destination of a blit. There is no fallback for the formats that don't.
+///
+/// # Panic
+///
+/// - Panics if the image was not created with `device`.
+///
+pub fn check_generate_mipmaps<I>(device: &Device, image: &I)
+                                 -> Result<(), CheckGenerateMipmapsError>
+    where I: ?Sized + ImageAccess
+{
+    let inner = image.inner().image;
+
+    assert_eq!(inner.device().internal_object(), device.internal_object());
+
+    if !inner.usage_transfer_source() || !inner.usage_transfer_destination() {
+        return Err(CheckGenerateMipmapsError::MissingTransferUsage);
+    }
+
+    if image.samples() != 1 {
+        return Err(CheckGenerateMipmapsError::MultisampledImage);
+    }
+
+    if !inner.supports_blit_source() || !inner.supports_blit_destination() {
+        return Err(CheckGenerateMipmapsError::BlitNotSupported);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_generate_mipmaps`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckGenerateMipmapsError {
+    /// The image is missing the transfer source or the transfer destination usage.
+    MissingTransferUsage,
+    /// Mipmaps can't be generated for a multisampled image.
+    MultisampledImage,
+    /// The format of the image doesn't support being used as the source and destination of a
+    /// blit.
+    BlitNotSupported,
+}
+
+impl error::Error for CheckGenerateMipmapsError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckGenerateMipmapsError::MissingTransferUsage => {
+                "the image is missing the transfer source or the transfer destination usage"
+            },
+            CheckGenerateMipmapsError::MultisampledImage => {
+                "mipmaps can't be generated for a multisampled image"
+            },
+            CheckGenerateMipmapsError::BlitNotSupported => {
+                "the format of the image doesn't support being used as the source and \
+                 destination of a blit"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckGenerateMipmapsError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use format::Format;
+    use image::AttachmentImage;
+
+    #[test]
+    fn missing_usage() {
+        let (device, _) = gfx_dev_and_queue!();
+        let image = AttachmentImage::new(device.clone(), [16, 16], Format::R8G8B8A8Unorm).unwrap();
+
+        match check_generate_mipmaps(&device, &image) {
+            Err(CheckGenerateMipmapsError::MissingTransferUsage) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn wrong_device() {
+        let (dev1, _) = gfx_dev_and_queue!();
+        let (dev2, _) = gfx_dev_and_queue!();
+        let image = AttachmentImage::new(dev1, [16, 16], Format::R8G8B8A8Unorm).unwrap();
+
+        assert_should_panic!({
+                                 let _ = check_generate_mipmaps(&dev2, &image);
+                             });
+    }
+}
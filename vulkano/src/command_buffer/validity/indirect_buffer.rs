@@ -0,0 +1,131 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use VulkanObject;
+use buffer::BufferAccess;
+use device::Device;
+use device::DeviceOwned;
+
+/// Checks whether a buffer can be used as the source of the parameters of an indirect command.
+///
+/// # Panic
+///
+/// - Panics if the buffer was not created with `device`.
+///
+pub fn check_indirect_buffer<B>(device: &Device, buffer: &B)
+                                -> Result<(), CheckIndirectBufferError>
+    where B: ?Sized + BufferAccess
+{
+    assert_eq!(buffer.inner().buffer.device().internal_object(),
+               device.internal_object());
+
+    if !buffer.inner().buffer.usage_indirect_buffer() {
+        return Err(CheckIndirectBufferError::BufferMissingUsage);
+    }
+
+    if buffer.inner().offset % 4 != 0 {
+        return Err(CheckIndirectBufferError::WrongAlignment);
+    }
+
+    if buffer.size() == 0 {
+        return Err(CheckIndirectBufferError::BufferEmpty);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when checking whether a buffer can be used as an indirect buffer.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckIndirectBufferError {
+    /// The "indirect buffer" usage must be enabled on the indirect buffer.
+    BufferMissingUsage,
+    /// The offset of the indirect buffer must be a multiple of 4.
+    WrongAlignment,
+    /// The indirect buffer doesn't contain any command.
+    BufferEmpty,
+}
+
+impl error::Error for CheckIndirectBufferError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckIndirectBufferError::BufferMissingUsage => {
+                "the indirect buffer usage must be enabled on the indirect buffer"
+            },
+            CheckIndirectBufferError::WrongAlignment => {
+                "the offset of the indirect buffer must be a multiple of 4"
+            },
+            CheckIndirectBufferError::BufferEmpty => {
+                "the indirect buffer doesn't contain any command"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckIndirectBufferError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer::BufferSlice;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use command_buffer::DispatchIndirectCommand;
+
+    #[test]
+    fn missing_usage() {
+        let (device, _) = gfx_dev_and_queue!();
+        let data = DispatchIndirectCommand { x: 1, y: 1, z: 1 };
+        let buffer = CpuAccessibleBuffer::from_data(device.clone(),
+                                                    BufferUsage::uniform_buffer(),
+                                                    data)
+            .unwrap();
+
+        match check_indirect_buffer(&device, &buffer) {
+            Err(CheckIndirectBufferError::BufferMissingUsage) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let (device, _) = gfx_dev_and_queue!();
+        let data = DispatchIndirectCommand { x: 1, y: 1, z: 1 };
+        let buffer = CpuAccessibleBuffer::from_iter(device.clone(),
+                                                    BufferUsage::indirect_buffer(),
+                                                    Some(data).into_iter())
+            .unwrap();
+        let slice = BufferSlice::from_typed_buffer_access(buffer).slice(0 .. 0).unwrap();
+
+        match check_indirect_buffer(&device, &slice) {
+            Err(CheckIndirectBufferError::BufferEmpty) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn wrong_device() {
+        let (dev1, _) = gfx_dev_and_queue!();
+        let (dev2, _) = gfx_dev_and_queue!();
+        let data = DispatchIndirectCommand { x: 1, y: 1, z: 1 };
+        let buffer = CpuAccessibleBuffer::from_data(dev1, BufferUsage::all(), data).unwrap();
+
+        assert_should_panic!({
+                                 let _ = check_indirect_buffer(&dev2, &buffer);
+                             });
+    }
+}
@@ -10,30 +10,68 @@
 //! Functions that check the validity of commands.
 
 pub use self::blit_image::{CheckBlitImageError, check_blit_image};
+pub use self::clear_attachments::{CheckClearAttachmentsError, check_clear_attachments};
 pub use self::clear_color_image::{CheckClearColorImageError, check_clear_color_image};
+pub use self::clear_depth_stencil_image::{CheckClearDepthStencilImageError,
+                                          check_clear_depth_stencil_image};
 pub use self::copy_buffer::{CheckCopyBuffer, CheckCopyBufferError, check_copy_buffer};
 pub use self::copy_image::{CheckCopyImageError, check_copy_image};
 pub use self::copy_image_buffer::{CheckCopyBufferImageError, CheckCopyBufferImageTy,
                                   check_copy_buffer_image};
+pub use self::debug_marker::{CheckDebugMarkerError, check_debug_marker};
 pub use self::descriptor_sets::{CheckDescriptorSetsValidityError, check_descriptor_sets_validity};
 pub use self::dispatch::{CheckDispatchError, check_dispatch};
-pub use self::dynamic_state::{CheckDynamicStateValidityError, check_dynamic_state_validity};
+pub use self::draw::{CheckDrawError, check_draw, check_draw_indexed};
+pub use self::draw_indirect_count::{CheckDrawIndirectCountError, check_draw_indirect_count};
+pub use self::dynamic_offsets::{CheckDynamicOffsetsError, check_dynamic_offsets,
+                                check_no_dynamic_offsets};
+pub use self::dynamic_state::{CheckDynamicStateValidityError, check_dynamic_state_set,
+                              check_dynamic_state_validity};
+pub use self::event::{CheckEventError, check_event};
 pub use self::fill_buffer::{CheckFillBufferError, check_fill_buffer};
+pub use self::generate_mipmaps::{CheckGenerateMipmapsError, check_generate_mipmaps};
 pub use self::index_buffer::{CheckIndexBuffer, CheckIndexBufferError, check_index_buffer};
+pub use self::indirect_buffer::{CheckIndirectBufferError, check_indirect_buffer};
+pub use self::pipeline_barrier::{CheckPipelineBarrierError, check_image_layout_transition,
+                                 check_buffer_ownership_transfer,
+                                 check_image_ownership_transfer};
 pub use self::push_constants::{CheckPushConstantsValidityError, check_push_constants_validity};
+pub use self::query::{CheckBeginQueryError, CheckCopyQueryPoolResultsError, CheckEndQueryError,
+                      CheckResetQueryPoolError, CheckWriteTimestampError, check_begin_query,
+                      check_copy_query_pool_results, check_end_query, check_reset_query_pool,
+                      check_write_timestamp};
+pub use self::resolve_image::{CheckResolveImageError, check_resolve_image};
+pub use self::set_dynamic_state::{CheckSetDynamicStateError, check_set_depth_bias,
+                                  check_set_depth_bounds, check_set_line_width, check_set_scissor,
+                                  check_set_viewport};
 pub use self::update_buffer::{CheckUpdateBufferError, check_update_buffer};
-pub use self::vertex_buffers::{CheckVertexBuffer, CheckVertexBufferError, check_vertex_buffers};
+pub use self::vertex_buffers::{CheckVertexBuffer, CheckVertexBufferError,
+                               check_bind_vertex_buffers, check_bound_vertex_buffers,
+                               check_vertex_buffers};
 
 mod blit_image;
+mod clear_attachments;
 mod clear_color_image;
+mod clear_depth_stencil_image;
 mod copy_buffer;
 mod copy_image;
 mod copy_image_buffer;
+mod debug_marker;
 mod descriptor_sets;
 mod dispatch;
+mod draw;
+mod draw_indirect_count;
+mod dynamic_offsets;
 mod dynamic_state;
+mod event;
 mod fill_buffer;
+mod generate_mipmaps;
 mod index_buffer;
+mod indirect_buffer;
+mod pipeline_barrier;
 mod push_constants;
+mod query;
+mod resolve_image;
+mod set_dynamic_state;
 mod update_buffer;
 mod vertex_buffers;
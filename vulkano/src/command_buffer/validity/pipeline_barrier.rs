@@ -0,0 +1,362 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use VulkanObject;
+use buffer::BufferAccess;
+use device::Device;
+use device::DeviceOwned;
+use image::ImageAccess;
+use image::ImageLayout;
+use instance::QueueFamily;
+use sync::AccessFlagBits;
+use sync::PipelineStages;
+
+/// Checks whether a command that transitions an image to a new layout is valid.
+///
+/// # Panic
+///
+/// - Panics if the image was not created with `device`.
+///
+pub fn check_image_layout_transition<I>(device: &Device, image: &I, layout: ImageLayout,
+                                        stages: PipelineStages, access: AccessFlagBits)
+                                        -> Result<(), CheckPipelineBarrierError>
+    where I: ?Sized + ImageAccess
+{
+    assert_eq!(image.inner().image.device().internal_object(),
+               device.internal_object());
+
+    if stages == PipelineStages::none() {
+        return Err(CheckPipelineBarrierError::NoStage);
+    }
+
+    if !access.is_compatible_with(&stages) {
+        return Err(CheckPipelineBarrierError::AccessNotSupportedByStage);
+    }
+
+    if stages.geometry_shader && !device.enabled_features().geometry_shader {
+        return Err(CheckPipelineBarrierError::GeometryShaderFeatureNotEnabled);
+    }
+
+    if (stages.tessellation_control_shader || stages.tessellation_evaluation_shader) &&
+        !device.enabled_features().tessellation_shader
+    {
+        return Err(CheckPipelineBarrierError::TessellationShaderFeatureNotEnabled);
+    }
+
+    let inner = image.inner().image;
+    let supported = match layout {
+        ImageLayout::Undefined | ImageLayout::Preinitialized => {
+            return Err(CheckPipelineBarrierError::InvalidLayout);
+        },
+        ImageLayout::General | ImageLayout::PresentSrc => true,
+        ImageLayout::ColorAttachmentOptimal => inner.usage_color_attachment(),
+        ImageLayout::DepthStencilAttachmentOptimal |
+        ImageLayout::DepthStencilReadOnlyOptimal => inner.usage_depth_stencil_attachment(),
+        ImageLayout::ShaderReadOnlyOptimal => {
+            inner.usage_sampled() || inner.usage_input_attachment()
+        },
+        ImageLayout::TransferSrcOptimal => inner.usage_transfer_source(),
+        ImageLayout::TransferDstOptimal => inner.usage_transfer_destination(),
+    };
+
+    if !supported {
+        return Err(CheckPipelineBarrierError::LayoutNotSupportedByUsage);
+    }
+
+    Ok(())
+}
+
+/// Checks whether a command that releases or acquires the ownership of a buffer is valid.
+///
+/// `current_family` is the queue family of the command buffer, and `other_family` the queue
+/// family the ownership is transferred to or from.
+///
+/// # Panic
+///
+/// - Panics if the buffer was not created with `device`.
+/// - Panics if `other_family` doesn't belong to the physical device of `device`.
+///
+pub fn check_buffer_ownership_transfer<B>(device: &Device, buffer: &B, current_family: u32,
+                                          other_family: QueueFamily)
+                                          -> Result<(), CheckPipelineBarrierError>
+    where B: ?Sized + BufferAccess
+{
+    assert_eq!(buffer.inner().buffer.device().internal_object(),
+               device.internal_object());
+
+    if !buffer.inner().buffer.exclusive_sharing() {
+        return Err(CheckPipelineBarrierError::ConcurrentSharing);
+    }
+
+    check_ownership_transfer(device, current_family, other_family)
+}
+
+/// Checks whether a command that releases or acquires the ownership of an image is valid.
+///
+/// `current_family` is the queue family of the command buffer, and `other_family` the queue
+/// family the ownership is transferred to or from.
+///
+/// # Panic
+///
+/// - Panics if the image was not created with `device`.
+/// - Panics if `other_family` doesn't belong to the physical device of `device`.
+///
+pub fn check_image_ownership_transfer<I>(device: &Device, image: &I, current_family: u32,
+                                         other_family: QueueFamily)
+                                         -> Result<(), CheckPipelineBarrierError>
+    where I: ?Sized + ImageAccess
+{
+    assert_eq!(image.inner().image.device().internal_object(),
+               device.internal_object());
+
+    if !image.inner().image.exclusive_sharing() {
+        return Err(CheckPipelineBarrierError::ConcurrentSharing);
+    }
+
+    check_ownership_transfer(device, current_family, other_family)
+}
+
+// Checks the queue families of an ownership transfer.
+fn check_ownership_transfer(device: &Device, current_family: u32, other_family: QueueFamily)
+                            -> Result<(), CheckPipelineBarrierError> {
+    assert_eq!(other_family.physical_device().internal_object(),
+               device.physical_device().internal_object());
+
+    if other_family.id() == current_family {
+        return Err(CheckPipelineBarrierError::SameQueueFamily);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_image_layout_transition`, `check_buffer_ownership_transfer`
+/// or `check_image_ownership_transfer`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckPipelineBarrierError {
+    /// At least one pipeline stage must be specified.
+    NoStage,
+    /// The access flags are not supported by the pipeline stages.
+    AccessNotSupportedByStage,
+    /// The geometry shader stage was used but the `geometry_shader` feature is not enabled.
+    GeometryShaderFeatureNotEnabled,
+    /// A tessellation stage was used but the `tessellation_shader` feature is not enabled.
+    TessellationShaderFeatureNotEnabled,
+    /// Images can't be transitioned to the `Undefined` or `Preinitialized` layouts.
+    InvalidLayout,
+    /// The image wasn't created with the usage required by the layout.
+    LayoutNotSupportedByUsage,
+    /// The ownership of a resource can't be transferred to or from the queue family of the
+    /// command buffer itself.
+    SameQueueFamily,
+    /// The ownership of a resource that was created with concurrent sharing can't be
+    /// transferred.
+    ConcurrentSharing,
+}
+
+impl error::Error for CheckPipelineBarrierError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckPipelineBarrierError::NoStage => {
+                "at least one pipeline stage must be specified"
+            },
+            CheckPipelineBarrierError::AccessNotSupportedByStage => {
+                "the access flags are not supported by the pipeline stages"
+            },
+            CheckPipelineBarrierError::GeometryShaderFeatureNotEnabled => {
+                "the geometry shader stage was used but the `geometry_shader` feature is not \
+                 enabled"
+            },
+            CheckPipelineBarrierError::TessellationShaderFeatureNotEnabled => {
+                "a tessellation stage was used but the `tessellation_shader` feature is not \
+                 enabled"
+            },
+            CheckPipelineBarrierError::InvalidLayout => {
+                "images can't be transitioned to the `Undefined` or `Preinitialized` layouts"
+            },
+            CheckPipelineBarrierError::LayoutNotSupportedByUsage => {
+                "the image wasn't created with the usage required by the layout"
+            },
+            CheckPipelineBarrierError::SameQueueFamily => {
+                "the ownership of a resource can't be transferred to or from the queue family of \
+                 the command buffer itself"
+            },
+            CheckPipelineBarrierError::ConcurrentSharing => {
+                "the ownership of a resource that was created with concurrent sharing can't be \
+                 transferred"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckPipelineBarrierError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer::BufferUsage;
+    use buffer::DeviceLocalBuffer;
+    use format::Format;
+    use image::AttachmentImage;
+
+    #[test]
+    fn no_stage() {
+        let (device, _) = gfx_dev_and_queue!();
+        let image = AttachmentImage::new(device.clone(), [16, 16], Format::R8G8B8A8Unorm).unwrap();
+
+        match check_image_layout_transition(&device,
+                                            &image,
+                                            ImageLayout::ColorAttachmentOptimal,
+                                            PipelineStages::none(),
+                                            AccessFlagBits::none()) {
+            Err(CheckPipelineBarrierError::NoStage) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn access_not_supported() {
+        let (device, _) = gfx_dev_and_queue!();
+        let image = AttachmentImage::new(device.clone(), [16, 16], Format::R8G8B8A8Unorm).unwrap();
+
+        let stages = PipelineStages {
+            compute_shader: true,
+            ..PipelineStages::none()
+        };
+        let access = AccessFlagBits {
+            color_attachment_write: true,
+            ..AccessFlagBits::none()
+        };
+
+        match check_image_layout_transition(&device,
+                                            &image,
+                                            ImageLayout::ColorAttachmentOptimal,
+                                            stages,
+                                            access) {
+            Err(CheckPipelineBarrierError::AccessNotSupportedByStage) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn invalid_layout() {
+        let (device, _) = gfx_dev_and_queue!();
+        let image = AttachmentImage::new(device.clone(), [16, 16], Format::R8G8B8A8Unorm).unwrap();
+
+        let stages = PipelineStages {
+            color_attachment_output: true,
+            ..PipelineStages::none()
+        };
+
+        match check_image_layout_transition(&device,
+                                            &image,
+                                            ImageLayout::Undefined,
+                                            stages,
+                                            AccessFlagBits::none()) {
+            Err(CheckPipelineBarrierError::InvalidLayout) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn layout_not_supported_by_usage() {
+        let (device, _) = gfx_dev_and_queue!();
+        let image = AttachmentImage::new(device.clone(), [16, 16], Format::R8G8B8A8Unorm).unwrap();
+
+        let stages = PipelineStages {
+            fragment_shader: true,
+            ..PipelineStages::none()
+        };
+
+        match check_image_layout_transition(&device,
+                                            &image,
+                                            ImageLayout::ShaderReadOnlyOptimal,
+                                            stages,
+                                            AccessFlagBits::none()) {
+            Err(CheckPipelineBarrierError::LayoutNotSupportedByUsage) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn valid_transition() {
+        let (device, _) = gfx_dev_and_queue!();
+        let image = AttachmentImage::new(device.clone(), [16, 16], Format::R8G8B8A8Unorm).unwrap();
+
+        let stages = PipelineStages {
+            color_attachment_output: true,
+            ..PipelineStages::none()
+        };
+        let access = AccessFlagBits {
+            color_attachment_write: true,
+            ..AccessFlagBits::none()
+        };
+
+        match check_image_layout_transition(&device,
+                                            &image,
+                                            ImageLayout::ColorAttachmentOptimal,
+                                            stages,
+                                            access) {
+            Ok(_) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn same_queue_family() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let image = AttachmentImage::new(device.clone(), [16, 16], Format::R8G8B8A8Unorm).unwrap();
+
+        match check_image_ownership_transfer(&device, &image, queue.family().id(),
+                                             queue.family()) {
+            Err(CheckPipelineBarrierError::SameQueueFamily) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn concurrent_sharing() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let other_family = device
+            .physical_device()
+            .queue_families()
+            .find(|family| family.id() != queue.family().id());
+        let other_family = match other_family {
+            Some(family) => family,
+            None => return,
+        };
+
+        let exclusive = DeviceLocalBuffer::<u32>::new(device.clone(), BufferUsage::all(),
+                                                      Some(queue.family()))
+            .unwrap();
+        let concurrent = DeviceLocalBuffer::<u32>::new(device.clone(), BufferUsage::all(),
+                                                       vec![queue.family(), other_family])
+            .unwrap();
+
+        match check_buffer_ownership_transfer(&device, &exclusive, queue.family().id(),
+                                              other_family) {
+            Ok(_) => (),
+            _ => panic!(),
+        }
+
+        match check_buffer_ownership_transfer(&device, &concurrent, queue.family().id(),
+                                              other_family) {
+            Err(CheckPipelineBarrierError::ConcurrentSharing) => (),
+            _ => panic!(),
+        }
+    }
+}
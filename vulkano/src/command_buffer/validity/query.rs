@@ -0,0 +1,443 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+use std::mem;
+use std::ops::Range;
+
+use VulkanObject;
+use buffer::TypedBufferAccess;
+use device::Device;
+use device::DeviceOwned;
+use query::QueryPoolAbstract;
+use query::QueryResultElement;
+use query::QueryResultFlags;
+use query::QueryType;
+use sync::PipelineStages;
+
+/// Checks whether a begin query command is valid.
+///
+/// # Panic
+///
+/// - Panics if the query pool was not created with `device`.
+///
+pub fn check_begin_query<Q>(device: &Device, query_pool: &Q, query: u32, precise: bool)
+                            -> Result<(), CheckBeginQueryError>
+    where Q: ?Sized + QueryPoolAbstract
+{
+    let query_pool = query_pool.inner();
+    assert_eq!(query_pool.device().internal_object(),
+               device.internal_object());
+
+    if query_pool.query(query).is_none() {
+        return Err(CheckBeginQueryError::OutOfRange);
+    }
+
+    match query_pool.ty() {
+        QueryType::Occlusion => {
+            if precise && !device.enabled_features().occlusion_query_precise {
+                return Err(CheckBeginQueryError::OcclusionQueryPreciseFeatureNotEnabled);
+            }
+        },
+        QueryType::PipelineStatistics(_) => {
+            if precise {
+                return Err(CheckBeginQueryError::InvalidFlags);
+            }
+        },
+        QueryType::Timestamp => {
+            return Err(CheckBeginQueryError::NotPermitted);
+        },
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_begin_query`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckBeginQueryError {
+    /// The query index is out of range of the query pool.
+    OutOfRange,
+    /// The `precise` flag was requested for a query that isn't an occlusion query.
+    InvalidFlags,
+    /// The `precise` flag was requested but the `occlusion_query_precise` feature wasn't enabled.
+    OcclusionQueryPreciseFeatureNotEnabled,
+    /// Queries of this type can't be used with a begin query command.
+    NotPermitted,
+}
+
+impl error::Error for CheckBeginQueryError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckBeginQueryError::OutOfRange => {
+                "the query index is out of range of the query pool"
+            },
+            CheckBeginQueryError::InvalidFlags => {
+                "the precise flag was requested for a query that isn't an occlusion query"
+            },
+            CheckBeginQueryError::OcclusionQueryPreciseFeatureNotEnabled => {
+                "the precise flag was requested but the occlusion_query_precise feature wasn't \
+                 enabled"
+            },
+            CheckBeginQueryError::NotPermitted => {
+                "queries of this type can't be used with a begin query command"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckBeginQueryError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+/// Checks whether an end query command is valid.
+///
+/// # Panic
+///
+/// - Panics if the query pool was not created with `device`.
+///
+pub fn check_end_query<Q>(device: &Device, query_pool: &Q, query: u32)
+                          -> Result<(), CheckEndQueryError>
+    where Q: ?Sized + QueryPoolAbstract
+{
+    let query_pool = query_pool.inner();
+    assert_eq!(query_pool.device().internal_object(),
+               device.internal_object());
+
+    if query_pool.query(query).is_none() {
+        return Err(CheckEndQueryError::OutOfRange);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_end_query`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckEndQueryError {
+    /// The query index is out of range of the query pool.
+    OutOfRange,
+}
+
+impl error::Error for CheckEndQueryError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckEndQueryError::OutOfRange => {
+                "the query index is out of range of the query pool"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckEndQueryError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+/// Checks whether a write timestamp command is valid.
+///
+/// # Panic
+///
+/// - Panics if the query pool was not created with `device`.
+///
+pub fn check_write_timestamp<Q>(device: &Device, query_pool: &Q, query: u32,
+                                stage: PipelineStages)
+                                -> Result<(), CheckWriteTimestampError>
+    where Q: ?Sized + QueryPoolAbstract
+{
+    let query_pool = query_pool.inner();
+    assert_eq!(query_pool.device().internal_object(),
+               device.internal_object());
+
+    if query_pool.ty() != QueryType::Timestamp {
+        return Err(CheckWriteTimestampError::NotPermitted);
+    }
+
+    if query_pool.query(query).is_none() {
+        return Err(CheckWriteTimestampError::OutOfRange);
+    }
+
+    if stage.into_vulkan_bits().count_ones() != 1 {
+        return Err(CheckWriteTimestampError::InvalidStage);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_write_timestamp`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckWriteTimestampError {
+    /// The query pool isn't a timestamp query pool.
+    NotPermitted,
+    /// The query index is out of range of the query pool.
+    OutOfRange,
+    /// Exactly one pipeline stage must be specified.
+    InvalidStage,
+}
+
+impl error::Error for CheckWriteTimestampError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckWriteTimestampError::NotPermitted => {
+                "the query pool isn't a timestamp query pool"
+            },
+            CheckWriteTimestampError::OutOfRange => {
+                "the query index is out of range of the query pool"
+            },
+            CheckWriteTimestampError::InvalidStage => {
+                "exactly one pipeline stage must be specified"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckWriteTimestampError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+/// Checks whether a reset query pool command is valid.
+///
+/// # Panic
+///
+/// - Panics if the query pool was not created with `device`.
+///
+pub fn check_reset_query_pool<Q>(device: &Device, query_pool: &Q, queries: Range<u32>)
+                                 -> Result<(), CheckResetQueryPoolError>
+    where Q: ?Sized + QueryPoolAbstract
+{
+    let query_pool = query_pool.inner();
+    assert_eq!(query_pool.device().internal_object(),
+               device.internal_object());
+
+    if queries.start >= queries.end ||
+        query_pool
+            .queries_range(queries.start, queries.end - queries.start)
+            .is_none()
+    {
+        return Err(CheckResetQueryPoolError::OutOfRange);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_reset_query_pool`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckResetQueryPoolError {
+    /// The range of queries is empty or out of range of the query pool.
+    OutOfRange,
+}
+
+impl error::Error for CheckResetQueryPoolError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckResetQueryPoolError::OutOfRange => {
+                "the range of queries is empty or out of range of the query pool"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckResetQueryPoolError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+/// Checks whether a copy query pool results command is valid.
+///
+/// On success, returns the stride in bytes between the results of two consecutive queries in the
+/// destination buffer.
+///
+/// # Panic
+///
+/// - Panics if the query pool or the destination buffer were not created with `device`.
+///
+pub fn check_copy_query_pool_results<Q, D, T>(device: &Device, query_pool: &Q,
+                                              queries: Range<u32>, destination: &D,
+                                              flags: QueryResultFlags)
+                                              -> Result<usize, CheckCopyQueryPoolResultsError>
+    where Q: ?Sized + QueryPoolAbstract,
+          D: ?Sized + TypedBufferAccess<Content = [T]>,
+          T: QueryResultElement
+{
+    let query_pool = query_pool.inner();
+    assert_eq!(query_pool.device().internal_object(),
+               device.internal_object());
+    assert_eq!(destination.inner().buffer.device().internal_object(),
+               device.internal_object());
+
+    if queries.start >= queries.end ||
+        query_pool
+            .queries_range(queries.start, queries.end - queries.start)
+            .is_none()
+    {
+        return Err(CheckCopyQueryPoolResultsError::OutOfRange);
+    }
+
+    if !destination.inner().buffer.usage_transfer_destination() {
+        return Err(CheckCopyQueryPoolResultsError::DestinationMissingTransferUsage);
+    }
+
+    if destination.inner().offset % mem::size_of::<T>() != 0 {
+        return Err(CheckCopyQueryPoolResultsError::WrongAlignment);
+    }
+
+    if flags.partial && query_pool.ty() == QueryType::Timestamp {
+        return Err(CheckCopyQueryPoolResultsError::InvalidFlags);
+    }
+
+    let per_query_len = query_pool.ty().result_size() + flags.with_availability as usize;
+    let required_len = per_query_len * (queries.end - queries.start) as usize;
+
+    if destination.len() < required_len {
+        return Err(CheckCopyQueryPoolResultsError::BufferTooSmall {
+                       required_len,
+                       actual_len: destination.len(),
+                   });
+    }
+
+    Ok(per_query_len * mem::size_of::<T>())
+}
+
+/// Error that can happen from `check_copy_query_pool_results`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckCopyQueryPoolResultsError {
+    /// The range of queries is empty or out of range of the query pool.
+    OutOfRange,
+    /// The "transfer destination" usage must be enabled on the destination buffer.
+    DestinationMissingTransferUsage,
+    /// The offset of the destination buffer is not a multiple of the size of the result type.
+    WrongAlignment,
+    /// The `partial` flag was requested for a timestamp query pool.
+    InvalidFlags,
+    /// The destination buffer is too small to hold the results.
+    BufferTooSmall {
+        /// Required number of elements in the buffer.
+        required_len: usize,
+        /// Actual number of elements in the buffer.
+        actual_len: usize,
+    },
+}
+
+impl error::Error for CheckCopyQueryPoolResultsError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckCopyQueryPoolResultsError::OutOfRange => {
+                "the range of queries is empty or out of range of the query pool"
+            },
+            CheckCopyQueryPoolResultsError::DestinationMissingTransferUsage => {
+                "the transfer destination usage must be enabled on the destination buffer"
+            },
+            CheckCopyQueryPoolResultsError::WrongAlignment => {
+                "the offset of the destination buffer is not a multiple of the size of the \
+                 result type"
+            },
+            CheckCopyQueryPoolResultsError::InvalidFlags => {
+                "the partial flag was requested for a timestamp query pool"
+            },
+            CheckCopyQueryPoolResultsError::BufferTooSmall { .. } => {
+                "the destination buffer is too small to hold the results"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckCopyQueryPoolResultsError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use query::UnsafeQueryPool;
+    use std::iter;
+
+    #[test]
+    fn begin_query_timestamp() {
+        let (device, _) = gfx_dev_and_queue!();
+        let pool = UnsafeQueryPool::new(device.clone(), QueryType::Timestamp, 4).unwrap();
+
+        match check_begin_query(&device, &pool, 0, false) {
+            Err(CheckBeginQueryError::NotPermitted) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn write_timestamp_multiple_stages() {
+        let (device, _) = gfx_dev_and_queue!();
+        let pool = UnsafeQueryPool::new(device.clone(), QueryType::Timestamp, 4).unwrap();
+
+        let stages = PipelineStages {
+            top_of_pipe: true,
+            bottom_of_pipe: true,
+            ..PipelineStages::none()
+        };
+
+        match check_write_timestamp(&device, &pool, 0, stages) {
+            Err(CheckWriteTimestampError::InvalidStage) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn reset_out_of_range() {
+        let (device, _) = gfx_dev_and_queue!();
+        let pool = UnsafeQueryPool::new(device.clone(), QueryType::Occlusion, 4).unwrap();
+
+        assert!(check_reset_query_pool(&device, &pool, 0 .. 4).is_ok());
+
+        match check_reset_query_pool(&device, &pool, 2 .. 5) {
+            Err(CheckResetQueryPoolError::OutOfRange) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn copy_results_buffer_too_small() {
+        let (device, _) = gfx_dev_and_queue!();
+        let pool = UnsafeQueryPool::new(device.clone(), QueryType::Occlusion, 4).unwrap();
+        let buffer = CpuAccessibleBuffer::from_iter(device.clone(),
+                                                    BufferUsage::all(),
+                                                    iter::repeat(0u64).take(4))
+            .unwrap();
+
+        let flags = QueryResultFlags {
+            with_availability: true,
+            ..QueryResultFlags::none()
+        };
+
+        match check_copy_query_pool_results(&device, &pool, 0 .. 4, &buffer, flags) {
+            Err(CheckCopyQueryPoolResultsError::BufferTooSmall { required_len: 8, .. }) => (),
+            _ => panic!(),
+        }
+
+        assert_eq!(check_copy_query_pool_results(&device, &pool, 0 .. 2, &buffer, flags)
+                       .unwrap(),
+                   16);
+    }
+}
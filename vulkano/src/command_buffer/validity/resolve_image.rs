@@ -0,0 +1,253 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use VulkanObject;
+use device::Device;
+use image::ImageAccess;
+use image::ImageDimensions;
+
+/// Checks whether a resolve image command is valid.
+///
+/// Note that this doesn't check whether `layer_count` is equal to 0.
+///
+/// # Panic
+///
+/// - Panics if the source or the destination was not created with `device`.
+///
+pub fn check_resolve_image<S, D>(device: &Device, source: &S, source_offset: [i32; 3],
+                                 source_base_array_layer: u32, source_mip_level: u32,
+                                 destination: &D, destination_offset: [i32; 3],
+                                 destination_base_array_layer: u32, destination_mip_level: u32,
+                                 extent: [u32; 3], layer_count: u32)
+                                 -> Result<(), CheckResolveImageError>
+    where S: ?Sized + ImageAccess,
+          D: ?Sized + ImageAccess
+{
+    let source_inner = source.inner();
+    let destination_inner = destination.inner();
+
+    assert_eq!(source_inner.image.device().internal_object(),
+               device.internal_object());
+    assert_eq!(destination_inner.image.device().internal_object(),
+               device.internal_object());
+
+    if source.samples() == 1 {
+        return Err(CheckResolveImageError::SourceNotMultisampled);
+    }
+
+    if destination.samples() != 1 {
+        return Err(CheckResolveImageError::DestinationMultisampled);
+    }
+
+    if !source_inner.image.usage_transfer_source() {
+        return Err(CheckResolveImageError::MissingTransferSourceUsage);
+    }
+
+    if !destination_inner.image.usage_transfer_destination() {
+        return Err(CheckResolveImageError::MissingTransferDestinationUsage);
+    }
+
+    if !source.has_color() {
+        return Err(CheckResolveImageError::NotColorFormat);
+    }
+
+    if source.format() != destination.format() {
+        return Err(CheckResolveImageError::FormatMismatch);
+    }
+
+    let source_dimensions = match source.dimensions().mipmap_dimensions(source_mip_level) {
+        Some(d) => d,
+        None => return Err(CheckResolveImageError::SourceCoordinatesOutOfRange),
+    };
+
+    let destination_dimensions = match destination
+        .dimensions()
+        .mipmap_dimensions(destination_mip_level) {
+        Some(d) => d,
+        None => return Err(CheckResolveImageError::DestinationCoordinatesOutOfRange),
+    };
+
+    if source_base_array_layer
+        .checked_add(layer_count)
+        .map_or(true, |end| end > source_dimensions.array_layers())
+    {
+        return Err(CheckResolveImageError::SourceCoordinatesOutOfRange);
+    }
+
+    if destination_base_array_layer
+        .checked_add(layer_count)
+        .map_or(true, |end| end > destination_dimensions.array_layers())
+    {
+        return Err(CheckResolveImageError::DestinationCoordinatesOutOfRange);
+    }
+
+    if !region_in_range(source_offset, extent, source_dimensions) {
+        return Err(CheckResolveImageError::SourceCoordinatesOutOfRange);
+    }
+
+    if !region_in_range(destination_offset, extent, destination_dimensions) {
+        return Err(CheckResolveImageError::DestinationCoordinatesOutOfRange);
+    }
+
+    Ok(())
+}
+
+// Returns true if the region starting at `offset` and of size `extent` fits in `dimensions`,
+// taking the type of the image into account.
+fn region_in_range(offset: [i32; 3], extent: [u32; 3], dimensions: ImageDimensions) -> bool {
+    let max = dimensions.width_height_depth();
+
+    for i in 0 .. 3 {
+        if offset[i] < 0 {
+            return false;
+        }
+
+        match (offset[i] as u32).checked_add(extent[i]) {
+            Some(end) if end <= max[i] => (),
+            _ => return false,
+        }
+    }
+
+    true
+}
+
+/// Error that can happen from `check_resolve_image`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckResolveImageError {
+    /// The source must be a multisampled image.
+    SourceNotMultisampled,
+    /// The destination must not be a multisampled image.
+    DestinationMultisampled,
+    /// The source is missing the transfer source usage.
+    MissingTransferSourceUsage,
+    /// The destination is missing the transfer destination usage.
+    MissingTransferDestinationUsage,
+    /// Only images with a color format can be resolved.
+    NotColorFormat,
+    /// The source and the destination must have the same format.
+    FormatMismatch,
+    /// The offsets, array layers and/or mipmap levels are out of range in the source image.
+    SourceCoordinatesOutOfRange,
+    /// The offsets, array layers and/or mipmap levels are out of range in the destination image.
+    DestinationCoordinatesOutOfRange,
+}
+
+impl error::Error for CheckResolveImageError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckResolveImageError::SourceNotMultisampled => {
+                "the source must be a multisampled image"
+            },
+            CheckResolveImageError::DestinationMultisampled => {
+                "the destination must not be a multisampled image"
+            },
+            CheckResolveImageError::MissingTransferSourceUsage => {
+                "the source is missing the transfer source usage"
+            },
+            CheckResolveImageError::MissingTransferDestinationUsage => {
+                "the destination is missing the transfer destination usage"
+            },
+            CheckResolveImageError::NotColorFormat => {
+                "only images with a color format can be resolved"
+            },
+            CheckResolveImageError::FormatMismatch => {
+                "the source and the destination must have the same format"
+            },
+            CheckResolveImageError::SourceCoordinatesOutOfRange => {
+                "the offsets, array layers and/or mipmap levels are out of range in the source \
+                 image"
+            },
+            CheckResolveImageError::DestinationCoordinatesOutOfRange => {
+                "the offsets, array layers and/or mipmap levels are out of range in the \
+                 destination image"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckResolveImageError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use format::Format;
+    use image::AttachmentImage;
+
+    #[test]
+    fn source_not_multisampled() {
+        let (device, _) = gfx_dev_and_queue!();
+        let source = AttachmentImage::new(device.clone(), [16, 16], Format::R8G8B8A8Unorm)
+            .unwrap();
+        let destination = AttachmentImage::new(device.clone(), [16, 16], Format::R8G8B8A8Unorm)
+            .unwrap();
+
+        match check_resolve_image(&device,
+                                  &source,
+                                  [0, 0, 0],
+                                  0,
+                                  0,
+                                  &destination,
+                                  [0, 0, 0],
+                                  0,
+                                  0,
+                                  [16, 16, 1],
+                                  1) {
+            Err(CheckResolveImageError::SourceNotMultisampled) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn destination_multisampled() {
+        let (device, _) = gfx_dev_and_queue!();
+        let source =
+            AttachmentImage::multisampled(device.clone(), [16, 16], 4, Format::R8G8B8A8Unorm)
+                .unwrap();
+        let destination =
+            AttachmentImage::multisampled(device.clone(), [16, 16], 4, Format::R8G8B8A8Unorm)
+                .unwrap();
+
+        match check_resolve_image(&device,
+                                  &source,
+                                  [0, 0, 0],
+                                  0,
+                                  0,
+                                  &destination,
+                                  [0, 0, 0],
+                                  0,
+                                  0,
+                                  [16, 16, 1],
+                                  1) {
+            Err(CheckResolveImageError::DestinationMultisampled) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn region_overflow() {
+        let dimensions = ImageDimensions::Dim2d {
+            width: 16,
+            height: 16,
+            array_layers: 1,
+            cubemap_compatible: false,
+        };
+
+        assert!(region_in_range([0, 0, 0], [16, 16, 1], dimensions));
+        assert!(!region_in_range([1, 0, 0], [u32::max_value(), 16, 1], dimensions));
+    }
+}
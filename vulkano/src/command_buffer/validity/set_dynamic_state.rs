@@ -0,0 +1,172 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use device::Device;
+
+/// Checks whether a command that sets the line width is valid.
+pub fn check_set_line_width(device: &Device, line_width: f32)
+                            -> Result<(), CheckSetDynamicStateError> {
+    if line_width != 1.0 && !device.enabled_features().wide_lines {
+        return Err(CheckSetDynamicStateError::WideLinesFeatureNotEnabled);
+    }
+
+    Ok(())
+}
+
+/// Checks whether a command that sets viewports is valid.
+#[inline]
+pub fn check_set_viewport(device: &Device, first_viewport: u32, num_viewports: u32)
+                          -> Result<(), CheckSetDynamicStateError> {
+    check_range(device, first_viewport, num_viewports)
+}
+
+/// Checks whether a command that sets scissors is valid.
+#[inline]
+pub fn check_set_scissor(device: &Device, first_scissor: u32, num_scissors: u32)
+                         -> Result<(), CheckSetDynamicStateError> {
+    check_range(device, first_scissor, num_scissors)
+}
+
+/// Checks whether a command that sets the depth bias is valid.
+pub fn check_set_depth_bias(device: &Device, clamp: f32) -> Result<(), CheckSetDynamicStateError> {
+    if clamp != 0.0 && !device.enabled_features().depth_bias_clamp {
+        return Err(CheckSetDynamicStateError::DepthBiasClampFeatureNotEnabled);
+    }
+
+    Ok(())
+}
+
+/// Checks whether a command that sets the depth bounds is valid.
+#[inline]
+pub fn check_set_depth_bounds(min: f32, max: f32) -> Result<(), CheckSetDynamicStateError> {
+    if !(min >= 0.0 && min <= 1.0) || !(max >= 0.0 && max <= 1.0) {
+        return Err(CheckSetDynamicStateError::DepthBoundsOutOfRange);
+    }
+
+    Ok(())
+}
+
+// Checks that a range of viewports or scissors is within the maximum number of viewports of the
+// device, and that the `multi_viewport` feature is enabled if it isn't just the first one.
+fn check_range(device: &Device, first: u32, count: u32) -> Result<(), CheckSetDynamicStateError> {
+    let max_viewports = device.physical_device().limits().max_viewports();
+    let in_range = first
+        .checked_add(count)
+        .map_or(false, |end| end <= max_viewports);
+
+    if count == 0 || !in_range {
+        return Err(CheckSetDynamicStateError::OutOfRange {
+                       first,
+                       count,
+                       max_viewports,
+                   });
+    }
+
+    if (first != 0 || count > 1) && !device.enabled_features().multi_viewport {
+        return Err(CheckSetDynamicStateError::MultiViewportFeatureNotEnabled);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when setting a dynamic state.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckSetDynamicStateError {
+    /// The range of viewports or scissors is empty or exceeds the maximum number of viewports
+    /// of the device.
+    OutOfRange {
+        /// Index of the first viewport or scissor that was passed.
+        first: u32,
+        /// Number of viewports or scissors that were passed.
+        count: u32,
+        /// Maximum number of viewports of the device.
+        max_viewports: u32,
+    },
+    /// The `multi_viewport` feature must be enabled in order to set a viewport or a scissor
+    /// other than the first one.
+    MultiViewportFeatureNotEnabled,
+    /// The `wide_lines` feature must be enabled in order to use line width values different
+    /// from 1.0.
+    WideLinesFeatureNotEnabled,
+    /// The `depth_bias_clamp` feature must be enabled in order to use a depth bias clamp
+    /// different from 0.0.
+    DepthBiasClampFeatureNotEnabled,
+    /// The depth bounds must be between 0.0 and 1.0.
+    DepthBoundsOutOfRange,
+}
+
+impl error::Error for CheckSetDynamicStateError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckSetDynamicStateError::OutOfRange { .. } => {
+                "the range of viewports or scissors is empty or exceeds the maximum number of \
+                 viewports of the device"
+            },
+            CheckSetDynamicStateError::MultiViewportFeatureNotEnabled => {
+                "the `multi_viewport` feature must be enabled in order to set a viewport or a \
+                 scissor other than the first one"
+            },
+            CheckSetDynamicStateError::WideLinesFeatureNotEnabled => {
+                "the `wide_lines` feature must be enabled in order to use line width values \
+                 different from 1.0"
+            },
+            CheckSetDynamicStateError::DepthBiasClampFeatureNotEnabled => {
+                "the `depth_bias_clamp` feature must be enabled in order to use a depth bias \
+                 clamp different from 0.0"
+            },
+            CheckSetDynamicStateError::DepthBoundsOutOfRange => {
+                "the depth bounds must be between 0.0 and 1.0"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckSetDynamicStateError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_viewport_feature() {
+        let (device, _) = gfx_dev_and_queue!();
+        if device.physical_device().limits().max_viewports() < 2 {
+            return;
+        }
+
+        check_set_viewport(&device, 0, 1).unwrap();
+
+        match check_set_viewport(&device, 0, 2) {
+            Err(CheckSetDynamicStateError::MultiViewportFeatureNotEnabled) => (),
+            _ => panic!(),
+        }
+
+        match check_set_scissor(&device, 1, 1) {
+            Err(CheckSetDynamicStateError::MultiViewportFeatureNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn multi_viewport_feature_enabled() {
+        let (device, _) = gfx_dev_and_queue!(multi_viewport);
+
+        check_set_viewport(&device, 0, 2).unwrap();
+        check_set_scissor(&device, 1, 1).unwrap();
+    }
+}
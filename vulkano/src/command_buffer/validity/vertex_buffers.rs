@@ -12,7 +12,9 @@ use std::fmt;
 
 use VulkanObject;
 use buffer::BufferAccess;
+use device::Device;
 use device::DeviceOwned;
+use pipeline::GraphicsPipelineAbstract;
 use pipeline::vertex::VertexSource;
 
 /// Checks whether vertex buffers can be bound.
@@ -23,10 +25,18 @@ use pipeline::vertex::VertexSource;
 ///
 pub fn check_vertex_buffers<P, V>(pipeline: &P, vertex_buffers: V)
                                   -> Result<CheckVertexBuffer, CheckVertexBufferError>
-    where P: DeviceOwned + VertexSource<V>
+    where P: GraphicsPipelineAbstract + VertexSource<V>
 {
     let (vertex_buffers, vertex_count, instance_count) = pipeline.decode(vertex_buffers);
 
+    let num_bindings = pipeline.vertex_buffer_strides().len();
+    if vertex_buffers.len() != num_bindings {
+        return Err(CheckVertexBufferError::BufferCountMismatch {
+                       expected: num_bindings,
+                       obtained: vertex_buffers.len(),
+                   });
+    }
+
     for (num, buf) in vertex_buffers.iter().enumerate() {
         assert_eq!(buf.inner().buffer.device().internal_object(),
                    pipeline.device().internal_object());
@@ -36,20 +46,84 @@ pub fn check_vertex_buffers<P, V>(pipeline: &P, vertex_buffers: V)
         }
     }
 
+    // `decode` returns `u32::max_value()` if no buffer limits the number of vertices or
+    // instances, in which case a single one is drawn.
+    let draw_count = |count: usize| if count >= u32::max_value() as usize {
+        1
+    } else {
+        count as u32
+    };
+
     Ok(CheckVertexBuffer {
            vertex_buffers,
-           vertex_count: vertex_count as u32,
-           instance_count: instance_count as u32,
+           vertex_count: draw_count(vertex_count),
+           instance_count: draw_count(instance_count),
        })
 }
 
+/// Checks whether buffers can be bound as vertex buffers, independently of any pipeline.
+///
+/// # Panic
+///
+/// - Panics if one of the vertex buffers was not created with `device`.
+///
+pub fn check_bind_vertex_buffers<B>(device: &Device, vertex_buffers: &[B])
+                                    -> Result<(), CheckVertexBufferError>
+    where B: BufferAccess
+{
+    for (num, buf) in vertex_buffers.iter().enumerate() {
+        assert_eq!(buf.inner().buffer.device().internal_object(),
+                   device.internal_object());
+
+        if !buf.inner().buffer.usage_vertex_buffer() {
+            return Err(CheckVertexBufferError::BufferMissingUsage { num_buffer: num });
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks whether vertex buffers that were bound earlier match the vertex input of a pipeline.
+///
+/// `element_sizes` contains, for each bound buffer, the size in bytes of its elements if known.
+pub fn check_bound_vertex_buffers<P>(pipeline: &P, element_sizes: &[Option<usize>])
+                                     -> Result<(), CheckVertexBufferError>
+    where P: ?Sized + GraphicsPipelineAbstract
+{
+    let strides = pipeline.vertex_buffer_strides();
+
+    if element_sizes.len() != strides.len() {
+        return Err(CheckVertexBufferError::BufferCountMismatch {
+                       expected: strides.len(),
+                       obtained: element_sizes.len(),
+                   });
+    }
+
+    for (num, (&stride, &element_size)) in strides.iter().zip(element_sizes.iter()).enumerate() {
+        match element_size {
+            Some(element_size) if stride != 0 && element_size != stride as usize => {
+                return Err(CheckVertexBufferError::StrideMismatch {
+                               num_buffer: num,
+                               stride,
+                               element_size,
+                           });
+            },
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
+
 /// Information returned if `check_vertex_buffer` succeeds.
 pub struct CheckVertexBuffer {
     /// The list of vertex buffers.
     pub vertex_buffers: Vec<Box<dyn BufferAccess + Send + Sync>>,
-    /// Number of vertices available in the intersection of the buffers.
+    /// Number of vertices available in the intersection of the buffers, or 1 if no buffer limits
+    /// it.
     pub vertex_count: u32,
-    /// Number of instances available in the intersection of the buffers.
+    /// Number of instances available in the intersection of the buffers, or 1 if no buffer limits
+    /// it.
     pub instance_count: u32,
 }
 
@@ -61,6 +135,24 @@ pub enum CheckVertexBufferError {
         /// Index of the buffer that is missing usage.
         num_buffer: usize,
     },
+    /// The number of vertex buffers doesn't match the number of vertex buffer bindings of the
+    /// pipeline.
+    BufferCountMismatch {
+        /// Number of bindings of the pipeline.
+        expected: usize,
+        /// Number of buffers that were bound.
+        obtained: usize,
+    },
+    /// The size of the elements of a vertex buffer doesn't match the stride of the corresponding
+    /// binding of the pipeline.
+    StrideMismatch {
+        /// Index of the buffer.
+        num_buffer: usize,
+        /// Stride of the binding in the pipeline.
+        stride: u32,
+        /// Size of the elements of the buffer.
+        element_size: usize,
+    },
 }
 
 impl error::Error for CheckVertexBufferError {
@@ -70,6 +162,14 @@ impl error::Error for CheckVertexBufferError {
             CheckVertexBufferError::BufferMissingUsage { .. } => {
                 "the vertex buffer usage is missing on a vertex buffer"
             },
+            CheckVertexBufferError::BufferCountMismatch { .. } => {
+                "the number of vertex buffers doesn't match the number of vertex buffer bindings \
+                 of the pipeline"
+            },
+            CheckVertexBufferError::StrideMismatch { .. } => {
+                "the size of the elements of a vertex buffer doesn't match the stride of the \
+                 corresponding binding of the pipeline"
+            },
         }
     }
 }
@@ -265,6 +265,10 @@ impl<L, R> PersistentDescriptorSetBuilder<L, R>
     ///
     /// An error is returned if the buffer isn't compatible with the descriptor.
     ///
+    /// If the descriptor is a dynamic buffer, the buffer describes the range that is accessed
+    /// with a dynamic offset of 0. The dynamic offsets are passed when binding the set, for
+    /// example with `AutoCommandBufferBuilder::bind_descriptor_sets`.
+    ///
     /// # Panic
     ///
     /// Panics if the buffer doesn't have the same device as the pipeline layout.
@@ -416,6 +420,10 @@ impl<L, R> PersistentDescriptorSetBuilderArray<L, R>
                             .enabled_features()
                             .robust_buffer_access);
 
+                // For dynamic buffers, the range of the buffer is shifted by the dynamic offset
+                // that is passed when binding the set.
+                let dynamic = buffer_desc.dynamic == Some(true);
+
                 if buffer_desc.storage {
                     if !buffer.inner().buffer.usage_storage_buffer() {
                         return Err(PersistentDescriptorSetError::MissingBufferUsage(
@@ -423,9 +431,15 @@ impl<L, R> PersistentDescriptorSetBuilderArray<L, R>
                     }
 
                     unsafe {
-                        DescriptorWrite::storage_buffer(self.builder.binding_id as u32,
-                                                        self.array_element as u32,
-                                                        &buffer)
+                        if dynamic {
+                            DescriptorWrite::dynamic_storage_buffer(self.builder.binding_id as u32,
+                                                                    self.array_element as u32,
+                                                                    &buffer)
+                        } else {
+                            DescriptorWrite::storage_buffer(self.builder.binding_id as u32,
+                                                            self.array_element as u32,
+                                                            &buffer)
+                        }
                     }
                 } else {
                     if !buffer.inner().buffer.usage_uniform_buffer() {
@@ -434,9 +448,15 @@ impl<L, R> PersistentDescriptorSetBuilderArray<L, R>
                     }
 
                     unsafe {
-                        DescriptorWrite::uniform_buffer(self.builder.binding_id as u32,
-                                                        self.array_element as u32,
-                                                        &buffer)
+                        if dynamic {
+                            DescriptorWrite::dynamic_uniform_buffer(self.builder.binding_id as u32,
+                                                                    self.array_element as u32,
+                                                                    &buffer)
+                        } else {
+                            DescriptorWrite::uniform_buffer(self.builder.binding_id as u32,
+                                                            self.array_element as u32,
+                                                            &buffer)
+                        }
                     }
                 }
             },
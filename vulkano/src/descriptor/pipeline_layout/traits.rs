@@ -15,6 +15,7 @@ use std::sync::Arc;
 use SafeDeref;
 use descriptor::descriptor::DescriptorDesc;
 use descriptor::descriptor::DescriptorDescSupersetError;
+use descriptor::descriptor::DescriptorDescTy;
 use descriptor::descriptor::ShaderStages;
 use descriptor::descriptor_set::DescriptorSetsCollection;
 use descriptor::descriptor_set::UnsafeDescriptorSetLayout;
@@ -170,6 +171,10 @@ pub unsafe trait PipelineLayoutSuperset<Other: ?Sized>: PipelineLayoutDesc
     where Other: PipelineLayoutDesc
 {
     /// Makes sure that `self` is a superset of `Other`. Returns an `Err` if this is not the case.
+    ///
+    /// A dynamic buffer descriptor in `self` is considered a superset of a non-dynamic buffer
+    /// descriptor in `Other`, so that a layout with dynamic buffers can be used with shaders.
+    /// A non-dynamic buffer descriptor in `self` is not a superset of a dynamic one.
     fn ensure_superset_of(&self, &Other) -> Result<(), PipelineLayoutNotSupersetError>;
 }
 
@@ -192,7 +197,21 @@ unsafe impl<T: ?Sized, U: ?Sized> PipelineLayoutSuperset<U> for T
 
             for desc_num in 0 .. other_num_bindings {
                 match (self.descriptor(set_num, desc_num), other.descriptor(set_num, desc_num)) {
-                    (Some(mine), Some(other)) => {
+                    (Some(mine), Some(mut other)) => {
+                        // A shader can't tell whether a buffer is dynamic, so a dynamic buffer
+                        // can be used where `other` has a non-dynamic one. The opposite isn't
+                        // true, and is caught by `is_superset_of`.
+                        if let (&DescriptorDescTy::Buffer(ref mine_buffer),
+                                &mut DescriptorDescTy::Buffer(ref mut other_buffer)) =
+                            (&mine.ty, &mut other.ty)
+                        {
+                            if mine_buffer.dynamic == Some(true) &&
+                                other_buffer.dynamic == Some(false)
+                            {
+                                other_buffer.dynamic = None;
+                            }
+                        }
+
                         if let Err(err) = mine.is_superset_of(&other) {
                             return Err(PipelineLayoutNotSupersetError::IncompatibleDescriptors {
                                            error: err,
@@ -17,6 +17,12 @@ use fnv::FnvHashSet;
 /// Transforms a `PipelineLayoutDesc`.
 ///
 /// Used to adjust automatically inferred `PipelineLayoutDesc`s with information that cannot be inferred.
+///
+/// Whether a buffer descriptor is dynamic can't be known from a shader, so the layouts reflected
+/// from shaders only contain non-dynamic buffers. Wrapping such a layout marks the given
+/// `(set, binding)` pairs as dynamic buffers, whose offsets are then passed when binding the
+/// descriptor sets. The resulting layout is still a superset of the original one, and can be
+/// used to build a pipeline with the same shaders.
 pub struct PipelineLayoutDescTweaks<T> {
     inner: T,
     dynamic_buffers: FnvHashSet<(usize, usize)>,
@@ -76,3 +82,37 @@ unsafe impl<T> PipelineLayoutDesc for PipelineLayoutDescTweaks<T>
     #[inline]
     fn push_constants_range(&self, num: usize) -> Option<PipelineLayoutDescPcRange> { self.inner.push_constants_range(num) }
 }
+
+#[cfg(test)]
+mod tests {
+    use descriptor::descriptor::DescriptorBufferDesc;
+    use descriptor::descriptor::DescriptorDesc;
+    use descriptor::descriptor::DescriptorDescTy;
+    use descriptor::descriptor::ShaderStages;
+    use descriptor::pipeline_layout::PipelineLayoutDescTweaks;
+    use descriptor::pipeline_layout::PipelineLayoutSuperset;
+    use descriptor::pipeline_layout::RuntimePipelineDesc;
+    use std::iter;
+
+    fn uniform_buffer_layout() -> RuntimePipelineDesc {
+        let desc = DescriptorDesc {
+            ty: DescriptorDescTy::Buffer(DescriptorBufferDesc {
+                                             dynamic: Some(false),
+                                             storage: false,
+                                         }),
+            array_count: 1,
+            stages: ShaderStages::all(),
+            readonly: true,
+        };
+
+        RuntimePipelineDesc::new(iter::once(iter::once(Some(desc))), iter::empty()).unwrap()
+    }
+
+    #[test]
+    fn dynamic_superset() {
+        let dynamic = PipelineLayoutDescTweaks::new(uniform_buffer_layout(), iter::once((0, 0)));
+
+        assert!(dynamic.ensure_superset_of(&uniform_buffer_layout()).is_ok());
+        assert!(uniform_buffer_layout().ensure_superset_of(&dynamic).is_err());
+    }
+}
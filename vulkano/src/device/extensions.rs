@@ -117,6 +117,8 @@ device_extensions! {
     khr_storage_buffer_storage_class => b"VK_KHR_storage_buffer_storage_class",
     ext_debug_utils => b"VK_EXT_debug_utils",
     khr_multiview => b"VK_KHR_multiview",
+    khr_draw_indirect_count => b"VK_KHR_draw_indirect_count",
+    ext_vertex_attribute_divisor => b"VK_EXT_vertex_attribute_divisor",
 }
 
 /// This helper type can only be instantiated inside this module.
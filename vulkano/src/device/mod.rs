@@ -273,9 +273,28 @@ impl Device {
                 features
             };
 
+            // The features of `VK_EXT_vertex_attribute_divisor` aren't part of `Features`. All the
+            // ones that are supported are enabled along with the extension.
+            let vertex_attribute_divisor_features = match phys.vertex_attribute_divisor_support() {
+                Some(support) if DeviceExtensions::from(&extensions).ext_vertex_attribute_divisor => {
+                    Some(vk::PhysicalDeviceVertexAttributeDivisorFeaturesEXT {
+                        sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_VERTEX_ATTRIBUTE_DIVISOR_FEATURES_EXT,
+                        pNext: ptr::null_mut(),
+                        vertexAttributeInstanceRateDivisor:
+                            if support.instance_rate_divisor { vk::TRUE } else { vk::FALSE },
+                        vertexAttributeInstanceRateZeroDivisor:
+                            if support.instance_rate_zero_divisor { vk::TRUE } else { vk::FALSE },
+                    })
+                },
+                _ => None,
+            };
+
             let infos = vk::DeviceCreateInfo {
                 sType: vk::STRUCTURE_TYPE_DEVICE_CREATE_INFO,
-                pNext: ptr::null(),
+                pNext: vertex_attribute_divisor_features
+                    .as_ref()
+                    .map(|f| f as *const _ as *const _)
+                    .unwrap_or(ptr::null()),
                 flags: 0, // reserved
                 queueCreateInfoCount: queues.len() as u32,
                 pQueueCreateInfos: queues.as_ptr(),
@@ -20,6 +20,8 @@ use command_buffer::AutoCommandBuffer;
 use command_buffer::AutoCommandBufferBuilder;
 use command_buffer::CommandBuffer;
 use command_buffer::CommandBufferExecFuture;
+use command_buffer::GenerateMipmapsError;
+use command_buffer::validity::CheckGenerateMipmapsError;
 use device::Device;
 use device::Queue;
 use format::AcceptsPixels;
@@ -188,36 +190,57 @@ impl<F> ImmutableImage<F> {
 
     /// Construct an ImmutableImage from the contents of `iter`.
     ///
-    /// TODO: Support mipmaps
-    #[inline]
-    pub fn from_iter<P, I>(iter: I, dimensions: Dimensions, format: F, queue: Arc<Queue>)
-                           -> Result<(Arc<Self>,
-                                      CommandBufferExecFuture<NowFuture, AutoCommandBuffer>),
-                                     ImageCreationError>
+    /// The data is written to the first mipmap level. If `mipmaps` allocates more than one
+    /// mipmap level, the other levels are generated from the first one.
+    #[inline]
+    pub fn from_iter<P, I, M>(iter: I, dimensions: Dimensions, mipmaps: M, format: F,
+                              queue: Arc<Queue>)
+                              -> Result<(Arc<Self>,
+                                         CommandBufferExecFuture<NowFuture, AutoCommandBuffer>),
+                                        ImageCreationError>
         where P: Send + Sync + Clone + 'static,
               F: FormatDesc + AcceptsPixels<P> + 'static + Send + Sync,
               I: ExactSizeIterator<Item = P>,
+              M: Into<MipmapsCount>,
               Format: AcceptsPixels<P>
     {
         let source = CpuAccessibleBuffer::from_iter(queue.device().clone(),
                                                     BufferUsage::transfer_source(),
                                                     iter)?;
-        ImmutableImage::from_buffer(source, dimensions, format, queue)
+        ImmutableImage::from_buffer(source, dimensions, mipmaps, format, queue)
     }
 
     /// Construct an ImmutableImage containing a copy of the data in `source`.
     ///
-    /// TODO: Support mipmaps
-    pub fn from_buffer<B, P>(source: B, dimensions: Dimensions, format: F, queue: Arc<Queue>)
-                             -> Result<(Arc<Self>,
-                                        CommandBufferExecFuture<NowFuture, AutoCommandBuffer>),
-                                       ImageCreationError>
+    /// The data is copied to the first mipmap level. If `mipmaps` allocates more than one
+    /// mipmap level, the other levels are generated from the first one, in which case `queue`
+    /// must support graphics operations and the format must support blitting. Otherwise
+    /// `GraphicsQueueRequired` or `BlitNotSupported` is returned. Vulkano doesn't fall back to a
+    /// compute shader in that case, so the mipmaps must be generated by other means.
+    pub fn from_buffer<B, P, M>(source: B, dimensions: Dimensions, mipmaps: M, format: F,
+                                queue: Arc<Queue>)
+                                -> Result<(Arc<Self>,
+                                           CommandBufferExecFuture<NowFuture, AutoCommandBuffer>),
+                                          ImageCreationError>
         where B: BufferAccess + TypedBufferAccess<Content = [P]> + 'static + Clone + Send + Sync,
               P: Send + Sync + Clone + 'static,
               F: FormatDesc + AcceptsPixels<P> + 'static + Send + Sync,
+              M: Into<MipmapsCount>,
               Format: AcceptsPixels<P>
     {
+        let mipmaps = mipmaps.into();
+        let generate_mipmaps = match mipmaps {
+            MipmapsCount::One => false,
+            MipmapsCount::Specific(num) => num > 1,
+            MipmapsCount::Log2 => true,
+        };
+
+        if generate_mipmaps && !queue.family().supports_graphics() {
+            return Err(ImageCreationError::GraphicsQueueRequired);
+        }
+
         let usage = ImageUsage {
+            transfer_source: generate_mipmaps,
             transfer_destination: true,
             sampled: true,
             ..ImageUsage::none()
@@ -228,23 +251,37 @@ impl<F> ImmutableImage<F> {
             ImmutableImage::uninitialized(source.device().clone(),
                                           dimensions,
                                           format,
-                                          MipmapsCount::One,
+                                          mipmaps,
                                           usage,
                                           layout,
                                           source.device().active_queue_families())?;
 
-        let cb = AutoCommandBufferBuilder::new(source.device().clone(), queue.family())?
+        // The initialization is used by both the copy and the mipmaps generation.
+        let init = Arc::new(init);
+
+        let mut cb = AutoCommandBufferBuilder::new(source.device().clone(), queue.family())?
             .copy_buffer_to_image_dimensions(source,
-                                             init,
+                                             init.clone(),
                                              [0, 0, 0],
                                              dimensions.width_height_depth(),
                                              0,
                                              dimensions.array_layers_with_cube(),
                                              0)
-            .unwrap()
-            .build()
             .unwrap();
 
+        if generate_mipmaps && buffer.mipmap_levels() > 1 {
+            cb = match cb.generate_mipmaps(init) {
+                Ok(cb) => cb,
+                Err(GenerateMipmapsError::CheckGenerateMipmapsError(
+                    CheckGenerateMipmapsError::BlitNotSupported)) => {
+                    return Err(ImageCreationError::BlitNotSupported);
+                },
+                Err(_) => unreachable!(),
+            };
+        }
+
+        let cb = cb.build().unwrap();
+
         let future = match cb.execute(queue) {
             Ok(f) => f,
             Err(_) => unreachable!(),
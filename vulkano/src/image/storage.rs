@@ -21,6 +21,7 @@ use image::Dimensions;
 use image::ImageInner;
 use image::ImageLayout;
 use image::ImageUsage;
+use image::MipmapsCount;
 use image::sys::ImageCreationError;
 use image::sys::UnsafeImage;
 use image::sys::UnsafeImageView;
@@ -99,11 +100,32 @@ impl<F> StorageImage<F> {
     }
 
     /// Same as `new`, but allows specifying the usage.
+    #[inline]
     pub fn with_usage<'a, I>(device: Arc<Device>, dimensions: Dimensions, format: F,
                              usage: ImageUsage, queue_families: I)
                              -> Result<Arc<StorageImage<F>>, ImageCreationError>
         where F: FormatDesc,
               I: IntoIterator<Item = QueueFamily<'a>>
+    {
+        StorageImage::with_mipmaps(device,
+                                   dimensions,
+                                   format,
+                                   MipmapsCount::One,
+                                   usage,
+                                   queue_families)
+    }
+
+    /// Same as `with_usage`, but allows specifying the number of mipmaps.
+    ///
+    /// The content of the mipmap levels can be generated from the first level with the
+    /// `generate_mipmaps` command of `AutoCommandBufferBuilder`. Note that an image with more
+    /// than one mipmap level can't be used as a framebuffer attachment.
+    pub fn with_mipmaps<'a, I, M>(device: Arc<Device>, dimensions: Dimensions, format: F,
+                                  mipmaps: M, usage: ImageUsage, queue_families: I)
+                                  -> Result<Arc<StorageImage<F>>, ImageCreationError>
+        where F: FormatDesc,
+              I: IntoIterator<Item = QueueFamily<'a>>,
+              M: Into<MipmapsCount>
     {
         let queue_families = queue_families
             .into_iter()
@@ -122,7 +144,7 @@ impl<F> StorageImage<F> {
                              format.format(),
                              dimensions.to_image_dimensions(),
                              1,
-                             1,
+                             mipmaps,
                              sharing,
                              false,
                              false)?
@@ -170,6 +192,12 @@ impl<F, A> StorageImage<F, A>
     pub fn dimensions(&self) -> Dimensions {
         self.dimensions
     }
+
+    /// Returns the number of mipmap levels of the image.
+    #[inline]
+    pub fn mipmap_levels(&self) -> u32 {
+        self.image.mipmap_levels()
+    }
 }
 
 unsafe impl<F, A> ImageAccess for StorageImage<F, A>
@@ -183,7 +211,7 @@ unsafe impl<F, A> ImageAccess for StorageImage<F, A>
             first_layer: 0,
             num_layers: self.dimensions.array_layers() as usize,
             first_mipmap_level: 0,
-            num_mipmap_levels: 1,
+            num_mipmap_levels: self.image.mipmap_levels() as usize,
         }
     }
 
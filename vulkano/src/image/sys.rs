@@ -69,6 +69,8 @@ pub struct UnsafeImage {
     // `vkDestroyImage` is called only if `needs_destruction` is true.
     needs_destruction: bool,
     preinitialized_layout: bool,
+    // True if the image was created with `Sharing::Exclusive`.
+    exclusive_sharing: bool,
 }
 
 impl UnsafeImage {
@@ -557,6 +559,7 @@ impl UnsafeImage {
             format_features: format_features,
             needs_destruction: true,
             preinitialized_layout,
+            exclusive_sharing: sh_mode == vk::SHARING_MODE_EXCLUSIVE,
         };
 
         Ok((image, mem_reqs))
@@ -564,7 +567,8 @@ impl UnsafeImage {
 
     /// Creates an image from a raw handle. The image won't be destroyed.
     ///
-    /// This function is for example used at the swapchain's initialization.
+    /// This function is for example used at the swapchain's initialization. The image is
+    /// assumed to have been created with exclusive sharing.
     pub unsafe fn from_raw(device: Arc<Device>, handle: u64, usage: u32, format: Format,
                            dimensions: ImageDimensions, samples: u32, mipmaps: u32)
                            -> UnsafeImage {
@@ -587,9 +591,21 @@ impl UnsafeImage {
             format_features: output.assume_init().optimalTilingFeatures,
             needs_destruction: false, // TODO: pass as parameter
             preinitialized_layout: false, // TODO: Maybe this should be passed in?
+            exclusive_sharing: true,
         }
     }
 
+    // Same as `from_raw`, but for images that were created with concurrent sharing.
+    pub(crate) unsafe fn from_raw_concurrent(device: Arc<Device>, handle: u64, usage: u32,
+                                             format: Format, dimensions: ImageDimensions,
+                                             samples: u32, mipmaps: u32)
+                                             -> UnsafeImage {
+        let mut image = UnsafeImage::from_raw(device, handle, usage, format, dimensions, samples,
+                                              mipmaps);
+        image.exclusive_sharing = false;
+        image
+    }
+
     pub unsafe fn bind_memory(&self, memory: &DeviceMemory, offset: usize) -> Result<(), OomError> {
         let vk = self.device.pointers();
 
@@ -793,6 +809,13 @@ impl UnsafeImage {
     pub fn preinitialized_layout(&self) -> bool {
         self.preinitialized_layout
     }
+
+    /// Returns true if the image was created with `Sharing::Exclusive`, in which case it is
+    /// owned by a single queue family at a time.
+    #[inline]
+    pub fn exclusive_sharing(&self) -> bool {
+        self.exclusive_sharing
+    }
 }
 
 unsafe impl VulkanObject for UnsafeImage {
@@ -847,6 +870,11 @@ pub enum ImageCreationError {
     UnsupportedUsage,
     /// The `shader_storage_image_multisample` feature must be enabled to create such an image.
     ShaderStorageImageMultisampleFeatureNotEnabled,
+    /// The mipmaps of the image must be generated, but the format doesn't support blitting.
+    BlitNotSupported,
+    /// The mipmaps of the image must be generated, but the queue doesn't support graphics
+    /// operations.
+    GraphicsQueueRequired,
 }
 
 impl error::Error for ImageCreationError {
@@ -869,6 +897,14 @@ impl error::Error for ImageCreationError {
                 "the `shader_storage_image_multisample` feature must be enabled to create such \
                  an image"
             },
+            ImageCreationError::BlitNotSupported => {
+                "the mipmaps of the image must be generated, but the format doesn't support \
+                 blitting"
+            },
+            ImageCreationError::GraphicsQueueRequired => {
+                "the mipmaps of the image must be generated, but the queue doesn't support \
+                 graphics operations"
+            },
         }
     }
 
@@ -344,6 +344,7 @@ impl Instance {
                             memory: memory,
                             queue_families: queue_families,
                             available_features: Features::from_vulkan_features(available_features),
+                            vertex_attribute_divisor: None,
                         });
         }
         output
@@ -412,12 +413,15 @@ impl Instance {
                 output.features
             };
 
+            let vertex_attribute_divisor = vertex_attribute_divisor_support(vk, device);
+
             output.push(PhysicalDeviceInfos {
                             device: device,
                             properties: properties,
                             memory: memory,
                             queue_families: queue_families,
                             available_features: Features::from_vulkan_features(available_features),
+                            vertex_attribute_divisor,
                         });
         }
         output
@@ -675,6 +679,96 @@ struct PhysicalDeviceInfos {
     queue_families: Vec<vk::QueueFamilyProperties>,
     memory: vk::PhysicalDeviceMemoryProperties,
     available_features: Features,
+    // `None` if `VK_EXT_vertex_attribute_divisor` isn't supported, or if it couldn't be queried
+    // because `VK_KHR_get_physical_device_properties2` isn't enabled on the instance.
+    vertex_attribute_divisor: Option<VertexAttributeDivisorSupport>,
+}
+
+// Queries the support of `VK_EXT_vertex_attribute_divisor`. Requires
+// `VK_KHR_get_physical_device_properties2` to be enabled on the instance.
+fn vertex_attribute_divisor_support(vk: &vk::InstancePointers, device: vk::PhysicalDevice)
+                                    -> Option<VertexAttributeDivisorSupport> {
+    // The structs of an extension can only be chained if the device supports it.
+    if !device_supports_extension(vk, device, b"VK_EXT_vertex_attribute_divisor") {
+        return None;
+    }
+
+    unsafe {
+        let mut properties = vk::PhysicalDeviceVertexAttributeDivisorPropertiesEXT {
+            sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_VERTEX_ATTRIBUTE_DIVISOR_PROPERTIES_EXT,
+            pNext: ptr::null_mut(),
+            maxVertexAttribDivisor: 0,
+        };
+        let mut output = vk::PhysicalDeviceProperties2KHR {
+            sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_PROPERTIES_2_KHR,
+            pNext: &mut properties as *mut _ as *mut _,
+            properties: mem::zeroed(),
+        };
+        vk.GetPhysicalDeviceProperties2KHR(device, &mut output);
+
+        let mut features = vk::PhysicalDeviceVertexAttributeDivisorFeaturesEXT {
+            sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_VERTEX_ATTRIBUTE_DIVISOR_FEATURES_EXT,
+            pNext: ptr::null_mut(),
+            vertexAttributeInstanceRateDivisor: vk::FALSE,
+            vertexAttributeInstanceRateZeroDivisor: vk::FALSE,
+        };
+        let mut output = vk::PhysicalDeviceFeatures2KHR {
+            sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_FEATURES_2_KHR,
+            pNext: &mut features as *mut _ as *mut _,
+            features: mem::zeroed(),
+        };
+        vk.GetPhysicalDeviceFeatures2KHR(device, &mut output);
+
+        Some(VertexAttributeDivisorSupport {
+                 max_vertex_attrib_divisor: properties.maxVertexAttribDivisor,
+                 instance_rate_divisor: features.vertexAttributeInstanceRateDivisor != 0,
+                 instance_rate_zero_divisor: features.vertexAttributeInstanceRateZeroDivisor != 0,
+             })
+    }
+}
+
+// Returns true if the physical device supports the device extension `name`.
+fn device_supports_extension(vk: &vk::InstancePointers, device: vk::PhysicalDevice,
+                             name: &[u8]) -> bool {
+    let properties: Vec<vk::ExtensionProperties> = unsafe {
+        let mut num = 0;
+        if check_errors(vk.EnumerateDeviceExtensionProperties(device,
+                                                              ptr::null(),
+                                                              &mut num,
+                                                              ptr::null_mut())).is_err()
+        {
+            return false;
+        }
+
+        let mut properties = Vec::with_capacity(num as usize);
+        if check_errors(vk.EnumerateDeviceExtensionProperties(device,
+                                                              ptr::null(),
+                                                              &mut num,
+                                                              properties.as_mut_ptr())).is_err()
+        {
+            return false;
+        }
+        properties.set_len(num as usize);
+        properties
+    };
+
+    properties
+        .iter()
+        .any(|p| unsafe { CStr::from_ptr(p.extensionName.as_ptr()) }.to_bytes() == name)
+}
+
+/// Support of the `VK_EXT_vertex_attribute_divisor` extension by a physical device.
+///
+/// When the extension is enabled on a device, all the features that are supported are enabled
+/// as well.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct VertexAttributeDivisorSupport {
+    /// Maximum divisor of a vertex buffer binding whose input rate is per instance.
+    pub max_vertex_attrib_divisor: u32,
+    /// True if divisors other than 1 can be used.
+    pub instance_rate_divisor: bool,
+    /// True if a divisor of 0 can be used.
+    pub instance_rate_zero_divisor: bool,
 }
 
 /// Represents one of the available devices on this machine.
@@ -834,6 +928,16 @@ impl<'a> PhysicalDevice<'a> {
         &self.infos().available_features
     }
 
+    /// Returns the support of the `VK_EXT_vertex_attribute_divisor` extension by this physical
+    /// device.
+    ///
+    /// Returns `None` if the extension isn't supported, or if the
+    /// `VK_KHR_get_physical_device_properties2` extension isn't enabled on the instance.
+    #[inline]
+    pub fn vertex_attribute_divisor_support(&self) -> Option<VertexAttributeDivisorSupport> {
+        self.infos().vertex_attribute_divisor
+    }
+
     /// Builds an iterator that enumerates all the queue families on this physical device.
     #[inline]
     pub fn queue_families(&self) -> QueueFamiliesIter<'a> {
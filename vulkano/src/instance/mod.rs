@@ -120,6 +120,7 @@ pub use self::instance::PhysicalDeviceType;
 pub use self::instance::PhysicalDevicesIter;
 pub use self::instance::QueueFamiliesIter;
 pub use self::instance::QueueFamily;
+pub use self::instance::VertexAttributeDivisorSupport;
 pub use self::layers::LayerProperties;
 pub use self::layers::LayersIterator;
 pub use self::layers::LayersListError;
@@ -13,21 +13,34 @@
 //! pipelines on the disk.
 //!
 //! You can create either an empty cache or a cache from some initial data. Whenever you create a
-//! graphics or compute pipeline, you have the possibility to pass a reference to that cache.
-//! TODO: ^ that's not the case yet
+//! graphics or compute pipeline, you have the possibility to pass a reference to that cache,
+//! with `GraphicsPipelineBuilder::build_with_cache`, `ComputePipeline::build_with_cache` or the
+//! other `*_and_cache` constructors of `ComputePipeline`.
 //! The Vulkan implementation will then look in the cache for an existing entry, or add one if it
 //! doesn't exist.
 //!
-//! Once that is done, you can extract the data from the cache and store it. See the documentation
-//! of [`get_data`](struct.PipelineCache.html#method.get_data) for example of how to store the data
-//! on the disk, and [`with_data`](struct.PipelineCache.html#method.with_data) for how to reload it.
+//! Once that is done, you can extract the data from the cache and store it. The simplest way is
+//! to use [`save_to_file`](struct.PipelineCache.html#method.save_to_file) and
+//! [`load_from_file`](struct.PipelineCache.html#method.load_from_file), which check that the data
+//! was produced by the same physical device and driver. You can also use
+//! [`get_data`](struct.PipelineCache.html#method.get_data) and
+//! [`with_data`](struct.PipelineCache.html#method.with_data) to store the data elsewhere.
 //!
 
+use std::error;
+use std::fmt;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Write;
 use std::mem::MaybeUninit;
+use std::path::Path;
 use std::ptr;
 use std::sync::Arc;
 
 use device::Device;
+use device::DeviceOwned;
 
 use OomError;
 use VulkanObject;
@@ -54,7 +67,11 @@ impl PipelineCache {
     ///
     /// This example loads a cache from a file, if it exists.
     /// See [`get_data`](#method.get_data) for how to store the data in a file.
-    /// TODO: there's a header in the cached data that must be checked ; talk about this
+    ///
+    /// Nothing checks that the header of the data matches the device. Prefer
+    /// [`load_from_file`](#method.load_from_file) or
+    /// [`with_checked_data`](#method.with_checked_data), which discard data produced by another
+    /// physical device or driver version.
     ///
     /// ```
     /// # use std::sync::Arc;
@@ -87,6 +104,57 @@ impl PipelineCache {
         PipelineCache::new_impl(device, Some(initial_data))
     }
 
+    /// Builds a new pipeline cache from existing data, if its header shows that it was produced
+    /// by the same physical device and driver as `device`. Otherwise the data is discarded and
+    /// the cache starts empty.
+    ///
+    /// The header contains the vendor ID, the device ID and the pipeline cache UUID of the
+    /// physical device, the latter changing whenever the driver can't read the data anymore.
+    #[inline]
+    pub fn with_checked_data(device: Arc<Device>, initial_data: &[u8])
+                             -> Result<Arc<PipelineCache>, OomError> {
+        let compatible = {
+            let physical = device.physical_device();
+            header_matches(initial_data,
+                           physical.pci_vendor_id(),
+                           physical.pci_device_id(),
+                           physical.uuid())
+        };
+
+        if compatible {
+            unsafe { PipelineCache::new_impl(device, Some(initial_data)) }
+        } else {
+            PipelineCache::empty(device)
+        }
+    }
+
+    /// Builds a new pipeline cache from the content of a file written by
+    /// [`save_to_file`](#method.save_to_file).
+    ///
+    /// If the file doesn't exist, can't be read, or contains data produced by another physical
+    /// device or driver version, the data is discarded and the cache starts empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use vulkano::device::Device;
+    /// use vulkano::pipeline::cache::PipelineCache;
+    /// # let device: Arc<Device> = return;
+    /// let cache = PipelineCache::load_from_file(device.clone(), "pipeline_cache.bin").unwrap();
+    /// ```
+    pub fn load_from_file<P>(device: Arc<Device>, path: P) -> Result<Arc<PipelineCache>, OomError>
+        where P: AsRef<Path>
+    {
+        let mut data = Vec::new();
+        let read = File::open(path).and_then(|mut file| file.read_to_end(&mut data));
+
+        match read {
+            Ok(_) => PipelineCache::with_checked_data(device, &data),
+            Err(_) => PipelineCache::empty(device),
+        }
+    }
+
     /// Builds a new empty pipeline cache.
     ///
     /// # Example
@@ -214,6 +282,168 @@ impl PipelineCache {
             Ok(data)
         }
     }
+
+    /// Writes the data of the cache to a file, so that it can be reloaded with
+    /// [`load_from_file`](#method.load_from_file).
+    ///
+    /// The data is first written to a temporary file next to `path`, which is then renamed. The
+    /// content of `path` is therefore either the previous data or the new data, even if the
+    /// application is interrupted while writing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// use vulkano::pipeline::cache::PipelineCache;
+    /// # let cache: Arc<PipelineCache> = return;
+    /// // If an error happens (eg. no permission for the file) we simply skip storing the cache.
+    /// let _ = cache.save_to_file("pipeline_cache.bin");
+    /// ```
+    pub fn save_to_file<P>(&self, path: P) -> Result<(), PipelineCacheFileError>
+        where P: AsRef<Path>
+    {
+        let data = self.get_data()?;
+        let path = path.as_ref();
+
+        let mut tmp_name = path.file_name().map(|n| n.to_owned()).unwrap_or_default();
+        tmp_name.push(".tmp");
+        let tmp_path = path.with_file_name(tmp_name);
+
+        match write_file(&tmp_path, &data).and_then(|_| fs::rename(&tmp_path, path)) {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                let _ = fs::remove_file(&tmp_path);
+                Err(PipelineCacheFileError::IoError(err))
+            },
+        }
+    }
+
+    /// Same as `save_to_file`, but also writes the data of `others`, for example the caches
+    /// used by worker threads to build pipelines in parallel.
+    ///
+    /// The caches are merged into a new cache before writing, so none of them is modified and
+    /// the worker threads can keep using them. An error is returned if the caches don't all
+    /// belong to the same device.
+    pub fn save_merged_to_file<'a, I, P>(&self, others: I, path: P)
+                                         -> Result<(), PipelineCacheFileError>
+        where I: IntoIterator<Item = &'a Arc<PipelineCache>>,
+              P: AsRef<Path>
+    {
+        let mut caches = vec![self.cache];
+        for other in others {
+            if other.device.internal_object() != self.device.internal_object() {
+                return Err(PipelineCacheFileError::WrongDevice);
+            }
+            caches.push(other.cache);
+        }
+
+        let merged = PipelineCache::empty(self.device.clone())?;
+
+        unsafe {
+
+            let vk = self.device.pointers();
+            check_errors(vk.MergePipelineCaches(self.device.internal_object(),
+                                                merged.cache,
+                                                caches.len() as u32,
+                                                caches.as_ptr()))
+                .map_err(OomError::from)?;
+        }
+
+        merged.save_to_file(path)
+    }
+}
+
+/// Writes `data` to a new file and waits until it reaches the disk.
+fn write_file(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(data)?;
+    file.sync_all()
+}
+
+/// Returns true if the header of some pipeline cache data matches the given physical device
+/// properties.
+fn header_matches(data: &[u8], vendor_id: u32, device_id: u32, uuid: &[u8; 16]) -> bool {
+    // The header is made of the header length, the header version, the vendor ID, the device ID
+    // and the pipeline cache UUID, with the integers written least significant byte first.
+    const HEADER_VERSION_ONE: u32 = 1;
+    const HEADER_LEN: usize = 16 + 16;
+
+    if data.len() < HEADER_LEN {
+        return false;
+    }
+
+    let read_u32 = |offset: usize| {
+        data[offset .. offset + 4]
+            .iter()
+            .rev()
+            .fold(0, |value, &byte| (value << 8) | byte as u32)
+    };
+
+    let header_len = read_u32(0) as usize;
+    header_len >= HEADER_LEN && header_len <= data.len() &&
+        read_u32(4) == HEADER_VERSION_ONE && read_u32(8) == vendor_id &&
+        read_u32(12) == device_id && &data[16 .. 32] == uuid
+}
+
+/// Error that can happen when writing a pipeline cache to a file.
+#[derive(Debug)]
+pub enum PipelineCacheFileError {
+    /// Not enough memory.
+    OomError(OomError),
+    /// Error while writing the file.
+    IoError(io::Error),
+    /// One of the caches to merge belongs to another device.
+    WrongDevice,
+}
+
+impl error::Error for PipelineCacheFileError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            PipelineCacheFileError::OomError(_) => "not enough memory available",
+            PipelineCacheFileError::IoError(_) => "error while writing the file",
+            PipelineCacheFileError::WrongDevice => {
+                "one of the caches to merge belongs to another device"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            PipelineCacheFileError::OomError(ref err) => Some(err),
+            PipelineCacheFileError::IoError(ref err) => Some(err),
+            PipelineCacheFileError::WrongDevice => None,
+        }
+    }
+}
+
+impl fmt::Display for PipelineCacheFileError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<OomError> for PipelineCacheFileError {
+    #[inline]
+    fn from(err: OomError) -> PipelineCacheFileError {
+        PipelineCacheFileError::OomError(err)
+    }
+}
+
+impl From<io::Error> for PipelineCacheFileError {
+    #[inline]
+    fn from(err: io::Error) -> PipelineCacheFileError {
+        PipelineCacheFileError::IoError(err)
+    }
+}
+
+unsafe impl DeviceOwned for PipelineCache {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
 }
 
 unsafe impl VulkanObject for PipelineCache {
@@ -240,6 +470,38 @@ impl Drop for PipelineCache {
 #[cfg(test)]
 mod tests {
     use pipeline::cache::PipelineCache;
+    use pipeline::cache::PipelineCacheFileError;
+    use pipeline::cache::header_matches;
+
+    fn header(len: u32, version: u32, vendor_id: u32, device_id: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        for value in &[len, version, vendor_id, device_id] {
+            data.extend_from_slice(&[*value as u8,
+                                     (*value >> 8) as u8,
+                                     (*value >> 16) as u8,
+                                     (*value >> 24) as u8]);
+        }
+        data.extend_from_slice(&[7; 16]);
+        data
+    }
+
+    #[test]
+    fn header_checks() {
+        assert!(header_matches(&header(32, 1, 0x10de, 0x1b80), 0x10de, 0x1b80, &[7; 16]));
+
+        let mut data = header(32, 1, 0x10de, 0x1b80);
+        data.extend_from_slice(&[0; 64]);
+        assert!(header_matches(&data, 0x10de, 0x1b80, &[7; 16]));
+
+        assert!(!header_matches(&header(32, 1, 0x10de, 0x1b80), 0x1002, 0x1b80, &[7; 16]));
+        assert!(!header_matches(&header(32, 1, 0x10de, 0x1b80), 0x10de, 0x1b81, &[7; 16]));
+        assert!(!header_matches(&header(32, 1, 0x10de, 0x1b80), 0x10de, 0x1b80, &[8; 16]));
+        assert!(!header_matches(&header(32, 2, 0x10de, 0x1b80), 0x10de, 0x1b80, &[7; 16]));
+        assert!(!header_matches(&header(16, 1, 0x10de, 0x1b80), 0x10de, 0x1b80, &[7; 16]));
+        assert!(!header_matches(&header(64, 1, 0x10de, 0x1b80), 0x10de, 0x1b80, &[7; 16]));
+        assert!(!header_matches(&header(32, 1, 0x10de, 0x1b80)[.. 31], 0x10de, 0x1b80, &[7; 16]));
+        assert!(!header_matches(&[], 0x10de, 0x1b80, &[7; 16]));
+    }
 
     #[test]
     fn merge_self_forbidden() {
@@ -249,4 +511,17 @@ mod tests {
                                  pipeline.merge(&[&pipeline]).unwrap();
                              });
     }
+
+    #[test]
+    fn save_merged_wrong_device() {
+        let (device, _) = gfx_dev_and_queue!();
+        let (other_device, _) = gfx_dev_and_queue!();
+
+        let cache = PipelineCache::empty(device).unwrap();
+        let other = PipelineCache::empty(other_device).unwrap();
+        match cache.save_merged_to_file(&[other], "pipeline_cache.bin") {
+            Err(PipelineCacheFileError::WrongDevice) => (),
+            _ => panic!(),
+        }
+    }
 }
@@ -25,6 +25,7 @@ use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
 use descriptor::pipeline_layout::PipelineLayoutNotSupersetError;
 use descriptor::pipeline_layout::PipelineLayoutSuperset;
 use descriptor::pipeline_layout::PipelineLayoutSys;
+use pipeline::cache::PipelineCache;
 use pipeline::shader::EntryPointAbstract;
 use pipeline::shader::SpecializationConstants;
 
@@ -61,13 +62,39 @@ impl ComputePipeline<()> {
         -> Result<ComputePipeline<PipelineLayout<Cs::PipelineLayout>>, ComputePipelineCreationError>
         where Cs::PipelineLayout: Clone,
               Cs: EntryPointAbstract
+    {
+        ComputePipeline::new_impl(device, shader, specialization, None)
+    }
+
+    /// Same as `new`, but uses a pipeline cache.
+    ///
+    /// The implementation can reuse the data stored in the cache to build the pipeline faster,
+    /// and stores the new pipeline in the cache. The cache must belong to the same device as the
+    /// pipeline.
+    pub fn build_with_cache<Cs>(
+        device: Arc<Device>, shader: &Cs, specialization: &Cs::SpecializationConstants,
+        cache: Arc<PipelineCache>)
+        -> Result<ComputePipeline<PipelineLayout<Cs::PipelineLayout>>, ComputePipelineCreationError>
+        where Cs::PipelineLayout: Clone,
+              Cs: EntryPointAbstract
+    {
+        ComputePipeline::new_impl(device, shader, specialization, Some(cache))
+    }
+
+    fn new_impl<Cs>(
+        device: Arc<Device>, shader: &Cs, specialization: &Cs::SpecializationConstants,
+        cache: Option<Arc<PipelineCache>>)
+        -> Result<ComputePipeline<PipelineLayout<Cs::PipelineLayout>>, ComputePipelineCreationError>
+        where Cs::PipelineLayout: Clone,
+              Cs: EntryPointAbstract
     {
         unsafe {
             let pipeline_layout = shader.layout().clone().build(device.clone())?;
-            ComputePipeline::with_unchecked_pipeline_layout(device,
-                                                            shader,
-                                                            specialization,
-                                                            pipeline_layout)
+            ComputePipeline::with_unchecked_pipeline_layout_impl(device,
+                                                                 shader,
+                                                                 specialization,
+                                                                 pipeline_layout,
+                                                                 cache)
         }
     }
 }
@@ -84,13 +111,46 @@ impl<Pl> ComputePipeline<Pl> {
         where Cs::PipelineLayout: Clone,
               Cs: EntryPointAbstract,
               Pl: PipelineLayoutAbstract
+    {
+        ComputePipeline::with_pipeline_layout_impl(device,
+                                                   shader,
+                                                   specialization,
+                                                   pipeline_layout,
+                                                   None)
+    }
+
+    /// Same as `with_pipeline_layout`, but uses a pipeline cache.
+    pub fn with_pipeline_layout_and_cache<Cs>(device: Arc<Device>, shader: &Cs,
+                                              specialization: &Cs::SpecializationConstants,
+                                              pipeline_layout: Pl, cache: Arc<PipelineCache>)
+                                              -> Result<ComputePipeline<Pl>,
+                                                        ComputePipelineCreationError>
+        where Cs::PipelineLayout: Clone,
+              Cs: EntryPointAbstract,
+              Pl: PipelineLayoutAbstract
+    {
+        ComputePipeline::with_pipeline_layout_impl(device,
+                                                   shader,
+                                                   specialization,
+                                                   pipeline_layout,
+                                                   Some(cache))
+    }
+
+    fn with_pipeline_layout_impl<Cs>(device: Arc<Device>, shader: &Cs,
+                                     specialization: &Cs::SpecializationConstants,
+                                     pipeline_layout: Pl, cache: Option<Arc<PipelineCache>>)
+                                     -> Result<ComputePipeline<Pl>, ComputePipelineCreationError>
+        where Cs::PipelineLayout: Clone,
+              Cs: EntryPointAbstract,
+              Pl: PipelineLayoutAbstract
     {
         unsafe {
             PipelineLayoutSuperset::ensure_superset_of(&pipeline_layout, shader.layout())?;
-            ComputePipeline::with_unchecked_pipeline_layout(device,
-                                                            shader,
-                                                            specialization,
-                                                            pipeline_layout)
+            ComputePipeline::with_unchecked_pipeline_layout_impl(device,
+                                                                 shader,
+                                                                 specialization,
+                                                                 pipeline_layout,
+                                                                 cache)
         }
     }
 
@@ -103,9 +163,47 @@ impl<Pl> ComputePipeline<Pl> {
         where Cs::PipelineLayout: Clone,
               Cs: EntryPointAbstract,
               Pl: PipelineLayoutAbstract
+    {
+        ComputePipeline::with_unchecked_pipeline_layout_impl(device,
+                                                             shader,
+                                                             specialization,
+                                                             pipeline_layout,
+                                                             None)
+    }
+
+    /// Same as `with_pipeline_layout_and_cache`, but doesn't check whether the pipeline layout is
+    /// a superset of what the shader expects.
+    pub unsafe fn with_unchecked_pipeline_layout_and_cache<Cs>(
+        device: Arc<Device>, shader: &Cs, specialization: &Cs::SpecializationConstants,
+        pipeline_layout: Pl, cache: Arc<PipelineCache>)
+        -> Result<ComputePipeline<Pl>, ComputePipelineCreationError>
+        where Cs::PipelineLayout: Clone,
+              Cs: EntryPointAbstract,
+              Pl: PipelineLayoutAbstract
+    {
+        ComputePipeline::with_unchecked_pipeline_layout_impl(device,
+                                                             shader,
+                                                             specialization,
+                                                             pipeline_layout,
+                                                             Some(cache))
+    }
+
+    unsafe fn with_unchecked_pipeline_layout_impl<Cs>(
+        device: Arc<Device>, shader: &Cs, specialization: &Cs::SpecializationConstants,
+        pipeline_layout: Pl, cache: Option<Arc<PipelineCache>>)
+        -> Result<ComputePipeline<Pl>, ComputePipelineCreationError>
+        where Cs::PipelineLayout: Clone,
+              Cs: EntryPointAbstract,
+              Pl: PipelineLayoutAbstract
     {
         let vk = device.pointers();
 
+        if let Some(ref cache) = cache {
+            if cache.device().internal_object() != device.internal_object() {
+                return Err(ComputePipelineCreationError::WrongDevice);
+            }
+        }
+
         let pipeline = {
             let spec_descriptors = Cs::SpecializationConstants::descriptors();
             let specialization = vk::SpecializationInfo {
@@ -139,9 +237,11 @@ impl<Pl> ComputePipeline<Pl> {
                 basePipelineIndex: 0,
             };
 
+            let cache = cache.as_ref().map(|cache| cache.internal_object()).unwrap_or(0);
+
             let mut output = MaybeUninit::uninit();
             check_errors(vk.CreateComputePipelines(device.internal_object(),
-                                                   0,
+                                                   cache,
                                                    1,
                                                    &infos,
                                                    ptr::null(),
@@ -302,6 +402,8 @@ pub enum ComputePipelineCreationError {
     PipelineLayoutCreationError(PipelineLayoutCreationError),
     /// The pipeline layout is not compatible with what the shader expects.
     IncompatiblePipelineLayout(PipelineLayoutNotSupersetError),
+    /// The pipeline cache belongs to another device.
+    WrongDevice,
 }
 
 impl error::Error for ComputePipelineCreationError {
@@ -313,6 +415,8 @@ impl error::Error for ComputePipelineCreationError {
                 "error while creating the pipeline layout object",
             ComputePipelineCreationError::IncompatiblePipelineLayout(_) =>
                 "the pipeline layout is not compatible with what the shader expects",
+            ComputePipelineCreationError::WrongDevice =>
+                "the pipeline cache belongs to another device",
         }
     }
 
@@ -322,6 +426,7 @@ impl error::Error for ComputePipelineCreationError {
             ComputePipelineCreationError::OomError(ref err) => Some(err),
             ComputePipelineCreationError::PipelineLayoutCreationError(ref err) => Some(err),
             ComputePipelineCreationError::IncompatiblePipelineLayout(ref err) => Some(err),
+            ComputePipelineCreationError::WrongDevice => None,
         }
     }
 }
@@ -381,7 +486,10 @@ mod tests {
     use descriptor::descriptor_set::PersistentDescriptorSet;
     use descriptor::pipeline_layout::PipelineLayoutDesc;
     use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
+    use device::Device;
     use pipeline::ComputePipeline;
+    use pipeline::ComputePipelineCreationError;
+    use pipeline::cache::PipelineCache;
     use pipeline::shader::ShaderModule;
     use pipeline::shader::SpecializationConstants;
     use pipeline::shader::SpecializationMapEntry;
@@ -994,4 +1102,126 @@ mod tests {
         let data_buffer_content = data_buffer.read().unwrap();
         assert_eq!(*data_buffer_content, 0x12345678);
     }
+
+    // Builds a pipeline with `cache`, from a shader that writes a specialization constant to a
+    // buffer.
+    fn build_with_cache(device: Arc<Device>, cache: Arc<PipelineCache>)
+                        -> Result<(), ComputePipelineCreationError> {
+        const MODULE: [u8; 480] = [
+            3, 2, 35, 7, 0, 0, 1, 0, 1, 0, 8, 0, 14, 0, 0, 0,
+            0, 0, 0, 0, 17, 0, 2, 0, 1, 0, 0, 0, 11, 0, 6, 0,
+            1, 0, 0, 0, 71, 76, 83, 76, 46, 115, 116, 100, 46, 52, 53, 48,
+            0, 0, 0, 0, 14, 0, 3, 0, 0, 0, 0, 0, 1, 0, 0, 0,
+            15, 0, 5, 0, 5, 0, 0, 0, 4, 0, 0, 0, 109, 97, 105, 110,
+            0, 0, 0, 0, 16, 0, 6, 0, 4, 0, 0, 0, 17, 0, 0, 0,
+            1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 3, 0, 3, 0,
+            2, 0, 0, 0, 194, 1, 0, 0, 5, 0, 4, 0, 4, 0, 0, 0,
+            109, 97, 105, 110, 0, 0, 0, 0, 5, 0, 4, 0, 7, 0, 0, 0,
+            79, 117, 116, 112, 117, 116, 0, 0, 6, 0, 5, 0, 7, 0, 0, 0,
+            0, 0, 0, 0, 119, 114, 105, 116, 101, 0, 0, 0, 5, 0, 4, 0,
+            9, 0, 0, 0, 119, 114, 105, 116, 101, 0, 0, 0, 5, 0, 4, 0,
+            11, 0, 0, 0, 86, 65, 76, 85, 69, 0, 0, 0, 72, 0, 5, 0,
+            7, 0, 0, 0, 0, 0, 0, 0, 35, 0, 0, 0, 0, 0, 0, 0,
+            71, 0, 3, 0, 7, 0, 0, 0, 3, 0, 0, 0, 71, 0, 4, 0,
+            9, 0, 0, 0, 34, 0, 0, 0, 0, 0, 0, 0, 71, 0, 4, 0,
+            9, 0, 0, 0, 33, 0, 0, 0, 0, 0, 0, 0, 71, 0, 4, 0,
+            11, 0, 0, 0, 1, 0, 0, 0, 83, 0, 0, 0, 19, 0, 2, 0,
+            2, 0, 0, 0, 33, 0, 3, 0, 3, 0, 0, 0, 2, 0, 0, 0,
+            21, 0, 4, 0, 6, 0, 0, 0, 32, 0, 0, 0, 1, 0, 0, 0,
+            30, 0, 3, 0, 7, 0, 0, 0, 6, 0, 0, 0, 32, 0, 4, 0,
+            8, 0, 0, 0, 2, 0, 0, 0, 7, 0, 0, 0, 59, 0, 4, 0,
+            8, 0, 0, 0, 9, 0, 0, 0, 2, 0, 0, 0, 43, 0, 4, 0,
+            6, 0, 0, 0, 10, 0, 0, 0, 0, 0, 0, 0, 50, 0, 4, 0,
+            6, 0, 0, 0, 11, 0, 0, 0, 239, 190, 173, 222, 32, 0, 4, 0,
+            12, 0, 0, 0, 2, 0, 0, 0, 6, 0, 0, 0, 54, 0, 5, 0,
+            2, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0,
+            248, 0, 2, 0, 5, 0, 0, 0, 65, 0, 5, 0, 12, 0, 0, 0,
+            13, 0, 0, 0, 9, 0, 0, 0, 10, 0, 0, 0, 62, 0, 3, 0,
+            13, 0, 0, 0, 11, 0, 0, 0, 253, 0, 1, 0, 56, 0, 1, 0,
+        ];
+
+        #[derive(Debug, Copy, Clone)]
+        struct Layout;
+        unsafe impl PipelineLayoutDesc for Layout {
+            fn num_sets(&self) -> usize {
+                1
+            }
+            fn num_bindings_in_set(&self, set: usize) -> Option<usize> {
+                match set {
+                    0 => Some(1),
+                    _ => None,
+                }
+            }
+            fn descriptor(&self, set: usize, binding: usize) -> Option<DescriptorDesc> {
+                match (set, binding) {
+                    (0, 0) => Some(DescriptorDesc {
+                                       ty: DescriptorDescTy::Buffer(DescriptorBufferDesc {
+                                                                        dynamic: Some(false),
+                                                                        storage: true,
+                                                                    }),
+                                       array_count: 1,
+                                       stages: ShaderStages {
+                                           compute: true,
+                                           ..ShaderStages::none()
+                                       },
+                                       readonly: true,
+                                   }),
+                    _ => None,
+                }
+            }
+            fn num_push_constants_ranges(&self) -> usize {
+                0
+            }
+            fn push_constants_range(&self, _: usize) -> Option<PipelineLayoutDescPcRange> {
+                None
+            }
+        }
+
+        #[derive(Debug, Copy, Clone)]
+        #[repr(C)]
+        struct SpecConsts {
+            value: i32,
+        }
+        unsafe impl SpecializationConstants for SpecConsts {
+            fn descriptors() -> &'static [SpecializationMapEntry] {
+                static DESCRIPTORS: [SpecializationMapEntry; 1] = [
+                    SpecializationMapEntry {
+                        constant_id: 83,
+                        offset: 0,
+                        size: 4,
+                    },
+                ];
+                &DESCRIPTORS
+            }
+        }
+
+        let module = unsafe { ShaderModule::new(device.clone(), &MODULE).unwrap() };
+        let shader = unsafe {
+            module.compute_entry_point(CStr::from_bytes_with_nul_unchecked(b"main\0"), Layout)
+        };
+
+        ComputePipeline::build_with_cache(device, &shader, &SpecConsts { value: 0 }, cache)
+            .map(|_| ())
+    }
+
+    #[test]
+    fn with_cache() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let cache = PipelineCache::empty(device.clone()).unwrap();
+        build_with_cache(device, cache.clone()).unwrap();
+        assert!(!cache.get_data().unwrap().is_empty());
+    }
+
+    #[test]
+    fn cache_wrong_device() {
+        let (device, _) = gfx_dev_and_queue!();
+        let (other_device, _) = gfx_dev_and_queue!();
+
+        let cache = PipelineCache::empty(other_device).unwrap();
+        match build_with_cache(device, cache) {
+            Err(ComputePipelineCreationError::WrongDevice) => (),
+            _ => panic!(),
+        }
+    }
 }
@@ -14,25 +14,30 @@
 use smallvec::SmallVec;
 use std::mem;
 use std::mem::MaybeUninit;
+use std::ops::Range;
 use std::ptr;
 use std::sync::Arc;
 use std::u32;
 
 use descriptor::pipeline_layout::PipelineLayoutAbstract;
 use device::Device;
+use device::DeviceOwned;
 use framebuffer::RenderPassAbstract;
 use framebuffer::Subpass;
 use pipeline::blend::AttachmentBlend;
 use pipeline::blend::AttachmentsBlend;
 use pipeline::blend::Blend;
 use pipeline::blend::LogicOp;
+use pipeline::cache::PipelineCache;
 use pipeline::depth_stencil::Compare;
 use pipeline::depth_stencil::DepthBounds;
 use pipeline::depth_stencil::DepthStencil;
+use pipeline::depth_stencil::Stencil;
 use pipeline::graphics_pipeline::GraphicsPipeline;
 use pipeline::graphics_pipeline::GraphicsPipelineCreationError;
 use pipeline::graphics_pipeline::Inner as GraphicsPipelineInner;
 use pipeline::input_assembly::PrimitiveTopology;
+use pipeline::multisample::Multisample;
 use pipeline::raster::CullMode;
 use pipeline::raster::DepthBiasControl;
 use pipeline::raster::FrontFace;
@@ -43,7 +48,10 @@ use pipeline::shader::GraphicsEntryPointAbstract;
 use pipeline::shader::GraphicsShaderType;
 use pipeline::shader::ShaderInterfaceDefMatch;
 use pipeline::shader::SpecializationConstants;
+use pipeline::shader::specialization_constants_are_valid;
+use pipeline::shader::specialization_info;
 use pipeline::vertex::BufferlessDefinition;
+use pipeline::vertex::InputRate;
 use pipeline::vertex::SingleBufferDefinition;
 use pipeline::vertex::VertexDefinition;
 use pipeline::viewport::Scissor;
@@ -71,11 +79,14 @@ pub struct GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss,
     geometry_shader: Option<(Gs, Gss)>,
     viewport: Option<ViewportsState>,
     raster: Rasterization,
-    multisample: vk::PipelineMultisampleStateCreateInfo,
+    multisample: Multisample,
     fragment_shader: Option<(Fs, Fss)>,
     depth_stencil: DepthStencil,
     blend: Blend,
     render_pass: Option<Subpass<Rp>>,
+    cache: Option<Arc<PipelineCache>>,
+    allow_derivatives: bool,
+    base_pipeline: Option<Arc<GraphicsPipelineInner>>,
 }
 
 // Additional parameters if tessellation is used.
@@ -114,14 +125,14 @@ impl
                 geometry_shader: None,
                 viewport: None,
                 raster: Default::default(),
-                multisample: vk::PipelineMultisampleStateCreateInfo {
-                    sType: vk::STRUCTURE_TYPE_PIPELINE_MULTISAMPLE_STATE_CREATE_INFO,
-                    ..mem::zeroed()
-                },
+                multisample: Multisample::disabled(),
                 fragment_shader: None,
                 depth_stencil: DepthStencil::disabled(),
                 blend: Blend::pass_through(),
                 render_pass: None,
+                cache: None,
+                allow_derivatives: false,
+                base_pipeline: None,
             }
         }
     }
@@ -162,6 +173,18 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         self.with_auto_layout(device, &[])
     }
 
+    /// Same as `build`, but uses a pipeline cache.
+    ///
+    /// The implementation can reuse the data stored in the cache to build the pipeline faster,
+    /// and stores the new pipeline in the cache so that similar pipelines can later reuse it.
+    /// The cache must belong to the same device as the pipeline.
+    pub fn build_with_cache(mut self, device: Arc<Device>, cache: Arc<PipelineCache>)
+                            -> Result<GraphicsPipeline<Vdef, Box<dyn PipelineLayoutAbstract + Send + Sync>, Rp>,
+                                      GraphicsPipelineCreationError> {
+        self.cache = Some(cache);
+        self.with_auto_layout(device, &[])
+    }
+
     /// Builds the graphics pipeline, using an inferred pipeline layout with some dynamic buffers.
     ///
     /// Configures the inferred layout for each descriptor `(set, binding)` in `dynamic_buffers` to accept dynamic
@@ -366,63 +389,26 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         let mut dynamic_states: SmallVec<[vk::DynamicState; 8]> = SmallVec::new();
 
         // Creating the specialization constants of the various stages.
-        let vertex_shader_specialization = {
-            let spec_descriptors = Vss::descriptors();
-            let constants = &self.vertex_shader.as_ref().unwrap().1;
-            vk::SpecializationInfo {
-                mapEntryCount: spec_descriptors.len() as u32,
-                pMapEntries: spec_descriptors.as_ptr() as *const _,
-                dataSize: mem::size_of_val(constants),
-                pData: constants as *const Vss as *const _,
-            }
-        };
-        let tess_shader_specialization = if let Some(ref tess) = self.tessellation {
-            let tcs_spec = {
-                let spec_descriptors = Tcss::descriptors();
-                let constants = &tess.tessellation_control_shader.1;
-                vk::SpecializationInfo {
-                    mapEntryCount: spec_descriptors.len() as u32,
-                    pMapEntries: spec_descriptors.as_ptr() as *const _,
-                    dataSize: mem::size_of_val(constants),
-                    pData: constants as *const Tcss as *const _,
-                }
-            };
-            let tes_spec = {
-                let spec_descriptors = Tess::descriptors();
-                let constants = &tess.tessellation_evaluation_shader.1;
-                vk::SpecializationInfo {
-                    mapEntryCount: spec_descriptors.len() as u32,
-                    pMapEntries: spec_descriptors.as_ptr() as *const _,
-                    dataSize: mem::size_of_val(constants),
-                    pData: constants as *const Tess as *const _,
-                }
-            };
-            Some((tcs_spec, tes_spec))
-        } else {
-            None
-        };
-        let geometry_shader_specialization = if let Some(ref gs) = self.geometry_shader {
-            let spec_descriptors = Gss::descriptors();
-            let constants = &gs.1;
-            Some(vk::SpecializationInfo {
-                     mapEntryCount: spec_descriptors.len() as u32,
-                     pMapEntries: spec_descriptors.as_ptr() as *const _,
-                     dataSize: mem::size_of_val(constants),
-                     pData: constants as *const Gss as *const _,
-                 })
-        } else {
-            None
-        };
-        let fragment_shader_specialization = {
-            let spec_descriptors = Fss::descriptors();
-            let constants = &self.fragment_shader.as_ref().unwrap().1;
-            vk::SpecializationInfo {
-                mapEntryCount: spec_descriptors.len() as u32,
-                pMapEntries: spec_descriptors.as_ptr() as *const _,
-                dataSize: mem::size_of_val(constants),
-                pData: constants as *const Fss as *const _,
-            }
-        };
+        if !specialization_constants_are_valid::<Vss>() ||
+            !specialization_constants_are_valid::<Fss>() ||
+            (self.geometry_shader.is_some() && !specialization_constants_are_valid::<Gss>()) ||
+            (self.tessellation.is_some() &&
+                 (!specialization_constants_are_valid::<Tcss>() ||
+                      !specialization_constants_are_valid::<Tess>()))
+        {
+            return Err(GraphicsPipelineCreationError::InvalidSpecializationConstants);
+        }
+
+        let vertex_shader_specialization =
+            specialization_info(&self.vertex_shader.as_ref().unwrap().1);
+        let tess_shader_specialization = self.tessellation.as_ref().map(|tess| {
+            (specialization_info(&tess.tessellation_control_shader.1),
+             specialization_info(&tess.tessellation_evaluation_shader.1))
+        });
+        let geometry_shader_specialization =
+            self.geometry_shader.as_ref().and_then(|gs| specialization_info(&gs.1));
+        let fragment_shader_specialization =
+            specialization_info(&self.fragment_shader.as_ref().unwrap().1);
 
         // List of shader stages.
         let stages = {
@@ -445,7 +431,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                                 .module()
                                 .internal_object(),
                             pName: self.vertex_shader.as_ref().unwrap().0.name().as_ptr(),
-                            pSpecializationInfo: &vertex_shader_specialization as *const _,
+                            pSpecializationInfo: spec_info_ptr(&vertex_shader_specialization),
                         });
 
             match self.fragment_shader.as_ref().unwrap().0.ty() {
@@ -465,7 +451,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                                 .module()
                                 .internal_object(),
                             pName: self.fragment_shader.as_ref().unwrap().0.name().as_ptr(),
-                            pSpecializationInfo: &fragment_shader_specialization as *const _,
+                            pSpecializationInfo: spec_info_ptr(&fragment_shader_specialization),
                         });
 
             if let Some(ref gs) = self.geometry_shader {
@@ -480,10 +466,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                                 stage: vk::SHADER_STAGE_GEOMETRY_BIT,
                                 module: gs.0.module().internal_object(),
                                 pName: gs.0.name().as_ptr(),
-                                pSpecializationInfo: geometry_shader_specialization
-                                    .as_ref()
-                                    .unwrap() as
-                                    *const _,
+                                pSpecializationInfo: spec_info_ptr(&geometry_shader_specialization),
                             });
             }
 
@@ -514,11 +497,10 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                                     .module()
                                     .internal_object(),
                                 pName: tess.tessellation_control_shader.0.name().as_ptr(),
-                                pSpecializationInfo: &tess_shader_specialization
-                                    .as_ref()
-                                    .unwrap()
-                                    .0 as
-                                    *const _,
+                                pSpecializationInfo: spec_info_ptr(&tess_shader_specialization
+                                                                        .as_ref()
+                                                                        .unwrap()
+                                                                        .0),
                             });
 
                 stages.push(vk::PipelineShaderStageCreateInfo {
@@ -531,11 +513,10 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                                     .module()
                                     .internal_object(),
                                 pName: tess.tessellation_evaluation_shader.0.name().as_ptr(),
-                                pSpecializationInfo: &tess_shader_specialization
-                                    .as_ref()
-                                    .unwrap()
-                                    .1 as
-                                    *const _,
+                                pSpecializationInfo: spec_info_ptr(&tess_shader_specialization
+                                                                        .as_ref()
+                                                                        .unwrap()
+                                                                        .1),
                             });
             }
 
@@ -543,12 +524,14 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         };
 
         // Vertex bindings.
-        let (binding_descriptions, attribute_descriptions) = {
+        let (binding_descriptions, binding_rates, attribute_descriptions, binding_divisors) = {
             let (buffers_iter, attribs_iter) =
                 self.vertex_input
                     .definition(self.vertex_shader.as_ref().unwrap().0.input())?;
 
             let mut binding_descriptions = SmallVec::<[_; 8]>::new();
+            let mut binding_rates = Vec::new();
+            let mut binding_divisors = SmallVec::<[_; 8]>::new();
             for (num, stride, rate) in buffers_iter {
                 if stride >
                     device
@@ -563,11 +546,45 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                     });
                 }
 
+                if let InputRate::Instance { divisor } = rate {
+                    if divisor != 1 {
+                        if !device.loaded_extensions().ext_vertex_attribute_divisor {
+                            return Err(GraphicsPipelineCreationError::VertexAttributeDivisorExtensionNotEnabled);
+                        }
+
+                        // The supported features of the extension are enabled along with it.
+                        let support = device.physical_device().vertex_attribute_divisor_support();
+
+                        if !support.map_or(false, |s| s.instance_rate_divisor) {
+                            return Err(GraphicsPipelineCreationError::VertexAttributeInstanceRateDivisorFeatureNotEnabled);
+                        }
+
+                        if divisor == 0 && !support.map_or(false, |s| s.instance_rate_zero_divisor) {
+                            return Err(GraphicsPipelineCreationError::VertexAttributeInstanceRateZeroDivisorFeatureNotEnabled);
+                        }
+
+                        let max = support.map_or(0, |s| s.max_vertex_attrib_divisor);
+                        if divisor > max {
+                            return Err(GraphicsPipelineCreationError::MaxVertexAttribDivisorExceeded {
+                                binding: num as usize,
+                                max,
+                                obtained: divisor,
+                            });
+                        }
+
+                        binding_divisors.push(vk::VertexInputBindingDivisorDescriptionEXT {
+                                                  binding: num as u32,
+                                                  divisor: divisor,
+                                              });
+                    }
+                }
+
                 binding_descriptions.push(vk::VertexInputBindingDescription {
                                               binding: num as u32,
                                               stride: stride as u32,
-                                              inputRate: rate as u32,
+                                              inputRate: rate.into(),
                                           });
+                binding_rates.push(rate);
             }
 
             let mut attribute_descriptions = SmallVec::<[_; 8]>::new();
@@ -599,7 +616,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                                             });
             }
 
-            (binding_descriptions, attribute_descriptions)
+            (binding_descriptions, binding_rates, attribute_descriptions, binding_divisors)
         };
 
         if binding_descriptions.len() >
@@ -634,9 +651,23 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                        });
         }
 
+        let vertex_input_divisor_state = if !binding_divisors.is_empty() {
+            Some(vk::PipelineVertexInputDivisorStateCreateInfoEXT {
+                sType: vk::STRUCTURE_TYPE_PIPELINE_VERTEX_INPUT_DIVISOR_STATE_CREATE_INFO_EXT,
+                pNext: ptr::null(),
+                vertexBindingDivisorCount: binding_divisors.len() as u32,
+                pVertexBindingDivisors: binding_divisors.as_ptr(),
+            })
+        } else {
+            None
+        };
+
         let vertex_input_state = vk::PipelineVertexInputStateCreateInfo {
             sType: vk::STRUCTURE_TYPE_PIPELINE_VERTEX_INPUT_STATE_CREATE_INFO,
-            pNext: ptr::null(),
+            pNext: vertex_input_divisor_state
+                .as_ref()
+                .map(|s| s as *const _ as *const _)
+                .unwrap_or(ptr::null()),
             flags: 0, // reserved
             vertexBindingDescriptionCount: binding_descriptions.len() as u32,
             pVertexBindingDescriptions: binding_descriptions.as_ptr(),
@@ -831,23 +862,71 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             lineWidth: self.raster.line_width.unwrap_or(1.0),
         };
 
-        self.multisample.rasterizationSamples = self.render_pass
-            .as_ref()
-            .unwrap()
-            .num_samples()
-            .unwrap_or(1);
-        if self.multisample.sampleShadingEnable != vk::FALSE {
-            debug_assert!(self.multisample.minSampleShading >= 0.0 &&
-                              self.multisample.minSampleShading <= 1.0);
-            if !device.enabled_features().sample_rate_shading {
-                return Err(GraphicsPipelineCreationError::SampleRateShadingFeatureNotEnabled);
+        let multisample = {
+            // All the attachments of a subpass have the same number of samples, which the
+            // rasterizer must use. Without attachments, any number supported by the device can
+            // be used.
+            let rasterization_samples = match (self.render_pass.as_ref().unwrap().num_samples(),
+                                               self.multisample.rasterization_samples) {
+                (Some(subpass), Some(obtained)) if subpass != obtained => {
+                    return Err(GraphicsPipelineCreationError::RasterizationSamplesMismatch {
+                                   expected: subpass,
+                                   obtained: obtained,
+                               });
+                },
+                (Some(subpass), _) => subpass,
+                (None, Some(obtained)) => {
+                    let supported = device
+                        .physical_device()
+                        .limits()
+                        .framebuffer_no_attachments_sample_counts();
+                    if !obtained.is_power_of_two() || obtained > 64 || supported & obtained == 0 {
+                        return Err(GraphicsPipelineCreationError::RasterizationSamplesNotSupported {
+                                       obtained: obtained,
+                                   });
+                    }
+                    obtained
+                },
+                (None, None) => 1,
+            };
+
+            if let Some(min_fract) = self.multisample.sample_shading {
+                if !device.enabled_features().sample_rate_shading {
+                    return Err(GraphicsPipelineCreationError::SampleRateShadingFeatureNotEnabled);
+                }
+                if !(0.0 ..= 1.0).contains(&min_fract) {
+                    return Err(GraphicsPipelineCreationError::MinSampleShadingOutOfRange);
+                }
             }
-        }
-        if self.multisample.alphaToOneEnable != vk::FALSE {
-            if !device.enabled_features().alpha_to_one {
+
+            if self.multisample.alpha_to_one && !device.enabled_features().alpha_to_one {
                 return Err(GraphicsPipelineCreationError::AlphaToOneFeatureNotEnabled);
             }
-        }
+
+            vk::PipelineMultisampleStateCreateInfo {
+                sType: vk::STRUCTURE_TYPE_PIPELINE_MULTISAMPLE_STATE_CREATE_INFO,
+                pNext: ptr::null(),
+                flags: 0, // reserved
+                rasterizationSamples: rasterization_samples,
+                sampleShadingEnable: if self.multisample.sample_shading.is_some() {
+                    vk::TRUE
+                } else {
+                    vk::FALSE
+                },
+                minSampleShading: self.multisample.sample_shading.unwrap_or(1.0),
+                pSampleMask: self.multisample.sample_mask.as_ptr(),
+                alphaToCoverageEnable: if self.multisample.alpha_to_coverage {
+                    vk::TRUE
+                } else {
+                    vk::FALSE
+                },
+                alphaToOneEnable: if self.multisample.alpha_to_one {
+                    vk::TRUE
+                } else {
+                    vk::FALSE
+                },
+            }
+        };
 
         let depth_stencil = {
             let db = match self.depth_stencil.depth_bounds_test {
@@ -857,6 +936,10 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                         return Err(GraphicsPipelineCreationError::DepthBoundsFeatureNotEnabled);
                     }
 
+                    if !(0.0 ..= 1.0).contains(&range.start) || !(0.0 ..= 1.0).contains(&range.end) {
+                        return Err(GraphicsPipelineCreationError::DepthBoundsOutOfRange);
+                    }
+
                     (vk::TRUE, range.start, range.end)
                 },
                 DepthBounds::Dynamic => {
@@ -916,7 +999,17 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                 return Err(GraphicsPipelineCreationError::NoStencilAttachment);
             }
 
-            // FIXME: stencil writability
+            // A stencil test whose operations all keep the current value, or that can't write any
+            // bit, doesn't need to write the stencil attachment.
+            let stencil_writes = |stencil: &Stencil| {
+                !stencil.always_keep() && stencil.write_mask != Some(0)
+            };
+            if (stencil_writes(&self.depth_stencil.stencil_front) ||
+                    stencil_writes(&self.depth_stencil.stencil_back)) &&
+                !self.render_pass.as_ref().unwrap().has_writable_stencil()
+            {
+                return Err(GraphicsPipelineCreationError::NoStencilAttachment);
+            }
 
             vk::PipelineDepthStencilStateCreateInfo {
                 sType: vk::STRUCTURE_TYPE_PIPELINE_DEPTH_STENCIL_STATE_CREATE_INFO,
@@ -1039,11 +1132,32 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             None
         };
 
+        // Checking the pipeline cache and the base pipeline.
+        if let Some(ref cache) = self.cache {
+            if cache.device().internal_object() != device.internal_object() {
+                return Err(GraphicsPipelineCreationError::WrongDevice);
+            }
+        }
+
+        let mut flags = 0;
+        if self.allow_derivatives {
+            flags |= vk::PIPELINE_CREATE_ALLOW_DERIVATIVES_BIT;
+        }
+        if let Some(ref base_pipeline) = self.base_pipeline {
+            if base_pipeline.device.internal_object() != device.internal_object() {
+                return Err(GraphicsPipelineCreationError::WrongDevice);
+            }
+            if !base_pipeline.allow_derivatives {
+                return Err(GraphicsPipelineCreationError::BasePipelineDoesntAllowDerivatives);
+            }
+            flags |= vk::PIPELINE_CREATE_DERIVATIVE_BIT;
+        }
+
         let pipeline = unsafe {
             let infos = vk::GraphicsPipelineCreateInfo {
                 sType: vk::STRUCTURE_TYPE_GRAPHICS_PIPELINE_CREATE_INFO,
                 pNext: ptr::null(),
-                flags: 0, // TODO: some flags are available but none are critical
+                flags: flags,
                 stageCount: stages.len() as u32,
                 pStages: stages.as_ptr(),
                 pVertexInputState: &vertex_input_state,
@@ -1054,7 +1168,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                     .unwrap_or(ptr::null()),
                 pViewportState: &viewport_info,
                 pRasterizationState: &rasterization,
-                pMultisampleState: &self.multisample,
+                pMultisampleState: &multisample,
                 pDepthStencilState: &depth_stencil,
                 pColorBlendState: &blend,
                 pDynamicState: dynamic_states
@@ -1069,13 +1183,21 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                     .inner()
                     .internal_object(),
                 subpass: self.render_pass.as_ref().unwrap().index(),
-                basePipelineHandle: 0, // TODO:
-                basePipelineIndex: -1, // TODO:
+                basePipelineHandle: self.base_pipeline
+                    .as_ref()
+                    .map(|p| p.pipeline)
+                    .unwrap_or(0),
+                basePipelineIndex: -1,
             };
 
+            let cache = self.cache
+                .as_ref()
+                .map(|cache| cache.internal_object())
+                .unwrap_or(0);
+
             let mut output = MaybeUninit::uninit();
             check_errors(vk.CreateGraphicsPipelines(device.internal_object(),
-                                                    0,
+                                                    cache,
                                                     1,
                                                     &infos,
                                                     ptr::null(),
@@ -1093,13 +1215,16 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         let (render_pass, render_pass_subpass) = self.render_pass.take().unwrap().into();
 
         Ok(GraphicsPipeline {
-               inner: GraphicsPipelineInner {
+               inner: Arc::new(GraphicsPipelineInner {
                    device: device.clone(),
                    pipeline: pipeline,
-               },
+                   allow_derivatives: self.allow_derivatives,
+               }),
                layout: pipeline_layout,
 
                vertex_definition: self.vertex_input,
+               vertex_buffer_strides: binding_descriptions.iter().map(|b| b.stride).collect(),
+               vertex_buffer_input_rates: binding_rates,
 
                render_pass: render_pass,
                render_pass_subpass: render_pass_subpass,
@@ -1117,13 +1242,27 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                num_viewports: self.viewport.as_ref().unwrap().num_viewports(),
            })
     }
-
-    // TODO: add build_with_cache method
 }
 
 impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp> {
-    // TODO: add pipeline derivate system
+    /// Allows other pipelines to be derived from the pipeline being built with `derive_from`.
+    #[inline]
+    pub fn allow_derivatives(mut self) -> Self {
+        self.allow_derivatives = true;
+        self
+    }
+
+    /// Builds the pipeline as a derivative of `parent`, which may let the implementation build
+    /// it faster and switch between the two pipelines faster.
+    ///
+    /// `parent` must have been built with `allow_derivatives` on the same device, otherwise
+    /// building the pipeline will return an error.
+    #[inline]
+    pub fn derive_from<Mv, L, Rp2>(mut self, parent: &GraphicsPipeline<Mv, L, Rp2>) -> Self {
+        self.base_pipeline = Some(parent.inner.clone());
+        self
+    }
 
     /// Sets the vertex input.
     #[inline]
@@ -1144,6 +1283,9 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             depth_stencil: self.depth_stencil,
             blend: self.blend,
             render_pass: self.render_pass,
+            cache: self.cache,
+            allow_derivatives: self.allow_derivatives,
+            base_pipeline: self.base_pipeline,
         }
     }
 
@@ -1169,7 +1311,6 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     }
 
     /// Sets the vertex shader to use.
-    // TODO: correct specialization constants
     #[inline]
     pub fn vertex_shader<Vs2, Vss2>(
         self, shader: Vs2, specialization_constants: Vss2)
@@ -1191,6 +1332,9 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             depth_stencil: self.depth_stencil,
             blend: self.blend,
             render_pass: self.render_pass,
+            cache: self.cache,
+            allow_derivatives: self.allow_derivatives,
+            base_pipeline: self.base_pipeline,
         }
     }
 
@@ -1315,7 +1459,6 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     }
 
     /// Sets the tessellation shaders to use.
-    // TODO: correct specialization constants
     #[inline]
     pub fn tessellation_shaders<Tcs2, Tcss2, Tes2, Tess2>(
         self, tessellation_control_shader: Tcs2,
@@ -1348,6 +1491,9 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             depth_stencil: self.depth_stencil,
             blend: self.blend,
             render_pass: self.render_pass,
+            cache: self.cache,
+            allow_derivatives: self.allow_derivatives,
+            base_pipeline: self.base_pipeline,
         }
     }
 
@@ -1359,7 +1505,6 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     }
 
     /// Sets the geometry shader to use.
-    // TODO: correct specialization constants
     #[inline]
     pub fn geometry_shader<Gs2, Gss2>(
         self, shader: Gs2, specialization_constants: Gss2)
@@ -1381,6 +1526,9 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             depth_stencil: self.depth_stencil,
             blend: self.blend,
             render_pass: self.render_pass,
+            cache: self.cache,
+            allow_derivatives: self.allow_derivatives,
+            base_pipeline: self.base_pipeline,
         }
     }
 
@@ -1564,7 +1712,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     /// Sample shading is disabled by default.
     #[inline]
     pub fn sample_shading_disabled(mut self) -> Self {
-        self.multisample.sampleShadingEnable = vk::FALSE;
+        self.multisample.sample_shading = None;
         self
     }
 
@@ -1578,29 +1726,30 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     /// value is 0.5, then the fragment shader will run for at least half of the samples. The other
     /// half of the samples will get their values determined automatically.
     ///
-    /// Sample shading is disabled by default.
-    ///
-    /// # Panic
-    ///
-    /// - Panics if `min_fract` is not between 0.0 and 1.0.
-    ///
+    /// Sample shading is disabled by default. Building the pipeline returns an error if
+    /// `min_fract` is not between 0.0 and 1.0.
     #[inline]
     pub fn sample_shading_enabled(mut self, min_fract: f32) -> Self {
-        assert!(min_fract >= 0.0 && min_fract <= 1.0);
-        self.multisample.sampleShadingEnable = vk::TRUE;
-        self.multisample.minSampleShading = min_fract;
+        self.multisample.sample_shading = Some(min_fract);
         self
     }
 
-    // TODO: doc
+    /// Disables alpha-to-coverage.
+    ///
+    /// Alpha-to-coverage is disabled by default.
+    #[inline]
     pub fn alpha_to_coverage_disabled(mut self) -> Self {
-        self.multisample.alphaToCoverageEnable = vk::FALSE;
+        self.multisample.alpha_to_coverage = false;
         self
     }
 
-    // TODO: doc
+    /// Enables alpha-to-coverage. The alpha component of the first color output of the fragment
+    /// shader determines which proportion of the samples of each pixel are covered.
+    ///
+    /// Alpha-to-coverage is disabled by default.
+    #[inline]
     pub fn alpha_to_coverage_enabled(mut self) -> Self {
-        self.multisample.alphaToCoverageEnable = vk::TRUE;
+        self.multisample.alpha_to_coverage = true;
         self
     }
 
@@ -1609,7 +1758,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     /// Alpha-to-one is disabled by default.
     #[inline]
     pub fn alpha_to_one_disabled(mut self) -> Self {
-        self.multisample.alphaToOneEnable = vk::FALSE;
+        self.multisample.alpha_to_one = false;
         self
     }
 
@@ -1621,16 +1770,41 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     /// Alpha-to-one is disabled by default.
     #[inline]
     pub fn alpha_to_one_enabled(mut self) -> Self {
-        self.multisample.alphaToOneEnable = vk::TRUE;
+        self.multisample.alpha_to_one = true;
         self
     }
 
-    // TODO: rasterizationSamples and pSampleMask
+    /// Sets the number of samples per pixel of the rasterizer.
+    ///
+    /// If the subpass has attachments, this must match their number of samples, which is used by
+    /// default. Otherwise this must be a number of samples supported by the device without
+    /// attachments, and defaults to 1.
+    #[inline]
+    pub fn rasterization_samples(mut self, samples: u32) -> Self {
+        self.multisample.rasterization_samples = Some(samples);
+        self
+    }
+
+    /// Sets the samples that can be covered by the fragments. Sample `n` is enabled if bit
+    /// `n % 32` of `mask[n / 32]` is set.
+    ///
+    /// All the samples are enabled by default.
+    #[inline]
+    pub fn sample_mask(mut self, mask: [u32; 2]) -> Self {
+        self.multisample.sample_mask = mask;
+        self
+    }
+
+    /// Sets the whole multisample state.
+    #[inline]
+    pub fn multisample(mut self, multisample: Multisample) -> Self {
+        self.multisample = multisample;
+        self
+    }
 
     /// Sets the fragment shader to use.
     ///
     /// The fragment shader is run once for each pixel that is covered by each primitive.
-    // TODO: correct specialization constants
     #[inline]
     pub fn fragment_shader<Fs2, Fss2>(
         self, shader: Fs2, specialization_constants: Fss2)
@@ -1652,6 +1826,9 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             depth_stencil: self.depth_stencil,
             blend: self.blend,
             render_pass: self.render_pass,
+            cache: self.cache,
+            allow_derivatives: self.allow_derivatives,
+            base_pipeline: self.base_pipeline,
         }
     }
 
@@ -1689,7 +1866,102 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         self
     }
 
-    // TODO: missing tons of depth-stencil stuff
+    /// Sets the comparison used by the depth test. The depth test is disabled if the comparison
+    /// is `Always` and the depth buffer isn't written.
+    #[inline]
+    pub fn depth_compare(mut self, compare: Compare) -> Self {
+        self.depth_stencil.depth_compare = compare;
+        self
+    }
+
+    /// Enables the depth bounds test, which discards the fragments whose existing depth value
+    /// in the depth buffer is outside of `range`. The bounds must be between 0.0 and 1.0.
+    ///
+    /// The `depth_bounds` feature must be enabled on the device.
+    #[inline]
+    pub fn depth_bounds(mut self, range: Range<f32>) -> Self {
+        self.depth_stencil.depth_bounds_test = DepthBounds::Fixed(range);
+        self
+    }
+
+    /// Enables the depth bounds test, with a range that will need to be set when drawing.
+    ///
+    /// The `depth_bounds` feature must be enabled on the device.
+    #[inline]
+    pub fn depth_bounds_dynamic(mut self) -> Self {
+        self.depth_stencil.depth_bounds_test = DepthBounds::Dynamic;
+        self
+    }
+
+    /// Disables the depth bounds test. This is the default.
+    #[inline]
+    pub fn depth_bounds_disabled(mut self) -> Self {
+        self.depth_stencil.depth_bounds_test = DepthBounds::Disabled;
+        self
+    }
+
+    /// Sets the stencil test of both the front-facing and the back-facing primitives.
+    #[inline]
+    pub fn stencil(mut self, stencil: Stencil) -> Self {
+        self.depth_stencil.stencil_front = stencil;
+        self.depth_stencil.stencil_back = stencil;
+        self
+    }
+
+    /// Sets the stencil test of points, lines and front-facing triangles.
+    ///
+    /// The compare mask, write mask and reference must be dynamic for both faces or for none
+    /// of them.
+    #[inline]
+    pub fn stencil_front(mut self, stencil: Stencil) -> Self {
+        self.depth_stencil.stencil_front = stencil;
+        self
+    }
+
+    /// Sets the stencil test of back-facing triangles.
+    ///
+    /// The compare mask, write mask and reference must be dynamic for both faces or for none
+    /// of them.
+    #[inline]
+    pub fn stencil_back(mut self, stencil: Stencil) -> Self {
+        self.depth_stencil.stencil_back = stencil;
+        self
+    }
+
+    /// Disables the stencil test. This is the default.
+    #[inline]
+    pub fn stencil_disabled(mut self) -> Self {
+        self.depth_stencil.stencil_front = Default::default();
+        self.depth_stencil.stencil_back = Default::default();
+        self
+    }
+
+    /// Sets the stencil compare mask of both faces as dynamic. It will need to be set with
+    /// `set_stencil_compare_mask` before drawing.
+    #[inline]
+    pub fn stencil_compare_mask_dynamic(mut self) -> Self {
+        self.depth_stencil.stencil_front.compare_mask = None;
+        self.depth_stencil.stencil_back.compare_mask = None;
+        self
+    }
+
+    /// Sets the stencil write mask of both faces as dynamic. It will need to be set with
+    /// `set_stencil_write_mask` before drawing.
+    #[inline]
+    pub fn stencil_write_mask_dynamic(mut self) -> Self {
+        self.depth_stencil.stencil_front.write_mask = None;
+        self.depth_stencil.stencil_back.write_mask = None;
+        self
+    }
+
+    /// Sets the stencil reference of both faces as dynamic. It will need to be set with
+    /// `set_stencil_reference` before drawing.
+    #[inline]
+    pub fn stencil_reference_dynamic(mut self) -> Self {
+        self.depth_stencil.stencil_front.reference = None;
+        self.depth_stencil.stencil_back.reference = None;
+        self
+    }
 
 
     #[inline]
@@ -1768,6 +2040,9 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             depth_stencil: self.depth_stencil,
             blend: self.blend,
             render_pass: Some(subpass),
+            cache: self.cache,
+            allow_derivatives: self.allow_derivatives,
+            base_pipeline: self.base_pipeline,
         }
     }
 }
@@ -1797,21 +2072,21 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp> Clone
             geometry_shader: self.geometry_shader.clone(),
             viewport: self.viewport.clone(),
             raster: self.raster.clone(),
-            multisample: vk::PipelineMultisampleStateCreateInfo {
-                sType: self.multisample.sType,
-                pNext: self.multisample.pNext,
-                flags: self.multisample.flags,
-                rasterizationSamples: self.multisample.rasterizationSamples,
-                sampleShadingEnable: self.multisample.sampleShadingEnable,
-                minSampleShading: self.multisample.minSampleShading,
-                pSampleMask: self.multisample.pSampleMask,
-                alphaToCoverageEnable: self.multisample.alphaToCoverageEnable,
-                alphaToOneEnable: self.multisample.alphaToOneEnable,
-            },
+            multisample: self.multisample,
             fragment_shader: self.fragment_shader.clone(),
             depth_stencil: self.depth_stencil.clone(),
             blend: self.blend.clone(),
             render_pass: self.render_pass.clone(),
+            cache: self.cache.clone(),
+            allow_derivatives: self.allow_derivatives,
+            base_pipeline: self.base_pipeline.clone(),
         }
     }
 }
+
+// Returns a pointer to the specialization info of a shader stage, or null if the stage doesn't
+// have any specialization constant.
+#[inline]
+fn spec_info_ptr(info: &Option<vk::SpecializationInfo>) -> *const vk::SpecializationInfo {
+    info.as_ref().map(|i| i as *const _).unwrap_or(ptr::null())
+}
@@ -94,6 +94,39 @@ pub enum GraphicsPipelineCreationError {
         obtained: usize,
     },
 
+    /// The `VK_EXT_vertex_attribute_divisor` extension must be enabled in order to use an
+    /// instance input rate with a divisor different from 1.
+    VertexAttributeDivisorExtensionNotEnabled,
+
+    /// The `vertex_attribute_instance_rate_divisor` feature of `VK_EXT_vertex_attribute_divisor`
+    /// must be supported in order to use an instance input rate with a divisor different from 1.
+    VertexAttributeInstanceRateDivisorFeatureNotEnabled,
+
+    /// The `vertex_attribute_instance_rate_zero_divisor` feature of
+    /// `VK_EXT_vertex_attribute_divisor` must be supported in order to use an instance input rate
+    /// with a divisor of 0.
+    VertexAttributeInstanceRateZeroDivisorFeatureNotEnabled,
+
+    /// The maximum divisor of an instance input rate has been exceeded.
+    MaxVertexAttribDivisorExceeded {
+        /// Index of the faulty binding.
+        binding: usize,
+        /// Maximum allowed value.
+        max: u32,
+        /// Value that was passed.
+        obtained: u32,
+    },
+
+    /// The descriptors of the specialization constants of a shader stage contain entries that
+    /// don't fit in the specialization constants, or several entries for the same constant.
+    InvalidSpecializationConstants,
+
+    /// The pipeline passed to `derive_from` wasn't created with `allow_derivatives`.
+    BasePipelineDoesntAllowDerivatives,
+
+    /// The pipeline passed to `derive_from` or the pipeline cache belongs to another device.
+    WrongDevice,
+
     /// The user requested to use primitive restart, but the primitive topology doesn't support it.
     PrimitiveDoesntSupportPrimitiveRestart {
         /// The topology that doesn't support primitive restart.
@@ -120,6 +153,25 @@ pub enum GraphicsPipelineCreationError {
     /// The `wide_lines` feature must be enabled in order to use a line width greater than 1.0.
     WideLinesFeatureNotEnabled,
 
+    /// The number of samples of the rasterizer doesn't match the number of samples of the
+    /// attachments of the subpass.
+    RasterizationSamplesMismatch {
+        /// Number of samples of the attachments.
+        expected: u32,
+        /// Number of samples that was requested.
+        obtained: u32,
+    },
+
+    /// The number of samples of the rasterizer isn't supported by the device for a subpass
+    /// without attachments.
+    RasterizationSamplesNotSupported {
+        /// Number of samples that was requested.
+        obtained: u32,
+    },
+
+    /// The minimum fraction of sample shading is not between 0.0 and 1.0.
+    MinSampleShadingOutOfRange,
+
     /// The `depth_clamp` feature must be enabled in order to use depth clamping.
     DepthClampFeatureNotEnabled,
 
@@ -134,6 +186,9 @@ pub enum GraphicsPipelineCreationError {
     /// The `depth_bounds` feature must be enabled in order to use depth bounds testing.
     DepthBoundsFeatureNotEnabled,
 
+    /// The depth bounds range is not between 0.0 and 1.0.
+    DepthBoundsOutOfRange,
+
     /// The requested stencil test is invalid.
     WrongStencilState,
 
@@ -238,6 +293,31 @@ impl error::Error for GraphicsPipelineCreationError {
             GraphicsPipelineCreationError::MaxVertexInputAttributesExceeded { .. } => {
                 "the maximum number of vertex attributes has been exceeded"
             },
+            GraphicsPipelineCreationError::VertexAttributeDivisorExtensionNotEnabled => {
+                "the `VK_EXT_vertex_attribute_divisor` extension must be enabled in order to use \
+                 an instance input rate with a divisor different from 1"
+            },
+            GraphicsPipelineCreationError::VertexAttributeInstanceRateDivisorFeatureNotEnabled => {
+                "the `vertex_attribute_instance_rate_divisor` feature must be supported in order \
+                 to use an instance input rate with a divisor different from 1"
+            },
+            GraphicsPipelineCreationError::VertexAttributeInstanceRateZeroDivisorFeatureNotEnabled => {
+                "the `vertex_attribute_instance_rate_zero_divisor` feature must be supported in \
+                 order to use an instance input rate with a divisor of 0"
+            },
+            GraphicsPipelineCreationError::MaxVertexAttribDivisorExceeded { .. } => {
+                "the maximum divisor of an instance input rate has been exceeded"
+            },
+            GraphicsPipelineCreationError::InvalidSpecializationConstants => {
+                "the descriptors of the specialization constants of a shader stage don't match \
+                 the specialization constants"
+            },
+            GraphicsPipelineCreationError::BasePipelineDoesntAllowDerivatives => {
+                "the base pipeline wasn't created with `allow_derivatives`"
+            },
+            GraphicsPipelineCreationError::WrongDevice => {
+                "the base pipeline or the pipeline cache belongs to another device"
+            },
             GraphicsPipelineCreationError::PrimitiveDoesntSupportPrimitiveRestart { .. } => {
                 "the user requested to use primitive restart, but the primitive topology \
                  doesn't support it"
@@ -259,6 +339,17 @@ impl error::Error for GraphicsPipelineCreationError {
                 "the `wide_lines` feature must be enabled in order to use a line width \
                  greater than 1.0"
             },
+            GraphicsPipelineCreationError::RasterizationSamplesMismatch { .. } => {
+                "the number of samples of the rasterizer doesn't match the number of samples of \
+                 the attachments of the subpass"
+            },
+            GraphicsPipelineCreationError::RasterizationSamplesNotSupported { .. } => {
+                "the number of samples of the rasterizer isn't supported by the device for a \
+                 subpass without attachments"
+            },
+            GraphicsPipelineCreationError::MinSampleShadingOutOfRange => {
+                "the minimum fraction of sample shading is not between 0.0 and 1.0"
+            },
             GraphicsPipelineCreationError::DepthClampFeatureNotEnabled => {
                 "the `depth_clamp` feature must be enabled in order to use depth clamping"
             },
@@ -273,6 +364,9 @@ impl error::Error for GraphicsPipelineCreationError {
             GraphicsPipelineCreationError::DepthBoundsFeatureNotEnabled => {
                 "the `depth_bounds` feature must be enabled in order to use depth bounds testing"
             },
+            GraphicsPipelineCreationError::DepthBoundsOutOfRange => {
+                "the depth bounds range is not between 0.0 and 1.0"
+            },
             GraphicsPipelineCreationError::WrongStencilState => {
                 "the requested stencil test is invalid"
             },
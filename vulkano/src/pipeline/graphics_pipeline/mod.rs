@@ -36,6 +36,7 @@ use framebuffer::Subpass;
 use pipeline::shader::EmptyEntryPointDummy;
 use pipeline::vertex::BufferlessDefinition;
 use pipeline::vertex::IncompatibleVertexDefinitionError;
+use pipeline::vertex::InputRate;
 use pipeline::vertex::VertexDefinition;
 use pipeline::vertex::VertexSource;
 use vk;
@@ -45,21 +46,22 @@ pub use self::creation_error::GraphicsPipelineCreationError;
 
 mod builder;
 mod creation_error;
-// FIXME: restore
-//mod tests;
+mod tests;
 
 /// Defines how the implementation should perform a draw operation.
 ///
 /// This object contains the shaders and the various fixed states that describe how the
 /// implementation should perform the various operations needed by a draw command.
 pub struct GraphicsPipeline<VertexDefinition, Layout, RenderP> {
-    inner: Inner,
+    inner: Arc<Inner>,
     layout: Layout,
 
     render_pass: RenderP,
     render_pass_subpass: u32,
 
     vertex_definition: VertexDefinition,
+    vertex_buffer_strides: Vec<u32>,
+    vertex_buffer_input_rates: Vec<InputRate>,
 
     dynamic_line_width: bool,
     dynamic_viewport: bool,
@@ -77,6 +79,7 @@ pub struct GraphicsPipeline<VertexDefinition, Layout, RenderP> {
 struct Inner {
     pipeline: vk::Pipeline,
     device: Arc<Device>,
+    allow_derivatives: bool,
 }
 
 impl GraphicsPipeline<(), (), ()> {
@@ -112,6 +115,13 @@ impl<Mv, L, Rp> GraphicsPipeline<Mv, L, Rp> {
     pub fn device(&self) -> &Arc<Device> {
         &self.inner.device
     }
+
+    /// Returns true if this pipeline was created with `allow_derivatives`, and can therefore be
+    /// passed to `derive_from` when building other pipelines.
+    #[inline]
+    pub fn allows_derivatives(&self) -> bool {
+        self.inner.allow_derivatives
+    }
 }
 
 impl<Mv, L, Rp> GraphicsPipeline<Mv, L, Rp>
@@ -165,6 +175,12 @@ impl<Mv, L, Rp> GraphicsPipeline<Mv, L, Rp> {
         self.dynamic_scissor
     }
 
+    /// Returns true if the depth bias used by this pipeline is dynamic.
+    #[inline]
+    pub fn has_dynamic_depth_bias(&self) -> bool {
+        self.dynamic_depth_bias
+    }
+
     /// Returns true if the depth bounds used by this pipeline are dynamic.
     #[inline]
     pub fn has_dynamic_depth_bounds(&self) -> bool {
@@ -188,6 +204,12 @@ impl<Mv, L, Rp> GraphicsPipeline<Mv, L, Rp> {
     pub fn has_dynamic_stencil_reference(&self) -> bool {
         self.dynamic_stencil_reference
     }
+
+    /// Returns true if the blend constants used by this pipeline are dynamic.
+    #[inline]
+    pub fn has_dynamic_blend_constants(&self) -> bool {
+        self.dynamic_blend_constants
+    }
 }
 
 unsafe impl<Mv, L, Rp> PipelineLayoutAbstract for GraphicsPipeline<Mv, L, Rp>
@@ -350,6 +372,9 @@ pub unsafe trait GraphicsPipelineAbstract: PipelineLayoutAbstract + RenderPassAb
     /// Returns true if the scissors used by this pipeline are dynamic.
     fn has_dynamic_scissors(&self) -> bool;
 
+    /// Returns true if the depth bias used by this pipeline is dynamic.
+    fn has_dynamic_depth_bias(&self) -> bool;
+
     /// Returns true if the depth bounds used by this pipeline are dynamic.
     fn has_dynamic_depth_bounds(&self) -> bool;
 
@@ -361,6 +386,17 @@ pub unsafe trait GraphicsPipelineAbstract: PipelineLayoutAbstract + RenderPassAb
 
     /// Returns true if the stencil references used by this pipeline are dynamic.
     fn has_dynamic_stencil_reference(&self) -> bool;
+
+    /// Returns true if the blend constants used by this pipeline are dynamic.
+    fn has_dynamic_blend_constants(&self) -> bool;
+
+    /// Returns the stride of each vertex buffer binding of this pipeline, in the order in which
+    /// the vertex buffers must be passed when drawing.
+    fn vertex_buffer_strides(&self) -> &[u32];
+
+    /// Returns the input rate of each vertex buffer binding of this pipeline, in the same order
+    /// as `vertex_buffer_strides`.
+    fn vertex_buffer_input_rates(&self) -> &[InputRate];
 }
 
 unsafe impl<Mv, L, Rp> GraphicsPipelineAbstract for GraphicsPipeline<Mv, L, Rp>
@@ -398,6 +434,11 @@ unsafe impl<Mv, L, Rp> GraphicsPipelineAbstract for GraphicsPipeline<Mv, L, Rp>
         self.dynamic_scissor
     }
 
+    #[inline]
+    fn has_dynamic_depth_bias(&self) -> bool {
+        self.dynamic_depth_bias
+    }
+
     #[inline]
     fn has_dynamic_depth_bounds(&self) -> bool {
         self.dynamic_depth_bounds
@@ -417,6 +458,21 @@ unsafe impl<Mv, L, Rp> GraphicsPipelineAbstract for GraphicsPipeline<Mv, L, Rp>
     fn has_dynamic_stencil_reference(&self) -> bool {
         self.dynamic_stencil_reference
     }
+
+    #[inline]
+    fn has_dynamic_blend_constants(&self) -> bool {
+        self.dynamic_blend_constants
+    }
+
+    #[inline]
+    fn vertex_buffer_strides(&self) -> &[u32] {
+        &self.vertex_buffer_strides
+    }
+
+    #[inline]
+    fn vertex_buffer_input_rates(&self) -> &[InputRate] {
+        &self.vertex_buffer_input_rates
+    }
 }
 
 unsafe impl<T> GraphicsPipelineAbstract for T
@@ -453,6 +509,11 @@ unsafe impl<T> GraphicsPipelineAbstract for T
         (**self).has_dynamic_scissors()
     }
 
+    #[inline]
+    fn has_dynamic_depth_bias(&self) -> bool {
+        (**self).has_dynamic_depth_bias()
+    }
+
     #[inline]
     fn has_dynamic_depth_bounds(&self) -> bool {
         (**self).has_dynamic_depth_bounds()
@@ -472,6 +533,21 @@ unsafe impl<T> GraphicsPipelineAbstract for T
     fn has_dynamic_stencil_reference(&self) -> bool {
         (**self).has_dynamic_stencil_reference()
     }
+
+    #[inline]
+    fn has_dynamic_blend_constants(&self) -> bool {
+        (**self).has_dynamic_blend_constants()
+    }
+
+    #[inline]
+    fn vertex_buffer_strides(&self) -> &[u32] {
+        (**self).vertex_buffer_strides()
+    }
+
+    #[inline]
+    fn vertex_buffer_input_rates(&self) -> &[InputRate] {
+        (**self).vertex_buffer_input_rates()
+    }
 }
 
 /// Opaque object that represents the inside of the graphics pipeline.
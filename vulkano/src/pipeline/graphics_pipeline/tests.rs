@@ -11,23 +11,31 @@
 
 #![cfg(test)]
 
-use std::ffi::CString;
+use std::ffi::CStr;
+use std::sync::Arc;
+use descriptor::pipeline_layout::EmptyPipelineDesc;
+use device::Device;
+use format::ClearValue;
 use format::Format;
+use framebuffer::AttachmentDescription;
+use framebuffer::LoadOp;
+use framebuffer::PassDependencyDescription;
+use framebuffer::PassDescription;
+use framebuffer::RenderPassAbstract;
+use framebuffer::RenderPassDesc;
+use framebuffer::RenderPassDescClearValues;
+use framebuffer::StoreOp;
 use framebuffer::Subpass;
-use descriptor::pipeline_layout::EmptyPipelineDesc;
-use descriptor::pipeline_layout::PipelineLayoutDesc;
+use image::ImageLayout;
 use pipeline::GraphicsPipeline;
-use pipeline::GraphicsPipelineParams;
 use pipeline::GraphicsPipelineCreationError;
-use pipeline::blend::Blend;
-use pipeline::depth_stencil::DepthStencil;
-use pipeline::input_assembly::InputAssembly;
-use pipeline::input_assembly::PrimitiveTopology;
-use pipeline::multisample::Multisample;
-use pipeline::shader::ShaderModule;
+use pipeline::depth_stencil::Compare;
+use pipeline::depth_stencil::Stencil;
+use pipeline::depth_stencil::StencilOp;
 use pipeline::shader::EmptyShaderInterfaceDef;
-use pipeline::vertex::SingleBufferDefinition;
-use pipeline::viewport::ViewportsState;
+use pipeline::shader::GraphicsEntryPoint;
+use pipeline::shader::GraphicsShaderType;
+use pipeline::shader::ShaderModule;
 
 #[test]
 fn create() {
@@ -36,32 +44,15 @@ fn create() {
     let vs = unsafe { ShaderModule::new(device.clone(), &BASIC_VS).unwrap() };
     let fs = unsafe { ShaderModule::new(device.clone(), &BASIC_FS).unwrap() };
 
-    let _ = GraphicsPipeline::new(&device, GraphicsPipelineParams {
-        vertex_input: SingleBufferDefinition::<()>::new(),
-        vertex_shader: unsafe {
-            vs.vertex_shader_entry_point::<(), _, _, _>(&CString::new("main").unwrap(),
-                                                        EmptyShaderInterfaceDef,
-                                                        EmptyShaderInterfaceDef,
-                                                        EmptyPipelineDesc)
-        },
-        input_assembly: InputAssembly::triangle_list(),
-        tessellation: None,
-        geometry_shader: None,
-        viewport: ViewportsState::Dynamic { num: 1 },
-        raster: Default::default(),
-        multisample: Multisample::disabled(),
-        fragment_shader: unsafe {
-            fs.fragment_shader_entry_point::<(), _, _, _>(&CString::new("main").unwrap(),
-                                                          EmptyShaderInterfaceDef,
-                                                          EmptyShaderInterfaceDef,
-                                                          EmptyPipelineDesc)
-        },
-        depth_stencil: DepthStencil::disabled(),
-        blend: Blend::pass_through(),
-        render_pass: Subpass::from(simple_rp::CustomRenderPass::new(&device, &{
-            simple_rp::Formats { color: (Format::R8G8B8A8Unorm, 1) }
-        }).unwrap(), 0).unwrap(),
-    }).unwrap();
+    let _ = GraphicsPipeline::start()
+        .vertex_input_single_buffer::<()>()
+        .vertex_shader(vertex_shader(&vs), ())
+        .triangle_list()
+        .viewports_dynamic_scissors_irrelevant(1)
+        .fragment_shader(fragment_shader(&fs), ())
+        .render_pass(Subpass::from(color_render_pass(device.clone()), 0).unwrap())
+        .build(device.clone())
+        .unwrap();
 }
 
 #[test]
@@ -71,35 +62,15 @@ fn bad_primitive_restart() {
     let vs = unsafe { ShaderModule::new(device.clone(), &BASIC_VS).unwrap() };
     let fs = unsafe { ShaderModule::new(device.clone(), &BASIC_FS).unwrap() };
 
-    let result = GraphicsPipeline::new(&device, GraphicsPipelineParams {
-        vertex_input: SingleBufferDefinition::<()>::new(),
-        vertex_shader: unsafe {
-            vs.vertex_shader_entry_point::<(), _, _, _>(&CString::new("main").unwrap(),
-                                                        EmptyShaderInterfaceDef,
-                                                        EmptyShaderInterfaceDef,
-                                                        EmptyPipelineDesc)
-        },
-        input_assembly: InputAssembly {
-            topology: PrimitiveTopology::TriangleList,
-            primitive_restart_enable: true,
-        },
-        tessellation: None,
-        geometry_shader: None,
-        viewport: ViewportsState::Dynamic { num: 1 },
-        raster: Default::default(),
-        multisample: Multisample::disabled(),
-        fragment_shader: unsafe {
-            fs.fragment_shader_entry_point::<(), _, _, _>(&CString::new("main").unwrap(),
-                                                          EmptyShaderInterfaceDef,
-                                                          EmptyShaderInterfaceDef,
-                                                          EmptyPipelineDesc)
-        },
-        depth_stencil: DepthStencil::disabled(),
-        blend: Blend::pass_through(),
-        render_pass: Subpass::from(simple_rp::CustomRenderPass::new(&device, &{
-            simple_rp::Formats { color: (Format::R8G8B8A8Unorm, 1) }
-        }).unwrap(), 0).unwrap(),
-    });
+    let result = GraphicsPipeline::start()
+        .vertex_input_single_buffer::<()>()
+        .vertex_shader(vertex_shader(&vs), ())
+        .triangle_list()
+        .primitive_restart(true)
+        .viewports_dynamic_scissors_irrelevant(1)
+        .fragment_shader(fragment_shader(&fs), ())
+        .render_pass(Subpass::from(color_render_pass(device.clone()), 0).unwrap())
+        .build(device.clone());
 
     match result {
         Err(GraphicsPipelineCreationError::PrimitiveDoesntSupportPrimitiveRestart { .. }) => (),
@@ -114,32 +85,14 @@ fn multi_viewport_feature() {
     let vs = unsafe { ShaderModule::new(device.clone(), &BASIC_VS).unwrap() };
     let fs = unsafe { ShaderModule::new(device.clone(), &BASIC_FS).unwrap() };
 
-    let result = GraphicsPipeline::new(&device, GraphicsPipelineParams {
-        vertex_input: SingleBufferDefinition::<()>::new(),
-        vertex_shader: unsafe {
-            vs.vertex_shader_entry_point::<(), _, _, _>(&CString::new("main").unwrap(),
-                                                        EmptyShaderInterfaceDef,
-                                                        EmptyShaderInterfaceDef,
-                                                        EmptyPipelineDesc)
-        },
-        input_assembly: InputAssembly::triangle_list(),
-        tessellation: None,
-        geometry_shader: None,
-        viewport: ViewportsState::Dynamic { num: 2 },
-        raster: Default::default(),
-        multisample: Multisample::disabled(),
-        fragment_shader: unsafe {
-            fs.fragment_shader_entry_point::<(), _, _, _>(&CString::new("main").unwrap(),
-                                                          EmptyShaderInterfaceDef,
-                                                          EmptyShaderInterfaceDef,
-                                                          EmptyPipelineDesc)
-        },
-        depth_stencil: DepthStencil::disabled(),
-        blend: Blend::pass_through(),
-        render_pass: Subpass::from(simple_rp::CustomRenderPass::new(&device, &{
-            simple_rp::Formats { color: (Format::R8G8B8A8Unorm, 1) }
-        }).unwrap(), 0).unwrap(),
-    });
+    let result = GraphicsPipeline::start()
+        .vertex_input_single_buffer::<()>()
+        .vertex_shader(vertex_shader(&vs), ())
+        .triangle_list()
+        .viewports_dynamic_scissors_irrelevant(2)
+        .fragment_shader(fragment_shader(&fs), ())
+        .render_pass(Subpass::from(color_render_pass(device.clone()), 0).unwrap())
+        .build(device.clone());
 
     match result {
         Err(GraphicsPipelineCreationError::MultiViewportFeatureNotEnabled) => (),
@@ -154,32 +107,14 @@ fn max_viewports() {
     let vs = unsafe { ShaderModule::new(device.clone(), &BASIC_VS).unwrap() };
     let fs = unsafe { ShaderModule::new(device.clone(), &BASIC_FS).unwrap() };
 
-    let result = GraphicsPipeline::new(&device, GraphicsPipelineParams {
-        vertex_input: SingleBufferDefinition::<()>::new(),
-        vertex_shader: unsafe {
-            vs.vertex_shader_entry_point::<(), _, _, _>(&CString::new("main").unwrap(),
-                                                        EmptyShaderInterfaceDef,
-                                                        EmptyShaderInterfaceDef,
-                                                        EmptyPipelineDesc)
-        },
-        input_assembly: InputAssembly::triangle_list(),
-        tessellation: None,
-        geometry_shader: None,
-        viewport: ViewportsState::Dynamic { num: !0 },
-        raster: Default::default(),
-        multisample: Multisample::disabled(),
-        fragment_shader: unsafe {
-            fs.fragment_shader_entry_point::<(), _, _, _>(&CString::new("main").unwrap(),
-                                                          EmptyShaderInterfaceDef,
-                                                          EmptyShaderInterfaceDef,
-                                                          EmptyPipelineDesc)
-        },
-        depth_stencil: DepthStencil::disabled(),
-        blend: Blend::pass_through(),
-        render_pass: Subpass::from(simple_rp::CustomRenderPass::new(&device, &{
-            simple_rp::Formats { color: (Format::R8G8B8A8Unorm, 1) }
-        }).unwrap(), 0).unwrap(),
-    });
+    let result = GraphicsPipeline::start()
+        .vertex_input_single_buffer::<()>()
+        .vertex_shader(vertex_shader(&vs), ())
+        .triangle_list()
+        .viewports_dynamic_scissors_irrelevant(!0)
+        .fragment_shader(fragment_shader(&fs), ())
+        .render_pass(Subpass::from(color_render_pass(device.clone()), 0).unwrap())
+        .build(device.clone());
 
     match result {
         Err(GraphicsPipelineCreationError::MaxViewportsExceeded { .. }) => (),
@@ -194,32 +129,15 @@ fn no_depth_attachment() {
     let vs = unsafe { ShaderModule::new(device.clone(), &BASIC_VS).unwrap() };
     let fs = unsafe { ShaderModule::new(device.clone(), &BASIC_FS).unwrap() };
 
-    let result = GraphicsPipeline::new(&device, GraphicsPipelineParams {
-        vertex_input: SingleBufferDefinition::<()>::new(),
-        vertex_shader: unsafe {
-            vs.vertex_shader_entry_point::<(), _, _, _>(&CString::new("main").unwrap(),
-                                                        EmptyShaderInterfaceDef,
-                                                        EmptyShaderInterfaceDef,
-                                                        EmptyPipelineDesc)
-        },
-        input_assembly: InputAssembly::triangle_list(),
-        tessellation: None,
-        geometry_shader: None,
-        viewport: ViewportsState::Dynamic { num: 1 },
-        raster: Default::default(),
-        multisample: Multisample::disabled(),
-        fragment_shader: unsafe {
-            fs.fragment_shader_entry_point::<(), _, _, _>(&CString::new("main").unwrap(),
-                                                          EmptyShaderInterfaceDef,
-                                                          EmptyShaderInterfaceDef,
-                                                          EmptyPipelineDesc)
-        },
-        depth_stencil: DepthStencil::simple_depth_test(),
-        blend: Blend::pass_through(),
-        render_pass: Subpass::from(simple_rp::CustomRenderPass::new(&device, &{
-            simple_rp::Formats { color: (Format::R8G8B8A8Unorm, 1) }
-        }).unwrap(), 0).unwrap(),
-    });
+    let result = GraphicsPipeline::start()
+        .vertex_input_single_buffer::<()>()
+        .vertex_shader(vertex_shader(&vs), ())
+        .triangle_list()
+        .viewports_dynamic_scissors_irrelevant(1)
+        .fragment_shader(fragment_shader(&fs), ())
+        .depth_stencil_simple_depth()
+        .render_pass(Subpass::from(color_render_pass(device.clone()), 0).unwrap())
+        .build(device.clone());
 
     match result {
         Err(GraphicsPipelineCreationError::NoDepthAttachment) => (),
@@ -227,22 +145,250 @@ fn no_depth_attachment() {
     }
 }
 
+#[test]
+fn depth_bounds_out_of_range() {
+    let (device, _) = gfx_dev_and_queue!(depth_bounds);
+
+    let vs = unsafe { ShaderModule::new(device.clone(), &BASIC_VS).unwrap() };
+    let fs = unsafe { ShaderModule::new(device.clone(), &BASIC_FS).unwrap() };
+
+    let result = GraphicsPipeline::start()
+        .vertex_input_single_buffer::<()>()
+        .vertex_shader(vertex_shader(&vs), ())
+        .triangle_list()
+        .viewports_dynamic_scissors_irrelevant(1)
+        .fragment_shader(fragment_shader(&fs), ())
+        .depth_stencil_simple_depth()
+        .depth_bounds(0.5 .. 2.0)
+        .render_pass(Subpass::from(depth_render_pass(device.clone()), 0).unwrap())
+        .build(device.clone());
+
+    match result {
+        Err(GraphicsPipelineCreationError::DepthBoundsOutOfRange) => (),
+        _ => panic!()
+    }
+}
+
+#[test]
+fn no_stencil_attachment() {
+    let (device, _) = gfx_dev_and_queue!();
+
+    let vs = unsafe { ShaderModule::new(device.clone(), &BASIC_VS).unwrap() };
+    let fs = unsafe { ShaderModule::new(device.clone(), &BASIC_FS).unwrap() };
+
+    let result = GraphicsPipeline::start()
+        .vertex_input_single_buffer::<()>()
+        .vertex_shader(vertex_shader(&vs), ())
+        .triangle_list()
+        .viewports_dynamic_scissors_irrelevant(1)
+        .fragment_shader(fragment_shader(&fs), ())
+        .stencil(replace_stencil())
+        .render_pass(Subpass::from(depth_render_pass(device.clone()), 0).unwrap())
+        .build(device.clone());
+
+    match result {
+        Err(GraphicsPipelineCreationError::NoStencilAttachment) => (),
+        _ => panic!()
+    }
+}
+
+#[test]
+fn read_only_stencil_attachment() {
+    let (device, _) = gfx_dev_and_queue!();
+
+    let vs = unsafe { ShaderModule::new(device.clone(), &BASIC_VS).unwrap() };
+    let fs = unsafe { ShaderModule::new(device.clone(), &BASIC_FS).unwrap() };
+
+    let render_pass = Arc::new(ReadOnlyDepthStencilDesc.build_render_pass(device.clone())
+                                   .unwrap());
+
+    let result = GraphicsPipeline::start()
+        .vertex_input_single_buffer::<()>()
+        .vertex_shader(vertex_shader(&vs), ())
+        .triangle_list()
+        .viewports_dynamic_scissors_irrelevant(1)
+        .fragment_shader(fragment_shader(&fs), ())
+        .stencil(replace_stencil())
+        .render_pass(Subpass::from(render_pass, 0).unwrap())
+        .build(device.clone());
+
+    match result {
+        Err(GraphicsPipelineCreationError::NoStencilAttachment) => (),
+        _ => panic!()
+    }
+}
+
+#[test]
+fn rasterization_samples_mismatch() {
+    let (device, _) = gfx_dev_and_queue!();
+
+    let vs = unsafe { ShaderModule::new(device.clone(), &BASIC_VS).unwrap() };
+    let fs = unsafe { ShaderModule::new(device.clone(), &BASIC_FS).unwrap() };
+
+    let result = GraphicsPipeline::start()
+        .vertex_input_single_buffer::<()>()
+        .vertex_shader(vertex_shader(&vs), ())
+        .triangle_list()
+        .viewports_dynamic_scissors_irrelevant(1)
+        .fragment_shader(fragment_shader(&fs), ())
+        .rasterization_samples(4)
+        .render_pass(Subpass::from(color_render_pass(device.clone()), 0).unwrap())
+        .build(device.clone());
+
+    match result {
+        Err(GraphicsPipelineCreationError::RasterizationSamplesMismatch {
+                expected: 1,
+                obtained: 4,
+            }) => (),
+        _ => panic!()
+    }
+}
+
+#[test]
+fn min_sample_shading_out_of_range() {
+    let (device, _) = gfx_dev_and_queue!(sample_rate_shading);
+
+    let vs = unsafe { ShaderModule::new(device.clone(), &BASIC_VS).unwrap() };
+    let fs = unsafe { ShaderModule::new(device.clone(), &BASIC_FS).unwrap() };
+
+    let result = GraphicsPipeline::start()
+        .vertex_input_single_buffer::<()>()
+        .vertex_shader(vertex_shader(&vs), ())
+        .triangle_list()
+        .viewports_dynamic_scissors_irrelevant(1)
+        .fragment_shader(fragment_shader(&fs), ())
+        .sample_shading_enabled(2.0)
+        .render_pass(Subpass::from(color_render_pass(device.clone()), 0).unwrap())
+        .build(device.clone());
+
+    match result {
+        Err(GraphicsPipelineCreationError::MinSampleShadingOutOfRange) => (),
+        _ => panic!()
+    }
+}
+
+fn vertex_shader<'a>(module: &'a ShaderModule)
+    -> GraphicsEntryPoint<'a, (), EmptyShaderInterfaceDef, EmptyShaderInterfaceDef,
+                          EmptyPipelineDesc> {
+    unsafe {
+        module.graphics_entry_point(CStr::from_bytes_with_nul(b"main\0").unwrap(),
+                                    EmptyShaderInterfaceDef,
+                                    EmptyShaderInterfaceDef,
+                                    EmptyPipelineDesc,
+                                    GraphicsShaderType::Vertex)
+    }
+}
+
+fn fragment_shader<'a>(module: &'a ShaderModule)
+    -> GraphicsEntryPoint<'a, (), EmptyShaderInterfaceDef, EmptyShaderInterfaceDef,
+                          EmptyPipelineDesc> {
+    unsafe {
+        module.graphics_entry_point(CStr::from_bytes_with_nul(b"main\0").unwrap(),
+                                    EmptyShaderInterfaceDef,
+                                    EmptyShaderInterfaceDef,
+                                    EmptyPipelineDesc,
+                                    GraphicsShaderType::Fragment)
+    }
+}
 
-mod simple_rp {
-    use format::Format;
+// A stencil test that writes the reference value to the stencil attachment.
+fn replace_stencil() -> Stencil {
+    Stencil {
+        compare: Compare::Always,
+        pass_op: StencilOp::Replace,
+        ..Stencil::default()
+    }
+}
 
-    single_pass_renderpass!{
+fn color_render_pass(device: Arc<Device>) -> Arc<dyn RenderPassAbstract + Send + Sync> {
+    Arc::new(single_pass_renderpass!(device,
         attachments: {
             color: {
                 load: Clear,
                 store: Store,
-                format: Format,
+                format: Format::R8G8B8A8Unorm,
+                samples: 1,
             }
         },
         pass: {
             color: [color],
             depth_stencil: {}
         }
+    ).unwrap())
+}
+
+fn depth_render_pass(device: Arc<Device>) -> Arc<dyn RenderPassAbstract + Send + Sync> {
+    Arc::new(single_pass_renderpass!(device,
+        attachments: {
+            depth: {
+                load: Clear,
+                store: Store,
+                format: Format::D16Unorm,
+                samples: 1,
+            }
+        },
+        pass: {
+            color: [],
+            depth_stencil: {depth}
+        }
+    ).unwrap())
+}
+
+// Render pass whose subpass uses a depth-stencil attachment in a read-only layout.
+struct ReadOnlyDepthStencilDesc;
+
+unsafe impl RenderPassDesc for ReadOnlyDepthStencilDesc {
+    fn num_attachments(&self) -> usize {
+        1
+    }
+
+    fn attachment_desc(&self, num: usize) -> Option<AttachmentDescription> {
+        if num != 0 {
+            return None;
+        }
+
+        Some(AttachmentDescription {
+                 format: Format::D24Unorm_S8Uint,
+                 samples: 1,
+                 load: LoadOp::Load,
+                 store: StoreOp::Store,
+                 stencil_load: LoadOp::Load,
+                 stencil_store: StoreOp::Store,
+                 initial_layout: ImageLayout::DepthStencilReadOnlyOptimal,
+                 final_layout: ImageLayout::DepthStencilReadOnlyOptimal,
+             })
+    }
+
+    fn num_subpasses(&self) -> usize {
+        1
+    }
+
+    fn subpass_desc(&self, num: usize) -> Option<PassDescription> {
+        if num != 0 {
+            return None;
+        }
+
+        Some(PassDescription {
+                 color_attachments: vec![],
+                 depth_stencil: Some((0, ImageLayout::DepthStencilReadOnlyOptimal)),
+                 input_attachments: vec![],
+                 resolve_attachments: vec![],
+                 preserve_attachments: vec![],
+             })
+    }
+
+    fn num_dependencies(&self) -> usize {
+        0
+    }
+
+    fn dependency_desc(&self, _: usize) -> Option<PassDependencyDescription> {
+        None
+    }
+}
+
+unsafe impl RenderPassDescClearValues<Vec<ClearValue>> for ReadOnlyDepthStencilDesc {
+    fn convert_clear_values(&self, values: Vec<ClearValue>) -> Box<dyn Iterator<Item = ClearValue>> {
+        Box::new(values.into_iter())
     }
 }
 
@@ -37,25 +37,54 @@
 /// State of the multisampling.
 ///
 /// See the documentation in this module.
-#[deprecated(note = "No longer needed")]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Multisample {
-    pub rasterization_samples: u32,
-    pub sample_mask: [u32; 4],
+    /// Number of samples per pixel, which must be a power of two between 1 and 64.
+    ///
+    /// If `None`, the number of samples of the attachments of the subpass is used, or 1 if the
+    /// subpass doesn't have any attachment. If `Some` and the subpass has attachments, it must
+    /// match their number of samples.
+    pub rasterization_samples: Option<u32>,
+
+    /// Samples that can be covered by the fragments. Sample `n` is enabled if bit `n % 32` of
+    /// `sample_mask[n / 32]` is set. The other samples are discarded.
+    pub sample_mask: [u32; 2],
+
+    /// If `Some`, the fragment shader runs for at least the given fraction of the samples of
+    /// each pixel, which must be between 0.0 and 1.0.
+    ///
+    /// Requires the `sample_rate_shading` feature to be enabled on the device.
     pub sample_shading: Option<f32>,
+
+    /// If true, the alpha value of the first color output of the fragment shader determines
+    /// which samples are covered.
     pub alpha_to_coverage: bool,
+
+    /// If true, the alpha value of the first color output of the fragment shader is replaced
+    /// with 1.0.
+    ///
+    /// Requires the `alpha_to_one` feature to be enabled on the device.
     pub alpha_to_one: bool,
 }
 
 impl Multisample {
+    /// Returns a `Multisample` that uses the number of samples of the subpass, with all the
+    /// samples enabled and without sample shading, alpha-to-coverage or alpha-to-one.
     #[inline]
     pub fn disabled() -> Multisample {
         Multisample {
-            rasterization_samples: 1,
-            sample_mask: [0xffffffff; 4],
+            rasterization_samples: None,
+            sample_mask: [0xffffffff; 2],
             sample_shading: None,
             alpha_to_coverage: false,
             alpha_to_one: false,
         }
     }
 }
+
+impl Default for Multisample {
+    #[inline]
+    fn default() -> Multisample {
+        Multisample::disabled()
+    }
+}
@@ -13,13 +13,16 @@
 //! code and can contain one or more entry points. Note that for the moment the official
 //! GLSL-to-SPIR-V compiler does not support multiple entry points.
 //!
-//! The vulkano library does not provide any functionality that checks and introspects the SPIR-V
-//! code, therefore the whole shader-related API is unsafe. You are encouraged to use the
-//! `vulkano-shaders` crate that will generate Rust code that wraps around vulkano's shaders API.
+//! Most of the shader-related API is unsafe, as it relies on the user correctly describing the
+//! SPIR-V code. You are encouraged to use the `vulkano-shaders` crate that will generate Rust code
+//! that wraps around vulkano's shaders API. For shaders that are only available at runtime,
+//! `ShaderModule::entry_point_by_name` introspects the SPIR-V code with the `spirv` module
+//! instead.
 
 use std::borrow::Cow;
 use std::error;
 use std::ffi::CStr;
+use std::ffi::CString;
 use std::fmt;
 use std::iter;
 use std::iter::Empty as EmptyIter;
@@ -29,11 +32,16 @@ use std::mem::MaybeUninit;
 use std::ops::Range;
 use std::ptr;
 use std::sync::Arc;
+use std::vec::IntoIter as VecIntoIter;
 
 use descriptor::pipeline_layout::EmptyPipelineDesc;
 use descriptor::pipeline_layout::PipelineLayoutDesc;
+use descriptor::pipeline_layout::RuntimePipelineDesc;
 use format::Format;
 use pipeline::input_assembly::PrimitiveTopology;
+use spirv;
+use spirv::EntryPointType;
+use spirv::ReflectionError;
 
 use OomError;
 use VulkanObject;
@@ -51,6 +59,8 @@ pub struct ShaderModule {
     module: vk::ShaderModule,
     // Pointer to the device.
     device: Arc<Device>,
+    // The SPIR-V code, if the module was created with one of the `with_reflection` constructors.
+    spirv: Option<Vec<u32>>,
 }
 
 impl ShaderModule {
@@ -63,8 +73,7 @@ impl ShaderModule {
     ///   this function either.
     ///
     pub unsafe fn new(device: Arc<Device>, spirv: &[u8]) -> Result<Arc<ShaderModule>, OomError> {
-        debug_assert!((spirv.len() % 4) == 0);
-        Self::from_ptr(device, spirv.as_ptr() as *const _, spirv.len())
+        Self::from_words(device, &words_from_bytes(spirv))
     }
 
     /// Builds a new shader module from SPIR-V 32-bit words.
@@ -77,26 +86,43 @@ impl ShaderModule {
     ///
     pub unsafe fn from_words(device: Arc<Device>, spirv: &[u32])
                              -> Result<Arc<ShaderModule>, OomError> {
-        Self::from_ptr(device, spirv.as_ptr(), spirv.len() * mem::size_of::<u32>())
+        Self::from_words_impl(device, spirv, false)
     }
 
-    /// Builds a new shader module from SPIR-V.
+    /// Builds a new shader module from SPIR-V bytes, and keeps a copy of the code so that
+    /// `entry_point_by_name` and `compute_entry_point_by_name` can introspect it.
     ///
     /// # Safety
     ///
-    /// - The SPIR-V code is not validated.
-    /// - The SPIR-V code may require some features that are not enabled. This isn't checked by
-    ///   this function either.
+    /// Same as `new`.
+    ///
+    pub unsafe fn with_reflection(device: Arc<Device>, spirv: &[u8])
+                                  -> Result<Arc<ShaderModule>, OomError> {
+        Self::from_words_impl(device, &words_from_bytes(spirv), true)
+    }
+
+    /// Builds a new shader module from SPIR-V 32-bit words, and keeps a copy of the code so that
+    /// `entry_point_by_name` and `compute_entry_point_by_name` can introspect it.
+    ///
+    /// # Safety
     ///
-    unsafe fn from_ptr(device: Arc<Device>, spirv: *const u32, spirv_len: usize)
-                       -> Result<Arc<ShaderModule>, OomError> {
+    /// Same as `from_words`.
+    ///
+    pub unsafe fn from_words_with_reflection(device: Arc<Device>, spirv: &[u32])
+                                             -> Result<Arc<ShaderModule>, OomError> {
+        Self::from_words_impl(device, spirv, true)
+    }
+
+    // Actual constructor. Keeps a copy of the code if `keep_spirv` is true.
+    unsafe fn from_words_impl(device: Arc<Device>, spirv: &[u32], keep_spirv: bool)
+                              -> Result<Arc<ShaderModule>, OomError> {
         let module = {
             let infos = vk::ShaderModuleCreateInfo {
                 sType: vk::STRUCTURE_TYPE_SHADER_MODULE_CREATE_INFO,
                 pNext: ptr::null(),
                 flags: 0, // reserved
-                codeSize: spirv_len,
-                pCode: spirv,
+                codeSize: spirv.len() * mem::size_of::<u32>(),
+                pCode: spirv.as_ptr(),
             };
 
             let vk = device.pointers();
@@ -111,6 +137,7 @@ impl ShaderModule {
         Ok(Arc::new(ShaderModule {
                         module: module,
                         device: device,
+                        spirv: if keep_spirv { Some(spirv.to_owned()) } else { None },
                     }))
     }
 
@@ -162,6 +189,80 @@ impl ShaderModule {
             marker: PhantomData,
         }
     }
+
+    /// Gets access to the graphics entry point named `name`.
+    ///
+    /// Contrary to `graphics_entry_point`, the SPIR-V code is parsed in order to check that the
+    /// entry point exists and to determine its type, inputs, outputs and layout. The returned
+    /// entry point doesn't accept any specialization constant.
+    ///
+    /// The module must have been created with `with_reflection` or `from_words_with_reflection`.
+    ///
+    /// Returns an error if the entry point doesn't exist, is a compute shader, or if its
+    /// interface can't be described by vulkano.
+    pub fn entry_point_by_name(&self, name: &str)
+                               -> Result<RuntimeGraphicsEntryPoint, ReflectionError> {
+        let reflection = spirv::reflect_entry_point(self.spirv()?, name)?;
+        let ty = match reflection.ty {
+            EntryPointType::Graphics(ty) => ty,
+            EntryPointType::Compute => return Err(ReflectionError::WrongEntryPointType),
+        };
+
+        Ok(RuntimeGraphicsEntryPoint {
+               module: self,
+               name: entry_point_name(name)?,
+               input: reflection.input,
+               output: reflection.output,
+               layout: reflection.layout,
+               ty,
+           })
+    }
+
+    /// Gets access to the compute entry point named `name`.
+    ///
+    /// Same as `entry_point_by_name`, but for compute shaders.
+    pub fn compute_entry_point_by_name(&self, name: &str)
+                                       -> Result<RuntimeComputeEntryPoint, ReflectionError> {
+        let reflection = spirv::reflect_entry_point(self.spirv()?, name)?;
+        if reflection.ty != EntryPointType::Compute {
+            return Err(ReflectionError::WrongEntryPointType);
+        }
+
+        Ok(RuntimeComputeEntryPoint {
+               module: self,
+               name: entry_point_name(name)?,
+               layout: reflection.layout,
+           })
+    }
+
+    // Returns the SPIR-V code that was kept for reflection.
+    #[inline]
+    fn spirv(&self) -> Result<&[u32], ReflectionError> {
+        match self.spirv {
+            Some(ref spirv) => Ok(spirv),
+            None => Err(ReflectionError::ReflectionDisabled),
+        }
+    }
+}
+
+// Converts SPIR-V bytes to words in the native endianness.
+fn words_from_bytes(spirv: &[u8]) -> Vec<u32> {
+    debug_assert!((spirv.len() % 4) == 0);
+    spirv
+        .chunks(4)
+        .map(|c| {
+            let mut word = [0; 4];
+            word[.. c.len()].copy_from_slice(c);
+            u32::from_ne_bytes(word)
+        })
+        .collect()
+}
+
+// A name found in the SPIR-V code can't contain a nul character, therefore a name that contains
+// one can't match any entry point.
+#[inline]
+fn entry_point_name(name: &str) -> Result<CString, ReflectionError> {
+    CString::new(name).map_err(|_| ReflectionError::EntryPointNotFound)
 }
 
 unsafe impl VulkanObject for ShaderModule {
@@ -354,6 +455,89 @@ unsafe impl<'a, S, L> EntryPointAbstract for ComputeEntryPoint<'a, S, L>
     }
 }
 
+/// Graphics entry point whose description was obtained by reflecting the SPIR-V code.
+///
+/// Can be obtained by calling `entry_point_by_name()` on the shader module.
+#[derive(Debug, Clone)]
+pub struct RuntimeGraphicsEntryPoint<'a> {
+    module: &'a ShaderModule,
+    name: CString,
+    input: RuntimeShaderInterface,
+    output: RuntimeShaderInterface,
+    layout: RuntimePipelineDesc,
+    ty: GraphicsShaderType,
+}
+
+unsafe impl<'a> EntryPointAbstract for RuntimeGraphicsEntryPoint<'a> {
+    type PipelineLayout = RuntimePipelineDesc;
+    type SpecializationConstants = ();
+
+    #[inline]
+    fn module(&self) -> &ShaderModule {
+        self.module
+    }
+
+    #[inline]
+    fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    #[inline]
+    fn layout(&self) -> &RuntimePipelineDesc {
+        &self.layout
+    }
+}
+
+unsafe impl<'a> GraphicsEntryPointAbstract for RuntimeGraphicsEntryPoint<'a> {
+    type InputDefinition = RuntimeShaderInterface;
+    type OutputDefinition = RuntimeShaderInterface;
+
+    #[inline]
+    fn input(&self) -> &RuntimeShaderInterface {
+        &self.input
+    }
+
+    #[inline]
+    fn output(&self) -> &RuntimeShaderInterface {
+        &self.output
+    }
+
+    #[inline]
+    fn ty(&self) -> GraphicsShaderType {
+        self.ty
+    }
+}
+
+/// Compute entry point whose description was obtained by reflecting the SPIR-V code.
+///
+/// Can be obtained by calling `compute_entry_point_by_name()` on the shader module.
+#[derive(Debug, Clone)]
+pub struct RuntimeComputeEntryPoint<'a> {
+    module: &'a ShaderModule,
+    name: CString,
+    layout: RuntimePipelineDesc,
+}
+
+unsafe impl<'a> EntryPointAbstract for RuntimeComputeEntryPoint<'a> {
+    type PipelineLayout = RuntimePipelineDesc;
+    type SpecializationConstants = ();
+
+    #[inline]
+    fn module(&self) -> &ShaderModule {
+        self.module
+    }
+
+    #[inline]
+    fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    #[inline]
+    fn layout(&self) -> &RuntimePipelineDesc {
+        &self.layout
+    }
+}
+
 /// A dummy that implements `GraphicsEntryPointAbstract` and `EntryPointAbstract`.
 ///
 /// When a function has a signature like: `fn foo<S: EntryPointAbstract>(shader: Option<S>)`, you
@@ -445,6 +629,120 @@ unsafe impl ShaderInterfaceDef for EmptyShaderInterfaceDef {
     }
 }
 
+/// Description of a shader interface whose elements are only known at runtime.
+///
+/// Can be built manually, or obtained through reflection with
+/// `ShaderModule::entry_point_by_name`.
+#[derive(Debug, Clone)]
+pub struct RuntimeShaderInterface {
+    elements: Vec<ShaderInterfaceDefEntry>,
+}
+
+impl RuntimeShaderInterface {
+    /// Builds a new `RuntimeShaderInterface` from a list of elements.
+    ///
+    /// Returns an error if the elements don't fulfill the requirements of `ShaderInterfaceDef`.
+    pub fn new<I>(elements: I) -> Result<RuntimeShaderInterface, RuntimeShaderInterfaceError>
+        where I: IntoIterator<Item = ShaderInterfaceDefEntry>
+    {
+        let elements: Vec<ShaderInterfaceDefEntry> = elements.into_iter().collect();
+
+        for (num, a) in elements.iter().enumerate() {
+            if a.location.start >= a.location.end {
+                return Err(RuntimeShaderInterfaceError::EmptyLocationRange {
+                               location: a.location.start,
+                           });
+            }
+
+            match a.format.size() {
+                Some(size) if size <= 16 => (),
+                _ => {
+                    return Err(RuntimeShaderInterfaceError::UnsupportedFormat {
+                                   location: a.location.start,
+                                   format: a.format,
+                               });
+                },
+            }
+
+            for b in elements.iter().skip(num + 1) {
+                if a.location.start < b.location.end && b.location.start < a.location.end {
+                    return Err(RuntimeShaderInterfaceError::OverlappingElements {
+                                   first_location: a.location.start,
+                                   second_location: b.location.start,
+                               });
+                }
+            }
+        }
+
+        Ok(RuntimeShaderInterface { elements })
+    }
+
+    /// Builds a `RuntimeShaderInterface` without any element.
+    #[inline]
+    pub fn empty() -> RuntimeShaderInterface {
+        RuntimeShaderInterface { elements: Vec::new() }
+    }
+}
+
+unsafe impl ShaderInterfaceDef for RuntimeShaderInterface {
+    type Iter = VecIntoIter<ShaderInterfaceDefEntry>;
+
+    #[inline]
+    fn elements(&self) -> Self::Iter {
+        self.elements.clone().into_iter()
+    }
+}
+
+/// Error that can happen when building a `RuntimeShaderInterface`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuntimeShaderInterfaceError {
+    /// An element covers an empty range of locations.
+    EmptyLocationRange {
+        /// First location of the element.
+        location: u32,
+    },
+
+    /// The format of an element is larger than 128 bits or has no fixed size.
+    UnsupportedFormat {
+        /// First location of the element.
+        location: u32,
+        /// Format of the element.
+        format: Format,
+    },
+
+    /// Two elements share some locations.
+    OverlappingElements {
+        /// First location of the first element.
+        first_location: u32,
+        /// First location of the second element.
+        second_location: u32,
+    },
+}
+
+impl error::Error for RuntimeShaderInterfaceError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            RuntimeShaderInterfaceError::EmptyLocationRange { .. } => {
+                "an element covers an empty range of locations"
+            },
+            RuntimeShaderInterfaceError::UnsupportedFormat { .. } => {
+                "the format of an element is larger than 128 bits or has no fixed size"
+            },
+            RuntimeShaderInterfaceError::OverlappingElements { .. } => {
+                "two elements share some locations"
+            },
+        }
+    }
+}
+
+impl fmt::Display for RuntimeShaderInterfaceError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
 /// Extension trait for `ShaderInterfaceDef` that specifies that the interface is potentially
 /// compatible with another one.
 pub unsafe trait ShaderInterfaceDefMatch<I>: ShaderInterfaceDef
@@ -647,3 +945,57 @@ pub struct SpecializationMapEntry {
     /// Size of the data in bytes. Must match the size of the constant (`4` for booleans).
     pub size: usize,
 }
+
+/// Returns true if the descriptors of `S` only contain entries that fit in `S`, and at most one
+/// entry per constant.
+#[inline]
+pub(crate) fn specialization_constants_are_valid<S>() -> bool
+    where S: SpecializationConstants
+{
+    descriptors_are_valid(S::descriptors(), mem::size_of::<S>())
+}
+
+/// Builds the `vk::SpecializationInfo` of some specialization constants, or returns `None` if
+/// they don't contain any constant.
+pub(crate) fn specialization_info<S>(constants: &S) -> Option<vk::SpecializationInfo>
+    where S: SpecializationConstants
+{
+    let descriptors = S::descriptors();
+    if descriptors.is_empty() {
+        return None;
+    }
+
+    Some(vk::SpecializationInfo {
+             mapEntryCount: descriptors.len() as u32,
+             pMapEntries: descriptors.as_ptr() as *const _,
+             dataSize: mem::size_of_val(constants),
+             pData: constants as *const S as *const _,
+         })
+}
+
+// Checks that the entries fit in a type of `size` bytes and that each constant appears only once.
+fn descriptors_are_valid(descriptors: &[SpecializationMapEntry], size: usize) -> bool {
+    descriptors.iter().enumerate().all(|(num, entry)| {
+        entry.size != 0 && entry.offset as usize + entry.size <= size &&
+            descriptors[.. num].iter().all(|other| other.constant_id != entry.constant_id)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pipeline::shader::SpecializationMapEntry;
+    use pipeline::shader::descriptors_are_valid;
+
+    #[test]
+    fn specialization_descriptors() {
+        let entry = |constant_id, offset, size| {
+            SpecializationMapEntry { constant_id, offset, size }
+        };
+
+        assert!(descriptors_are_valid(&[], 0));
+        assert!(descriptors_are_valid(&[entry(0, 0, 4), entry(3, 8, 8)], 16));
+        assert!(!descriptors_are_valid(&[entry(0, 0, 4), entry(3, 8, 8)], 12));
+        assert!(!descriptors_are_valid(&[entry(0, 0, 4), entry(0, 4, 4)], 8));
+        assert!(!descriptors_are_valid(&[entry(0, 0, 0)], 8));
+    }
+}
@@ -0,0 +1,355 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::fmt;
+use std::mem;
+use std::sync::Arc;
+use std::vec::IntoIter as VecIntoIter;
+
+use buffer::BufferAccess;
+use pipeline::shader::ShaderInterfaceDef;
+use pipeline::vertex::AttributeInfo;
+use pipeline::vertex::IncompatibleVertexDefinitionError;
+use pipeline::vertex::InputRate;
+use pipeline::vertex::Vertex;
+use pipeline::vertex::VertexDefinition;
+use pipeline::vertex::VertexMemberInfo;
+use pipeline::vertex::VertexSource;
+
+/// A vertex definition for any number of vertex and instance buffers.
+///
+/// Each call to `vertex`, `instance`, `instance_with_divisor` or `binding` adds a binding, and
+/// the buffers must be passed in the same order when drawing. Each attribute of the vertex shader
+/// is read from the first binding whose type has a member with the same name.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate vulkano;
+/// use vulkano::pipeline::vertex::BuffersDefinition;
+///
+/// #[derive(Default, Copy, Clone)]
+/// struct Position { position: [f32; 3] }
+/// impl_vertex!(Position, position);
+///
+/// #[derive(Default, Copy, Clone)]
+/// struct Normal { normal: [f32; 3] }
+/// impl_vertex!(Normal, normal);
+///
+/// #[derive(Default, Copy, Clone)]
+/// struct InstanceData { offset: [f32; 3] }
+/// impl_vertex!(InstanceData, offset);
+///
+/// # fn main() {
+/// let definition = BuffersDefinition::new()
+///     .vertex::<Position>()
+///     .vertex::<Normal>()
+///     .instance::<InstanceData>();
+/// # let _ = definition;
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BuffersDefinition(Vec<VertexBuffer>);
+
+/// A binding of a `BuffersDefinition`.
+#[derive(Clone, Copy)]
+struct VertexBuffer {
+    member: fn(&str) -> Option<VertexMemberInfo>,
+    stride: usize,
+    input_rate: InputRate,
+}
+
+impl fmt::Debug for VertexBuffer {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        fmt.debug_struct("VertexBuffer")
+            .field("stride", &self.stride)
+            .field("input_rate", &self.input_rate)
+            .finish()
+    }
+}
+
+impl BuffersDefinition {
+    /// Builds a definition without any binding.
+    #[inline]
+    pub fn new() -> BuffersDefinition {
+        BuffersDefinition(Vec::new())
+    }
+
+    /// Adds a binding whose elements of type `V` correspond to vertices.
+    #[inline]
+    pub fn vertex<V: Vertex>(self) -> BuffersDefinition {
+        self.binding::<V>(mem::size_of::<V>(), InputRate::Vertex)
+    }
+
+    /// Adds a binding whose elements of type `V` correspond to instances.
+    #[inline]
+    pub fn instance<V: Vertex>(self) -> BuffersDefinition {
+        self.instance_with_divisor::<V>(1)
+    }
+
+    /// Adds a binding whose elements of type `V` each correspond to `divisor` consecutive
+    /// instances.
+    ///
+    /// A divisor different from 1 requires the `VK_EXT_vertex_attribute_divisor` extension to be
+    /// enabled on the device.
+    #[inline]
+    pub fn instance_with_divisor<V: Vertex>(self, divisor: u32) -> BuffersDefinition {
+        self.binding::<V>(mem::size_of::<V>(), InputRate::Instance { divisor })
+    }
+
+    /// Adds a binding whose elements of type `V` are `stride` bytes apart, for example if the
+    /// buffer contains other data between the elements.
+    ///
+    /// A stride of 0 reads the same element for all the vertices or instances.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `stride` is neither 0 nor at least the size of `V`, as the elements would
+    ///   overlap.
+    ///
+    #[inline]
+    pub fn binding<V: Vertex>(mut self, stride: usize, input_rate: InputRate) -> BuffersDefinition {
+        assert!(stride == 0 || stride >= mem::size_of::<V>(),
+                "The stride of a binding is smaller than the size of its elements");
+
+        self.0.push(VertexBuffer {
+            member: V::member,
+            stride,
+            input_rate,
+        });
+        self
+    }
+}
+
+unsafe impl<I> VertexDefinition<I> for BuffersDefinition
+    where I: ShaderInterfaceDef
+{
+    type BuffersIter = VecIntoIter<(u32, usize, InputRate)>;
+    type AttribsIter = VecIntoIter<(u32, u32, AttributeInfo)>;
+
+    fn definition(
+        &self, interface: &I)
+        -> Result<(Self::BuffersIter, Self::AttribsIter), IncompatibleVertexDefinitionError> {
+        let mut attribs = Vec::with_capacity(interface.elements().len());
+        for e in interface.elements() {
+            let name = e.name.as_ref().unwrap();
+
+            let (infos, binding) = match self.0
+                .iter()
+                .enumerate()
+                .filter_map(|(binding, buffer)| (buffer.member)(name).map(|infos| (infos, binding)))
+                .next()
+            {
+                Some(m) => m,
+                None => return Err(IncompatibleVertexDefinitionError::MissingAttribute {
+                                       attribute: name.clone().into_owned(),
+                                   }),
+            };
+
+            let format = match infos.attribute_format(e.format,
+                                                      e.location.end - e.location.start) {
+                Some(format) => format,
+                None => {
+                    return Err(IncompatibleVertexDefinitionError::FormatMismatch {
+                                   attribute: name.clone().into_owned(),
+                                   shader: (e.format, (e.location.end - e.location.start) as usize),
+                                   definition: (infos.ty, infos.array_size),
+                               });
+                },
+            };
+
+            let mut offset = infos.offset;
+            for loc in e.location.clone() {
+                attribs.push((loc,
+                              binding as u32,
+                              AttributeInfo {
+                                  offset: offset,
+                                  format: format,
+                              }));
+                offset += format.size().unwrap();
+            }
+        }
+
+        let buffers = self.0
+            .iter()
+            .enumerate()
+            .map(|(binding, buffer)| (binding as u32, buffer.stride, buffer.input_rate))
+            .collect::<Vec<_>>();
+
+        Ok((buffers.into_iter(), attribs.into_iter()))
+    }
+}
+
+unsafe impl VertexSource<Vec<Arc<dyn BufferAccess + Send + Sync>>> for BuffersDefinition {
+    fn decode(&self, source: Vec<Arc<dyn BufferAccess + Send + Sync>>)
+              -> (Vec<Box<dyn BufferAccess + Send + Sync>>, usize, usize) {
+        // If the number of buffers doesn't match the number of bindings, the buffers are returned
+        // as they are and `check_vertex_buffers` reports the mismatch.
+        // The number of vertices and instances is limited by the smallest buffer of each rate, and
+        // is unbounded if no buffer of that rate limits it. Buffers with a stride or a divisor of
+        // 0 use the same element for all vertices or instances, and don't limit anything.
+        let mut vertices: Option<usize> = None;
+        let mut instances: Option<usize> = None;
+        for (buffer, binding) in source.iter().zip(self.0.iter()) {
+            if binding.stride == 0 {
+                continue;
+            }
+
+            let len = buffer.size() / binding.stride;
+            match binding.input_rate {
+                InputRate::Vertex => {
+                    vertices = Some(vertices.map_or(len, |v| v.min(len)));
+                },
+                InputRate::Instance { divisor: 0 } => (),
+                InputRate::Instance { divisor } => {
+                    let len = len * divisor as usize;
+                    instances = Some(instances.map_or(len, |i| i.min(len)));
+                },
+            }
+        }
+
+        let buffers = source.into_iter().map(|b| Box::new(b) as Box<_>).collect();
+        let unbounded = u32::max_value() as usize;
+        (buffers, vertices.unwrap_or(unbounded), instances.unwrap_or(unbounded))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use buffer::BufferAccess;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use format::Format;
+    use pipeline::shader::EmptyShaderInterfaceDef;
+    use pipeline::vertex::BuffersDefinition;
+    use pipeline::vertex::IncompatibleVertexDefinitionError;
+    use pipeline::vertex::InputRate;
+    use pipeline::vertex::VertexDefinition;
+    use pipeline::vertex::VertexSource;
+    use pipeline::shader::ShaderInterfaceDef;
+    use pipeline::shader::ShaderInterfaceDefEntry;
+    use std::borrow::Cow;
+    use std::sync::Arc;
+    use std::vec::IntoIter as VecIntoIter;
+
+    #[derive(Default, Copy, Clone)]
+    struct Position {
+        position: [f32; 3],
+    }
+    ::impl_vertex!(Position, position);
+
+    #[derive(Default, Copy, Clone)]
+    #[repr(C)]
+    struct Instance {
+        offset: [f32; 2],
+        color: [f32; 4],
+    }
+    ::impl_vertex!(Instance, offset, color);
+
+    struct Interface;
+    unsafe impl ShaderInterfaceDef for Interface {
+        type Iter = VecIntoIter<ShaderInterfaceDefEntry>;
+
+        fn elements(&self) -> Self::Iter {
+            vec![
+                ShaderInterfaceDefEntry {
+                    location: 0 .. 1,
+                    format: Format::R32G32B32Sfloat,
+                    name: Some(Cow::Borrowed("position")),
+                },
+                ShaderInterfaceDefEntry {
+                    location: 1 .. 2,
+                    format: Format::R32G32B32A32Sfloat,
+                    name: Some(Cow::Borrowed("color")),
+                },
+            ].into_iter()
+        }
+    }
+
+    #[test]
+    fn bindings() {
+        let definition = BuffersDefinition::new()
+            .vertex::<Position>()
+            .instance_with_divisor::<Instance>(2);
+
+        let (buffers, attribs) = definition.definition(&Interface).unwrap();
+        let buffers = buffers.collect::<Vec<_>>();
+        assert_eq!(buffers,
+                   vec![(0, 12, InputRate::Vertex), (1, 24, InputRate::Instance { divisor: 2 })]);
+
+        let attribs = attribs.map(|(loc, binding, info)| (loc, binding, info.offset, info.format))
+            .collect::<Vec<_>>();
+        assert_eq!(attribs,
+                   vec![(0, 0, 0, Format::R32G32B32Sfloat), (1, 1, 8, Format::R32G32B32A32Sfloat)]);
+
+        assert!(VertexDefinition::<EmptyShaderInterfaceDef>::definition(&definition,
+                                                                       &EmptyShaderInterfaceDef)
+                    .is_ok());
+    }
+
+    #[test]
+    fn missing_attribute() {
+        let definition = BuffersDefinition::new().vertex::<Position>();
+
+        match definition.definition(&Interface) {
+            Err(IncompatibleVertexDefinitionError::MissingAttribute { ref attribute })
+                if attribute == "color" => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn stride_too_small() {
+        assert_should_panic!("smaller than the size of its elements", {
+            let _ = BuffersDefinition::new().binding::<Instance>(16, InputRate::Vertex);
+        });
+
+        let _ = BuffersDefinition::new().binding::<Instance>(0, InputRate::Vertex);
+    }
+
+    #[test]
+    fn decode_only_instance_bindings() {
+        let (device, _) = gfx_dev_and_queue!();
+        let definition = BuffersDefinition::new().instance::<Instance>();
+
+        let instances = CpuAccessibleBuffer::from_iter(device,
+                                                       BufferUsage::vertex_buffer(),
+                                                       vec![Instance::default(); 4].into_iter())
+            .unwrap();
+        let source = vec![instances as Arc<dyn BufferAccess + Send + Sync>];
+
+        let (_, vertices, instances) = definition.decode(source);
+        assert_eq!(vertices, u32::max_value() as usize);
+        assert_eq!(instances, 4);
+    }
+
+    #[test]
+    fn decode_zero_divisor() {
+        let (device, _) = gfx_dev_and_queue!();
+        let definition = BuffersDefinition::new()
+            .vertex::<Position>()
+            .instance_with_divisor::<Instance>(0);
+
+        let positions = CpuAccessibleBuffer::from_iter(device.clone(),
+                                                       BufferUsage::vertex_buffer(),
+                                                       vec![Position::default(); 3].into_iter())
+            .unwrap();
+        let instances = CpuAccessibleBuffer::from_iter(device,
+                                                       BufferUsage::vertex_buffer(),
+                                                       vec![Instance::default(); 1].into_iter())
+            .unwrap();
+        let source = vec![positions as Arc<dyn BufferAccess + Send + Sync>,
+                          instances as Arc<dyn BufferAccess + Send + Sync>];
+
+        let (_, vertices, instances) = definition.decode(source);
+        assert_eq!(vertices, 3);
+        assert_eq!(instances, u32::max_value() as usize);
+    }
+}
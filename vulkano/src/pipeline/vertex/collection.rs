@@ -0,0 +1,75 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::mem;
+use std::sync::Arc;
+
+use buffer::BufferAccess;
+use buffer::TypedBufferAccess;
+
+/// A collection of vertex buffers that can be bound with
+/// `AutoCommandBufferBuilder::bind_vertex_buffers`.
+///
+/// Implemented on typed buffers, on tuples of typed buffers, and on a `Vec` of type-erased
+/// buffers. The size of the elements of a typed buffer is checked against the stride of the
+/// corresponding binding of the pipeline when drawing, while type-erased buffers are only checked
+/// for their number.
+pub unsafe trait VertexBuffersCollection {
+    /// Turns the collection into a list of buffers. Each buffer comes with the size in bytes of
+    /// its elements, or `None` if it is unknown.
+    fn into_vec(self) -> Vec<(Arc<dyn BufferAccess + Send + Sync>, Option<usize>)>;
+}
+
+unsafe impl VertexBuffersCollection for Vec<Arc<dyn BufferAccess + Send + Sync>> {
+    #[inline]
+    fn into_vec(self) -> Vec<(Arc<dyn BufferAccess + Send + Sync>, Option<usize>)> {
+        self.into_iter().map(|buffer| (buffer, None)).collect()
+    }
+}
+
+unsafe impl<B, T> VertexBuffersCollection for B
+    where B: BufferAccess + TypedBufferAccess<Content = [T]> + Send + Sync + 'static
+{
+    #[inline]
+    fn into_vec(self) -> Vec<(Arc<dyn BufferAccess + Send + Sync>, Option<usize>)> {
+        vec![(Arc::new(self) as Arc<_>, Some(mem::size_of::<T>()))]
+    }
+}
+
+macro_rules! impl_collection {
+    ($first:ident: $first_ty:ident $(, $others:ident: $others_ty:ident)+) => (
+        unsafe impl<$first, $first_ty $(, $others, $others_ty)+> VertexBuffersCollection
+            for ($first, $($others),+)
+            where $first: BufferAccess + TypedBufferAccess<Content = [$first_ty]> + Send + Sync
+                          + 'static
+                  $(, $others: BufferAccess + TypedBufferAccess<Content = [$others_ty]> + Send
+                               + Sync + 'static)+
+        {
+            #[inline]
+            fn into_vec(self) -> Vec<(Arc<dyn BufferAccess + Send + Sync>, Option<usize>)> {
+                #![allow(non_snake_case)]
+
+                let ($first, $($others,)+) = self;
+
+                let mut list = Vec::new();
+                list.push((Arc::new($first) as Arc<_>, Some(mem::size_of::<$first_ty>())));
+                $(
+                    list.push((Arc::new($others) as Arc<_>, Some(mem::size_of::<$others_ty>())));
+                )+
+                list
+            }
+        }
+
+        impl_collection!($($others: $others_ty),+);
+    );
+
+    ($i:ident: $ty:ident) => ();
+}
+
+impl_collection!(A: Ta, B: Tb, C: Tc, D: Td, E: Te, F: Tf, G: Tg, H: Th);
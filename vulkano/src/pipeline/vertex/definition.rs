@@ -48,13 +48,28 @@ unsafe impl<I, T> VertexDefinition<I> for T
 }
 
 /// How the vertex source should be unrolled.
-#[derive(Copy, Clone, Debug)]
-#[repr(u32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum InputRate {
     /// Each element of the source corresponds to a vertex.
-    Vertex = vk::VERTEX_INPUT_RATE_VERTEX,
-    /// Each element of the source corresponds to an instance.
-    Instance = vk::VERTEX_INPUT_RATE_INSTANCE,
+    Vertex,
+    /// Each element of the source corresponds to `divisor` consecutive instances.
+    ///
+    /// A divisor different from 1 requires the `VK_EXT_vertex_attribute_divisor` extension. A
+    /// divisor of 0 means that all the instances use the first element.
+    Instance {
+        /// Number of consecutive instances that use the same element.
+        divisor: u32,
+    },
+}
+
+impl From<InputRate> for vk::VertexInputRate {
+    #[inline]
+    fn from(val: InputRate) -> vk::VertexInputRate {
+        match val {
+            InputRate::Vertex => vk::VERTEX_INPUT_RATE_VERTEX,
+            InputRate::Instance { .. } => vk::VERTEX_INPUT_RATE_INSTANCE,
+        }
+    }
 }
 
 /// Information about a single attribute within a vertex.
@@ -111,6 +126,9 @@ impl fmt::Display for IncompatibleVertexDefinitionError {
 /// vertex definition.
 pub unsafe trait VertexSource<L> {
     /// Checks and returns the list of buffers with offsets, number of vertices and number of instances.
+    ///
+    /// If no buffer limits the number of vertices or instances, `u32::max_value()` can be
+    /// returned for it.
     // TODO: return error if problem
     // TODO: better than a Vec
     // TODO: return a struct instead
@@ -7,6 +7,8 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
+use half::f16;
+
 use pipeline::vertex::VertexMemberTy;
 
 /// Implements the `Vertex` trait on a struct.
@@ -29,7 +31,6 @@ macro_rules! impl_vertex {
         unsafe impl $crate::pipeline::vertex::Vertex for $out {
             #[inline(always)]
             fn member(name: &str) -> Option<$crate::pipeline::vertex::VertexMemberInfo> {
-                use std::ptr;
                 #[allow(unused_imports)]
                 use $crate::format::Format;
                 use $crate::pipeline::vertex::VertexMemberInfo;
@@ -49,6 +50,7 @@ macro_rules! impl_vertex {
                             offset: member_ptr as usize - dummy_ptr as usize,
                             ty: ty,
                             array_size: array_size,
+                            format: None,
                         });
                     }
                 )*
@@ -107,6 +109,27 @@ unsafe impl VertexMember for u32 {
     }
 }
 
+unsafe impl VertexMember for i64 {
+    #[inline]
+    fn format() -> (VertexMemberTy, usize) {
+        (VertexMemberTy::I64, 1)
+    }
+}
+
+unsafe impl VertexMember for u64 {
+    #[inline]
+    fn format() -> (VertexMemberTy, usize) {
+        (VertexMemberTy::U64, 1)
+    }
+}
+
+unsafe impl VertexMember for f16 {
+    #[inline]
+    fn format() -> (VertexMemberTy, usize) {
+        (VertexMemberTy::F16, 1)
+    }
+}
+
 unsafe impl VertexMember for f32 {
     #[inline]
     fn format() -> (VertexMemberTy, usize) {
@@ -59,17 +59,17 @@ where
                     }
                 };
 
-                if !infos.ty.matches(
-                    infos.array_size,
-                    e.format,
-                    e.location.end - e.location.start,
-                ) {
-                    return Err(IncompatibleVertexDefinitionError::FormatMismatch {
-                        attribute: name.clone().into_owned(),
-                        shader: (e.format, (e.location.end - e.location.start) as usize),
-                        definition: (infos.ty, infos.array_size),
-                    });
-                }
+                let format = match infos.attribute_format(e.format,
+                                                          e.location.end - e.location.start) {
+                    Some(format) => format,
+                    None => {
+                        return Err(IncompatibleVertexDefinitionError::FormatMismatch {
+                            attribute: name.clone().into_owned(),
+                            shader: (e.format, (e.location.end - e.location.start) as usize),
+                            definition: (infos.ty, infos.array_size),
+                        });
+                    },
+                };
 
                 let mut offset = infos.offset;
                 for loc in e.location.clone() {
@@ -78,16 +78,16 @@ where
                         0,
                         AttributeInfo {
                             offset: offset,
-                            format: e.format,
+                            format: format,
                         },
                     ));
-                    offset += e.format.size().unwrap();
+                    offset += format.size().unwrap();
                 }
             }
             attribs
         }.into_iter(); // TODO: meh
 
-        let buffers = Some((0, mem::size_of::<T>(), InputRate::Instance)).into_iter();
+        let buffers = Some((0, mem::size_of::<T>(), InputRate::Instance { divisor: 1 })).into_iter();
         Ok((buffers, attrib))
     }
 }
@@ -19,7 +19,9 @@
 //!
 //! The vulkano library provides some structs that already implement these traits.
 //! The most common situation is a single vertex buffer and no instancing, in which case you can
-//! pass a `SingleBufferDefinition` when you create the pipeline.
+//! pass a `SingleBufferDefinition` when you create the pipeline. For any other combination of
+//! vertex and instance buffers, for example separate position and normal buffers, you can build
+//! a `BuffersDefinition`.
 //!
 //! # Implementing `Vertex`
 //!
@@ -28,7 +30,9 @@
 //! implements the `Vertex` trait.
 //!
 //! The `Vertex` trait is unsafe, but can be implemented on a struct with the `impl_vertex!`
-//! macro.
+//! macro, or with `#[derive(Vertex)]` from the `vulkano-shaders` crate. The derive also supports
+//! generic structs, renaming fields and reading fields with a different format than the shader
+//! input, for example a normalized `[u8; 4]` color.
 //!
 //! # Example
 //!
@@ -65,6 +69,8 @@
 
 pub use self::bufferless::BufferlessDefinition;
 pub use self::bufferless::BufferlessVertices;
+pub use self::buffers::BuffersDefinition;
+pub use self::collection::VertexBuffersCollection;
 pub use self::definition::AttributeInfo;
 pub use self::definition::IncompatibleVertexDefinitionError;
 pub use self::definition::InputRate;
@@ -80,6 +86,8 @@ pub use self::vertex::VertexMemberInfo;
 pub use self::vertex::VertexMemberTy;
 
 mod bufferless;
+mod buffers;
+mod collection;
 mod definition;
 mod impl_vertex;
 mod instance_buffer;
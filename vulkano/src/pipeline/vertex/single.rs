@@ -55,16 +55,17 @@ unsafe impl<T, I> VertexDefinition<I> for SingleBufferDefinition<T>
                                        }),
                 };
 
-                if !infos.ty.matches(infos.array_size,
-                                     e.format,
-                                     e.location.end - e.location.start)
-                {
-                    return Err(IncompatibleVertexDefinitionError::FormatMismatch {
-                                   attribute: name.clone().into_owned(),
-                                   shader: (e.format, (e.location.end - e.location.start) as usize),
-                                   definition: (infos.ty, infos.array_size),
-                               });
-                }
+                let format = match infos.attribute_format(e.format,
+                                                          e.location.end - e.location.start) {
+                    Some(format) => format,
+                    None => {
+                        return Err(IncompatibleVertexDefinitionError::FormatMismatch {
+                                       attribute: name.clone().into_owned(),
+                                       shader: (e.format, (e.location.end - e.location.start) as usize),
+                                       definition: (infos.ty, infos.array_size),
+                                   });
+                    },
+                };
 
                 let mut offset = infos.offset;
                 for loc in e.location.clone() {
@@ -72,9 +73,9 @@ unsafe impl<T, I> VertexDefinition<I> for SingleBufferDefinition<T>
                                   0,
                                   AttributeInfo {
                                       offset: offset,
-                                      format: e.format,
+                                      format: format,
                                   }));
-                    offset += e.format.size().unwrap();
+                    offset += format.size().unwrap();
                 }
             }
             attribs
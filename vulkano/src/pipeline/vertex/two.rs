@@ -23,7 +23,8 @@ use pipeline::vertex::VertexDefinition;
 use pipeline::vertex::VertexSource;
 
 /// Unstable.
-// TODO: shouldn't be just `Two` but `Multi`
+///
+/// See `BuffersDefinition` for any number of buffers.
 pub struct TwoBuffersDefinition<T, U>(pub PhantomData<(T, U)>);
 
 impl<T, U> TwoBuffersDefinition<T, U> {
@@ -59,16 +60,17 @@ unsafe impl<T, U, I> VertexDefinition<I> for TwoBuffersDefinition<T, U>
                                });
                 };
 
-                if !infos.ty.matches(infos.array_size,
-                                     e.format,
-                                     e.location.end - e.location.start)
-                {
-                    return Err(IncompatibleVertexDefinitionError::FormatMismatch {
-                                   attribute: name.clone().into_owned(),
-                                   shader: (e.format, (e.location.end - e.location.start) as usize),
-                                   definition: (infos.ty, infos.array_size),
-                               });
-                }
+                let format = match infos.attribute_format(e.format,
+                                                          e.location.end - e.location.start) {
+                    Some(format) => format,
+                    None => {
+                        return Err(IncompatibleVertexDefinitionError::FormatMismatch {
+                                       attribute: name.clone().into_owned(),
+                                       shader: (e.format, (e.location.end - e.location.start) as usize),
+                                       definition: (infos.ty, infos.array_size),
+                                   });
+                    },
+                };
 
                 let mut offset = infos.offset;
                 for loc in e.location.clone() {
@@ -76,9 +78,9 @@ unsafe impl<T, U, I> VertexDefinition<I> for TwoBuffersDefinition<T, U>
                                   buf_offset,
                                   AttributeInfo {
                                       offset: offset,
-                                      format: e.format,
+                                      format: format,
                                   }));
-                    offset += e.format.size().unwrap();
+                    offset += format.size().unwrap();
                 }
             }
             attribs
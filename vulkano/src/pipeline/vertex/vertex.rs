@@ -8,6 +8,7 @@
 // according to those terms.
 
 use format::Format;
+use format::FormatTy;
 
 /// Describes an individual `Vertex`. In other words a collection of attributes that can be read
 /// from a vertex shader.
@@ -34,6 +35,42 @@ pub struct VertexMemberInfo {
     pub ty: VertexMemberTy,
     /// Number of consecutive elements of that type.
     pub array_size: usize,
+    /// Format with which the member is read, if it differs from the format of the shader input.
+    ///
+    /// For example a `vec4` input can be read from a `[u8; 4]` member with the `R8G8B8A8Unorm`
+    /// format. If `None`, the format of the shader input is used and `ty` must match it.
+    pub format: Option<Format>,
+}
+
+impl VertexMemberInfo {
+    /// Returns the format with which the member must be read to feed a shader input of format
+    /// `shader_format` that spans `num_locs` locations, or `None` if they don't match.
+    pub fn attribute_format(&self, shader_format: Format, num_locs: u32) -> Option<Format> {
+        let format = match self.format {
+            Some(format) => format,
+            None => {
+                return if self.ty.matches(self.array_size, shader_format, num_locs) {
+                    Some(shader_format)
+                } else {
+                    None
+                };
+            },
+        };
+
+        let format_size = format.size()?;
+        if format_size * num_locs as usize != self.ty.size() * self.array_size {
+            return None;
+        }
+
+        // Integer inputs must be read from integer formats, and float inputs from float,
+        // normalized or scaled formats.
+        match (format.ty(), shader_format.ty()) {
+            (FormatTy::Float, FormatTy::Float) |
+            (FormatTy::Uint, FormatTy::Uint) |
+            (FormatTy::Sint, FormatTy::Sint) => Some(format),
+            _ => None,
+        }
+    }
 }
 
 /// Type of a member of a vertex struct.
@@ -46,25 +83,37 @@ pub enum VertexMemberTy {
     U16,
     I32,
     U32,
+    I64,
+    U64,
+    F16,
     F32,
     F64,
 }
 
 impl VertexMemberTy {
-    /// Returns true if a combination of `(type, array_size)` matches a format.
+    /// Returns the size in bytes of one element of this type.
     #[inline]
-    pub fn matches(&self, array_size: usize, format: Format, num_locs: u32) -> bool {
-        // TODO: implement correctly
-        let my_size = match *self {
+    pub fn size(&self) -> usize {
+        match *self {
             VertexMemberTy::I8 => 1,
             VertexMemberTy::U8 => 1,
             VertexMemberTy::I16 => 2,
             VertexMemberTy::U16 => 2,
             VertexMemberTy::I32 => 4,
             VertexMemberTy::U32 => 4,
+            VertexMemberTy::I64 => 8,
+            VertexMemberTy::U64 => 8,
+            VertexMemberTy::F16 => 2,
             VertexMemberTy::F32 => 4,
             VertexMemberTy::F64 => 8,
-        };
+        }
+    }
+
+    /// Returns true if a combination of `(type, array_size)` matches a format.
+    #[inline]
+    pub fn matches(&self, array_size: usize, format: Format, num_locs: u32) -> bool {
+        // TODO: implement correctly
+        let my_size = self.size();
 
         let format_size = match format.size() {
             None => return false,
@@ -74,3 +123,47 @@ impl VertexMemberTy {
         array_size * my_size == format_size * num_locs as usize
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use format::Format;
+    use pipeline::vertex::VertexMemberInfo;
+    use pipeline::vertex::VertexMemberTy;
+
+    #[test]
+    fn attribute_format() {
+        let info = VertexMemberInfo {
+            offset: 0,
+            ty: VertexMemberTy::F32,
+            array_size: 4,
+            format: None,
+        };
+        assert_eq!(info.attribute_format(Format::R32G32B32A32Sfloat, 1),
+                   Some(Format::R32G32B32A32Sfloat));
+        assert_eq!(info.attribute_format(Format::R32G32Sfloat, 1), None);
+
+        let info = VertexMemberInfo {
+            offset: 0,
+            ty: VertexMemberTy::U8,
+            array_size: 4,
+            format: Some(Format::R8G8B8A8Unorm),
+        };
+        assert_eq!(info.attribute_format(Format::R32G32B32A32Sfloat, 1),
+                   Some(Format::R8G8B8A8Unorm));
+        assert_eq!(info.attribute_format(Format::R32G32B32A32Uint, 1), None);
+        assert_eq!(info.attribute_format(Format::R32G32B32A32Sfloat, 2), None);
+    }
+
+    #[test]
+    fn member_types() {
+        use half::f16;
+        use pipeline::vertex::VertexMember;
+
+        let (ty, array_size) = <[f16; 2] as VertexMember>::format();
+        assert!(ty.matches(array_size, Format::R16G16Sfloat, 1));
+
+        let (ty, array_size) = <u64 as VertexMember>::format();
+        assert!(ty.matches(array_size, Format::R64Uint, 1));
+        assert!(!ty.matches(array_size, Format::R32Uint, 1));
+    }
+}
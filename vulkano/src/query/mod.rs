@@ -12,18 +12,29 @@
 //! In Vulkan, queries are not created individually. Instead you manipulate **query pools**, which
 //! represent a collection of queries. Whenever you use a query, you have to specify both the query
 //! pool and the slot id within that query pool.
+//!
+//! The `QueryPool` type is the safe way to create a query pool. The queries are written by
+//! commands of a command buffer (see for example `AutoCommandBufferBuilder::begin_query`), and
+//! their results can then be read from the host with the methods of `QueryPool`.
 
 use std::error;
 use std::fmt;
+use std::mem;
 use std::mem::MaybeUninit;
+use std::ops::Range;
 use std::ptr;
 use std::sync::Arc;
 
 use device::Device;
 use device::DeviceOwned;
+use instance::QueueFamily;
+
+pub mod profiler;
 
 use Error;
 use OomError;
+use SafeDeref;
+use Success;
 use VulkanObject;
 use check_errors;
 use vk;
@@ -31,6 +42,7 @@ use vk;
 pub struct UnsafeQueryPool {
     pool: vk::QueryPool,
     device: Arc<Device>,
+    ty: QueryType,
     num_slots: u32,
 }
 
@@ -38,15 +50,14 @@ impl UnsafeQueryPool {
     /// Builds a new query pool.
     pub fn new(device: Arc<Device>, ty: QueryType, num_slots: u32)
                -> Result<UnsafeQueryPool, QueryPoolCreationError> {
-        let (vk_ty, statistics) = match ty {
-            QueryType::Occlusion => (vk::QUERY_TYPE_OCCLUSION, 0),
-            QueryType::Timestamp => (vk::QUERY_TYPE_TIMESTAMP, 0),
+        let statistics = match ty {
+            QueryType::Occlusion | QueryType::Timestamp => 0,
             QueryType::PipelineStatistics(flags) => {
                 if !device.enabled_features().pipeline_statistics_query {
                     return Err(QueryPoolCreationError::PipelineStatisticsQueryFeatureNotEnabled);
                 }
 
-                (vk::QUERY_TYPE_PIPELINE_STATISTICS, flags.into())
+                flags.into()
             },
         };
 
@@ -55,7 +66,7 @@ impl UnsafeQueryPool {
                 sType: vk::STRUCTURE_TYPE_QUERY_POOL_CREATE_INFO,
                 pNext: ptr::null(),
                 flags: 0, // reserved
-                queryType: vk_ty,
+                queryType: ty.into_vulkan(),
                 queryCount: num_slots,
                 pipelineStatistics: statistics,
             };
@@ -72,10 +83,17 @@ impl UnsafeQueryPool {
         Ok(UnsafeQueryPool {
                pool: pool,
                device: device,
+               ty: ty,
                num_slots: num_slots,
            })
     }
 
+    /// Returns the type of queries of that query pool.
+    #[inline]
+    pub fn ty(&self) -> QueryType {
+        self.ty
+    }
+
     /// Returns the number of slots of that query pool.
     #[inline]
     pub fn num_slots(&self) -> u32 {
@@ -99,7 +117,7 @@ impl UnsafeQueryPool {
     pub fn queries_range(&self, first_index: u32, count: u32) -> Option<UnsafeQueriesRange> {
         assert!(count >= 1);
 
-        if first_index + count < self.num_slots() {
+        if first_index.checked_add(count).map_or(false, |end| end <= self.num_slots()) {
             Some(UnsafeQueriesRange {
                      pool: self,
                      first: first_index,
@@ -129,6 +147,32 @@ unsafe impl DeviceOwned for UnsafeQueryPool {
     }
 }
 
+/// Trait for objects that contain a Vulkan query pool.
+///
+/// This trait is implemented on `UnsafeQueryPool` and on the higher-level query pool types, and
+/// is what the command buffer builders accept when recording query commands.
+pub unsafe trait QueryPoolAbstract: DeviceOwned {
+    /// Returns the inner `UnsafeQueryPool`.
+    fn inner(&self) -> &UnsafeQueryPool;
+}
+
+unsafe impl QueryPoolAbstract for UnsafeQueryPool {
+    #[inline]
+    fn inner(&self) -> &UnsafeQueryPool {
+        self
+    }
+}
+
+unsafe impl<T> QueryPoolAbstract for T
+    where T: SafeDeref,
+          T::Target: QueryPoolAbstract
+{
+    #[inline]
+    fn inner(&self) -> &UnsafeQueryPool {
+        (**self).inner()
+    }
+}
+
 pub struct UnsafeQuery<'a> {
     pool: &'a UnsafeQueryPool,
     index: u32,
@@ -169,14 +213,35 @@ impl<'a> UnsafeQueriesRange<'a> {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum QueryType {
     Occlusion,
     PipelineStatistics(QueryPipelineStatisticFlags),
     Timestamp,
 }
 
-#[derive(Debug, Copy, Clone)]
+impl QueryType {
+    /// Returns the number of values that a single query of this type produces when its results
+    /// are retrieved, not counting the availability value.
+    #[inline]
+    pub fn result_size(&self) -> usize {
+        match *self {
+            QueryType::Occlusion | QueryType::Timestamp => 1,
+            QueryType::PipelineStatistics(flags) => flags.count(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn into_vulkan(self) -> vk::QueryType {
+        match self {
+            QueryType::Occlusion => vk::QUERY_TYPE_OCCLUSION,
+            QueryType::PipelineStatistics(_) => vk::QUERY_TYPE_PIPELINE_STATISTICS,
+            QueryType::Timestamp => vk::QUERY_TYPE_TIMESTAMP,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct QueryPipelineStatisticFlags {
     pub input_assembly_vertices: bool,
     pub input_assembly_primitives: bool,
@@ -208,6 +273,13 @@ impl QueryPipelineStatisticFlags {
             compute_shader_invocations: false,
         }
     }
+
+    /// Returns the number of statistics that are enabled.
+    #[inline]
+    pub fn count(&self) -> usize {
+        let vk: vk::QueryPipelineStatisticFlags = (*self).into();
+        vk.count_ones() as usize
+    }
 }
 
 impl Into<vk::QueryPipelineStatisticFlags> for QueryPipelineStatisticFlags {
@@ -250,6 +322,64 @@ impl Into<vk::QueryPipelineStatisticFlags> for QueryPipelineStatisticFlags {
     }
 }
 
+/// Flags that control how the results of queries are retrieved.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct QueryResultFlags {
+    /// Wait for the results of all the queries to become available before returning.
+    ///
+    /// > **Warning**: A query only becomes available once a command buffer that ends it has
+    /// > finished executing. Waiting on a query that was never submitted, or that was reset
+    /// > without being ended again, blocks forever.
+    pub wait: bool,
+    /// Write an additional value after the results of each query, which is non-zero if the
+    /// results of that query were available.
+    pub with_availability: bool,
+    /// Allow returning partial results for queries that are not available yet.
+    pub partial: bool,
+}
+
+impl QueryResultFlags {
+    /// Builds a `QueryResultFlags` struct with none of the flags set.
+    #[inline]
+    pub fn none() -> QueryResultFlags {
+        QueryResultFlags {
+            wait: false,
+            with_availability: false,
+            partial: false,
+        }
+    }
+}
+
+impl Into<vk::QueryResultFlags> for QueryResultFlags {
+    fn into(self) -> vk::QueryResultFlags {
+        let mut result = 0;
+        if self.wait {
+            result |= vk::QUERY_RESULT_WAIT_BIT;
+        }
+        if self.with_availability {
+            result |= vk::QUERY_RESULT_WITH_AVAILABILITY_BIT;
+        }
+        if self.partial {
+            result |= vk::QUERY_RESULT_PARTIAL_BIT;
+        }
+        result
+    }
+}
+
+/// Type that the results of a query can be written as. Implemented on `u32` and `u64`.
+pub unsafe trait QueryResultElement: Copy + Send + Sync + 'static {
+    /// Extra flag to pass to Vulkan to select the width of the results.
+    const FLAG: vk::QueryResultFlags;
+}
+
+unsafe impl QueryResultElement for u32 {
+    const FLAG: vk::QueryResultFlags = 0;
+}
+
+unsafe impl QueryResultElement for u64 {
+    const FLAG: vk::QueryResultFlags = vk::QUERY_RESULT_64_BIT;
+}
+
 impl Drop for UnsafeQueryPool {
     #[inline]
     fn drop(&mut self) {
@@ -315,6 +445,366 @@ impl From<Error> for QueryPoolCreationError {
     }
 }
 
+/// A query pool whose results can be retrieved safely from the host.
+pub struct QueryPool {
+    inner: UnsafeQueryPool,
+}
+
+impl QueryPool {
+    /// Builds a new query pool.
+    #[inline]
+    pub fn new(device: Arc<Device>, ty: QueryType, num_slots: u32)
+               -> Result<Arc<QueryPool>, QueryPoolCreationError> {
+        Ok(Arc::new(QueryPool { inner: UnsafeQueryPool::new(device, ty, num_slots)? }))
+    }
+
+    /// Returns the type of queries of that query pool.
+    #[inline]
+    pub fn ty(&self) -> QueryType {
+        self.inner.ty()
+    }
+
+    /// Returns the number of slots of that query pool.
+    #[inline]
+    pub fn num_slots(&self) -> u32 {
+        self.inner.num_slots()
+    }
+
+    /// Copies the raw results of a range of queries to `destination`.
+    ///
+    /// The results of each query are written one after the other. A query produces
+    /// `ty().result_size()` values, plus one availability value if `flags.with_availability` is
+    /// true. The width of the values is chosen by the type of the elements of `destination`.
+    ///
+    /// Returns `true` if the results of all the queries were available. If it returns `false`,
+    /// then the values of the unavailable queries are left untouched, unless `flags.partial` is
+    /// true in which case intermediate results are written instead.
+    ///
+    /// > **Warning**: If `flags.wait` is true, this function blocks until the queries are
+    /// > available. It never returns if one of the queries is not going to be written by a
+    /// > command buffer that has been submitted.
+    pub fn get_results<T>(&self, queries: Range<u32>, destination: &mut [T],
+                          flags: QueryResultFlags)
+                          -> Result<bool, GetResultsError>
+        where T: QueryResultElement
+    {
+        if queries.start >= queries.end ||
+            self.inner
+                .queries_range(queries.start, queries.end - queries.start)
+                .is_none()
+        {
+            return Err(GetResultsError::OutOfRange);
+        }
+
+        if flags.partial && self.ty() == QueryType::Timestamp {
+            return Err(GetResultsError::InvalidFlags);
+        }
+
+        let per_query_len = self.ty().result_size() + flags.with_availability as usize;
+        let required_len = per_query_len * (queries.end - queries.start) as usize;
+        if destination.len() < required_len {
+            return Err(GetResultsError::BufferTooSmall {
+                           required_len,
+                           actual_len: destination.len(),
+                       });
+        }
+
+        let result = unsafe {
+            let vk = self.inner.device.pointers();
+            check_errors(vk.GetQueryPoolResults(self.inner.device.internal_object(),
+                                                self.inner.internal_object(),
+                                                queries.start,
+                                                queries.end - queries.start,
+                                                required_len * mem::size_of::<T>(),
+                                                destination.as_mut_ptr() as *mut _,
+                                                (per_query_len * mem::size_of::<T>()) as
+                                                    vk::DeviceSize,
+                                                Into::<vk::QueryResultFlags>::into(flags) |
+                                                    T::FLAG))?
+        };
+
+        Ok(match result {
+               Success::Success => true,
+               Success::NotReady => false,
+               _ => return Err(GetResultsError::UnexpectedResult),
+           })
+    }
+
+    /// Returns the number of samples that passed the depth and stencil tests for each query of
+    /// an occlusion query pool.
+    ///
+    /// If `wait` is true, waits for all the results to be available. Otherwise the results of
+    /// the queries that are not available yet are `None`. Only pass `true` if all the queries
+    /// are going to be written by a command buffer that has been submitted, otherwise this
+    /// function never returns.
+    pub fn occlusion_results(&self, queries: Range<u32>, wait: bool)
+                             -> Result<Vec<Option<u64>>, GetResultsError> {
+        if self.ty() != QueryType::Occlusion {
+            return Err(GetResultsError::WrongQueryType);
+        }
+
+        Ok(self.results_with_availability(queries, wait)?
+               .into_iter()
+               .map(|r| r.map(|values| values[0]))
+               .collect())
+    }
+
+    /// Returns the statistics written by each query of a pipeline statistics query pool.
+    ///
+    /// If `wait` is true, waits for all the results to be available. Otherwise the results of
+    /// the queries that are not available yet are `None`. Only pass `true` if all the queries
+    /// are going to be written by a command buffer that has been submitted, otherwise this
+    /// function never returns.
+    pub fn pipeline_statistics_results(&self, queries: Range<u32>, wait: bool)
+                                       -> Result<Vec<Option<PipelineStatistics>>,
+                                                 GetResultsError> {
+        let flags = match self.ty() {
+            QueryType::PipelineStatistics(flags) => flags,
+            _ => return Err(GetResultsError::WrongQueryType),
+        };
+
+        Ok(self.results_with_availability(queries, wait)?
+               .into_iter()
+               .map(|r| r.map(|values| PipelineStatistics::from_values(flags, values)))
+               .collect())
+    }
+
+    /// Returns the timestamps written by each query of a timestamp query pool, converted to
+    /// nanoseconds using the `timestamp_period` limit of the physical device.
+    ///
+    /// `queue_family` must be the queue family of the queue that executed the commands that
+    /// wrote the timestamps. Only the bits of the results that are valid for this queue family
+    /// are kept.
+    ///
+    /// If `wait` is true, waits for all the results to be available. Otherwise the results of
+    /// the queries that are not available yet are `None`. Only pass `true` if all the queries
+    /// are going to be written by a command buffer that has been submitted, otherwise this
+    /// function never returns.
+    ///
+    /// > **Note**: Only the difference between two timestamps is meaningful.
+    pub fn timestamp_results(&self, queries: Range<u32>, queue_family: QueueFamily, wait: bool)
+                             -> Result<Vec<Option<u64>>, GetResultsError> {
+        if self.ty() != QueryType::Timestamp {
+            return Err(GetResultsError::WrongQueryType);
+        }
+
+        let mask = match queue_family.timestamp_valid_bits() {
+            Some(bits) => timestamp_mask(bits),
+            None => return Err(GetResultsError::TimestampsNotSupported),
+        };
+
+        let period = TimestampPeriod::new(self.inner
+                                              .device
+                                              .physical_device()
+                                              .limits()
+                                              .timestamp_period());
+
+        Ok(self.results_with_availability(queries, wait)?
+               .into_iter()
+               .map(|r| r.map(|values| period.to_nanoseconds(values[0] & mask)))
+               .collect())
+    }
+
+    // Retrieves the 64-bits results of a range of queries, together with their availability.
+    // Returns `None` for the queries whose results are not available.
+    fn results_with_availability(&self, queries: Range<u32>, wait: bool)
+                                 -> Result<Vec<Option<Vec<u64>>>, GetResultsError> {
+        let flags = QueryResultFlags {
+            wait,
+            with_availability: true,
+            partial: false,
+        };
+
+        let per_query_len = self.ty().result_size() + 1;
+        let num_queries = queries.end.saturating_sub(queries.start) as usize;
+        let mut data = vec![0u64; per_query_len * num_queries];
+        self.get_results(queries, &mut data, flags)?;
+
+        Ok(data.chunks(per_query_len)
+               .map(|chunk| {
+                        let (values, availability) = chunk.split_at(per_query_len - 1);
+                        if availability[0] != 0 {
+                            Some(values.to_vec())
+                        } else {
+                            None
+                        }
+                    })
+               .collect())
+    }
+}
+
+unsafe impl DeviceOwned for QueryPool {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.inner.device()
+    }
+}
+
+unsafe impl QueryPoolAbstract for QueryPool {
+    #[inline]
+    fn inner(&self) -> &UnsafeQueryPool {
+        &self.inner
+    }
+}
+
+// Returns the mask of the bits of a timestamp that are valid, given the `timestampValidBits` of
+// the queue family that wrote it.
+#[inline]
+pub(crate) fn timestamp_mask(valid_bits: u32) -> u64 {
+    if valid_bits >= 64 {
+        u64::MAX
+    } else {
+        (1 << valid_bits) - 1
+    }
+}
+
+// Number of nanoseconds per timestamp tick, stored as a 32.32 fixed-point number so that the
+// conversion of large timestamps doesn't lose precision the way a `f64` multiplication would.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct TimestampPeriod(u64);
+
+impl TimestampPeriod {
+    #[inline]
+    pub(crate) fn new(period: f32) -> TimestampPeriod {
+        TimestampPeriod((period as f64 * (1u64 << 32) as f64).round() as u64)
+    }
+
+    // Converts a number of ticks to nanoseconds, saturating at `u64::MAX`.
+    #[inline]
+    pub(crate) fn to_nanoseconds(&self, ticks: u64) -> u64 {
+        let ns = (ticks as u128 * self.0 as u128) >> 32;
+        if ns > u64::MAX as u128 {
+            u64::MAX
+        } else {
+            ns as u64
+        }
+    }
+}
+
+/// Results of a pipeline statistics query.
+///
+/// Each field is `None` if the corresponding statistic was not enabled when creating the pool.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PipelineStatistics {
+    pub input_assembly_vertices: Option<u64>,
+    pub input_assembly_primitives: Option<u64>,
+    pub vertex_shader_invocations: Option<u64>,
+    pub geometry_shader_invocations: Option<u64>,
+    pub geometry_shader_primitives: Option<u64>,
+    pub clipping_invocations: Option<u64>,
+    pub clipping_primitives: Option<u64>,
+    pub fragment_shader_invocations: Option<u64>,
+    pub tessellation_control_shader_patches: Option<u64>,
+    pub tessellation_evaluation_shader_invocations: Option<u64>,
+    pub compute_shader_invocations: Option<u64>,
+}
+
+impl PipelineStatistics {
+    // Builds the statistics from the values written by Vulkan. The values are written in the
+    // order of the bits of the flags, which is also the order of the fields.
+    fn from_values(flags: QueryPipelineStatisticFlags, values: Vec<u64>) -> PipelineStatistics {
+        let mut values = values.into_iter();
+        let mut next = |enabled: bool| if enabled { values.next() } else { None };
+
+        PipelineStatistics {
+            input_assembly_vertices: next(flags.input_assembly_vertices),
+            input_assembly_primitives: next(flags.input_assembly_primitives),
+            vertex_shader_invocations: next(flags.vertex_shader_invocations),
+            geometry_shader_invocations: next(flags.geometry_shader_invocations),
+            geometry_shader_primitives: next(flags.geometry_shader_primitives),
+            clipping_invocations: next(flags.clipping_invocations),
+            clipping_primitives: next(flags.clipping_primitives),
+            fragment_shader_invocations: next(flags.fragment_shader_invocations),
+            tessellation_control_shader_patches: next(flags.tessellation_control_shader_patches),
+            tessellation_evaluation_shader_invocations:
+                next(flags.tessellation_evaluation_shader_invocations),
+            compute_shader_invocations: next(flags.compute_shader_invocations),
+        }
+    }
+}
+
+/// Error that can happen when retrieving the results of queries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GetResultsError {
+    /// Not enough memory.
+    OomError(OomError),
+    /// The connection to the device has been lost.
+    DeviceLost,
+    /// The range of queries is empty or out of range of the query pool.
+    OutOfRange,
+    /// The `partial` flag was requested for a timestamp query pool.
+    InvalidFlags,
+    /// The destination is too small to hold the results.
+    BufferTooSmall {
+        /// Required number of elements in the destination.
+        required_len: usize,
+        /// Actual number of elements in the destination.
+        actual_len: usize,
+    },
+    /// The type of the query pool doesn't match the requested results.
+    WrongQueryType,
+    /// The queue family passed to `timestamp_results` doesn't support timestamps.
+    TimestampsNotSupported,
+    /// The implementation returned a result code that isn't allowed when getting the results of
+    /// queries.
+    UnexpectedResult,
+}
+
+impl error::Error for GetResultsError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            GetResultsError::OomError(_) => "not enough memory available",
+            GetResultsError::DeviceLost => "the connection to the device has been lost",
+            GetResultsError::OutOfRange => {
+                "the range of queries is empty or out of range of the query pool"
+            },
+            GetResultsError::InvalidFlags => {
+                "the partial flag was requested for a timestamp query pool"
+            },
+            GetResultsError::BufferTooSmall { .. } => {
+                "the destination is too small to hold the results"
+            },
+            GetResultsError::WrongQueryType => {
+                "the type of the query pool doesn't match the requested results"
+            },
+            GetResultsError::TimestampsNotSupported => {
+                "the queue family doesn't support timestamps"
+            },
+            GetResultsError::UnexpectedResult => {
+                "the implementation returned an unexpected result code"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            GetResultsError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GetResultsError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<Error> for GetResultsError {
+    #[inline]
+    fn from(err: Error) -> GetResultsError {
+        match err {
+            err @ Error::OutOfHostMemory => GetResultsError::OomError(OomError::from(err)),
+            err @ Error::OutOfDeviceMemory => GetResultsError::OomError(OomError::from(err)),
+            Error::DeviceLost => GetResultsError::DeviceLost,
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
 pub struct OcclusionQueriesPool {
     inner: UnsafeQueryPool,
 }
@@ -358,13 +848,43 @@ unsafe impl DeviceOwned for OcclusionQueriesPool {
     }
 }
 
+unsafe impl QueryPoolAbstract for OcclusionQueriesPool {
+    #[inline]
+    fn inner(&self) -> &UnsafeQueryPool {
+        &self.inner
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use query::OcclusionQueriesPool;
     use query::QueryPipelineStatisticFlags;
+    use query::GetResultsError;
+    use query::QueryPool;
     use query::QueryPoolCreationError;
+    use query::QueryResultFlags;
     use query::QueryType;
+    use query::TimestampPeriod;
     use query::UnsafeQueryPool;
+    use query::timestamp_mask;
+
+    #[test]
+    fn timestamp_period_exact() {
+        // 2^60 + 1 can't be represented exactly by a `f64`.
+        let ticks = (1u64 << 60) + 1;
+        assert_eq!(TimestampPeriod::new(1.0).to_nanoseconds(ticks), ticks);
+        assert_eq!(TimestampPeriod::new(0.5).to_nanoseconds(ticks), ticks / 2);
+        assert_eq!(TimestampPeriod::new(4.0).to_nanoseconds(ticks), ticks * 4);
+        assert_eq!(TimestampPeriod::new(83.333).to_nanoseconds(3), 249);
+        assert_eq!(TimestampPeriod::new(2.0).to_nanoseconds(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn timestamp_mask_bits() {
+        assert_eq!(timestamp_mask(64), u64::MAX);
+        assert_eq!(timestamp_mask(36), (1u64 << 36) - 1);
+        assert_eq!(timestamp_mask(1), 1);
+    }
 
     #[test]
     fn occlusion_create() {
@@ -382,4 +902,50 @@ mod tests {
             _ => panic!(),
         };
     }
+
+    #[test]
+    fn queries_range_bounds() {
+        let (device, _) = gfx_dev_and_queue!();
+        let pool = UnsafeQueryPool::new(device, QueryType::Occlusion, 8).unwrap();
+
+        assert!(pool.queries_range(0, 8).is_some());
+        assert!(pool.queries_range(7, 1).is_some());
+        assert!(pool.queries_range(7, 2).is_none());
+        assert!(pool.queries_range(u32::max_value(), 2).is_none());
+    }
+
+    #[test]
+    fn get_results_checks() {
+        let (device, _) = gfx_dev_and_queue!();
+        let pool = QueryPool::new(device, QueryType::Timestamp, 4).unwrap();
+
+        let mut data = [0u64; 4];
+        match pool.get_results(2 .. 6, &mut data, QueryResultFlags::none()) {
+            Err(GetResultsError::OutOfRange) => (),
+            _ => panic!(),
+        };
+
+        let flags = QueryResultFlags {
+            with_availability: true,
+            ..QueryResultFlags::none()
+        };
+        match pool.get_results(0 .. 4, &mut data, flags) {
+            Err(GetResultsError::BufferTooSmall { required_len: 8, actual_len: 4 }) => (),
+            _ => panic!(),
+        };
+
+        let flags = QueryResultFlags {
+            partial: true,
+            ..QueryResultFlags::none()
+        };
+        match pool.get_results(0 .. 4, &mut data, flags) {
+            Err(GetResultsError::InvalidFlags) => (),
+            _ => panic!(),
+        };
+
+        match pool.occlusion_results(0 .. 4, false) {
+            Err(GetResultsError::WrongQueryType) => (),
+            _ => panic!(),
+        };
+    }
 }
@@ -0,0 +1,689 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! GPU profiler that measures named scopes with timestamp queries.
+//!
+//! The `GpuProfiler` records a pair of timestamps around each scope of a frame, and reads them
+//! back once the future of the submission of that frame has been signalled. The profiler owns
+//! one query pool per frame in flight, so that recording a new frame doesn't overwrite the
+//! results of a frame that is still being executed.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use vulkano::command_buffer::AutoCommandBufferBuilder;
+//! # use vulkano::command_buffer::CommandBuffer;
+//! # use vulkano::device::Device;
+//! # use vulkano::device::Queue;
+//! use vulkano::query::profiler::GpuProfiler;
+//! use vulkano::sync::GpuFuture;
+//! # let device: Arc<Device> = return;
+//! # let queue: Arc<Queue> = return;
+//!
+//! let mut profiler = GpuProfiler::new(device.clone(), 2, 64).unwrap();
+//!
+//! let builder = AutoCommandBufferBuilder::primary(device.clone(), queue.family()).unwrap();
+//! let builder = profiler.begin_frame(builder).unwrap();
+//! let builder = profiler.begin_scope(builder, "shadows").unwrap();
+//! // ... record the commands of the scope ...
+//! let builder = profiler.end_scope(builder).unwrap();
+//! let builder = profiler.end_frame(builder).unwrap();
+//! let command_buffer = builder.build().unwrap();
+//!
+//! let future = command_buffer.execute(queue.clone()).unwrap()
+//!     .then_signal_fence_and_flush().unwrap();
+//!
+//! let frame = profiler.resolve_frame(&future).unwrap();
+//! println!("frame {} took {} ns", frame.frame, frame.root.duration_ns);
+//! ```
+
+use std::collections::VecDeque;
+use std::error;
+use std::fmt;
+use std::fmt::Write as FmtWrite;
+use std::io;
+use std::sync::Arc;
+
+use command_buffer::AutoCommandBufferBuilder;
+use command_buffer::DebugMarkerError;
+use command_buffer::ResetQueryPoolError;
+use command_buffer::WriteTimestampError;
+use device::Device;
+use device::DeviceOwned;
+use query::GetResultsError;
+use query::QueryPool;
+use query::QueryPoolCreationError;
+use query::QueryResultFlags;
+use query::QueryType;
+use query::TimestampPeriod;
+use query::timestamp_mask;
+use sync::FenceSignalFuture;
+use sync::FlushError;
+use sync::GpuFuture;
+use sync::PipelineStages;
+
+/// Records timestamps around named, nested scopes of command buffers, and turns them into a tree
+/// of GPU timings for each frame.
+pub struct GpuProfiler {
+    device: Arc<Device>,
+    // One slot per frame in flight.
+    slots: Vec<FrameSlot>,
+    // Index in `slots` of the slot of the current or last recorded frame.
+    current_slot: usize,
+    // Index of the next frame to be recorded.
+    next_frame: u64,
+    // True between `begin_frame` and `end_frame`.
+    recording: bool,
+    // Maximum number of scopes per frame, not counting the frame itself.
+    max_scopes: u32,
+    // Number of nanoseconds per timestamp tick.
+    timestamp_period: TimestampPeriod,
+    // True if scopes should also be emitted as debug markers.
+    debug_markers: bool,
+    // Indices in `slots` of the frames that were ended and not resolved yet, from the oldest to
+    // the newest.
+    pending: VecDeque<usize>,
+    // Number of frames that were not resolved before their slot was reused.
+    lost_frames: u64,
+}
+
+// State of one frame in flight.
+struct FrameSlot {
+    query_pool: Arc<QueryPool>,
+    // Index of the frame recorded in this slot, or `None` if there is nothing to resolve.
+    frame: Option<u64>,
+    // Scopes recorded in this slot. The first entry is the frame itself.
+    scopes: Vec<ScopeRecord>,
+    // Stack of the indices within `scopes` of the scopes that are currently open.
+    open_scopes: Vec<usize>,
+    // Mask of the valid bits of the timestamps, which depends on the queue family of the
+    // command buffer the frame was recorded in.
+    timestamp_mask: u64,
+}
+
+// A scope recorded in a command buffer.
+struct ScopeRecord {
+    name: String,
+    // Index within the scopes of the frame of the parent scope. `None` for the frame itself.
+    parent: Option<usize>,
+    // Query that holds the timestamp of the start of the scope. The end of the scope is written
+    // to the next query.
+    first_query: u32,
+}
+
+impl GpuProfiler {
+    /// Builds a new profiler.
+    ///
+    /// `frames_in_flight` is the number of frames that can be executed by the GPU at the same
+    /// time, and `max_scopes_per_frame` is the maximum number of scopes that can be recorded in
+    /// a single frame.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `frames_in_flight` is 0.
+    ///
+    pub fn new(device: Arc<Device>, frames_in_flight: usize, max_scopes_per_frame: u32)
+               -> Result<GpuProfiler, QueryPoolCreationError> {
+        assert!(frames_in_flight >= 1);
+
+        let num_queries = (max_scopes_per_frame + 1) * 2;
+        let slots = (0 .. frames_in_flight)
+            .map(|_| {
+                Ok(FrameSlot {
+                       query_pool: QueryPool::new(device.clone(), QueryType::Timestamp,
+                                                  num_queries)?,
+                       frame: None,
+                       scopes: Vec::new(),
+                       open_scopes: Vec::new(),
+                       timestamp_mask: 0,
+                   })
+            })
+            .collect::<Result<Vec<_>, QueryPoolCreationError>>()?;
+
+        let timestamp_period = TimestampPeriod::new(device.physical_device()
+                                                          .limits()
+                                                          .timestamp_period());
+        let debug_markers = device.instance().loaded_extensions().ext_debug_utils;
+
+        Ok(GpuProfiler {
+               device,
+               slots,
+               current_slot: frames_in_flight - 1,
+               next_frame: 0,
+               recording: false,
+               max_scopes: max_scopes_per_frame,
+               timestamp_period,
+               debug_markers,
+               pending: VecDeque::new(),
+               lost_frames: 0,
+           })
+    }
+
+    /// Returns the number of frames that were not resolved with `resolve_frame` before the
+    /// profiler needed to reuse their query pool. Their timings are lost.
+    #[inline]
+    pub fn lost_frames(&self) -> u64 {
+        self.lost_frames
+    }
+
+    /// Sets whether scopes should also be emitted as debug markers, so that they show up in
+    /// debugging tools. Enabled by default if the `ext_debug_utils` extension is enabled.
+    #[inline]
+    pub fn set_debug_markers(&mut self, enabled: bool) {
+        self.debug_markers = enabled &&
+            self.device.instance().loaded_extensions().ext_debug_utils;
+    }
+
+    /// Starts recording a new frame in `builder`.
+    ///
+    /// This must be called outside of a render pass, as it resets the queries of the frame. If
+    /// the oldest frame in flight wasn't resolved yet, its timings are lost.
+    pub fn begin_frame<P>(&mut self, builder: AutoCommandBufferBuilder<P>)
+                          -> Result<AutoCommandBufferBuilder<P>, GpuProfilerError> {
+        if self.recording {
+            return Err(GpuProfilerError::AlreadyInFrame);
+        }
+
+        let slot_index = (self.current_slot + 1) % self.slots.len();
+        let query_pool = self.slots[slot_index].query_pool.clone();
+        let num_queries = query_pool.num_slots();
+
+        let builder = builder.reset_query_pool(query_pool.clone(), 0 .. num_queries)?;
+        let builder = builder.write_timestamp(query_pool,
+                                              0,
+                                              PipelineStages {
+                                                  top_of_pipe: true,
+                                                  ..PipelineStages::none()
+                                              })?;
+
+        // `write_timestamp` fails on queue families that don't support timestamps.
+        let timestamp_mask = builder
+            .queue_family()
+            .timestamp_valid_bits()
+            .map_or(0, timestamp_mask);
+
+        if self.slots[slot_index].frame.is_some() {
+            self.pending.retain(|&index| index != slot_index);
+            self.lost_frames += 1;
+        }
+
+        let frame = self.next_frame;
+        self.next_frame += 1;
+        self.current_slot = slot_index;
+        self.recording = true;
+
+        let slot = &mut self.slots[slot_index];
+        slot.scopes.clear();
+        slot.scopes.push(ScopeRecord {
+                             name: format!("frame {}", frame),
+                             parent: None,
+                             first_query: 0,
+                         });
+        slot.open_scopes.clear();
+        slot.open_scopes.push(0);
+        slot.frame = Some(frame);
+        slot.timestamp_mask = timestamp_mask;
+
+        Ok(builder)
+    }
+
+    /// Opens a new scope named `name`, nested in the scope that is currently open.
+    pub fn begin_scope<P>(&mut self, builder: AutoCommandBufferBuilder<P>, name: &str)
+                          -> Result<AutoCommandBufferBuilder<P>, GpuProfilerError> {
+        if !self.recording {
+            return Err(GpuProfilerError::NotInFrame);
+        }
+
+        let slot = &mut self.slots[self.current_slot];
+        if slot.scopes.len() as u32 > self.max_scopes {
+            return Err(GpuProfilerError::TooManyScopes);
+        }
+
+        let first_query = slot.scopes.len() as u32 * 2;
+        let mut builder = builder.write_timestamp(slot.query_pool.clone(),
+                                                  first_query,
+                                                  PipelineStages {
+                                                      top_of_pipe: true,
+                                                      ..PipelineStages::none()
+                                                  })?;
+        if self.debug_markers {
+            builder = builder.debug_marker_begin(name, [1.0, 1.0, 1.0, 1.0])?;
+        }
+
+        let parent = slot.open_scopes.last().cloned();
+        slot.open_scopes.push(slot.scopes.len());
+        slot.scopes.push(ScopeRecord {
+                             name: name.to_owned(),
+                             parent,
+                             first_query,
+                         });
+        Ok(builder)
+    }
+
+    /// Closes the scope that was last opened with `begin_scope`.
+    pub fn end_scope<P>(&mut self, builder: AutoCommandBufferBuilder<P>)
+                        -> Result<AutoCommandBufferBuilder<P>, GpuProfilerError> {
+        if !self.recording {
+            return Err(GpuProfilerError::NotInFrame);
+        }
+
+        let slot = &mut self.slots[self.current_slot];
+        // The first open scope is the frame itself, which is closed by `end_frame`.
+        if slot.open_scopes.len() <= 1 {
+            return Err(GpuProfilerError::UnbalancedScopes);
+        }
+        let scope = *slot.open_scopes.last().unwrap();
+
+        let mut builder = builder;
+        if self.debug_markers {
+            builder = builder.debug_marker_end()?;
+        }
+        let builder = builder.write_timestamp(slot.query_pool.clone(),
+                                              slot.scopes[scope].first_query + 1,
+                                              PipelineStages {
+                                                  bottom_of_pipe: true,
+                                                  ..PipelineStages::none()
+                                              })?;
+
+        slot.open_scopes.pop();
+        Ok(builder)
+    }
+
+    /// Finishes recording the current frame. All the scopes must have been closed.
+    ///
+    /// Once the command buffer has been submitted, the timings of the frame can be retrieved
+    /// with `resolve_frame`.
+    pub fn end_frame<P>(&mut self, builder: AutoCommandBufferBuilder<P>)
+                        -> Result<AutoCommandBufferBuilder<P>, GpuProfilerError> {
+        if !self.recording {
+            return Err(GpuProfilerError::NotInFrame);
+        }
+
+        let slot = &mut self.slots[self.current_slot];
+        if slot.open_scopes.len() != 1 {
+            return Err(GpuProfilerError::UnbalancedScopes);
+        }
+
+        let builder = builder.write_timestamp(slot.query_pool.clone(),
+                                              1,
+                                              PipelineStages {
+                                                  bottom_of_pipe: true,
+                                                  ..PipelineStages::none()
+                                              })?;
+
+        slot.open_scopes.clear();
+        self.recording = false;
+        self.pending.push_back(self.current_slot);
+        Ok(builder)
+    }
+
+    /// Returns the timings of the oldest frame that was ended with `end_frame` and that wasn't
+    /// resolved yet.
+    ///
+    /// Frames must be resolved in the order in which they were ended: the profiler doesn't know
+    /// which submission `future` belongs to, and always reads the oldest unresolved frame.
+    /// `future` must therefore be the future of the submission of the command buffer in which
+    /// that frame was recorded. This function waits for it to be signalled before reading the
+    /// results, so frames should be resolved when their future is waited upon anyway, for
+    /// example before reusing the resources of a frame in flight.
+    pub fn resolve_frame<F>(&mut self, future: &FenceSignalFuture<F>)
+                            -> Result<FrameProfile, GpuProfilerError>
+        where F: GpuFuture
+    {
+        let index = match self.pending.front() {
+            Some(&index) => index,
+            None => return Err(GpuProfilerError::NoFrameToResolve),
+        };
+
+        future.wait(None)?;
+
+        let profile = self.read_slot(index)?;
+        self.pending.pop_front();
+        self.slots[index].frame = None;
+        Ok(profile)
+    }
+
+    // Reads the results of the frame recorded in a slot, whose command buffer must have
+    // finished executing.
+    fn read_slot(&self, index: usize) -> Result<FrameProfile, GpuProfilerError> {
+        let slot = &self.slots[index];
+        let frame = slot.frame.unwrap();
+
+        let num_queries = slot.scopes.len() as u32 * 2;
+        let mut data = vec![0u64; num_queries as usize];
+        let available = slot.query_pool
+            .get_results(0 .. num_queries, &mut data, QueryResultFlags::none())?;
+        if !available {
+            return Err(GpuProfilerError::ResultsNotAvailable);
+        }
+
+        let period = self.timestamp_period;
+        let mask = slot.timestamp_mask;
+        let to_ns = |ticks: u64| period.to_nanoseconds(ticks & mask);
+        let frame_start = to_ns(data[0]);
+
+        let mut nodes: Vec<Option<ProfileScope>> = slot.scopes
+            .iter()
+            .map(|scope| {
+                let start = to_ns(data[scope.first_query as usize]);
+                let end = to_ns(data[scope.first_query as usize + 1]);
+                Some(ProfileScope {
+                         name: scope.name.clone(),
+                         start_ns: start.saturating_sub(frame_start),
+                         duration_ns: end.saturating_sub(start),
+                         children: Vec::new(),
+                     })
+            })
+            .collect();
+
+        // Children are always recorded after their parent, so attaching them in reverse order
+        // guarantees that a scope is complete before it is moved into its parent.
+        for (index, scope) in slot.scopes.iter().enumerate().rev() {
+            if let Some(parent) = scope.parent {
+                let node = nodes[index].take().unwrap();
+                nodes[parent].as_mut().unwrap().children.insert(0, node);
+            }
+        }
+
+        Ok(FrameProfile {
+               frame,
+               gpu_start_ns: frame_start,
+               root: nodes[0].take().unwrap(),
+           })
+    }
+}
+
+unsafe impl DeviceOwned for GpuProfiler {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+/// Timings of one frame recorded by a `GpuProfiler`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameProfile {
+    /// Index of the frame, starting at 0 for the first frame recorded by the profiler.
+    pub frame: u64,
+    /// Value of the GPU timestamp at the start of the frame, in nanoseconds. Only meaningful
+    /// when compared to other GPU timestamps.
+    pub gpu_start_ns: u64,
+    /// The scope that covers the whole frame. The scopes recorded by the user are its children.
+    pub root: ProfileScope,
+}
+
+/// Timings of a scope recorded by a `GpuProfiler`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileScope {
+    /// Name of the scope.
+    pub name: String,
+    /// Start of the scope, in nanoseconds relative to the start of the frame.
+    pub start_ns: u64,
+    /// Duration of the scope, in nanoseconds.
+    pub duration_ns: u64,
+    /// Scopes that were recorded inside of this one, in the order in which they were recorded.
+    pub children: Vec<ProfileScope>,
+}
+
+/// Writes a list of frames in the Chrome trace event JSON format, which can be loaded in
+/// `chrome://tracing` or similar tools.
+///
+/// Each scope is written as a complete event (`"ph": "X"`) whose timestamp is the GPU timestamp
+/// in microseconds. `pid` and `tid` are the values to use for the events, so that GPU events can
+/// be told apart from CPU events when merged in the same trace.
+pub fn write_chrome_trace<W>(frames: &[FrameProfile], pid: u32, tid: u32, mut out: W)
+                             -> io::Result<()>
+    where W: io::Write
+{
+    let mut events = Vec::new();
+    for frame in frames {
+        write_chrome_events(&mut events, frame.frame, frame.gpu_start_ns, &frame.root, pid, tid);
+    }
+
+    write!(out, "{{\"traceEvents\":[{}]}}", events.join(","))
+}
+
+/// Same as `write_chrome_trace`, but returns a `String`.
+pub fn chrome_trace(frames: &[FrameProfile], pid: u32, tid: u32) -> String {
+    let mut out = Vec::new();
+    write_chrome_trace(frames, pid, tid, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+// Appends to `events` the JSON object of a scope and of all its children.
+fn write_chrome_events(events: &mut Vec<String>, frame: u64, frame_start_ns: u64,
+                       scope: &ProfileScope, pid: u32, tid: u32) {
+    let mut event = String::new();
+    write!(event,
+           "{{\"name\":\"{}\",\"cat\":\"gpu\",\"ph\":\"X\",\"ts\":{:.3},\"dur\":{:.3},\
+            \"pid\":{},\"tid\":{},\"args\":{{\"frame\":{}}}}}",
+           escape_json(&scope.name),
+           (frame_start_ns + scope.start_ns) as f64 / 1000.0,
+           scope.duration_ns as f64 / 1000.0,
+           pid,
+           tid,
+           frame)
+        .unwrap();
+    events.push(event);
+
+    for child in &scope.children {
+        write_chrome_events(events, frame, frame_start_ns, child, pid, tid);
+    }
+}
+
+// Escapes a string so that it can be put between quotes in a JSON document.
+fn escape_json(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Error that can happen when using a `GpuProfiler`.
+#[derive(Debug, Clone)]
+pub enum GpuProfilerError {
+    /// Error while resetting the queries of a frame.
+    ResetQueryPoolError(ResetQueryPoolError),
+    /// Error while writing a timestamp.
+    WriteTimestampError(WriteTimestampError),
+    /// Error while emitting a debug marker.
+    DebugMarkerError(DebugMarkerError),
+    /// Error while reading the results of a frame.
+    GetResultsError(GetResultsError),
+    /// Error while waiting for the future of a frame.
+    FlushError(FlushError),
+    /// `begin_frame` was called while a frame was already being recorded.
+    AlreadyInFrame,
+    /// A scope or frame function was called while no frame was being recorded.
+    NotInFrame,
+    /// More scopes than the maximum passed when creating the profiler were recorded in a frame.
+    TooManyScopes,
+    /// Tried to end a scope that wasn't begun, or to end a frame while scopes are still open.
+    UnbalancedScopes,
+    /// `resolve_frame` was called while there was no ended frame left to resolve.
+    NoFrameToResolve,
+    /// The results of a frame were not available after its future was signalled. The future
+    /// passed to `resolve_frame` probably doesn't belong to the frame.
+    ResultsNotAvailable,
+}
+
+impl error::Error for GpuProfilerError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            GpuProfilerError::ResetQueryPoolError(_) => {
+                "error while resetting the queries of a frame"
+            },
+            GpuProfilerError::WriteTimestampError(_) => "error while writing a timestamp",
+            GpuProfilerError::DebugMarkerError(_) => "error while emitting a debug marker",
+            GpuProfilerError::GetResultsError(_) => "error while reading the results of a frame",
+            GpuProfilerError::FlushError(_) => "error while waiting for the future of a frame",
+            GpuProfilerError::AlreadyInFrame => {
+                "begin_frame was called while a frame was already being recorded"
+            },
+            GpuProfilerError::NotInFrame => {
+                "a scope or frame function was called while no frame was being recorded"
+            },
+            GpuProfilerError::TooManyScopes => {
+                "more scopes than the maximum passed when creating the profiler were recorded \
+                 in a frame"
+            },
+            GpuProfilerError::UnbalancedScopes => {
+                "tried to end a scope that wasn't begun, or to end a frame while scopes are \
+                 still open"
+            },
+            GpuProfilerError::NoFrameToResolve => {
+                "resolve_frame was called while there was no ended frame left to resolve"
+            },
+            GpuProfilerError::ResultsNotAvailable => {
+                "the results of a frame were not available after its future was signalled"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            GpuProfilerError::ResetQueryPoolError(ref err) => Some(err),
+            GpuProfilerError::WriteTimestampError(ref err) => Some(err),
+            GpuProfilerError::DebugMarkerError(ref err) => Some(err),
+            GpuProfilerError::GetResultsError(ref err) => Some(err),
+            GpuProfilerError::FlushError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GpuProfilerError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<ResetQueryPoolError> for GpuProfilerError {
+    #[inline]
+    fn from(err: ResetQueryPoolError) -> GpuProfilerError {
+        GpuProfilerError::ResetQueryPoolError(err)
+    }
+}
+
+impl From<WriteTimestampError> for GpuProfilerError {
+    #[inline]
+    fn from(err: WriteTimestampError) -> GpuProfilerError {
+        GpuProfilerError::WriteTimestampError(err)
+    }
+}
+
+impl From<DebugMarkerError> for GpuProfilerError {
+    #[inline]
+    fn from(err: DebugMarkerError) -> GpuProfilerError {
+        GpuProfilerError::DebugMarkerError(err)
+    }
+}
+
+impl From<GetResultsError> for GpuProfilerError {
+    #[inline]
+    fn from(err: GetResultsError) -> GpuProfilerError {
+        GpuProfilerError::GetResultsError(err)
+    }
+}
+
+impl From<FlushError> for GpuProfilerError {
+    #[inline]
+    fn from(err: FlushError) -> GpuProfilerError {
+        GpuProfilerError::FlushError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use command_buffer::AutoCommandBufferBuilder;
+    use command_buffer::CommandBuffer;
+    use query::profiler::FrameProfile;
+    use query::profiler::GpuProfiler;
+    use query::profiler::GpuProfilerError;
+    use query::profiler::ProfileScope;
+    use query::profiler::chrome_trace;
+    use sync::GpuFuture;
+
+    #[test]
+    fn chrome_trace_format() {
+        let frame = FrameProfile {
+            frame: 3,
+            gpu_start_ns: 1000,
+            root: ProfileScope {
+                name: "frame 3".to_owned(),
+                start_ns: 0,
+                duration_ns: 5000,
+                children: vec![
+                    ProfileScope {
+                        name: "sha\"dows".to_owned(),
+                        start_ns: 500,
+                        duration_ns: 1500,
+                        children: Vec::new(),
+                    },
+                ],
+            },
+        };
+
+        assert_eq!(chrome_trace(&[frame], 1, 2),
+                   "{\"traceEvents\":[\
+                    {\"name\":\"frame 3\",\"cat\":\"gpu\",\"ph\":\"X\",\"ts\":1.000,\
+                    \"dur\":5.000,\"pid\":1,\"tid\":2,\"args\":{\"frame\":3}},\
+                    {\"name\":\"sha\\\"dows\",\"cat\":\"gpu\",\"ph\":\"X\",\"ts\":1.500,\
+                    \"dur\":1.500,\"pid\":1,\"tid\":2,\"args\":{\"frame\":3}}]}");
+    }
+
+    #[test]
+    fn scopes_must_be_balanced() {
+        let (device, queue) = gfx_dev_and_queue!();
+        if queue.family().timestamp_valid_bits().is_none() {
+            return;
+        }
+
+        let mut profiler = GpuProfiler::new(device.clone(), 2, 4).unwrap();
+        let builder = AutoCommandBufferBuilder::primary(device, queue.family()).unwrap();
+
+        let builder = profiler.begin_frame(builder).unwrap();
+        match profiler.end_scope(builder) {
+            Err(GpuProfilerError::UnbalancedScopes) => (),
+            _ => panic!(),
+        };
+    }
+
+    #[test]
+    fn resolve_without_frame() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let mut profiler = GpuProfiler::new(device.clone(), 2, 4).unwrap();
+        let future = AutoCommandBufferBuilder::primary(device, queue.family())
+            .unwrap()
+            .build()
+            .unwrap()
+            .execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+        match profiler.resolve_frame(&future) {
+            Err(GpuProfilerError::NoFrameToResolve) => (),
+            _ => panic!(),
+        };
+    }
+}
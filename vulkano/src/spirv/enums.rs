@@ -7,10 +7,11 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
-#![allow(dead_code)]
+//! Enumerations of the SPIR-V specification.
+
 #![allow(non_camel_case_types)]
 
-use crate::parse::ParseError;
+use spirv::parse::ParseError;
 
 macro_rules! enumeration {
     ($(typedef enum $unused:ident { $($elem:ident = $value:expr,)+ } $name:ident;)+) => (
@@ -0,0 +1,789 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Runtime reflection of SPIR-V code.
+//!
+//! The `vulkano-shaders` crate generates, at compile time, Rust types that describe the inputs,
+//! outputs and layout of each entry point of a shader. When a shader is only available at
+//! runtime, this module extracts the same information by parsing the SPIR-V code and returns it
+//! as a `RuntimeShaderInterface` and a `RuntimePipelineDesc`.
+//!
+//! Most of the time you don't need to use this module directly. Instead, load the shader with
+//! `ShaderModule::with_reflection` and call `entry_point_by_name` on it.
+
+use std::borrow::Cow;
+use std::cmp;
+use std::error;
+use std::fmt;
+
+use descriptor::descriptor::DescriptorBufferDesc;
+use descriptor::descriptor::DescriptorDesc;
+use descriptor::descriptor::DescriptorDescTy;
+use descriptor::descriptor::DescriptorImageDesc;
+use descriptor::descriptor::DescriptorImageDescArray;
+use descriptor::descriptor::DescriptorImageDescDimensions;
+use descriptor::descriptor::ShaderStages;
+use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
+use descriptor::pipeline_layout::RuntimePipelineDesc;
+use format::Format;
+use pipeline::shader::GeometryShaderExecutionMode;
+use pipeline::shader::GraphicsShaderType;
+use pipeline::shader::RuntimeShaderInterface;
+use pipeline::shader::RuntimeShaderInterfaceError;
+use pipeline::shader::ShaderInterfaceDefEntry;
+
+use self::enums::Decoration;
+use self::enums::Dim;
+use self::enums::ExecutionMode;
+use self::enums::ExecutionModel;
+use self::enums::ImageFormat;
+use self::enums::StorageClass;
+use self::parse::Instruction;
+use self::parse::Spirv;
+
+pub use self::parse::ParseError;
+
+// The parser is also used by `vulkano-shaders`, which generates Rust code from the same
+// instructions at compile time.
+#[doc(hidden)]
+pub mod enums;
+#[doc(hidden)]
+pub mod parse;
+
+/// Kind of an entry point found in a SPIR-V module.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EntryPointType {
+    /// The entry point is a stage of a graphics pipeline.
+    Graphics(GraphicsShaderType),
+    /// The entry point is a compute shader.
+    Compute,
+}
+
+/// Description of an entry point, extracted from its SPIR-V code.
+#[derive(Debug, Clone)]
+pub struct EntryPointReflection {
+    /// Kind of the entry point.
+    pub ty: EntryPointType,
+    /// Input attributes of the entry point. Always empty for compute shaders.
+    pub input: RuntimeShaderInterface,
+    /// Output attributes of the entry point. Always empty for compute shaders.
+    pub output: RuntimeShaderInterface,
+    /// Descriptors and push constants used by the module.
+    pub layout: RuntimePipelineDesc,
+}
+
+/// Parses SPIR-V code and returns the description of the entry point named `name`.
+///
+/// Built-in variables such as `gl_Position` are not part of the returned interfaces.
+pub fn reflect_entry_point(spirv: &[u32], name: &str)
+                           -> Result<EntryPointReflection, ReflectionError> {
+    let doc = parse::parse_spirv(spirv)?;
+
+    let (execution, id, interface) = doc.instructions
+        .iter()
+        .filter_map(|i| match *i {
+            Instruction::EntryPoint { ref execution, id, name: ref n, ref interface }
+                if n == name => Some((execution.clone(), id, interface)),
+            _ => None,
+        })
+        .next()
+        .ok_or(ReflectionError::EntryPointNotFound)?;
+
+    let (ty, stages, ignore_first_array_in, ignore_first_array_out) = match execution {
+        ExecutionModel::ExecutionModelVertex => {
+            (EntryPointType::Graphics(GraphicsShaderType::Vertex),
+             ShaderStages { vertex: true, ..ShaderStages::none() }, false, false)
+        },
+        ExecutionModel::ExecutionModelTessellationControl => {
+            (EntryPointType::Graphics(GraphicsShaderType::TessellationControl),
+             ShaderStages { tessellation_control: true, ..ShaderStages::none() }, true, true)
+        },
+        ExecutionModel::ExecutionModelTessellationEvaluation => {
+            (EntryPointType::Graphics(GraphicsShaderType::TessellationEvaluation),
+             ShaderStages { tessellation_evaluation: true, ..ShaderStages::none() }, true, false)
+        },
+        ExecutionModel::ExecutionModelGeometry => {
+            let mode = geometry_execution_mode(&doc, id)?;
+            (EntryPointType::Graphics(GraphicsShaderType::Geometry(mode)),
+             ShaderStages { geometry: true, ..ShaderStages::none() }, true, false)
+        },
+        ExecutionModel::ExecutionModelFragment => {
+            (EntryPointType::Graphics(GraphicsShaderType::Fragment),
+             ShaderStages { fragment: true, ..ShaderStages::none() }, false, false)
+        },
+        ExecutionModel::ExecutionModelGLCompute => {
+            (EntryPointType::Compute,
+             ShaderStages { compute: true, ..ShaderStages::none() }, false, false)
+        },
+        ExecutionModel::ExecutionModelKernel => {
+            return Err(ReflectionError::UnsupportedExecutionModel);
+        },
+    };
+
+    let (input, output) = match ty {
+        EntryPointType::Graphics(_) => {
+            interfaces(&doc, interface, ignore_first_array_in, ignore_first_array_out)?
+        },
+        EntryPointType::Compute => {
+            (RuntimeShaderInterface::empty(), RuntimeShaderInterface::empty())
+        },
+    };
+
+    let layout = layout(&doc, stages)?;
+
+    Ok(EntryPointReflection {
+           ty,
+           input,
+           output,
+           layout,
+       })
+}
+
+/// Error that can happen when reflecting SPIR-V code.
+#[derive(Debug, Clone)]
+pub enum ReflectionError {
+    /// The shader module wasn't created with one of the `with_reflection` constructors, and
+    /// didn't keep its SPIR-V code.
+    ReflectionDisabled,
+    /// The SPIR-V code couldn't be parsed.
+    ParseError(ParseError),
+    /// There is no entry point with the requested name in the module.
+    EntryPointNotFound,
+    /// The entry point doesn't have the expected type.
+    WrongEntryPointType,
+    /// The execution model of the entry point isn't supported by Vulkan.
+    UnsupportedExecutionModel,
+    /// A geometry shader doesn't declare which kind of primitives it expects.
+    MissingGeometryExecutionMode,
+    /// An input or output variable doesn't have a `Location` decoration.
+    MissingLocation {
+        /// Id of the variable.
+        id: u32,
+    },
+    /// A descriptor doesn't have a `Binding` decoration.
+    MissingBinding {
+        /// Id of the variable.
+        id: u32,
+    },
+    /// A type is referenced but never declared.
+    TypeNotFound {
+        /// Id of the type.
+        id: u32,
+    },
+    /// A type can't be represented with vulkano's descriptions.
+    UnsupportedType {
+        /// Id of the type.
+        id: u32,
+    },
+    /// The push constants contain a runtime-sized array.
+    RuntimeSizedPushConstants,
+    /// The input or output interface of the entry point is invalid.
+    InterfaceError(RuntimeShaderInterfaceError),
+}
+
+impl error::Error for ReflectionError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            ReflectionError::ReflectionDisabled => {
+                "the shader module wasn't created with reflection enabled"
+            },
+            ReflectionError::ParseError(_) => {
+                "the SPIR-V code couldn't be parsed"
+            },
+            ReflectionError::EntryPointNotFound => {
+                "there is no entry point with this name in the module"
+            },
+            ReflectionError::WrongEntryPointType => {
+                "the entry point doesn't have the expected type"
+            },
+            ReflectionError::UnsupportedExecutionModel => {
+                "the execution model of the entry point isn't supported"
+            },
+            ReflectionError::MissingGeometryExecutionMode => {
+                "a geometry shader doesn't declare which kind of primitives it expects"
+            },
+            ReflectionError::MissingLocation { .. } => {
+                "an input or output variable doesn't have a location"
+            },
+            ReflectionError::MissingBinding { .. } => {
+                "a descriptor doesn't have a binding"
+            },
+            ReflectionError::TypeNotFound { .. } => {
+                "a type is referenced but never declared"
+            },
+            ReflectionError::UnsupportedType { .. } => {
+                "a type can't be represented with vulkano's descriptions"
+            },
+            ReflectionError::RuntimeSizedPushConstants => {
+                "the push constants contain a runtime-sized array"
+            },
+            ReflectionError::InterfaceError(_) => {
+                "the input or output interface of the entry point is invalid"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            ReflectionError::ParseError(ref err) => Some(err),
+            ReflectionError::InterfaceError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ReflectionError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<ParseError> for ReflectionError {
+    #[inline]
+    fn from(err: ParseError) -> ReflectionError {
+        ReflectionError::ParseError(err)
+    }
+}
+
+impl From<RuntimeShaderInterfaceError> for ReflectionError {
+    #[inline]
+    fn from(err: RuntimeShaderInterfaceError) -> ReflectionError {
+        ReflectionError::InterfaceError(err)
+    }
+}
+
+fn geometry_execution_mode(doc: &Spirv, entry_point_id: u32)
+                           -> Result<GeometryShaderExecutionMode, ReflectionError> {
+    for instruction in doc.instructions.iter() {
+        if let Instruction::ExecutionMode { target_id, ref mode, .. } = *instruction {
+            if target_id != entry_point_id {
+                continue;
+            }
+
+            match *mode {
+                ExecutionMode::ExecutionModeInputPoints => {
+                    return Ok(GeometryShaderExecutionMode::Points);
+                },
+                ExecutionMode::ExecutionModeInputLines => {
+                    return Ok(GeometryShaderExecutionMode::Lines);
+                },
+                ExecutionMode::ExecutionModeInputLinesAdjacency => {
+                    return Ok(GeometryShaderExecutionMode::LinesWithAdjacency);
+                },
+                ExecutionMode::ExecutionModeTriangles => {
+                    return Ok(GeometryShaderExecutionMode::Triangles);
+                },
+                ExecutionMode::ExecutionModeInputTrianglesAdjacency => {
+                    return Ok(GeometryShaderExecutionMode::TrianglesWithAdjacency);
+                },
+                _ => (),
+            }
+        }
+    }
+
+    Err(ReflectionError::MissingGeometryExecutionMode)
+}
+
+/// Returns the input and output interfaces made of the variables listed in `interface`.
+fn interfaces(doc: &Spirv, interface: &[u32], ignore_first_array_in: bool,
+              ignore_first_array_out: bool)
+              -> Result<(RuntimeShaderInterface, RuntimeShaderInterface), ReflectionError> {
+    let mut input_elements = Vec::new();
+    let mut output_elements = Vec::new();
+
+    for &interface_id in interface.iter() {
+        for instruction in doc.instructions.iter() {
+            let (result_type_id, storage_class) = match *instruction {
+                Instruction::Variable { result_type_id, result_id, ref storage_class, .. }
+                    if result_id == interface_id => (result_type_id, storage_class),
+                _ => continue,
+            };
+
+            if is_builtin(doc, interface_id) {
+                continue;
+            }
+
+            let (to_write, ignore_first_array) = match *storage_class {
+                StorageClass::StorageClassInput => (&mut input_elements, ignore_first_array_in),
+                StorageClass::StorageClassOutput => (&mut output_elements, ignore_first_array_out),
+                _ => continue,
+            };
+
+            let location = match doc.get_decoration_params(interface_id,
+                                                           Decoration::DecorationLocation) {
+                Some(ref params) if !params.is_empty() => params[0],
+                _ => return Err(ReflectionError::MissingLocation { id: interface_id }),
+            };
+
+            let (format, location_len) = format_from_id(doc, result_type_id, ignore_first_array)?;
+            to_write.push(ShaderInterfaceDefEntry {
+                location: location .. location + location_len,
+                format,
+                name: name_from_id(doc, interface_id).map(Cow::Owned),
+            });
+        }
+    }
+
+    Ok((RuntimeShaderInterface::new(input_elements)?,
+        RuntimeShaderInterface::new(output_elements)?))
+}
+
+/// Returns the descriptors and push constants declared in the module.
+fn layout(doc: &Spirv, stages: ShaderStages) -> Result<RuntimePipelineDesc, ReflectionError> {
+    let mut sets: Vec<Vec<Option<DescriptorDesc>>> = Vec::new();
+
+    for set_decoration in doc.get_decorations(Decoration::DecorationDescriptorSet) {
+        let variable_id = set_decoration.target_id;
+        let set = set_decoration.params.get(0).cloned().unwrap_or(0) as usize;
+
+        let binding = match doc.get_decoration_params(variable_id, Decoration::DecorationBinding) {
+            Some(ref params) if !params.is_empty() => params[0] as usize,
+            _ => return Err(ReflectionError::MissingBinding { id: variable_id }),
+        };
+
+        let (pointed_ty, storage_class) = pointer_variable_ty(doc, variable_id)?;
+        let (ty, readonly, array_count) =
+            descriptor_infos(doc, pointed_ty, &storage_class, false)?;
+
+        if sets.len() <= set {
+            sets.resize(set + 1, Vec::new());
+        }
+        if sets[set].len() <= binding {
+            sets[set].resize(binding + 1, None);
+        }
+        sets[set][binding] = Some(DescriptorDesc {
+                                      ty,
+                                      array_count,
+                                      stages,
+                                      readonly,
+                                  });
+    }
+
+    let mut push_constants_size = 0;
+    for instruction in doc.instructions.iter() {
+        let type_id = match *instruction {
+            Instruction::TypePointer {
+                type_id,
+                storage_class: StorageClass::StorageClassPushConstant,
+                ..
+            } => type_id,
+            _ => continue,
+        };
+
+        let size = type_size(doc, type_id)?.ok_or(ReflectionError::RuntimeSizedPushConstants)?;
+        push_constants_size = cmp::max(push_constants_size, size);
+    }
+
+    // Same as what `vulkano-shaders` generates, so that layouts obtained through reflection can
+    // be mixed with the ones generated at compile time.
+    let push_constants = if push_constants_size == 0 {
+        None
+    } else {
+        Some(PipelineLayoutDescPcRange {
+                 offset: 0,
+                 size: push_constants_size,
+                 stages: ShaderStages::all(),
+             })
+    };
+
+    Ok(RuntimePipelineDesc::new(sets, push_constants)
+           .expect("a single push constants range can't conflict with itself"))
+}
+
+/// Returns the vulkano `Format` and number of occupied locations of a type.
+///
+/// If `ignore_first_array` is true, the outermost `OpTypeArray` is skipped. This is used for the
+/// per-vertex inputs and outputs of tessellation and geometry shaders.
+fn format_from_id(doc: &Spirv, searched: u32, ignore_first_array: bool)
+                  -> Result<(Format, u32), ReflectionError> {
+    for instruction in doc.instructions.iter() {
+        match *instruction {
+            Instruction::TypeInt { result_id, width, signedness } if result_id == searched => {
+                let format = match (width, signedness) {
+                    (8, true) => Format::R8Sint,
+                    (8, false) => Format::R8Uint,
+                    (16, true) => Format::R16Sint,
+                    (16, false) => Format::R16Uint,
+                    (32, true) => Format::R32Sint,
+                    (32, false) => Format::R32Uint,
+                    (64, true) => Format::R64Sint,
+                    (64, false) => Format::R64Uint,
+                    _ => return Err(ReflectionError::UnsupportedType { id: searched }),
+                };
+                return Ok((format, 1));
+            },
+            Instruction::TypeFloat { result_id, width } if result_id == searched => {
+                let format = match width {
+                    32 => Format::R32Sfloat,
+                    64 => Format::R64Sfloat,
+                    _ => return Err(ReflectionError::UnsupportedType { id: searched }),
+                };
+                return Ok((format, 1));
+            },
+            Instruction::TypeVector { result_id, component_id, count } if result_id == searched => {
+                let (component, _) = format_from_id(doc, component_id, false)?;
+                let format = match (component, count) {
+                    (Format::R32Sint, 1) => Format::R32Sint,
+                    (Format::R32Sint, 2) => Format::R32G32Sint,
+                    (Format::R32Sint, 3) => Format::R32G32B32Sint,
+                    (Format::R32Sint, 4) => Format::R32G32B32A32Sint,
+                    (Format::R32Uint, 1) => Format::R32Uint,
+                    (Format::R32Uint, 2) => Format::R32G32Uint,
+                    (Format::R32Uint, 3) => Format::R32G32B32Uint,
+                    (Format::R32Uint, 4) => Format::R32G32B32A32Uint,
+                    (Format::R32Sfloat, 1) => Format::R32Sfloat,
+                    (Format::R32Sfloat, 2) => Format::R32G32Sfloat,
+                    (Format::R32Sfloat, 3) => Format::R32G32B32Sfloat,
+                    (Format::R32Sfloat, 4) => Format::R32G32B32A32Sfloat,
+                    _ => return Err(ReflectionError::UnsupportedType { id: searched }),
+                };
+                return Ok((format, 1));
+            },
+            Instruction::TypeMatrix { result_id, column_type_id, column_count }
+                if result_id == searched => {
+                let (format, len) = format_from_id(doc, column_type_id, false)?;
+                return Ok((format, len * column_count));
+            },
+            Instruction::TypeArray { result_id, type_id, length_id } if result_id == searched => {
+                let (format, len) = format_from_id(doc, type_id, false)?;
+                if ignore_first_array {
+                    return Ok((format, len));
+                }
+
+                let array_len = constant_value(doc, length_id)?;
+                return Ok((format, len * array_len as u32));
+            },
+            Instruction::TypePointer { result_id, type_id, .. } if result_id == searched => {
+                return format_from_id(doc, type_id, ignore_first_array);
+            },
+            _ => (),
+        }
+    }
+
+    Err(ReflectionError::TypeNotFound { id: searched })
+}
+
+/// Returns the size in bytes of a type, or `None` if it contains a runtime-sized array.
+fn type_size(doc: &Spirv, searched: u32) -> Result<Option<usize>, ReflectionError> {
+    for instruction in doc.instructions.iter() {
+        match *instruction {
+            Instruction::TypeInt { result_id, width, .. } |
+            Instruction::TypeFloat { result_id, width } if result_id == searched => {
+                return Ok(Some(width as usize / 8));
+            },
+            Instruction::TypeVector { result_id, component_id, count } if result_id == searched => {
+                return Ok(type_size(doc, component_id)?.map(|s| s * count as usize));
+            },
+            Instruction::TypeMatrix { result_id, column_type_id, column_count }
+                if result_id == searched => {
+                return Ok(type_size(doc, column_type_id)?.map(|s| s * column_count as usize));
+            },
+            Instruction::TypeArray { result_id, type_id, length_id } if result_id == searched => {
+                let stride = match doc.get_decoration_params(searched,
+                                                             Decoration::DecorationArrayStride) {
+                    Some(ref params) if !params.is_empty() => params[0] as usize,
+                    _ => match type_size(doc, type_id)? {
+                        Some(size) => size,
+                        None => return Err(ReflectionError::UnsupportedType { id: searched }),
+                    },
+                };
+                let len = constant_value(doc, length_id)?;
+                return Ok(Some(stride * len as usize));
+            },
+            Instruction::TypeRuntimeArray { result_id, .. } if result_id == searched => {
+                return Ok(None);
+            },
+            Instruction::TypeStruct { result_id, ref member_types } if result_id == searched => {
+                let mut size = 0;
+                for (member, &member_ty) in member_types.iter().enumerate() {
+                    let offset = match doc.get_member_decoration_params(searched,
+                                                                        member as u32,
+                                                                        Decoration::DecorationOffset) {
+                        Some(ref params) if !params.is_empty() => params[0] as usize,
+                        _ => size,
+                    };
+
+                    match type_size(doc, member_ty)? {
+                        Some(member_size) => size = cmp::max(size, offset + member_size),
+                        None => return Ok(None),
+                    }
+                }
+                return Ok(Some(size));
+            },
+            _ => (),
+        }
+    }
+
+    Err(ReflectionError::TypeNotFound { id: searched })
+}
+
+/// Returns the value of an integer constant, such as the length of an array.
+fn constant_value(doc: &Spirv, searched: u32) -> Result<u64, ReflectionError> {
+    doc.instructions
+        .iter()
+        .filter_map(|i| match *i {
+            Instruction::Constant { result_id, ref data, .. } if result_id == searched => {
+                Some(data.iter().rev().fold(0u64, |a, &b| (a << 32) | b as u64))
+            },
+            _ => None,
+        })
+        .next()
+        .ok_or(ReflectionError::TypeNotFound { id: searched })
+}
+
+fn name_from_id(doc: &Spirv, searched: u32) -> Option<String> {
+    doc.instructions
+        .iter()
+        .filter_map(|i| match *i {
+            Instruction::Name { target_id, ref name } if target_id == searched && !name.is_empty() => {
+                Some(name.clone())
+            },
+            _ => None,
+        })
+        .next()
+}
+
+/// Returns true if a `BuiltIn` decoration is applied on an id.
+fn is_builtin(doc: &Spirv, id: u32) -> bool {
+    if doc.get_decoration_params(id, Decoration::DecorationBuiltIn).is_some() {
+        return true;
+    }
+    if doc.get_member_decoration_builtin_params(id).is_some() {
+        return true;
+    }
+
+    for instruction in doc.instructions.iter() {
+        match *instruction {
+            Instruction::Variable { result_type_id, result_id, .. } if result_id == id => {
+                return is_builtin(doc, result_type_id);
+            },
+            Instruction::TypeArray { result_id, type_id, .. } if result_id == id => {
+                return is_builtin(doc, type_id);
+            },
+            Instruction::TypeRuntimeArray { result_id, type_id } if result_id == id => {
+                return is_builtin(doc, type_id);
+            },
+            Instruction::TypeStruct { result_id, ref member_types } if result_id == id => {
+                return member_types.iter().any(|&mem| is_builtin(doc, mem));
+            },
+            Instruction::TypePointer { result_id, type_id, .. } if result_id == id => {
+                return is_builtin(doc, type_id);
+            },
+            _ => (),
+        }
+    }
+
+    false
+}
+
+/// Returns the id of the type pointed to by a variable, and the storage class of the pointer.
+fn pointer_variable_ty(doc: &Spirv, variable: u32)
+                       -> Result<(u32, StorageClass), ReflectionError> {
+    let var_ty = doc.instructions
+        .iter()
+        .filter_map(|i| match *i {
+            Instruction::Variable { result_type_id, result_id, .. } if result_id == variable => {
+                Some(result_type_id)
+            },
+            _ => None,
+        })
+        .next()
+        .ok_or(ReflectionError::TypeNotFound { id: variable })?;
+
+    doc.instructions
+        .iter()
+        .filter_map(|i| match *i {
+            Instruction::TypePointer { result_id, type_id, ref storage_class }
+                if result_id == var_ty => Some((type_id, storage_class.clone())),
+            _ => None,
+        })
+        .next()
+        .ok_or(ReflectionError::TypeNotFound { id: var_ty })
+}
+
+/// Returns the type of a descriptor, whether it is read-only, and its number of array elements.
+///
+/// See also section 14.5.2 of the Vulkan specs: Descriptor Set Interface.
+fn descriptor_infos(doc: &Spirv, pointed_ty: u32, pointer_storage: &StorageClass,
+                    force_combined_image_sampled: bool)
+                    -> Result<(DescriptorDescTy, bool, u32), ReflectionError> {
+    for instruction in doc.instructions.iter() {
+        match *instruction {
+            Instruction::TypeStruct { result_id, .. } if result_id == pointed_ty => {
+                let block = doc.get_decoration_params(pointed_ty, Decoration::DecorationBlock)
+                    .is_some();
+                let buffer_block = doc.get_decoration_params(pointed_ty,
+                                                             Decoration::DecorationBufferBlock)
+                    .is_some();
+                if !block && !buffer_block {
+                    return Err(ReflectionError::UnsupportedType { id: pointed_ty });
+                }
+
+                let storage = buffer_block ||
+                    *pointer_storage == StorageClass::StorageClassStorageBuffer;
+                let desc = DescriptorDescTy::Buffer(DescriptorBufferDesc {
+                                                        dynamic: Some(false),
+                                                        storage,
+                                                    });
+                return Ok((desc, true, 1));
+            },
+
+            Instruction::TypeImage { result_id, ref dim, arrayed, ms, sampled, ref format, .. }
+                if result_id == pointed_ty => {
+                let sampled = match sampled {
+                    Some(sampled) => sampled,
+                    None => return Err(ReflectionError::UnsupportedType { id: pointed_ty }),
+                };
+
+                let array_layers = if arrayed {
+                    DescriptorImageDescArray::Arrayed { max_layers: None }
+                } else {
+                    DescriptorImageDescArray::NonArrayed
+                };
+
+                let desc = match *dim {
+                    Dim::DimSubpassData => {
+                        if force_combined_image_sampled || sampled ||
+                            *format != ImageFormat::ImageFormatUnknown
+                        {
+                            return Err(ReflectionError::UnsupportedType { id: pointed_ty });
+                        }
+
+                        DescriptorDescTy::InputAttachment {
+                            multisampled: ms,
+                            array_layers,
+                        }
+                    },
+                    Dim::DimBuffer => {
+                        DescriptorDescTy::TexelBuffer {
+                            storage: !sampled,
+                            format: None,
+                        }
+                    },
+                    _ => {
+                        let dimensions = match *dim {
+                            Dim::Dim1D => DescriptorImageDescDimensions::OneDimensional,
+                            Dim::Dim2D => DescriptorImageDescDimensions::TwoDimensional,
+                            Dim::Dim3D => DescriptorImageDescDimensions::ThreeDimensional,
+                            Dim::DimCube => DescriptorImageDescDimensions::Cube,
+                            _ => return Err(ReflectionError::UnsupportedType { id: pointed_ty }),
+                        };
+
+                        let desc = DescriptorImageDesc {
+                            sampled,
+                            dimensions,
+                            format: None,
+                            multisampled: ms,
+                            array_layers,
+                        };
+
+                        if force_combined_image_sampled {
+                            DescriptorDescTy::CombinedImageSampler(desc)
+                        } else {
+                            DescriptorDescTy::Image(desc)
+                        }
+                    },
+                };
+
+                return Ok((desc, true, 1));
+            },
+
+            Instruction::TypeSampledImage { result_id, image_type_id }
+                if result_id == pointed_ty => {
+                return descriptor_infos(doc, image_type_id, pointer_storage, true);
+            },
+
+            Instruction::TypeSampler { result_id } if result_id == pointed_ty => {
+                return Ok((DescriptorDescTy::Sampler, true, 1));
+            },
+
+            Instruction::TypeArray { result_id, type_id, length_id } if result_id == pointed_ty => {
+                let (desc, readonly, array_count) =
+                    descriptor_infos(doc, type_id, pointer_storage, false)?;
+                if array_count != 1 {
+                    return Err(ReflectionError::UnsupportedType { id: pointed_ty });
+                }
+
+                let len = constant_value(doc, length_id)?;
+                return Ok((desc, readonly, len as u32));
+            },
+
+            _ => (),
+        }
+    }
+
+    Err(ReflectionError::UnsupportedType { id: pointed_ty })
+}
+
+#[cfg(test)]
+mod tests {
+    use format::Format;
+    use pipeline::shader::GraphicsShaderType;
+    use pipeline::shader::ShaderInterfaceDef;
+    use spirv::EntryPointType;
+    use spirv::ReflectionError;
+    use spirv::reflect_entry_point;
+
+    fn words(bytes: &[u8]) -> Vec<u32> {
+        bytes.chunks(4)
+            .map(|c| {
+                ((c[3] as u32) << 24) | ((c[2] as u32) << 16) | ((c[1] as u32) << 8) | c[0] as u32
+            })
+            .collect()
+    }
+
+    #[test]
+    fn vertex_interface() {
+        let spirv = words(include_bytes!("tests/vert.spv"));
+        let reflection = reflect_entry_point(&spirv, "main").unwrap();
+        assert_eq!(reflection.ty, EntryPointType::Graphics(GraphicsShaderType::Vertex));
+
+        let mut input: Vec<_> = reflection.input.elements().collect();
+        input.sort_by_key(|e| e.location.start);
+        assert_eq!(input.len(), 2);
+        assert_eq!(input[0].location, 0 .. 1);
+        assert_eq!(input[0].format, Format::R32G32Sfloat);
+        assert_eq!(input[0].name.as_ref().map(|n| &**n), Some("position"));
+        assert_eq!(input[1].location, 1 .. 2);
+        assert_eq!(input[1].format, Format::R32G32B32Sfloat);
+
+        // `gl_Position` is a built-in and must not appear in the interface.
+        let output: Vec<_> = reflection.output.elements().collect();
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].location, 0 .. 1);
+        assert_eq!(output[0].format, Format::R32G32B32Sfloat);
+    }
+
+    #[test]
+    fn fragment_interface() {
+        let spirv = words(include_bytes!("tests/frag.spv"));
+        let reflection = reflect_entry_point(&spirv, "main").unwrap();
+        assert_eq!(reflection.ty, EntryPointType::Graphics(GraphicsShaderType::Fragment));
+
+        let input: Vec<_> = reflection.input.elements().collect();
+        assert_eq!(input.len(), 1);
+        assert_eq!(input[0].format, Format::R32G32B32Sfloat);
+
+        let output: Vec<_> = reflection.output.elements().collect();
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].format, Format::R32G32B32A32Sfloat);
+    }
+
+    #[test]
+    fn missing_entry_point() {
+        let spirv = words(include_bytes!("tests/vert.spv"));
+        match reflect_entry_point(&spirv, "foo") {
+            Err(ReflectionError::EntryPointNotFound) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -7,7 +7,13 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
-use crate::enums::*;
+//! Parsing of SPIR-V code into a list of instructions.
+
+use std::cmp;
+use std::error;
+use std::fmt;
+
+use spirv::enums::*;
 
 /// Parses a SPIR-V document from a list of words.
 pub fn parse_spirv(i: &[u32]) -> Result<Spirv, ParseError> {
@@ -48,6 +54,25 @@ pub enum ParseError {
     UnknownConstant(&'static str, u32),
 }
 
+impl error::Error for ParseError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            ParseError::MissingHeader => "the SPIR-V header is missing",
+            ParseError::WrongHeader => "the SPIR-V magic number is wrong",
+            ParseError::IncompleteInstruction => "an instruction is truncated",
+            ParseError::UnknownConstant(..) => "an enumeration has an unknown value",
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Spirv {
     pub version: (u8, u8),
@@ -182,10 +207,9 @@ fn parse_instruction(i: &[u32]) -> Result<(Instruction, &[u32]), ParseError> {
     assert!(i.len() >= 1);
 
     let word_count = (i[0] >> 16) as usize;
-    assert!(word_count >= 1);
     let opcode = (i[0] & 0xffff) as u16;
 
-    if i.len() < word_count {
+    if word_count == 0 || i.len() < word_count {
         return Err(ParseError::IncompleteInstruction);
     }
 
@@ -374,12 +398,13 @@ fn parse_string(data: &[u32]) -> (String, &[u32]) {
         .collect::<Vec<u8>>();
 
     let r = 1 + bytes.len() / 4;
-    let s = String::from_utf8(bytes).expect("Shader content is not UTF-8");
+    let s = String::from_utf8_lossy(&bytes).into_owned();
 
-    (s, &data[r ..])
+    (s, &data[cmp::min(r, data.len()) ..])
 }
 
-pub(crate) struct FoundDecoration {
+/// A decoration found with `Spirv::get_decorations`.
+pub struct FoundDecoration {
     pub target_id: u32,
     pub params: Vec<u32>
 }
@@ -392,7 +417,7 @@ impl Spirv {
     ///         creates a FoundDecoration with its params and target_id
     ///     if it points at a group:
     ///         the OpDecorate's target_id is ignored and a seperate FoundDecoration is created only for each target_id given in matching OpGroupDecorate instructions.
-    pub(crate) fn get_decorations(&self, find_decoration: Decoration) -> Vec<FoundDecoration> {
+    pub fn get_decorations(&self, find_decoration: Decoration) -> Vec<FoundDecoration> {
         let mut decorations = vec!();
         for instruction in &self.instructions {
             if let Instruction::Decorate { target_id, ref decoration, ref params } = instruction {
@@ -436,7 +461,7 @@ impl Spirv {
     /// Returns the params held by the decoration for the specified id and type
     /// Searches OpDecorate and OpGroupMemberDecorate
     /// Returns None if such a decoration does not exist
-    pub(crate) fn get_decoration_params(&self, id: u32, find_decoration: Decoration) -> Option<Vec<u32>> {
+    pub fn get_decoration_params(&self, id: u32, find_decoration: Decoration) -> Option<Vec<u32>> {
         for instruction in &self.instructions {
             match instruction {
                 Instruction::Decorate { target_id, ref decoration, ref params }
@@ -465,7 +490,7 @@ impl Spirv {
     /// Returns the params held by the decoration for the member specified by id, member and type
     /// Searches OpMemberDecorate and OpGroupMemberDecorate
     /// Returns None if such a decoration does not exist
-    pub(crate) fn get_member_decoration_params(&self, struct_id: u32, member_literal: u32, find_decoration: Decoration) -> Option<Vec<u32>> {
+    pub fn get_member_decoration_params(&self, struct_id: u32, member_literal: u32, find_decoration: Decoration) -> Option<Vec<u32>> {
         for instruction in &self.instructions {
             match instruction {
                 Instruction::MemberDecorate { target_id, member, ref decoration, ref params }
@@ -497,7 +522,7 @@ impl Spirv {
     ///
     /// This function does not need a member_literal argument because the spirv spec requires that a
     /// struct must contain either all builtin or all non-builtin members.
-    pub(crate) fn get_member_decoration_builtin_params(&self, struct_id: u32) -> Option<Vec<u32>> {
+    pub fn get_member_decoration_builtin_params(&self, struct_id: u32) -> Option<Vec<u32>> {
         for instruction in &self.instructions {
             match instruction {
                 Instruction::MemberDecorate { target_id, decoration: Decoration::DecorationBuiltIn, ref params, .. }
@@ -526,11 +551,11 @@ impl Spirv {
 
 #[cfg(test)]
 mod test {
-    use crate::parse;
+    use spirv::parse;
 
     #[test]
     fn test() {
-        let data = include_bytes!("../tests/frag.spv");
+        let data = include_bytes!("tests/frag.spv");
         let insts: Vec<_> = data.chunks(4)
             .map(|c| {
                 ((c[3] as u32) << 24) | ((c[2] as u32) << 16) | ((c[1] as u32) << 8) | c[0] as u32
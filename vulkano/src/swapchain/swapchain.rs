@@ -483,13 +483,26 @@ impl <W> Swapchain<W> {
                     cubemap_compatible: false,
                 };
 
-                let img = UnsafeImage::from_raw(device.clone(),
-                                                image,
-                                                usage.to_usage_bits(),
-                                                format,
-                                                dims,
-                                                1,
-                                                1);
+                let img = match sharing {
+                    SharingMode::Exclusive(_) => {
+                        UnsafeImage::from_raw(device.clone(),
+                                              image,
+                                              usage.to_usage_bits(),
+                                              format,
+                                              dims,
+                                              1,
+                                              1)
+                    },
+                    SharingMode::Concurrent(_) => {
+                        UnsafeImage::from_raw_concurrent(device.clone(),
+                                                         image,
+                                                         usage.to_usage_bits(),
+                                                         format,
+                                                         dims,
+                                                         1,
+                                                         1)
+                    },
+                };
 
                 ImageEntry {
                     image: img,